@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+/// Other names for crates, from `--alias NAME=CRATE`: team shorthand (`k8s` for
+/// `kube`) or internal fork names mapped to the upstream crate on docs.rs.
+#[derive(Debug, Clone, Default)]
+pub struct CrateAliases {
+    /// Alias (hyphens as underscores) to crate name.
+    aliases: HashMap<String, String>,
+}
+
+impl CrateAliases {
+    /// Parse a `NAME=CRATE` flag value.
+    pub fn parse(spec: &str) -> Option<(String, String)> {
+        let (alias, crate_name) = spec.split_once('=')?;
+        let (alias, crate_name) = (alias.trim(), crate_name.trim());
+        if alias.is_empty() || crate_name.is_empty() {
+            return None;
+        }
+        Some((alias.to_string(), crate_name.to_string()))
+    }
+
+    /// Make `alias` stand for `crate_name`, replacing an earlier mapping of it.
+    pub fn insert(&mut self, alias: &str, crate_name: &str) {
+        self.aliases
+            .insert(alias.replace('-', "_"), crate_name.to_string());
+    }
+
+    /// The crate `name` is an alias for. Hyphens and underscores are interchangeable.
+    pub fn resolve(&self, name: &str) -> Option<&str> {
+        self.aliases
+            .get(&name.replace('-', "_"))
+            .map(String::as_str)
+    }
+}
+
+/// `path` with its first segment replaced by `crate_name` if that segment is `alias`
+/// (e.g. "k8s::api::Api" to "kube::api::Api"). Paths rooted elsewhere are unchanged.
+pub fn replace_path_root(path: &str, alias: &str, crate_name: &str) -> Option<String> {
+    let (root, rest) = path.split_once("::")?;
+    (root.replace('-', "_") == alias.replace('-', "_"))
+        .then(|| format!("{}::{rest}", crate_name.replace('-', "_")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== CrateAliases tests ==========
+
+    #[test]
+    fn parse_reads_name_and_crate() {
+        assert_eq!(
+            CrateAliases::parse("k8s=kube"),
+            Some(("k8s".to_string(), "kube".to_string()))
+        );
+        assert_eq!(
+            CrateAliases::parse(" acme-serde = serde "),
+            Some(("acme-serde".to_string(), "serde".to_string()))
+        );
+        assert_eq!(CrateAliases::parse("k8s"), None);
+        assert_eq!(CrateAliases::parse("k8s="), None);
+        assert_eq!(CrateAliases::parse("=kube"), None);
+    }
+
+    #[test]
+    fn resolve_ignores_hyphen_underscore_difference() {
+        let mut aliases = CrateAliases::default();
+        aliases.insert("acme-serde", "serde");
+        aliases.insert("k8s", "kube");
+        aliases.insert("k8s", "kube-client");
+
+        assert_eq!(aliases.resolve("acme_serde"), Some("serde"));
+        assert_eq!(aliases.resolve("acme-serde"), Some("serde"));
+        assert_eq!(aliases.resolve("k8s"), Some("kube-client"));
+        assert_eq!(aliases.resolve("serde"), None);
+    }
+
+    // ========== replace_path_root tests ==========
+
+    #[test]
+    fn replace_path_root_rewrites_only_paths_rooted_at_the_alias() {
+        assert_eq!(
+            replace_path_root("k8s::api::Api", "k8s", "kube-client"),
+            Some("kube_client::api::Api".to_string())
+        );
+        assert_eq!(
+            replace_path_root("acme_serde::Serialize", "acme-serde", "serde"),
+            Some("serde::Serialize".to_string())
+        );
+        assert_eq!(replace_path_root("api::Api", "k8s", "kube"), None);
+        assert_eq!(replace_path_root("k8s", "k8s", "kube"), None);
+    }
+}
@@ -0,0 +1,217 @@
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::docs::local::{FNV_OFFSET, fnv1a};
+
+/// Where the docs of a crate loaded during a tool call came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheOutcome {
+    /// Already indexed in memory.
+    Memory,
+    /// Read from the disk cache.
+    Disk,
+    /// Read from the `--corpus-dir` directory.
+    Corpus,
+    /// Downloaded from docs.rs.
+    Fetched,
+    /// Built locally with rustdoc.
+    Built,
+    /// Left in the target dir by an earlier local doc build.
+    Prebuilt,
+    /// An older cached copy, because docs.rs couldn't be reached.
+    Stale,
+}
+
+/// One crate load made by a tool call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Load {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub version: String,
+    pub cache: CacheOutcome,
+}
+
+tokio::task_local! {
+    /// Crate loads of the tool call running on this task, for its audit entry.
+    static CALL_LOADS: RefCell<Vec<Load>>;
+}
+
+/// Record a crate load for the audit entry of the tool call running on this task.
+/// Does nothing outside of one (e.g. in background tasks).
+pub fn note_load(crate_name: &str, version: &str, cache: CacheOutcome) {
+    let _ = CALL_LOADS.try_with(|loads| {
+        loads.borrow_mut().push(Load {
+            crate_name: crate_name.to_string(),
+            version: version.to_string(),
+            cache,
+        });
+    });
+}
+
+/// Run a tool call, collecting the crate loads it notes.
+pub async fn collect_loads<F: Future>(call: F) -> (F::Output, Vec<Load>) {
+    CALL_LOADS
+        .scope(RefCell::new(Vec::new()), async {
+            let output = call.await;
+            (output, CALL_LOADS.with(RefCell::take))
+        })
+        .await
+}
+
+/// One line of the audit log.
+#[derive(Debug, Serialize)]
+pub struct AuditEntry {
+    /// When the call finished, RFC 3339 in UTC.
+    pub timestamp: String,
+    pub tool: String,
+    #[serde(rename = "crate", skip_serializing_if = "Option::is_none")]
+    pub crate_name: Option<String>,
+    /// The version argument as given; the versions actually loaded are in `loads`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// FNV-1a hash of the arguments as canonical JSON, to group identical calls.
+    pub params_hash: String,
+    pub duration_ms: u64,
+    pub is_error: bool,
+    pub loads: Vec<Load>,
+}
+
+impl AuditEntry {
+    pub fn new(
+        tool: &str,
+        arguments: Option<&serde_json::Map<String, serde_json::Value>>,
+        duration: Duration,
+        is_error: bool,
+        loads: Vec<Load>,
+    ) -> Self {
+        let argument = |name: &str| {
+            arguments
+                .and_then(|arguments| arguments.get(name))
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+        };
+        // serde_json's map is sorted by key, so equal arguments serialize identically
+        let canonical = arguments.map(|a| serde_json::to_string(a).unwrap_or_default());
+        Self {
+            timestamp: rfc3339(SystemTime::now()),
+            tool: tool.to_string(),
+            crate_name: argument("crate_name"),
+            version: argument("version"),
+            params_hash: format!(
+                "{:016x}",
+                fnv1a(FNV_OFFSET, canonical.unwrap_or_default().as_bytes())
+            ),
+            duration_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+            is_error,
+            loads,
+        }
+    }
+}
+
+/// An append-only JSONL file with one `AuditEntry` per tool call (`--audit-log`).
+pub struct AuditLog {
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let file = File::options().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Append `entry` as one line. Failures are logged, never passed to the caller.
+    pub fn record(&self, entry: &AuditEntry) {
+        let mut line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to serialize audit entry: {e}");
+                return;
+            }
+        };
+        line.push('\n');
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = file.write_all(line.as_bytes()) {
+            tracing::warn!("Failed to write audit log: {e}");
+        }
+    }
+}
+
+/// `time` as an RFC 3339 UTC timestamp with milliseconds (e.g. "2026-10-16T17:22:53.001Z").
+fn rfc3339(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, rem) = ((secs / 86_400) as i64, secs % 86_400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's `civil_from_days`)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== rfc3339 tests ==========
+
+    #[test]
+    fn rfc3339_formats_utc_dates() {
+        let at = |secs: u64, millis: u64| {
+            rfc3339(UNIX_EPOCH + Duration::from_secs(secs) + Duration::from_millis(millis))
+        };
+        assert_eq!(at(0, 0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(at(951_782_400, 0), "2000-02-29T00:00:00.000Z");
+        assert_eq!(at(1_700_000_000, 42), "2023-11-14T22:13:20.042Z");
+    }
+
+    // ========== AuditEntry tests ==========
+
+    #[test]
+    fn audit_entry_hashes_arguments_independent_of_order() {
+        let arguments = |json: &str| serde_json::from_str(json).unwrap();
+        let a: serde_json::Map<_, _> = arguments(r#"{"crate_name": "serde", "query": "de"}"#);
+        let b: serde_json::Map<_, _> = arguments(r#"{"query": "de", "crate_name": "serde"}"#);
+        let entry =
+            |args| AuditEntry::new("search_crate", Some(args), Duration::ZERO, false, vec![]);
+
+        let (first, second) = (entry(&a), entry(&b));
+        assert_eq!(first.params_hash, second.params_hash);
+        assert_eq!(first.crate_name.as_deref(), Some("serde"));
+        assert_eq!(first.version, None);
+    }
+
+    #[tokio::test]
+    async fn collect_loads_gathers_loads_noted_during_the_call() {
+        let ((), loads) = collect_loads(async {
+            note_load("serde", "1.0.210", CacheOutcome::Disk);
+            note_load("serde", "1.0.210", CacheOutcome::Memory);
+        })
+        .await;
+        assert_eq!(loads.len(), 2);
+        assert_eq!(loads[0].cache, CacheOutcome::Disk);
+
+        // Outside of a call, loads are dropped
+        note_load("serde", "1.0.210", CacheOutcome::Fetched);
+    }
+}
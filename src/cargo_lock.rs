@@ -1,14 +1,22 @@
-use cargo_lock::Lockfile;
-use std::collections::HashMap;
+use cargo_lock::{Lockfile, Version};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 /// Parsed Cargo.lock data with fast crate name → version lookup.
+#[derive(Default)]
 pub struct CargoLockIndex {
-    /// Map from crate name to version string (e.g. "1.0.210").
-    /// If multiple versions exist, keeps the latest.
-    versions: HashMap<String, String>,
+    /// Map from crate name to its locked versions (e.g. "1.0.210"), newest first.
+    /// Crates often appear in several major versions pulled in by different dependencies.
+    versions: HashMap<String, Vec<String>>,
+    /// Non-local crates that local packages (workspace members, path dependencies)
+    /// depend on directly, sorted.
+    direct: Vec<String>,
 }
 
+/// Prefix of an explicit `version` that selects among a crate's locked versions,
+/// e.g. "from-lock:0.8".
+pub const FROM_LOCK_PREFIX: &str = "from-lock:";
+
 impl CargoLockIndex {
     /// Walk up from `start_dir` looking for Cargo.lock, then parse it.
     pub fn find_and_parse(start_dir: &Path) -> Option<Self> {
@@ -20,28 +28,151 @@ impl CargoLockIndex {
     /// Parse a Cargo.lock file at the given path.
     pub fn from_path(path: &Path) -> Result<Self, crate::error::Error> {
         let lockfile = Lockfile::load(path)?;
-        let mut versions = HashMap::new();
-
+        let mut versions: HashMap<String, Vec<Version>> = HashMap::new();
         for package in &lockfile.packages {
-            let name = package.name.as_str().to_string();
-            let version = package.version.to_string();
-            // If multiple versions of the same crate exist, keep the latest
             versions
-                .entry(name)
-                .and_modify(|existing: &mut String| {
-                    if version > *existing {
-                        *existing = version.clone();
-                    }
-                })
-                .or_insert(version);
+                .entry(package.name.as_str().to_string())
+                .or_default()
+                .push(package.version.clone());
         }
 
-        Ok(Self { versions })
+        let versions = versions
+            .into_iter()
+            .map(|(name, mut versions)| {
+                versions.sort_by(|a, b| b.cmp(a));
+                versions.dedup();
+                (name, versions.iter().map(Version::to_string).collect())
+            })
+            .collect();
+
+        // Lockfiles only give a dependency's source when its name is ambiguous
+        let sourced: HashSet<&str> = lockfile
+            .packages
+            .iter()
+            .filter(|package| package.source.is_some())
+            .map(|package| package.name.as_str())
+            .collect();
+        let mut direct: Vec<String> = lockfile
+            .packages
+            .iter()
+            .filter(|package| package.source.is_none())
+            .flat_map(|package| &package.dependencies)
+            .map(|dependency| dependency.name.as_str())
+            .filter(|name| sourced.contains(name))
+            .map(str::to_string)
+            .collect();
+        direct.sort();
+        direct.dedup();
+        Ok(Self { versions, direct })
+    }
+
+    /// Crates the project's own packages depend on directly.
+    pub fn direct_dependencies(&self) -> &[String] {
+        &self.direct
+    }
+
+    /// Names of all crates in the lockfile.
+    pub fn crate_names(&self) -> impl Iterator<Item = &str> {
+        self.versions.keys().map(String::as_str)
     }
 
-    /// Look up the version of a crate.
+    /// The locked package whose lib name is `lib_name` (hyphens read as underscores),
+    /// e.g. "tokio-util" for `tokio_util`.
+    pub fn package_name(&self, lib_name: &str) -> Option<&str> {
+        self.versions
+            .keys()
+            .map(String::as_str)
+            .find(|name| name.replace('-', "_") == lib_name)
+    }
+
+    /// Look up the version of a crate (the newest, if several are locked).
     pub fn get_version(&self, crate_name: &str) -> Option<&str> {
-        self.versions.get(crate_name).map(|s| s.as_str())
+        self.all_versions(crate_name).first().map(String::as_str)
+    }
+
+    /// Every locked version of a crate, newest first.
+    pub fn all_versions(&self, crate_name: &str) -> &[String] {
+        self.versions.get(crate_name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Crates locked at more than one version, with their versions (newest first),
+    /// sorted by name.
+    pub fn duplicates(&self) -> Vec<(&str, &[String])> {
+        let mut duplicates: Vec<(&str, &[String])> = self
+            .versions
+            .iter()
+            .filter(|(_, versions)| versions.len() > 1)
+            .map(|(name, versions)| (name.as_str(), versions.as_slice()))
+            .collect();
+        duplicates.sort();
+        duplicates
+    }
+
+    /// The newest locked version of a crate matching `prefix` segment by segment
+    /// (`"0.8"` matches `0.8.5` but not `0.80.1`).
+    pub fn select(&self, crate_name: &str, prefix: &str) -> Option<&str> {
+        let wanted: Vec<&str> = prefix.split('.').collect();
+        self.all_versions(crate_name)
+            .iter()
+            .find(|version| {
+                let segments: Vec<&str> = version.split(['.', '-', '+']).collect();
+                segments.len() >= wanted.len() && segments[..wanted.len()] == wanted[..]
+            })
+            .map(String::as_str)
+    }
+
+    /// The shortest `from-lock:` prefix that selects `version` of a crate, no shorter
+    /// than its semver-compatible part (e.g. "0.7" for 0.7.3 next to 0.8.5), or the
+    /// full version.
+    pub fn selector(&self, crate_name: &str, version: &str) -> String {
+        let segments: Vec<&str> = version.split('.').collect();
+        let shortest = if segments[0] == "0" { 2 } else { 1 };
+        (shortest..segments.len())
+            .map(|len| segments[..len].join("."))
+            .find(|prefix| self.select(crate_name, prefix) == Some(version))
+            .unwrap_or_else(|| version.to_string())
+    }
+
+    /// Compare this lockfile's versions with those of `newer`. Crates locked at several
+    /// versions are compared as a whole, their versions joined with ", ".
+    pub fn diff(&self, newer: &CargoLockIndex) -> LockDiff {
+        let mut diff = LockDiff::default();
+        for (name, old) in &self.versions {
+            match newer.versions.get(name) {
+                None => diff.removed.push((name.clone(), old.join(", "))),
+                Some(new) if new != old => {
+                    diff.changed
+                        .push((name.clone(), old.join(", "), new.join(", ")));
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, new) in &newer.versions {
+            if !self.versions.contains_key(name) {
+                diff.added.push((name.clone(), new.join(", ")));
+            }
+        }
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+        diff
+    }
+}
+
+/// Version changes between two lockfiles, each sorted by crate name.
+#[derive(Debug, Default)]
+pub struct LockDiff {
+    /// (crate, version) pairs only in the newer lockfile.
+    pub added: Vec<(String, String)>,
+    /// (crate, version) pairs only in the older lockfile.
+    pub removed: Vec<(String, String)>,
+    /// (crate, old version, new version).
+    pub changed: Vec<(String, String, String)>,
+}
+
+impl LockDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
     }
 }
 
@@ -58,3 +189,150 @@ fn find_cargo_lock(start_dir: &Path) -> Option<PathBuf> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock(versions: &[(&str, &str)]) -> CargoLockIndex {
+        let mut index = CargoLockIndex::default();
+        for (name, version) in versions {
+            index
+                .versions
+                .entry(name.to_string())
+                .or_default()
+                .push(version.to_string());
+        }
+        index
+    }
+
+    // ========== version selection tests ==========
+
+    #[test]
+    fn from_path_keeps_every_version_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Cargo.lock");
+        std::fs::write(
+            &path,
+            r#"version = 3
+
+[[package]]
+name = "rand"
+version = "0.8.5"
+
+[[package]]
+name = "rand"
+version = "0.10.0"
+
+[[package]]
+name = "rand"
+version = "0.7.3"
+
+[[package]]
+name = "serde"
+version = "1.0.210"
+"#,
+        )
+        .unwrap();
+        let index = CargoLockIndex::from_path(&path).unwrap();
+
+        assert_eq!(index.all_versions("rand"), ["0.10.0", "0.8.5", "0.7.3"]);
+        assert_eq!(index.get_version("rand"), Some("0.10.0"));
+        assert_eq!(index.duplicates().len(), 1);
+        assert_eq!(index.duplicates()[0].0, "rand");
+    }
+
+    #[test]
+    fn from_path_finds_direct_dependencies_of_local_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Cargo.lock");
+        std::fs::write(
+            &path,
+            r#"version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["helper", "serde"]
+
+[[package]]
+name = "helper"
+version = "0.1.0"
+dependencies = ["rand"]
+
+[[package]]
+name = "rand"
+version = "0.8.5"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = ["libc"]
+
+[[package]]
+name = "libc"
+version = "0.2.155"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "serde"
+version = "1.0.210"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        )
+        .unwrap();
+        let index = CargoLockIndex::from_path(&path).unwrap();
+
+        assert_eq!(index.direct_dependencies(), ["rand", "serde"]);
+    }
+
+    #[test]
+    fn select_matches_whole_version_segments() {
+        let index = lock(&[("rand", "0.80.1"), ("rand", "0.8.5"), ("rand", "0.7.3")]);
+        assert_eq!(index.select("rand", "0.8"), Some("0.8.5"));
+        assert_eq!(index.select("rand", "0.7.3"), Some("0.7.3"));
+        assert_eq!(index.select("rand", "0"), Some("0.80.1"));
+        assert_eq!(index.select("rand", "0.9"), None);
+        assert_eq!(index.select("serde", "1"), None);
+    }
+
+    #[test]
+    fn selector_is_shortest_unambiguous_prefix() {
+        let index = lock(&[("rand", "1.2.0"), ("rand", "0.8.5"), ("rand", "0.7.3")]);
+        assert_eq!(index.selector("rand", "1.2.0"), "1");
+        assert_eq!(index.selector("rand", "0.8.5"), "0.8");
+        assert_eq!(index.selector("rand", "0.7.3"), "0.7");
+    }
+
+    // ========== diff tests ==========
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_versions() {
+        let old = lock(&[("serde", "1.0.200"), ("tokio", "1.38.0"), ("log", "0.4.21")]);
+        let new = lock(&[
+            ("serde", "1.0.210"),
+            ("tokio", "1.38.0"),
+            ("tracing", "0.1.40"),
+        ]);
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.changed,
+            [(
+                "serde".to_string(),
+                "1.0.200".to_string(),
+                "1.0.210".to_string()
+            )]
+        );
+        assert_eq!(diff.added, [("tracing".to_string(), "0.1.40".to_string())]);
+        assert_eq!(diff.removed, [("log".to_string(), "0.4.21".to_string())]);
+        assert!(new.diff(&new).is_empty());
+
+        let dup = lock(&[("serde", "1.0.210"), ("serde", "0.9.15")]);
+        assert_eq!(
+            new.diff(&dup).changed[0],
+            (
+                "serde".to_string(),
+                "1.0.210".to_string(),
+                "1.0.210, 0.9.15".to_string()
+            )
+        );
+    }
+}
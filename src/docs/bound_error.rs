@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+
+use super::index::{CrateIndex, ImplBlock};
+
+/// The trait, type, and requiring item parsed from a rustc trait-bound error (E0277).
+#[derive(Debug, PartialEq, Eq)]
+pub struct BoundError {
+    /// The unsatisfied trait as written in the message (e.g. "serde::Serialize").
+    pub trait_path: String,
+    /// The type that does not implement it (e.g. "Rc<MyType>").
+    pub type_name: String,
+    /// The item whose bound requires the trait (e.g. "tokio::spawn"), if mentioned.
+    pub required_by: Option<String>,
+}
+
+impl BoundError {
+    /// The crate named by a path's first segment, unless it is the standard library.
+    fn crate_of(path: &str) -> Option<&str> {
+        let (first, _) = path.split_once("::")?;
+        (!matches!(first, "std" | "core" | "alloc" | "crate")).then_some(first)
+    }
+
+    /// The trait path without generics or a `for<'a>` binder
+    /// (e.g. "for<'de> serde::Deserialize<'de>" → "serde::Deserialize").
+    pub fn trait_item_path(&self) -> &str {
+        let path = match self.trait_path.strip_prefix("for<") {
+            Some(rest) => rest.split_once("> ").map_or(rest, |(_, path)| path),
+            None => &self.trait_path,
+        };
+        path.split('<').next().unwrap_or(path).trim()
+    }
+
+    /// The crate defining the trait, if the message names it with a path.
+    pub fn trait_crate(&self) -> Option<&str> {
+        Self::crate_of(self.trait_item_path())
+    }
+
+    /// The crate defining the type, if the message names it with a path.
+    pub fn type_crate(&self) -> Option<&str> {
+        Self::crate_of(
+            self.type_name
+                .trim_start_matches(['&', '*'])
+                .trim_start_matches("mut "),
+        )
+    }
+
+    /// Simple names of the types mentioned in the offending type, outermost first
+    /// (e.g. `Vec<my::Thing>` → ["Vec", "Thing"]).
+    pub fn type_names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for token in self
+            .type_name
+            .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        {
+            let name = token.rsplit("::").next().unwrap_or(token);
+            if name.starts_with(|c: char| c.is_ascii_uppercase()) && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+}
+
+/// Parse the first trait-bound failure out of a rustc error message.
+///
+/// Recognizes "the trait bound `T: Trait` is not satisfied", "the trait `Trait` is not
+/// implemented for `T`", and the `Send`/`Sync` ("cannot be sent/shared between threads
+/// safely") and "`T` doesn't implement `Trait`" forms, plus "required by a bound in `f`".
+pub fn parse_bound_error(message: &str) -> Option<BoundError> {
+    let (trait_path, type_name) = message.lines().find_map(parse_bound_line)?;
+    let required_by = message.lines().find_map(|line| {
+        let rest = line.split_once("required by a bound in `")?.1;
+        Some(rest.split('`').next()?.to_string())
+    });
+    Some(BoundError {
+        trait_path,
+        type_name,
+        required_by,
+    })
+}
+
+/// Parse (trait, type) from one line of an E0277 message.
+fn parse_bound_line(line: &str) -> Option<(String, String)> {
+    let ticks: Vec<&str> = line.split('`').skip(1).step_by(2).collect();
+    if line.contains("the trait bound `") {
+        // `Type: Trait` — split at the first `: ` outside generics
+        let bound = ticks.first()?;
+        let mut depth = 0usize;
+        for (i, c) in bound.char_indices() {
+            match c {
+                '<' | '(' | '[' => depth += 1,
+                '>' | ')' | ']' => depth = depth.saturating_sub(1),
+                ':' if depth == 0 && bound[i..].starts_with(": ") => {
+                    return Some((bound[i + 2..].to_string(), bound[..i].to_string()));
+                }
+                _ => {}
+            }
+        }
+        return None;
+    }
+    if line.contains("is not implemented for `") && ticks.len() >= 2 {
+        return Some((ticks[0].to_string(), ticks[1].to_string()));
+    }
+    if line.contains("cannot be sent between threads safely") {
+        return Some(("Send".to_string(), ticks.first()?.to_string()));
+    }
+    if line.contains("cannot be shared between threads safely") {
+        return Some(("Sync".to_string(), ticks.first()?.to_string()));
+    }
+    if line.contains("doesn't implement `") && ticks.len() >= 2 {
+        let trait_path = ticks[1].split_whitespace().next()?;
+        return Some((trait_path.to_string(), ticks[0].to_string()));
+    }
+    None
+}
+
+/// Feature flags whose name (or enabled features) mention `needle`, e.g. the
+/// `serde` feature of a crate whose type fails a `serde::Serialize` bound.
+pub fn matching_features<'a>(
+    features: &'a BTreeMap<String, Vec<String>>,
+    needle: &str,
+) -> Vec<&'a str> {
+    let needle = needle.to_lowercase().replace('_', "-");
+    features
+        .iter()
+        .filter(|(name, enables)| {
+            name.as_str() != "default"
+                && (name.to_lowercase().contains(&needle)
+                    || enables.iter().any(|e| e.to_lowercase().contains(&needle)))
+        })
+        .map(|(name, _)| name.as_str())
+        .collect()
+}
+
+impl CrateIndex {
+    /// Types implementing the trait named `trait_name`, with their impl blocks, sorted by path.
+    pub fn trait_implementors(&self, trait_name: &str) -> Vec<(&str, &ImplBlock)> {
+        let mut implementors: Vec<(&str, &ImplBlock)> = self
+            .impl_blocks
+            .iter()
+            .flat_map(|(type_path, blocks)| blocks.iter().map(move |b| (type_path.as_str(), b)))
+            .filter(|(_, block)| block.trait_name.as_deref() == Some(trait_name))
+            .collect();
+        implementors.sort_by(|a, b| a.0.cmp(b.0));
+        implementors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== parse_bound_error tests ==========
+
+    #[test]
+    fn parse_trait_bound_not_satisfied() {
+        let message = "error[E0277]: the trait bound `my::Config: serde::Serialize` is not satisfied\n  --> src/main.rs:5:5\n   |\nnote: required by a bound in `serde_json::to_string`";
+        let parsed = parse_bound_error(message).unwrap();
+        assert_eq!(parsed.trait_path, "serde::Serialize");
+        assert_eq!(parsed.type_name, "my::Config");
+        assert_eq!(parsed.required_by.as_deref(), Some("serde_json::to_string"));
+        assert_eq!(parsed.trait_crate(), Some("serde"));
+        assert_eq!(parsed.trait_item_path(), "serde::Serialize");
+    }
+
+    #[test]
+    fn parse_not_implemented_and_send_forms() {
+        let parsed = parse_bound_error(
+            "error[E0277]: the trait `Serialize` is not implemented for `chrono::DateTime<Utc>`",
+        )
+        .unwrap();
+        assert_eq!(parsed.trait_path, "Serialize");
+        assert_eq!(parsed.type_crate(), Some("chrono"));
+        assert_eq!(parsed.type_names(), ["DateTime", "Utc"]);
+
+        let parsed =
+            parse_bound_error("error[E0277]: `Rc<String>` cannot be sent between threads safely")
+                .unwrap();
+        assert_eq!(parsed.trait_path, "Send");
+        assert_eq!(parsed.type_name, "Rc<String>");
+
+        let parsed = parse_bound_error("error[E0277]: `Widget` doesn't implement `Debug`").unwrap();
+        assert_eq!(parsed.trait_path, "Debug");
+        assert_eq!(parsed.type_name, "Widget");
+    }
+
+    #[test]
+    fn parse_bound_with_generic_colons() {
+        let parsed = parse_bound_error(
+            "the trait bound `HashMap<K, V>: for<'de> Deserialize<'de>` is not satisfied",
+        )
+        .unwrap();
+        assert_eq!(parsed.type_name, "HashMap<K, V>");
+        assert_eq!(parsed.trait_path, "for<'de> Deserialize<'de>");
+        assert_eq!(parsed.trait_item_path(), "Deserialize");
+        assert_eq!(parsed.type_names(), ["HashMap", "K", "V"]);
+        assert!(parse_bound_error("error[E0308]: mismatched types").is_none());
+    }
+
+    #[test]
+    fn matching_features_checks_names_and_enables() {
+        let features = BTreeMap::from([
+            ("default".to_string(), vec!["serde".to_string()]),
+            ("serde".to_string(), vec!["dep:serde".to_string()]),
+            ("with-serde_json".to_string(), vec![]),
+            ("alloc".to_string(), vec![]),
+            ("derive".to_string(), vec!["serde?/derive".to_string()]),
+        ]);
+        assert_eq!(
+            matching_features(&features, "serde"),
+            ["derive", "serde", "with-serde_json"]
+        );
+    }
+}
@@ -1,4 +1,16 @@
-use std::path::PathBuf;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use cargo_lock::Version;
+
+use super::fetcher::{BodyEncoding, sniff_encoding};
+use super::render::format_size;
+
+/// zstd level `compact` recompresses entries at. Fetches and local builds write at
+/// level 3, which is fast but leaves a lot on the table for large JSON.
+const COMPACT_LEVEL: i32 = 19;
 
 /// On-disk cache for raw zstd-compressed rustdoc JSON bytes from docs.rs.
 ///
@@ -7,6 +19,66 @@ use std::path::PathBuf;
 /// All disk errors are non-fatal — logged as warnings and treated as cache misses.
 pub struct DiskCache {
     base_dir: PathBuf,
+    /// Never write or delete entries (`--sandbox`).
+    read_only: bool,
+}
+
+/// A crate version's rustdoc JSON stored in the disk cache.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub crate_name: String,
+    /// Version or cache key (e.g. "1.0.210", "git-abc123").
+    pub version: String,
+    /// Compressed size in bytes.
+    pub size: u64,
+    /// When the entry was written, if the filesystem reports it.
+    pub modified: Option<SystemTime>,
+}
+
+/// What `DiskCache::compact` did.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CompactReport {
+    /// Cached rustdoc JSON entries looked at.
+    pub entries: usize,
+    /// zstd entries rewritten smaller.
+    pub recompressed: usize,
+    /// Legacy entries stored as gzip or plain JSON, rewritten as zstd.
+    pub converted: usize,
+    /// Entries that aren't rustdoc JSON in any encoding (e.g. a cached error page),
+    /// left as they are.
+    pub unreadable: usize,
+    /// Temp files left behind by interrupted writes, removed.
+    pub removed_temp_files: usize,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+impl fmt::Display for CompactReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let saved = self.bytes_before.saturating_sub(self.bytes_after);
+        let percent = (saved * 100).checked_div(self.bytes_before).unwrap_or(0);
+        writeln!(
+            f,
+            "Compacted {} cache entries: {} -> {} (saved {}, {percent}%)",
+            self.entries,
+            format_size(self.bytes_before),
+            format_size(self.bytes_after),
+            format_size(saved),
+        )?;
+        write!(
+            f,
+            "Recompressed {}, converted {} legacy gzip/JSON entries to zstd, removed {} temp files",
+            self.recompressed, self.converted, self.removed_temp_files
+        )?;
+        if self.unreadable > 0 {
+            write!(
+                f,
+                "; {} unreadable entries left as they are",
+                self.unreadable
+            )?;
+        }
+        Ok(())
+    }
 }
 
 impl DiskCache {
@@ -20,12 +92,26 @@ impl DiskCache {
     pub fn new() -> Option<Self> {
         let base_dir = Self::base_dir()?;
         migrate_old_cache_dir(&base_dir);
-        Some(Self { base_dir })
+        Some(Self {
+            base_dir,
+            read_only: false,
+        })
+    }
+
+    /// The same cache, but only read from: writes and removals are skipped.
+    pub fn read_only(self) -> Self {
+        Self {
+            read_only: true,
+            ..self
+        }
     }
 
     #[cfg(test)]
-    fn with_base_dir(base_dir: PathBuf) -> Self {
-        Self { base_dir }
+    pub(crate) fn with_base_dir(base_dir: PathBuf) -> Self {
+        Self {
+            base_dir,
+            read_only: false,
+        }
     }
 
     /// Read cached raw bytes for a crate version. Returns `None` on miss or error.
@@ -44,8 +130,18 @@ impl DiskCache {
         }
     }
 
+    /// When a cached entry was written. Returns `None` on miss or error.
+    pub async fn modified(&self, crate_name: &str, version: &str) -> Option<SystemTime> {
+        let metadata = tokio::fs::metadata(self.cache_path(crate_name, version)).await;
+        metadata.ok()?.modified().ok()
+    }
+
     /// Write raw bytes to cache using temp-file-then-rename for atomicity.
     pub async fn write(&self, crate_name: &str, version: &str, bytes: &[u8]) {
+        if self.read_only {
+            tracing::debug!("Read-only disk cache, not caching {crate_name} v{version}");
+            return;
+        }
         let path = self.cache_path(crate_name, version);
 
         let Some(parent) = path.parent() else {
@@ -73,16 +169,93 @@ impl DiskCache {
         }
     }
 
-    /// Remove a corrupted cache entry.
-    pub async fn remove(&self, crate_name: &str, version: &str) {
+    /// Remove a cache entry, returning whether a file was deleted.
+    pub async fn remove(&self, crate_name: &str, version: &str) -> bool {
+        if self.read_only {
+            return false;
+        }
         let path = self.cache_path(crate_name, version);
-        if let Err(e) = tokio::fs::remove_file(&path).await
-            && e.kind() != std::io::ErrorKind::NotFound
-        {
-            tracing::warn!("Failed to remove cache entry {}: {e}", path.display());
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => true,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+            Err(e) => {
+                tracing::warn!("Failed to remove cache entry {}: {e}", path.display());
+                false
+            }
         }
     }
 
+    /// List cached rustdoc JSON, sorted by crate name and version.
+    ///
+    /// Crate archives (`crate-*` keys) and keyword sources (`keywords-*`) are not docs
+    /// and are skipped.
+    pub fn entries(&self) -> Vec<CacheEntry> {
+        let Ok(crates) = std::fs::read_dir(&self.base_dir) else {
+            return Vec::new();
+        };
+        let mut entries = Vec::new();
+        for crate_dir in crates.flatten() {
+            let crate_name = crate_dir.file_name().to_string_lossy().into_owned();
+            let Ok(files) = std::fs::read_dir(crate_dir.path()) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let file_name = file.file_name().to_string_lossy().into_owned();
+                if let Some(version) = file_name.strip_suffix(".json.zst")
+                    && is_docs_key(version)
+                {
+                    let metadata = file.metadata().ok();
+                    entries.push(CacheEntry {
+                        crate_name: crate_name.clone(),
+                        version: version.to_string(),
+                        size: metadata.as_ref().map_or(0, |m| m.len()),
+                        modified: metadata.and_then(|m| m.modified().ok()),
+                    });
+                }
+            }
+        }
+        entries.sort_by(|a, b| (&a.crate_name, &a.version).cmp(&(&b.crate_name, &b.version)));
+        entries
+    }
+
+    /// Recompress every cached rustdoc JSON entry at a higher zstd level, convert legacy
+    /// gzip and plain-JSON entries to zstd, and remove temp files of interrupted writes.
+    ///
+    /// An entry is only replaced when the result is smaller or it wasn't zstd. Blocking.
+    pub fn compact(&self) -> CompactReport {
+        let mut report = CompactReport::default();
+        let Ok(crates) = std::fs::read_dir(&self.base_dir) else {
+            return report;
+        };
+        for crate_dir in crates.flatten() {
+            let Ok(files) = std::fs::read_dir(crate_dir.path()) else {
+                continue;
+            };
+            for file in files.flatten() {
+                let path = file.path();
+                let file_name = file.file_name().to_string_lossy().into_owned();
+                if file_name.ends_with(".tmp") {
+                    match std::fs::remove_file(&path) {
+                        Ok(()) => report.removed_temp_files += 1,
+                        Err(e) => tracing::warn!("Failed to remove {}: {e}", path.display()),
+                    }
+                } else if let Some(version) = file_name.strip_suffix(".json.zst")
+                    && is_docs_key(version)
+                {
+                    compact_entry(&path, &mut report);
+                }
+            }
+        }
+        report
+    }
+
+    /// The cached copy to serve when docs.rs can't be reached: the requested version,
+    /// then the last copy of "latest", then the newest cached release (preferring
+    /// semver-compatible ones). Locally built docs are never picked.
+    pub fn stale_entry(&self, crate_name: &str, version: &str) -> Option<CacheEntry> {
+        pick_stale(self.entries(), crate_name, version)
+    }
+
     /// Delete the entire cache directory.
     pub async fn clear() {
         let Some(base_dir) = Self::base_dir() else {
@@ -102,6 +275,88 @@ impl DiskCache {
     }
 }
 
+/// Recompress one cache file in place, recording the outcome in `report`.
+fn compact_entry(path: &Path, report: &mut CompactReport) {
+    let Ok(bytes) = std::fs::read(path) else {
+        return;
+    };
+    let size = bytes.len() as u64;
+    report.entries += 1;
+    report.bytes_before += size;
+
+    let encoding = sniff_encoding(&bytes);
+    let json = match encoding {
+        BodyEncoding::Zstd => zstd::stream::decode_all(bytes.as_slice()).ok(),
+        BodyEncoding::Gzip => {
+            let mut json = Vec::new();
+            flate2::read::GzDecoder::new(bytes.as_slice())
+                .read_to_end(&mut json)
+                .ok()
+                .map(|_| json)
+        }
+        BodyEncoding::Json => Some(bytes),
+        BodyEncoding::Unknown => None,
+    };
+    let packed =
+        json.and_then(|json| zstd::stream::encode_all(json.as_slice(), COMPACT_LEVEL).ok());
+    let Some(packed) = packed else {
+        report.unreadable += 1;
+        report.bytes_after += size;
+        return;
+    };
+
+    let converted = encoding != BodyEncoding::Zstd;
+    if !converted && packed.len() as u64 >= size {
+        report.bytes_after += size;
+        return;
+    }
+    let tmp_path = path.with_extension("tmp");
+    let replaced =
+        std::fs::write(&tmp_path, &packed).and_then(|()| std::fs::rename(&tmp_path, path));
+    match replaced {
+        Ok(()) => {
+            report.bytes_after += packed.len() as u64;
+            if converted {
+                report.converted += 1;
+            } else {
+                report.recompressed += 1;
+            }
+        }
+        Err(e) => {
+            tracing::warn!("Failed to rewrite {}: {e}", path.display());
+            let _ = std::fs::remove_file(&tmp_path);
+            report.bytes_after += size;
+        }
+    }
+}
+
+fn pick_stale(entries: Vec<CacheEntry>, crate_name: &str, version: &str) -> Option<CacheEntry> {
+    let normalize = |name: &str| name.replace('-', "_");
+    let wanted = normalize(crate_name);
+    let requested = version.parse::<Version>().ok();
+    let compatible = |v: &Version| {
+        requested
+            .as_ref()
+            .is_none_or(|r| r.major == v.major && (r.major != 0 || r.minor == v.minor))
+    };
+    entries
+        .into_iter()
+        .filter(|entry| normalize(&entry.crate_name) == wanted)
+        .filter_map(|entry| {
+            let release = entry.version.parse::<Version>().ok();
+            let rank = if entry.version == version {
+                3
+            } else if entry.version == "latest" {
+                2
+            } else {
+                u8::from(compatible(release.as_ref()?))
+            };
+            Some((rank, release, entry))
+        })
+        .max_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)))
+        .map(|(_, _, entry)| entry)
+}
+
 /// One-time migration: rename the old `rust-docs-mcp` cache directory to `docsrs-mcp`.
 /// Only acts when the old directory exists and the new one does not.
 fn migrate_old_cache_dir(new_base: &std::path::Path) {
@@ -124,6 +379,12 @@ fn migrate_old_cache_dir(new_base: &std::path::Path) {
     }
 }
 
+/// Whether a cache key holds rustdoc JSON rather than a crate archive (`crate-*`) or
+/// std keyword source (`keywords-*`).
+fn is_docs_key(version: &str) -> bool {
+    !version.starts_with("crate-") && !version.starts_with("keywords-")
+}
+
 /// Sanitize a string for use as a single path component.
 /// Rejects path separators and traversal sequences to prevent directory escape.
 fn sanitize_path_component(s: &str) -> &str {
@@ -259,6 +520,139 @@ mod tests {
         assert!(cache_path.exists());
     }
 
+    #[tokio::test]
+    async fn entries_lists_docs_but_not_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::with_base_dir(dir.path().to_path_buf());
+        cache.write("tokio", "1.40.0", b"x").await;
+        cache.write("serde", "1.0.210", b"abc").await;
+        cache.write("serde", "crate-1.0.210", b"x").await;
+        cache.write("std", "keywords-1.82.0", b"x").await;
+
+        let entries = cache.entries();
+        let keys: Vec<(&str, &str)> = entries
+            .iter()
+            .map(|e| (e.crate_name.as_str(), e.version.as_str()))
+            .collect();
+        assert_eq!(keys, [("serde", "1.0.210"), ("tokio", "1.40.0")]);
+        assert_eq!(entries[0].size, 3);
+        assert!(entries[0].modified.is_some());
+    }
+
+    // ========== compact tests ==========
+
+    #[tokio::test]
+    async fn compact_recompresses_converts_and_cleans_up() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::with_base_dir(dir.path().to_path_buf());
+        let items: Vec<String> = (0..2_000u64)
+            .map(|i| {
+                format!(
+                    "{{\"id\": {i}, \"docs\": \"Returns item {}.\"}}",
+                    i * i % 7919
+                )
+            })
+            .collect();
+        let json = format!("{{\"items\": [{}]}}", items.join(","));
+
+        let light = zstd::stream::encode_all(json.as_bytes(), 1).unwrap();
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gz.write_all(json.as_bytes()).unwrap();
+        cache.write("serde", "1.0.0", &light).await;
+        cache.write("serde", "1.0.1", &gz.finish().unwrap()).await;
+        cache.write("serde", "1.0.2", json.as_bytes()).await;
+        cache.write("serde", "1.0.3", b"<!DOCTYPE html>").await;
+        cache.write("serde", "crate-1.0.0", b"archive").await;
+        std::fs::write(dir.path().join("serde").join("1.0.4.tmp"), b"partial").unwrap();
+
+        let report = cache.compact();
+        assert_eq!(report.entries, 4);
+        assert_eq!(report.recompressed, 1);
+        assert_eq!(report.converted, 2);
+        assert_eq!(report.unreadable, 1);
+        assert_eq!(report.removed_temp_files, 1);
+        assert!(report.bytes_after < report.bytes_before);
+
+        for version in ["1.0.0", "1.0.1", "1.0.2"] {
+            let bytes = cache.read("serde", version).await.unwrap();
+            assert_eq!(
+                zstd::stream::decode_all(bytes.as_slice()).unwrap(),
+                json.as_bytes()
+            );
+        }
+        assert_eq!(
+            cache.read("serde", "1.0.3").await.unwrap(),
+            b"<!DOCTYPE html>"
+        );
+        assert_eq!(
+            cache.read("serde", "crate-1.0.0").await.unwrap(),
+            b"archive"
+        );
+        assert!(!dir.path().join("serde").join("1.0.4.tmp").exists());
+    }
+
+    // ========== stale_entry tests ==========
+
+    fn entry(crate_name: &str, version: &str) -> CacheEntry {
+        CacheEntry {
+            crate_name: crate_name.to_string(),
+            version: version.to_string(),
+            size: 0,
+            modified: None,
+        }
+    }
+
+    #[test]
+    fn pick_stale_prefers_exact_then_latest_then_compatible_release() {
+        let entries = || {
+            vec![
+                entry("serde", "0.9.15"),
+                entry("serde", "1.0.100"),
+                entry("serde", "1.0.210"),
+                entry("serde", "git-abc123"),
+                entry("tokio", "1.40.0"),
+            ]
+        };
+        let pick = |entries, version| pick_stale(entries, "serde", version).map(|e| e.version);
+
+        assert_eq!(pick(entries(), "1.0.100").as_deref(), Some("1.0.100"));
+        assert_eq!(pick(entries(), "1.0.300").as_deref(), Some("1.0.210"));
+        assert_eq!(pick(entries(), "0.9.20").as_deref(), Some("0.9.15"));
+        assert_eq!(pick(entries(), "latest").as_deref(), Some("1.0.210"));
+
+        let mut with_latest = entries();
+        with_latest.push(entry("serde", "latest"));
+        assert_eq!(pick(with_latest, "latest").as_deref(), Some("latest"));
+        assert!(pick_stale(entries(), "rand", "latest").is_none());
+    }
+
+    #[tokio::test]
+    async fn modified_reports_write_time_of_existing_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::with_base_dir(dir.path().to_path_buf());
+        assert!(cache.modified("foo", "latest").await.is_none());
+
+        let before = SystemTime::now() - std::time::Duration::from_secs(1);
+        cache.write("foo", "latest", b"data").await;
+        let modified = cache.modified("foo", "latest").await.unwrap();
+        assert!(modified >= before);
+    }
+
+    #[tokio::test]
+    async fn read_only_cache_neither_writes_nor_removes() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::with_base_dir(dir.path().to_path_buf());
+        cache.write("foo", "1.0.0", b"data").await;
+
+        let cache = cache.read_only();
+        cache.write("foo", "2.0.0", b"data").await;
+        assert!(!cache.remove("foo", "1.0.0").await);
+        assert_eq!(cache.read("foo", "1.0.0").await.unwrap(), b"data");
+        assert!(cache.read("foo", "2.0.0").await.is_none());
+    }
+
     #[tokio::test]
     async fn remove_deletes_cache_entry() {
         let dir = tempfile::tempdir().unwrap();
@@ -267,7 +661,7 @@ mod tests {
         cache.write("my-crate", "1.0.0", b"data").await;
         assert!(cache.read("my-crate", "1.0.0").await.is_some());
 
-        cache.remove("my-crate", "1.0.0").await;
+        assert!(cache.remove("my-crate", "1.0.0").await);
         assert!(cache.read("my-crate", "1.0.0").await.is_none());
     }
 
@@ -277,7 +671,7 @@ mod tests {
         let cache = DiskCache::with_base_dir(dir.path().to_path_buf());
 
         // Should not panic or error
-        cache.remove("nonexistent", "0.0.0").await;
+        assert!(!cache.remove("nonexistent", "0.0.0").await);
     }
 
     #[tokio::test]
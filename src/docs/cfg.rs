@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+
+use super::index::{CrateIndex, IndexedItem, ItemKind, Visibility};
+
+/// The predicate of a `#[doc(cfg(...))]` attribute (e.g. `feature = "full"`), which
+/// docs.rs builds turn into "Available on crate feature `full` only".
+pub fn parse_doc_cfg(attr: &str) -> Option<String> {
+    let inner = attr.strip_prefix("#[doc(cfg(")?.strip_suffix("))]")?;
+    Some(inner.trim().to_string())
+}
+
+/// A cfg predicate in words: `feature = "a"` becomes `` `a` ``, and `all`/`any`/`not`
+/// become "and"/"or"/"not" (e.g. "`rt` and (`unix` or `fs`)").
+pub fn describe_cfg(predicate: &str) -> String {
+    let tokens = tokenize(predicate);
+    let mut pos = 0;
+    match parse_predicate(&tokens, &mut pos) {
+        Some(cfg) if pos == tokens.len() => cfg.describe(),
+        _ => format!("`{predicate}`"),
+    }
+}
+
+/// The crate features named anywhere in a cfg predicate, sorted.
+pub fn cfg_features(predicate: &str) -> Vec<String> {
+    let tokens = tokenize(predicate);
+    let mut features: Vec<String> = tokens
+        .windows(3)
+        .filter_map(|window| match window {
+            [Token::Ident(key), Token::Eq, Token::Str(value)] if key == "feature" => {
+                Some(value.clone())
+            }
+            _ => None,
+        })
+        .collect();
+    features.sort();
+    features.dedup();
+    features
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Eq,
+    Open,
+    Close,
+    Comma,
+}
+
+fn tokenize(predicate: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = predicate.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            ',' => tokens.push(Token::Comma),
+            '=' => tokens.push(Token::Eq),
+            '"' => {
+                let value: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut ident = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_alphanumeric() || next == '_' || next == '-' {
+                        ident.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Ident(ident));
+            }
+        }
+    }
+    tokens
+}
+
+enum Cfg {
+    /// `unix`, or `target_os = "linux"` with the value
+    Name(String, Option<String>),
+    All(Vec<Cfg>),
+    Any(Vec<Cfg>),
+    Not(Box<Cfg>),
+}
+
+fn parse_predicate(tokens: &[Token], pos: &mut usize) -> Option<Cfg> {
+    let Token::Ident(name) = tokens.get(*pos)? else {
+        return None;
+    };
+    *pos += 1;
+    match tokens.get(*pos) {
+        Some(Token::Eq) => {
+            let Some(Token::Str(value)) = tokens.get(*pos + 1) else {
+                return None;
+            };
+            *pos += 2;
+            Some(Cfg::Name(name.clone(), Some(value.clone())))
+        }
+        Some(Token::Open) => {
+            *pos += 1;
+            let mut args = Vec::new();
+            while tokens.get(*pos) != Some(&Token::Close) {
+                args.push(parse_predicate(tokens, pos)?);
+                if tokens.get(*pos) == Some(&Token::Comma) {
+                    *pos += 1;
+                }
+            }
+            *pos += 1;
+            match name.as_str() {
+                "all" => Some(Cfg::All(args)),
+                "any" => Some(Cfg::Any(args)),
+                "not" if args.len() == 1 => Some(Cfg::Not(Box::new(args.pop()?))),
+                _ => None,
+            }
+        }
+        _ => Some(Cfg::Name(name.clone(), None)),
+    }
+}
+
+impl Cfg {
+    fn describe(&self) -> String {
+        let nested = |cfg: &Cfg| match cfg {
+            Cfg::All(args) | Cfg::Any(args) if args.len() > 1 => format!("({})", cfg.describe()),
+            _ => cfg.describe(),
+        };
+        match self {
+            Cfg::Name(key, Some(value)) if key == "feature" => format!("`{value}`"),
+            Cfg::Name(key, Some(value)) => format!("`{key} = \"{value}\"`"),
+            Cfg::Name(key, None) => format!("`{key}`"),
+            Cfg::All(args) => args.iter().map(nested).collect::<Vec<_>>().join(" and "),
+            Cfg::Any(args) => args.iter().map(nested).collect::<Vec<_>>().join(" or "),
+            Cfg::Not(arg) => format!("not {}", nested(arg)),
+        }
+    }
+}
+
+/// A module of the feature map, with its gated items and submodules.
+pub struct FeatureModule<'a> {
+    pub module: &'a IndexedItem,
+    /// Public items other than modules directly in the module.
+    pub item_count: usize,
+    /// Items whose cfg differs from the module's.
+    pub gated_items: Vec<&'a IndexedItem>,
+    pub submodules: Vec<FeatureModule<'a>>,
+}
+
+/// Module tree annotated with `#[doc(cfg)]` requirements (for `feature_map`).
+pub struct FeatureMap<'a> {
+    /// The crate root; `None` if the index has no root module item.
+    pub root: Option<FeatureModule<'a>>,
+    /// Features named in any cfg → number of modules and items gated on them.
+    pub features: BTreeMap<String, usize>,
+}
+
+impl CrateIndex {
+    /// Walk the public module tree from the root, keeping each module's cfg and the
+    /// items whose cfg adds to their module's.
+    pub fn feature_map(&self) -> FeatureMap<'_> {
+        let mut features = BTreeMap::new();
+        for item in self.items.values() {
+            if item.visibility != Visibility::Public || item.is_hidden {
+                continue;
+            }
+            for feature in item.cfg.as_deref().map(cfg_features).unwrap_or_default() {
+                *features.entry(feature).or_default() += 1;
+            }
+        }
+        let root = self
+            .items
+            .get(&self.crate_name)
+            .filter(|item| item.kind == ItemKind::Module)
+            .map(|root| self.feature_module(root, 0));
+        FeatureMap { root, features }
+    }
+
+    fn feature_module<'a>(&'a self, module: &'a IndexedItem, depth: usize) -> FeatureModule<'a> {
+        let children = if module.path == self.crate_name {
+            Some(&self.root_items)
+        } else {
+            self.modules.get(&module.path)
+        };
+        let mut children: Vec<&IndexedItem> = children
+            .into_iter()
+            .flatten()
+            .filter_map(|path| self.items.get(path))
+            .filter(|item| item.visibility == Visibility::Public && !item.is_hidden)
+            .collect();
+        children.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut item_count = 0;
+        let mut gated_items = Vec::new();
+        let mut submodules = Vec::new();
+        for child in children {
+            if child.kind == ItemKind::Module {
+                if depth < MAX_DEPTH {
+                    submodules.push(self.feature_module(child, depth + 1));
+                }
+                continue;
+            }
+            item_count += 1;
+            if child.cfg.is_some() && child.cfg != module.cfg {
+                gated_items.push(child);
+            }
+        }
+        FeatureModule {
+            module,
+            item_count,
+            gated_items,
+            submodules,
+        }
+    }
+}
+
+/// Module nesting the feature map follows, as a guard against cycles.
+const MAX_DEPTH: usize = 16;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== parse_doc_cfg tests ==========
+
+    #[test]
+    fn parse_doc_cfg_takes_the_predicate() {
+        assert_eq!(
+            parse_doc_cfg(r#"#[doc(cfg(feature = "full"))]"#).as_deref(),
+            Some(r#"feature = "full""#)
+        );
+        assert_eq!(parse_doc_cfg("#[doc(hidden)]"), None);
+    }
+
+    // ========== describe_cfg tests ==========
+
+    #[test]
+    fn describe_cfg_names_features_and_combinators() {
+        assert_eq!(describe_cfg(r#"feature = "fs""#), "`fs`");
+        assert_eq!(
+            describe_cfg(r#"all(feature = "rt", any(unix, feature = "net"))"#),
+            "`rt` and (`unix` or `net`)"
+        );
+        assert_eq!(
+            describe_cfg(r#"not(target_os = "wasi")"#),
+            r#"not `target_os = "wasi"`"#
+        );
+        assert_eq!(
+            cfg_features(r#"all(feature = "rt", any(unix, feature = "net"))"#),
+            ["net", "rt"]
+        );
+    }
+}
@@ -0,0 +1,353 @@
+use super::index::{CrateIndex, ImplBlock, IndexedItem, ItemKind, MethodInfo};
+
+/// A builder type for some target type, with its methods sorted by role.
+pub struct BuilderInfo<'a> {
+    pub builder: &'a IndexedItem,
+    /// Methods on the target type that return the builder (e.g. `Widget::builder()`).
+    pub entry_points: Vec<&'a MethodInfo>,
+    /// Methods on the builder that create it without a receiver (e.g. `new`).
+    pub constructors: Vec<&'a MethodInfo>,
+    /// Chainable methods taking the builder and returning it.
+    pub setters: Vec<&'a MethodInfo>,
+    /// Methods taking the builder and returning the target type (e.g. `build`).
+    pub finishers: Vec<&'a MethodInfo>,
+}
+
+/// Every way to obtain a value of some type, ordered roughly by ergonomics.
+pub struct ConstructionGuide<'a> {
+    /// Whether the type implements `Default`.
+    pub default: bool,
+    /// Inherent associated functions returning the type, fewest parameters first.
+    pub constructors: Vec<&'a MethodInfo>,
+    /// `From`/`TryFrom` source types and whether `FromStr` is implemented.
+    pub conversions: Vec<Conversion>,
+    pub builders: Vec<BuilderInfo<'a>>,
+    /// A struct literal, when every field is public and the struct is exhaustive.
+    pub literal: Option<String>,
+    /// Other functions and methods in the crate that return the type.
+    pub producers: Vec<(String, &'a str)>,
+}
+
+/// A conversion trait implementation that produces the type.
+pub struct Conversion {
+    /// "From", "TryFrom", or "FromStr".
+    pub trait_name: String,
+    /// The source type (`str` for `FromStr`).
+    pub source: String,
+}
+
+impl CrateIndex {
+    /// Inherent (non-trait) methods of a type.
+    pub fn inherent_methods(&self, type_path: &str) -> impl Iterator<Item = &MethodInfo> {
+        self.impl_blocks
+            .get(type_path)
+            .into_iter()
+            .flatten()
+            .filter(|block| block.trait_name.is_none())
+            .flat_map(|block| &block.methods)
+    }
+
+    /// Locate builders for `target`: structs named `{Target}Builder`, `*Builder`
+    /// structs with a method returning the target, and types returned by the
+    /// target's own methods that look like builder entry points.
+    pub fn find_builders(&self, target: &IndexedItem) -> Vec<BuilderInfo<'_>> {
+        let target_methods: Vec<&MethodInfo> = self.inherent_methods(&target.path).collect();
+        let mut builders: Vec<BuilderInfo<'_>> = self
+            .items
+            .values()
+            .filter(|item| item.kind == ItemKind::Struct && item.path != target.path)
+            .filter_map(|builder| {
+                let entry_points: Vec<&MethodInfo> = target_methods
+                    .iter()
+                    .copied()
+                    .filter(|m| {
+                        !has_receiver(&m.signature)
+                            && return_type(&m.signature)
+                                .is_some_and(|ty| mentions_type(ty, &builder.name))
+                    })
+                    .collect();
+
+                let mut constructors = Vec::new();
+                let mut setters = Vec::new();
+                let mut finishers = Vec::new();
+                for method in self.inherent_methods(&builder.path) {
+                    let Some(ret) = return_type(&method.signature) else {
+                        continue;
+                    };
+                    let returns_builder =
+                        mentions_type(ret, "Self") || mentions_type(ret, &builder.name);
+                    if mentions_type(ret, &target.name) && has_receiver(&method.signature) {
+                        finishers.push(method);
+                    } else if returns_builder && has_receiver(&method.signature) {
+                        setters.push(method);
+                    } else if returns_builder {
+                        constructors.push(method);
+                    }
+                }
+
+                let named_builder = builder.name.ends_with("Builder");
+                let is_builder = builder.name == format!("{}Builder", target.name)
+                    || (named_builder && !finishers.is_empty())
+                    || !entry_points.is_empty();
+                is_builder.then_some(BuilderInfo {
+                    builder,
+                    entry_points,
+                    constructors,
+                    setters,
+                    finishers,
+                })
+            })
+            .collect();
+        builders.sort_by(|a, b| a.builder.path.cmp(&b.builder.path));
+        builders
+    }
+}
+
+impl CrateIndex {
+    /// Gather constructors, `Default`, conversions, builders, struct literals, and
+    /// other producers of `target` into one guide.
+    pub fn construction_guide<'a>(&'a self, target: &'a IndexedItem) -> ConstructionGuide<'a> {
+        let returns_target =
+            |ty: &str| mentions_type(ty, "Self") || mentions_type(ty, &target.name);
+
+        let mut constructors: Vec<&MethodInfo> = self
+            .inherent_methods(&target.path)
+            .filter(|m| {
+                !has_receiver(&m.signature) && return_type(&m.signature).is_some_and(returns_target)
+            })
+            .collect();
+        constructors.sort_by_key(|m| param_count(&m.signature));
+
+        let trait_impls: Vec<&ImplBlock> = self
+            .impl_blocks
+            .get(&target.path)
+            .into_iter()
+            .flatten()
+            .filter(|block| block.trait_name.is_some())
+            .collect();
+        let default = trait_impls
+            .iter()
+            .any(|block| block.trait_name.as_deref() == Some("Default"));
+
+        let mut conversions = Vec::new();
+        for block in &trait_impls {
+            let trait_name = block.trait_name.as_deref().unwrap_or_default();
+            match trait_name {
+                "From" | "TryFrom" => {
+                    for m in &block.methods {
+                        if let Some(source) = first_param_type(&m.signature)
+                            && return_type(&m.signature).is_some_and(returns_target)
+                        {
+                            conversions.push(Conversion {
+                                trait_name: trait_name.to_string(),
+                                source: source.to_string(),
+                            });
+                        }
+                    }
+                }
+                "FromStr" => conversions.push(Conversion {
+                    trait_name: trait_name.to_string(),
+                    source: "str".to_string(),
+                }),
+                _ => {}
+            }
+        }
+        conversions.sort_by(|a, b| {
+            a.trait_name
+                .cmp(&b.trait_name)
+                .then_with(|| a.source.cmp(&b.source))
+        });
+
+        let builders = self.find_builders(target);
+        let mut producers: Vec<(String, &str)> = Vec::new();
+        for item in self.items.values() {
+            if item.kind == ItemKind::Function
+                && return_type(&item.signature).is_some_and(|ty| mentions_type(ty, &target.name))
+            {
+                producers.push((item.path.clone(), item.signature.as_str()));
+            }
+        }
+        for (type_path, blocks) in &self.impl_blocks {
+            let is_builder = builders.iter().any(|b| &b.builder.path == type_path);
+            if type_path == &target.path || is_builder {
+                continue;
+            }
+            for m in blocks.iter().flat_map(|block| &block.methods) {
+                if return_type(&m.signature).is_some_and(|ty| mentions_type(ty, &target.name)) {
+                    producers.push((format!("{type_path}::{}", m.name), m.signature.as_str()));
+                }
+            }
+        }
+        producers.sort();
+        producers.dedup();
+
+        ConstructionGuide {
+            default,
+            constructors,
+            conversions,
+            builders,
+            literal: struct_literal(target),
+            producers,
+        }
+    }
+}
+
+/// A struct literal for `item` with placeholder field values, if it can be
+/// written outside the crate (exhaustive, no private fields).
+fn struct_literal(item: &IndexedItem) -> Option<String> {
+    let exhaustive = !item.attrs.iter().any(|a| a.starts_with("#[non_exhaustive"));
+    if item.kind != ItemKind::Struct || !exhaustive || item.signature.contains("private fields") {
+        return None;
+    }
+    let fields = &item.detail.fields;
+    if fields.is_empty() {
+        return item.signature.ends_with(';').then(|| item.name.clone());
+    }
+    let tuple = fields.iter().all(|f| f.name.parse::<usize>().is_ok());
+    let values: Vec<String> = fields
+        .iter()
+        .map(|f| {
+            if tuple {
+                format!("/* {} */", f.type_str)
+            } else {
+                format!("{}: /* {} */", f.name, f.type_str)
+            }
+        })
+        .collect();
+    Some(if tuple {
+        format!("{}({})", item.name, values.join(", "))
+    } else {
+        format!("{} {{ {} }}", item.name, values.join(", "))
+    })
+}
+
+/// Number of parameters in a rendered function signature.
+pub fn param_count(signature: &str) -> usize {
+    let Some((_, rest)) = signature.split_once('(') else {
+        return 0;
+    };
+    let mut depth = 0usize;
+    let mut count = 0;
+    let mut any = false;
+    for c in rest.chars() {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            ')' if depth == 0 => break,
+            ')' | '>' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => count += 1,
+            c if !c.is_whitespace() => any = true,
+            _ => {}
+        }
+    }
+    if any { count + 1 } else { 0 }
+}
+
+/// The type of the first parameter of a rendered function signature
+/// (e.g. `String` for `fn from(s: String) -> Self`).
+fn first_param_type(signature: &str) -> Option<&str> {
+    let (_, rest) = signature.split_once('(')?;
+    let (_, rest) = rest.split_once(':')?;
+    let mut depth = 0usize;
+    let mut end = rest.len();
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' | '<' | '[' => depth += 1,
+            ')' | ',' if depth == 0 => {
+                end = i;
+                break;
+            }
+            ')' | '>' | ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    let ty = rest[..end].trim();
+    (!ty.is_empty()).then_some(ty)
+}
+
+/// The return type of a rendered function signature, without any where clause.
+pub fn return_type(signature: &str) -> Option<&str> {
+    let (_, ret) = signature.rsplit_once(" -> ")?;
+    let ret = ret.split("\nwhere").next().unwrap_or(ret);
+    let ret = ret.split(" where ").next().unwrap_or(ret);
+    Some(ret.trim())
+}
+
+/// Whether a rendered function signature takes `self` in any form
+/// (`self: Self`, `&self`, `&'a mut self`, ...).
+pub fn has_receiver(signature: &str) -> bool {
+    let Some((_, params)) = signature.split_once('(') else {
+        return false;
+    };
+    let first = params.split([',', ')']).next().unwrap_or("");
+    let first = first.split(':').next().unwrap_or(first);
+    first
+        .split_whitespace()
+        .map(|token| token.trim_start_matches('&'))
+        .filter(|token| !token.starts_with('\'') && *token != "mut")
+        .eq(["self"])
+}
+
+/// Whether `ty` refers to a type called `name` (as a whole path segment).
+pub fn mentions_type(ty: &str, name: &str) -> bool {
+    ty.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .any(|token| token == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== signature helper tests ==========
+
+    #[test]
+    fn return_type_strips_where_clause() {
+        assert_eq!(
+            return_type("pub fn build(self: Self) -> Result<Widget, Error>"),
+            Some("Result<Widget, Error>")
+        );
+        assert_eq!(
+            return_type("pub fn new<T>(t: T) -> Self\nwhere\n    T: Into<u32>"),
+            Some("Self")
+        );
+        assert_eq!(return_type("pub fn run(self: &Self)"), None);
+    }
+
+    #[test]
+    fn has_receiver_detects_self_params() {
+        assert!(has_receiver("pub fn size(self: Self, s: u32) -> Self"));
+        assert!(has_receiver("pub fn size(&mut self, s: u32) -> &mut Self"));
+        assert!(has_receiver("pub fn get<'a>(&'a self) -> &'a u32"));
+        assert!(!has_receiver("pub fn new(selfish: u32) -> Self"));
+        assert!(!has_receiver("pub fn builder() -> WidgetBuilder"));
+    }
+
+    #[test]
+    fn param_count_ignores_nested_commas() {
+        assert_eq!(param_count("pub fn new() -> Self"), 0);
+        assert_eq!(param_count("pub fn new(a: u32) -> Self"), 1);
+        assert_eq!(
+            param_count("pub fn with(map: HashMap<String, u32>, f: fn(u8, u8)) -> Self"),
+            2
+        );
+    }
+
+    #[test]
+    fn first_param_type_reads_conversion_source() {
+        assert_eq!(
+            first_param_type("fn from(s: String) -> Self"),
+            Some("String")
+        );
+        assert_eq!(
+            first_param_type("fn from(v: Vec<(u8, u8)>) -> Self"),
+            Some("Vec<(u8, u8)>")
+        );
+        assert_eq!(first_param_type("fn new() -> Self"), None);
+    }
+
+    #[test]
+    fn mentions_type_matches_whole_segments() {
+        assert!(mentions_type("Result<Widget, Error>", "Widget"));
+        assert!(!mentions_type("WidgetBuilder", "Widget"));
+        assert!(mentions_type("&mut Self", "Self"));
+    }
+}
@@ -0,0 +1,210 @@
+use std::path::{Path, PathBuf};
+
+use cargo_lock::Version;
+
+use super::fetcher::{decode_json_bytes, decode_raw_bytes};
+use crate::error::Error;
+
+/// A directory of pre-generated rustdoc JSON (`--corpus-dir`), consulted before docs.rs.
+///
+/// Files are named `{crate_name}-{version}.json` or `{crate_name}-{version}.json.zst`
+/// (e.g. `serde_json-1.0.128.json.zst`). The directory is listed once at startup;
+/// files are read when a crate is first looked up.
+#[derive(Debug)]
+pub struct Corpus {
+    dir: PathBuf,
+    entries: Vec<CorpusEntry>,
+}
+
+/// One crate version's rustdoc JSON file in the corpus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusEntry {
+    /// Crate name as written in the file name.
+    pub crate_name: String,
+    pub version: String,
+    pub path: PathBuf,
+    /// Whether the file is zstd-compressed (`.json.zst`).
+    pub compressed: bool,
+}
+
+impl Corpus {
+    /// List the rustdoc JSON files in `dir`. Files not named `{crate}-{version}.json[.zst]`
+    /// are skipped. Fails if the directory can't be read.
+    pub fn open(dir: &Path) -> std::io::Result<Self> {
+        let mut entries: Vec<CorpusEntry> = std::fs::read_dir(dir)?
+            .flatten()
+            .filter_map(|file| {
+                let file_name = file.file_name().to_string_lossy().into_owned();
+                let entry = parse_file_name(&file_name, file.path());
+                if entry.is_none() {
+                    tracing::debug!("Skipping {file_name} in the corpus: not name-version.json");
+                }
+                entry
+            })
+            .collect();
+        entries.sort_by(|a, b| (&a.crate_name, &a.version).cmp(&(&b.crate_name, &b.version)));
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            entries,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn entries(&self) -> &[CorpusEntry] {
+        &self.entries
+    }
+
+    /// The file for a crate version: the newest version for "latest", otherwise the
+    /// newest one matching `version` segment by segment ("0.8" matches 0.8.5, and a full
+    /// version only itself). Hyphens and underscores in names are interchangeable.
+    pub fn find(&self, crate_name: &str, version: &str) -> Option<&CorpusEntry> {
+        let wanted_name = crate_name.replace('-', "_");
+        let wanted: Vec<&str> = version.split('.').collect();
+        self.entries
+            .iter()
+            .filter(|entry| entry.crate_name.replace('-', "_") == wanted_name)
+            .filter(|entry| {
+                let segments: Vec<&str> = entry.version.split(['.', '-', '+']).collect();
+                version == "latest"
+                    || entry.version == version
+                    || (segments.len() >= wanted.len() && segments[..wanted.len()] == wanted[..])
+            })
+            .max_by(|a, b| match (a.semver(), b.semver()) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                _ => a.version.cmp(&b.version),
+            })
+    }
+}
+
+impl CorpusEntry {
+    fn semver(&self) -> Option<Version> {
+        Version::parse(&self.version).ok()
+    }
+
+    /// Read and decode the file. Blocking.
+    pub fn load(&self) -> Result<rustdoc_types::Crate, Error> {
+        let bytes = std::fs::read(&self.path)
+            .map_err(|e| Error::Other(format!("Failed to read {}: {e}", self.path.display())))?;
+        if self.compressed {
+            decode_raw_bytes(&bytes, &self.crate_name, &self.version)
+        } else {
+            decode_json_bytes(&bytes, &self.crate_name, &self.version)
+        }
+    }
+}
+
+/// Split `{crate_name}-{version}.json[.zst]` at the first hyphen followed by a valid
+/// semver version, so hyphenated names (`tokio-util-0.7.12.json`) and pre-releases
+/// (`foo-1.0.0-beta.1.json`) both work.
+fn parse_file_name(file_name: &str, path: PathBuf) -> Option<CorpusEntry> {
+    let (stem, compressed) = match file_name.strip_suffix(".json.zst") {
+        Some(stem) => (stem, true),
+        None => (file_name.strip_suffix(".json")?, false),
+    };
+    let (split, _) = stem
+        .match_indices('-')
+        .find(|&(i, _)| Version::parse(&stem[i + 1..]).is_ok())?;
+    Some(CorpusEntry {
+        crate_name: stem[..split].to_string(),
+        version: stem[split + 1..].to_string(),
+        path,
+        compressed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(file_name: &str) -> Option<CorpusEntry> {
+        parse_file_name(file_name, PathBuf::from(file_name))
+    }
+
+    // ========== parse_file_name tests ==========
+
+    #[test]
+    fn parse_file_name_splits_name_and_version() {
+        let parsed = entry("serde_json-1.0.128.json.zst").unwrap();
+        assert_eq!(parsed.crate_name, "serde_json");
+        assert_eq!(parsed.version, "1.0.128");
+        assert!(parsed.compressed);
+
+        let parsed = entry("tokio-util-0.7.12.json").unwrap();
+        assert_eq!(parsed.crate_name, "tokio-util");
+        assert_eq!(parsed.version, "0.7.12");
+        assert!(!parsed.compressed);
+
+        let parsed = entry("foo-1.0.0-beta.1.json").unwrap();
+        assert_eq!(parsed.crate_name, "foo");
+        assert_eq!(parsed.version, "1.0.0-beta.1");
+    }
+
+    #[test]
+    fn parse_file_name_skips_other_files() {
+        assert_eq!(entry("serde.json"), None);
+        assert_eq!(entry("serde-latest.json"), None);
+        assert_eq!(entry("serde-1.0.0.json.gz"), None);
+        assert_eq!(entry("README.md"), None);
+    }
+
+    // ========== Corpus tests ==========
+
+    #[test]
+    fn find_picks_exact_prefix_and_latest_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in [
+            "rand-0.8.5.json",
+            "rand-0.9.0.json.zst",
+            "rand-0.10.0-alpha.1.json",
+            "tokio-util-0.7.12.json",
+            "notes.txt",
+        ] {
+            std::fs::write(dir.path().join(name), "{}").unwrap();
+        }
+        let corpus = Corpus::open(dir.path()).unwrap();
+        assert_eq!(corpus.entries().len(), 4);
+
+        let version = |name: &str, version: &str| {
+            corpus
+                .find(name, version)
+                .map(|entry| entry.version.as_str())
+        };
+        assert_eq!(version("rand", "0.8.5"), Some("0.8.5"));
+        assert_eq!(version("rand", "0.9"), Some("0.9.0"));
+        assert_eq!(version("rand", "latest"), Some("0.10.0-alpha.1"));
+        assert_eq!(version("rand", "0.7"), None);
+        assert_eq!(version("tokio_util", "latest"), Some("0.7.12"));
+        assert_eq!(version("serde", "latest"), None);
+    }
+
+    #[test]
+    fn load_decodes_plain_and_zstd_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = serde_json::json!({
+            "root": 0,
+            "crate_version": "1.0.0",
+            "includes_private": false,
+            "index": {},
+            "paths": {},
+            "external_crates": {},
+            "target": { "triple": "x86_64-unknown-linux-gnu", "target_features": [] },
+            "format_version": 56
+        })
+        .to_string();
+        std::fs::write(dir.path().join("plain-1.0.0.json"), &json).unwrap();
+        std::fs::write(
+            dir.path().join("packed-1.0.0.json.zst"),
+            zstd::encode_all(json.as_bytes(), 3).unwrap(),
+        )
+        .unwrap();
+        let corpus = Corpus::open(dir.path()).unwrap();
+
+        for name in ["plain", "packed"] {
+            let krate = corpus.find(name, "1.0.0").unwrap().load().unwrap();
+            assert_eq!(krate.crate_version.as_deref(), Some("1.0.0"));
+        }
+    }
+}
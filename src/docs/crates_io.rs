@@ -0,0 +1,452 @@
+use std::collections::{BTreeMap, HashMap};
+
+use cargo_lock::Version;
+use serde::Deserialize;
+
+use super::suggest::levenshtein;
+use crate::error::Error;
+use crate::http::HttpClient;
+
+const API_BASE: &str = "https://crates.io/api/v1";
+
+/// A crate as listed by the crates.io search API.
+#[derive(Debug, Deserialize)]
+pub struct CrateSummary {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub max_version: String,
+    #[serde(default)]
+    pub max_stable_version: Option<String>,
+    pub downloads: u64,
+    #[serde(default)]
+    pub recent_downloads: Option<u64>,
+    /// RFC 3339 timestamp of the most recent publish.
+    pub updated_at: String,
+    #[serde(default)]
+    pub repository: Option<String>,
+}
+
+impl CrateSummary {
+    /// The version most users would depend on: the newest stable release if any.
+    pub fn version(&self) -> &str {
+        self.max_stable_version
+            .as_deref()
+            .unwrap_or(&self.max_version)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    crates: Vec<CrateSummary>,
+    meta: SearchMeta,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchMeta {
+    total: u64,
+}
+
+/// Search crates.io by keyword, ordered by relevance.
+///
+/// Returns up to `limit` crates and the total number of matches.
+pub async fn search_crates(
+    client: &HttpClient,
+    query: &str,
+    limit: usize,
+) -> Result<(Vec<CrateSummary>, u64), Error> {
+    let url = reqwest::Url::parse_with_params(
+        &format!("{API_BASE}/crates"),
+        &[("q", query), ("per_page", &limit.to_string())],
+    )
+    .map_err(|e| Error::Other(format!("Invalid crates.io search URL: {e}")))?;
+    tracing::info!("Searching crates.io: {url}");
+
+    let _slot = client.slot(url.as_str()).await?;
+    let response = client.get(url).send().await?.error_for_status()?;
+    let body: SearchResponse = serde_json::from_slice(&response.bytes().await?)?;
+    Ok((body.crates, body.meta.total))
+}
+
+/// `name` with hyphens and underscores swapped (crates.io treats them as equal, docs.rs
+/// URLs don't always), or `None` if it has neither.
+pub fn swap_separators(name: &str) -> Option<String> {
+    if !name.contains(['-', '_']) {
+        return None;
+    }
+    Some(
+        name.chars()
+            .map(|c| match c {
+                '-' => '_',
+                '_' => '-',
+                c => c,
+            })
+            .collect(),
+    )
+}
+
+/// The search result whose name is closest to `name` (ignoring case and `-`/`_`),
+/// if it is close enough to be a likely typo.
+pub fn closest_crate_name<'a>(crates: &'a [CrateSummary], name: &str) -> Option<&'a str> {
+    let fold = |s: &str| s.to_lowercase().replace('-', "_");
+    let wanted = fold(name);
+    let max_distance = (wanted.chars().count() / 3).max(2);
+    crates
+        .iter()
+        .map(|krate| {
+            (
+                levenshtein(&fold(&krate.name), &wanted),
+                krate.name.as_str(),
+            )
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+/// A crate that depends on the queried crate.
+#[derive(Debug)]
+pub struct Dependent {
+    pub name: String,
+    /// The dependent's most recent version that declares the dependency.
+    pub version: String,
+    /// The version requirement it declares (e.g. `^1.0`).
+    pub req: String,
+    /// "normal", "dev", or "build".
+    pub kind: String,
+    pub optional: bool,
+    /// Total downloads of the dependent crate.
+    pub downloads: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReverseDependenciesResponse {
+    dependencies: Vec<DependencyEntry>,
+    versions: Vec<VersionEntry>,
+    meta: SearchMeta,
+}
+
+#[derive(Debug, Deserialize)]
+struct DependencyEntry {
+    version_id: u64,
+    req: String,
+    #[serde(default)]
+    kind: Option<String>,
+    #[serde(default)]
+    optional: bool,
+    #[serde(default)]
+    downloads: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionEntry {
+    id: u64,
+    #[serde(rename = "crate")]
+    crate_name: String,
+    num: String,
+}
+
+impl ReverseDependenciesResponse {
+    /// Join each dependency with the dependent version it belongs to.
+    fn into_dependents(self) -> Vec<Dependent> {
+        let versions: HashMap<u64, VersionEntry> =
+            self.versions.into_iter().map(|v| (v.id, v)).collect();
+        self.dependencies
+            .into_iter()
+            .filter_map(|dep| {
+                let version = versions.get(&dep.version_id)?;
+                Some(Dependent {
+                    name: version.crate_name.clone(),
+                    version: version.num.clone(),
+                    req: dep.req,
+                    kind: dep.kind.unwrap_or_else(|| "normal".to_string()),
+                    optional: dep.optional,
+                    downloads: dep.downloads,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Fetch the most downloaded crates depending on `crate_name`.
+///
+/// Returns up to `limit` dependents and the total number of dependents.
+pub async fn reverse_dependencies(
+    client: &HttpClient,
+    crate_name: &str,
+    limit: usize,
+) -> Result<(Vec<Dependent>, u64), Error> {
+    let url = reqwest::Url::parse_with_params(
+        &format!("{API_BASE}/crates/{crate_name}/reverse_dependencies"),
+        &[("per_page", limit.to_string())],
+    )
+    .map_err(|e| Error::Other(format!("Invalid crates.io URL: {e}")))?;
+    tracing::info!("Fetching reverse dependencies: {url}");
+
+    let _slot = client.slot(url.as_str()).await?;
+    let response = client.get(url).send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(Error::CrateNotFound(crate_name.to_string()));
+    }
+    let body: ReverseDependenciesResponse =
+        serde_json::from_slice(&response.error_for_status()?.bytes().await?)?;
+    let total = body.meta.total;
+    Ok((body.into_dependents(), total))
+}
+
+/// A published version of a crate.
+#[derive(Debug, Deserialize)]
+pub struct VersionInfo {
+    pub num: String,
+    pub yanked: bool,
+    /// RFC 3339 timestamp of the publish.
+    pub created_at: String,
+    /// The `rust-version` (MSRV) declared in the version's manifest.
+    #[serde(default)]
+    pub rust_version: Option<String>,
+    /// Feature name → features and optional dependencies it enables.
+    #[serde(default)]
+    pub features: BTreeMap<String, Vec<String>>,
+    /// Whether the version has a library target (not reported for old versions).
+    #[serde(default)]
+    pub has_lib: Option<bool>,
+    /// Names of the version's binary targets.
+    #[serde(default)]
+    pub bin_names: Vec<String>,
+}
+
+impl VersionInfo {
+    fn semver(&self) -> Option<Version> {
+        Version::parse(&self.num).ok()
+    }
+
+    /// True if crates.io knows the version has no library target, so there are no API docs.
+    pub fn is_binary_only(&self) -> bool {
+        self.has_lib == Some(false)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionsResponse {
+    versions: Vec<VersionInfo>,
+}
+
+/// Fetch the most recent versions of a crate (up to 100), newest first by semver.
+pub async fn crate_versions(
+    client: &HttpClient,
+    crate_name: &str,
+) -> Result<Vec<VersionInfo>, Error> {
+    let url = reqwest::Url::parse_with_params(
+        &format!("{API_BASE}/crates/{crate_name}/versions"),
+        &[("per_page", "100"), ("sort", "semver")],
+    )
+    .map_err(|e| Error::Other(format!("Invalid crates.io URL: {e}")))?;
+    tracing::info!("Fetching versions: {url}");
+
+    let _slot = client.slot(url.as_str()).await?;
+    let response = client.get(url).send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(Error::CrateNotFound(crate_name.to_string()));
+    }
+    let body: VersionsResponse =
+        serde_json::from_slice(&response.error_for_status()?.bytes().await?)?;
+    Ok(body.versions)
+}
+
+/// Find `version` among `versions`, treating "latest" as the newest non-yanked
+/// stable release.
+pub fn find_version<'a>(versions: &'a [VersionInfo], version: &str) -> Option<&'a VersionInfo> {
+    if version != "latest" {
+        return versions.iter().find(|v| v.num == version);
+    }
+    versions
+        .iter()
+        .filter(|v| !v.yanked)
+        .filter_map(|v| Some((v.semver()?, v)))
+        .filter(|(semver, _)| semver.pre.is_empty())
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, v)| v)
+}
+
+/// Versions newer than `pinned`, newest first.
+pub fn newer_versions<'a>(versions: &'a [VersionInfo], pinned: &Version) -> Vec<&'a VersionInfo> {
+    let mut newer: Vec<(Version, &VersionInfo)> = versions
+        .iter()
+        .filter_map(|v| Some((v.semver()?, v)))
+        .filter(|(version, _)| version > pinned)
+        .collect();
+    newer.sort_by(|a, b| b.0.cmp(&a.0));
+    newer.into_iter().map(|(_, v)| v).collect()
+}
+
+/// The version to upgrade to from `pinned`: the newest non-yanked release,
+/// considering pre-releases only when already on one.
+pub fn upgrade_target<'a>(newer: &[&'a VersionInfo], pinned: &Version) -> Option<&'a VersionInfo> {
+    newer.iter().copied().find(|v| {
+        !v.yanked && (!pinned.pre.is_empty() || v.semver().is_some_and(|s| s.pre.is_empty()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== search response tests ==========
+
+    #[test]
+    fn search_response_parses_crates_io_payload() {
+        let body = r#"{
+            "crates": [{
+                "name": "reqwest",
+                "description": "higher level HTTP client library",
+                "max_version": "0.13.0-rc.1",
+                "max_stable_version": "0.12.9",
+                "downloads": 250000000,
+                "recent_downloads": 30000000,
+                "updated_at": "2024-11-12T10:20:30.123456+00:00",
+                "repository": "https://github.com/seanmonstar/reqwest",
+                "exact_match": false
+            }, {
+                "name": "tiny-http-client",
+                "description": null,
+                "max_version": "0.1.0",
+                "max_stable_version": null,
+                "downloads": 12,
+                "recent_downloads": null,
+                "updated_at": "2019-01-01T00:00:00+00:00"
+            }],
+            "meta": { "total": 1234, "next_page": "?page=2" }
+        }"#;
+        let response: SearchResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(response.meta.total, 1234);
+        assert_eq!(response.crates.len(), 2);
+        assert_eq!(response.crates[0].version(), "0.12.9");
+        assert_eq!(response.crates[1].version(), "0.1.0");
+        assert_eq!(response.crates[1].description, None);
+    }
+
+    // ========== reverse dependency tests ==========
+
+    #[test]
+    fn reverse_dependencies_join_versions() {
+        let body = r#"{
+            "dependencies": [
+                {"id": 1, "version_id": 10, "crate_id": "serde", "req": "^1.0",
+                 "optional": false, "kind": "normal", "downloads": 5000},
+                {"id": 2, "version_id": 20, "crate_id": "serde", "req": "^1",
+                 "optional": true, "kind": "dev", "downloads": 40},
+                {"id": 3, "version_id": 99, "crate_id": "serde", "req": "*",
+                 "optional": false, "kind": "normal", "downloads": 1}
+            ],
+            "versions": [
+                {"id": 10, "crate": "serde_json", "num": "1.0.100"},
+                {"id": 20, "crate": "toml", "num": "0.8.0"}
+            ],
+            "meta": { "total": 3 }
+        }"#;
+        let response: ReverseDependenciesResponse = serde_json::from_str(body).unwrap();
+        let dependents = response.into_dependents();
+        assert_eq!(dependents.len(), 2);
+        assert_eq!(dependents[0].name, "serde_json");
+        assert_eq!(dependents[0].version, "1.0.100");
+        assert_eq!(dependents[0].downloads, 5000);
+        assert_eq!(dependents[1].kind, "dev");
+        assert!(dependents[1].optional);
+    }
+
+    // ========== crate name tests ==========
+
+    #[test]
+    fn swap_separators_swaps_hyphens_and_underscores() {
+        assert_eq!(swap_separators("serde-json").as_deref(), Some("serde_json"));
+        assert_eq!(swap_separators("tokio_util").as_deref(), Some("tokio-util"));
+        assert_eq!(swap_separators("tokio"), None);
+    }
+
+    #[test]
+    fn closest_crate_name_picks_likely_typo() {
+        let json = r#"{"crates": [
+            {"name": "serde", "max_version": "1.0.0", "downloads": 1, "updated_at": ""},
+            {"name": "serde_json", "max_version": "1.0.0", "downloads": 1, "updated_at": ""},
+            {"name": "simd-json", "max_version": "0.1.0", "downloads": 1, "updated_at": ""}
+        ], "meta": {"total": 3}}"#;
+        let body: SearchResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            closest_crate_name(&body.crates, "serde-jsno"),
+            Some("serde_json")
+        );
+        assert_eq!(closest_crate_name(&body.crates, "reqwest"), None);
+    }
+
+    // ========== version tests ==========
+
+    fn version(num: &str, yanked: bool) -> VersionInfo {
+        VersionInfo {
+            num: num.to_string(),
+            yanked,
+            created_at: "2024-01-01T00:00:00+00:00".to_string(),
+            rust_version: None,
+            features: BTreeMap::new(),
+            has_lib: None,
+            bin_names: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn versions_response_reports_binary_only_crates() {
+        let json = r#"{"versions": [
+            {"num": "14.1.1", "yanked": false, "created_at": "2024-09-08T00:00:00Z",
+             "has_lib": false, "bin_names": ["rg"]},
+            {"num": "0.1.0", "yanked": false, "created_at": "2016-09-23T00:00:00Z"}
+        ]}"#;
+        let body: VersionsResponse = serde_json::from_str(json).unwrap();
+        assert!(body.versions[0].is_binary_only());
+        assert_eq!(body.versions[0].bin_names, ["rg"]);
+        assert!(!body.versions[1].is_binary_only());
+    }
+
+    #[test]
+    fn newer_versions_sorts_by_semver() {
+        let versions = [
+            version("1.0.9", false),
+            version("1.0.10", false),
+            version("0.9.0", false),
+            version("1.0.2", false),
+        ];
+        let pinned = Version::parse("1.0.2").unwrap();
+        let newer: Vec<&str> = newer_versions(&versions, &pinned)
+            .iter()
+            .map(|v| v.num.as_str())
+            .collect();
+        assert_eq!(newer, ["1.0.10", "1.0.9"]);
+    }
+
+    #[test]
+    fn find_version_resolves_latest_to_newest_stable() {
+        let versions = [
+            version("2.0.0-rc.1", false),
+            version("1.2.0", true),
+            version("1.1.0", false),
+        ];
+        assert_eq!(find_version(&versions, "latest").unwrap().num, "1.1.0");
+        assert_eq!(find_version(&versions, "1.2.0").unwrap().num, "1.2.0");
+        assert!(find_version(&versions, "0.1.0").is_none());
+    }
+
+    #[test]
+    fn upgrade_target_skips_yanked_and_prereleases() {
+        let versions = [
+            version("2.0.0-rc.1", false),
+            version("1.2.0", true),
+            version("1.1.0", false),
+            version("1.0.0", false),
+        ];
+        let pinned = Version::parse("1.0.0").unwrap();
+        let newer = newer_versions(&versions, &pinned);
+        assert_eq!(upgrade_target(&newer, &pinned).unwrap().num, "1.1.0");
+
+        let pinned = Version::parse("2.0.0-beta.1").unwrap();
+        let newer = newer_versions(&versions, &pinned);
+        assert_eq!(upgrade_target(&newer, &pinned).unwrap().num, "2.0.0-rc.1");
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+
+use super::index::{CrateIndex, ItemKind, Visibility};
+
+/// A public API entry that differs between two versions of a crate.
+pub struct ApiChange {
+    /// Path relative to the crate root (e.g. "sync::Mutex::lock").
+    pub path: String,
+    /// Item kind label ("struct", "fn", ...), or "method" for inherent methods.
+    pub kind: String,
+    /// Signature in the older version, if the entry exists there.
+    pub old: Option<String>,
+    /// Signature in the newer version, if the entry exists there.
+    pub new: Option<String>,
+}
+
+/// Public API differences between two versions of a crate, each sorted by path.
+pub struct ApiDiff {
+    pub added: Vec<ApiChange>,
+    pub removed: Vec<ApiChange>,
+    pub changed: Vec<ApiChange>,
+}
+
+impl ApiDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl CrateIndex {
+    /// Compare this (older) version's public API with `newer`.
+    ///
+    /// Items are compared by crate-relative path and rendered signature, so a
+    /// struct gaining a public field or a function changing a parameter type
+    /// shows up as changed. Inherent methods are compared individually; trait
+    /// impl methods are left out since the trait dictates their signatures.
+    pub fn diff(&self, newer: &CrateIndex) -> ApiDiff {
+        let old_surface = self.api_surface();
+        let new_surface = newer.api_surface();
+
+        let mut diff = ApiDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+        for (path, (kind, old_sig)) in &old_surface {
+            match new_surface.get(path) {
+                None => diff.removed.push(ApiChange {
+                    path: path.clone(),
+                    kind: kind.clone(),
+                    old: Some(old_sig.clone()),
+                    new: None,
+                }),
+                Some((_, new_sig)) if new_sig != old_sig => diff.changed.push(ApiChange {
+                    path: path.clone(),
+                    kind: kind.clone(),
+                    old: Some(old_sig.clone()),
+                    new: Some(new_sig.clone()),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (path, (kind, new_sig)) in &new_surface {
+            if !old_surface.contains_key(path) {
+                diff.added.push(ApiChange {
+                    path: path.clone(),
+                    kind: kind.clone(),
+                    old: None,
+                    new: Some(new_sig.clone()),
+                });
+            }
+        }
+        diff
+    }
+
+    /// Public items and inherent methods: crate-relative path → (kind label, signature).
+    fn api_surface(&self) -> BTreeMap<String, (String, String)> {
+        let relative = |path: &str| -> String {
+            path.strip_prefix(&self.crate_name)
+                .and_then(|rest| rest.strip_prefix("::"))
+                .unwrap_or(path)
+                .to_string()
+        };
+
+        let mut surface = BTreeMap::new();
+        for item in self.items.values() {
+            if item.visibility != Visibility::Public {
+                continue;
+            }
+            surface.insert(
+                relative(&item.path),
+                (item.kind.to_string(), item.signature.clone()),
+            );
+            if matches!(
+                item.kind,
+                ItemKind::Struct | ItemKind::Enum | ItemKind::Union
+            ) {
+                for method in self.inherent_methods(&item.path) {
+                    surface.insert(
+                        format!("{}::{}", relative(&item.path), method.name),
+                        ("method".to_string(), method.signature.clone()),
+                    );
+                }
+            }
+        }
+        surface
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::docs::index::{ImplBlock, IndexedItem, ItemDetail, MethodInfo};
+    use crate::docs::suggest::NameIndex;
+
+    fn test_index(items: &[(&str, ItemKind, &str)], methods: &[(&str, &str)]) -> CrateIndex {
+        let items: HashMap<String, IndexedItem> = items
+            .iter()
+            .map(|(path, kind, signature)| {
+                let (parent, name) = path.rsplit_once("::").unwrap();
+                let item = IndexedItem {
+                    path: path.to_string(),
+                    name: name.to_string(),
+                    kind: kind.clone(),
+                    signature: signature.to_string(),
+                    attrs: Vec::new(),
+                    visibility: Visibility::Public,
+                    aliases: Vec::new(),
+                    reexports: Vec::new(),
+                    short_doc: String::new(),
+                    doc: String::new(),
+                    deprecation: None,
+                    stability: None,
+                    cfg: None,
+                    is_unsafe: false,
+                    async_kind: None,
+                    is_hidden: false,
+                    span: None,
+                    headings: Vec::new(),
+                    external_refs: Vec::new(),
+                    detail: ItemDetail::default(),
+                    parent_module: parent.to_string(),
+                };
+                (path.to_string(), item)
+            })
+            .collect();
+        let mut impl_blocks: HashMap<String, Vec<ImplBlock>> = HashMap::new();
+        for (type_path, signature) in methods {
+            let name = signature
+                .trim_start_matches("pub fn ")
+                .split('(')
+                .next()
+                .unwrap();
+            impl_blocks
+                .entry(type_path.to_string())
+                .or_default()
+                .push(ImplBlock {
+                    header: String::new(),
+                    trait_name: None,
+                    trait_path: None,
+                    provided_methods: Vec::new(),
+                    methods: vec![MethodInfo {
+                        name: name.to_string(),
+                        signature: signature.to_string(),
+                        doc: String::new(),
+                        is_required: false,
+                        is_unsafe: false,
+                        async_kind: None,
+                        is_hidden: false,
+                        deprecation: None,
+                        stability: None,
+                        span: None,
+                    }],
+                });
+        }
+        CrateIndex {
+            crate_name: "my_crate".to_string(),
+            package_name: "my_crate".to_string(),
+            version: "1.0.0".to_string(),
+            items,
+            modules: HashMap::new(),
+            impl_blocks,
+            auto_traits: HashMap::new(),
+            root_items: Vec::new(),
+            aliases: HashMap::new(),
+            foreign_reexports: HashMap::new(),
+            name_index: NameIndex::default(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            format_version: 56,
+            parse_time: Duration::ZERO,
+            partial: false,
+            stale: None,
+            fetched_at: None,
+        }
+    }
+
+    // ========== diff tests ==========
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_items() {
+        let old = test_index(
+            &[
+                ("my_crate::Client", ItemKind::Struct, "pub struct Client"),
+                (
+                    "my_crate::connect",
+                    ItemKind::Function,
+                    "pub fn connect(url: &str)",
+                ),
+                ("my_crate::legacy", ItemKind::Function, "pub fn legacy()"),
+            ],
+            &[("my_crate::Client", "pub fn send(&self)")],
+        );
+        let new = test_index(
+            &[
+                ("my_crate::Client", ItemKind::Struct, "pub struct Client"),
+                (
+                    "my_crate::connect",
+                    ItemKind::Function,
+                    "pub fn connect(url: Url)",
+                ),
+                ("my_crate::Url", ItemKind::Struct, "pub struct Url"),
+            ],
+            &[
+                ("my_crate::Client", "pub fn send(&self)"),
+                ("my_crate::Client", "pub fn close(self)"),
+            ],
+        );
+
+        let diff = old.diff(&new);
+        let paths = |changes: &[ApiChange]| -> Vec<String> {
+            changes.iter().map(|c| c.path.clone()).collect()
+        };
+        assert_eq!(paths(&diff.added), ["Client::close", "Url"]);
+        assert_eq!(paths(&diff.removed), ["legacy"]);
+        assert_eq!(paths(&diff.changed), ["connect"]);
+        assert_eq!(
+            diff.changed[0].new.as_deref(),
+            Some("pub fn connect(url: Url)")
+        );
+        assert_eq!(diff.added[0].kind, "method");
+    }
+
+    #[test]
+    fn diff_of_identical_indexes_is_empty() {
+        let items = [("my_crate::Client", ItemKind::Struct, "pub struct Client")];
+        let diff = test_index(&items, &[]).diff(&test_index(&items, &[]));
+        assert!(diff.is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+use super::index::CrateIndex;
+
+/// How rustdoc treats a doctest, from its code fence attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoctestMode {
+    /// Compiled and run.
+    Run,
+    /// `no_run`: compiled but not run.
+    NoRun,
+    /// `ignore`: neither compiled nor run.
+    Ignore,
+    /// `should_panic`: run and expected to panic.
+    ShouldPanic,
+    /// `compile_fail`: expected not to compile.
+    CompileFail,
+}
+
+impl std::fmt::Display for DoctestMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DoctestMode::Run => write!(f, "runs"),
+            DoctestMode::NoRun => write!(f, "no_run"),
+            DoctestMode::Ignore => write!(f, "ignore"),
+            DoctestMode::ShouldPanic => write!(f, "should_panic"),
+            DoctestMode::CompileFail => write!(f, "compile_fail"),
+        }
+    }
+}
+
+/// A Rust code block from a doc comment.
+#[derive(Debug, Clone)]
+pub struct Doctest {
+    /// The code, including hidden (`# `) lines.
+    pub code: String,
+    pub mode: DoctestMode,
+}
+
+/// The doctests attached to one item or method.
+pub struct DoctestGroup<'a> {
+    pub path: String,
+    pub doctests: Vec<Doctest>,
+    /// The item's or method's signature.
+    pub signature: &'a str,
+}
+
+impl CrateIndex {
+    /// Doctests of every item, trait method, and inherent method under `path_prefix`
+    /// (crate-relative or absolute), keeping only `mode` if given. Sorted by path.
+    pub fn doctests(
+        &self,
+        path_prefix: Option<&str>,
+        mode: Option<DoctestMode>,
+    ) -> Vec<DoctestGroup<'_>> {
+        let prefix = path_prefix.map(|p| {
+            if p == self.crate_name || p.starts_with(&format!("{}::", self.crate_name)) {
+                p.to_string()
+            } else {
+                format!("{}::{p}", self.crate_name)
+            }
+        });
+        let mut docs: Vec<(String, &str, &str)> = Vec::new();
+        for item in self.items.values() {
+            docs.push((item.path.clone(), &item.doc, &item.signature));
+            for method in &item.detail.methods {
+                let path = format!("{}::{}", item.path, method.name);
+                docs.push((path, &method.doc, &method.signature));
+            }
+            for method in self.inherent_methods(&item.path) {
+                let path = format!("{}::{}", item.path, method.name);
+                docs.push((path, &method.doc, &method.signature));
+            }
+        }
+
+        let mut groups: Vec<DoctestGroup<'_>> = docs
+            .into_iter()
+            .filter(|(path, _, _)| prefix.as_ref().is_none_or(|p| path.starts_with(p.as_str())))
+            .filter_map(|(path, doc, signature)| {
+                let doctests: Vec<Doctest> = extract_doctests(doc)
+                    .into_iter()
+                    .filter(|t| mode.is_none_or(|m| t.mode == m))
+                    .collect();
+                (!doctests.is_empty()).then_some(DoctestGroup {
+                    path,
+                    doctests,
+                    signature,
+                })
+            })
+            .collect();
+        groups.sort_by(|a, b| a.path.cmp(&b.path));
+        groups
+    }
+}
+
+/// Extract the Rust code blocks of a markdown doc string with their doctest mode.
+///
+/// Fences with no language tag are Rust by rustdoc convention; fences tagged
+/// with a non-Rust language (e.g. `text`, `toml`) are skipped.
+pub fn extract_doctests(doc: &str) -> Vec<Doctest> {
+    let mut doctests = Vec::new();
+    let mut current: Option<(String, Option<DoctestMode>)> = None;
+
+    for line in doc.lines() {
+        let trimmed = line.trim_start();
+        let fence = trimmed.starts_with("```") || trimmed.starts_with("~~~");
+        match current.take() {
+            None if fence => {
+                let info = trimmed.trim_start_matches(['`', '~']).trim();
+                current = Some((String::new(), fence_mode(info)));
+            }
+            None => {}
+            Some((code, Some(mode))) if fence => doctests.push(Doctest { code, mode }),
+            Some((_, None)) if fence => {}
+            Some((mut code, mode)) => {
+                code.push_str(line);
+                code.push('\n');
+                current = Some((code, mode));
+            }
+        }
+    }
+    doctests
+}
+
+/// The doctest mode of a code fence info string (e.g. `rust,no_run`), or `None`
+/// if the fence is not Rust code.
+pub(crate) fn fence_mode(info: &str) -> Option<DoctestMode> {
+    let mut mode = DoctestMode::Run;
+    for tag in info.split([',', ' ']).filter(|tag| !tag.is_empty()) {
+        mode = match tag {
+            "rust" | "edition2015" | "edition2018" | "edition2021" | "edition2024" => continue,
+            "no_run" => DoctestMode::NoRun,
+            "ignore" => DoctestMode::Ignore,
+            "should_panic" => DoctestMode::ShouldPanic,
+            "compile_fail" => DoctestMode::CompileFail,
+            _ => return None,
+        };
+    }
+    Some(mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== extract_doctests tests ==========
+
+    #[test]
+    fn extract_doctests_reads_fence_modes() {
+        let doc = "```\na();\n```\n\n```rust,no_run\nb();\n```\n\n```text\nnot code\n```\n\n```ignore\nc();\n```\n\n```should_panic\nd();\n```";
+        let doctests = extract_doctests(doc);
+        let modes: Vec<DoctestMode> = doctests.iter().map(|t| t.mode).collect();
+        assert_eq!(
+            modes,
+            [
+                DoctestMode::Run,
+                DoctestMode::NoRun,
+                DoctestMode::Ignore,
+                DoctestMode::ShouldPanic
+            ]
+        );
+        assert_eq!(doctests[1].code, "b();\n");
+    }
+
+    #[test]
+    fn fence_mode_rejects_other_languages() {
+        assert_eq!(fence_mode(""), Some(DoctestMode::Run));
+        assert_eq!(fence_mode("rust,edition2021"), Some(DoctestMode::Run));
+        assert_eq!(fence_mode("compile_fail"), Some(DoctestMode::CompileFail));
+        assert_eq!(fence_mode("toml"), None);
+        assert_eq!(fence_mode("rust,text"), None);
+    }
+}
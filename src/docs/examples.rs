@@ -0,0 +1,206 @@
+use std::io::Read;
+use std::path::Path;
+
+use crate::error::Error;
+use crate::http::HttpClient;
+
+/// A source file from a crate's `examples/` directory.
+pub struct ExampleFile {
+    /// Path relative to the package root (e.g. "examples/client.rs").
+    pub path: String,
+    pub content: String,
+}
+
+impl ExampleFile {
+    /// The example's name as passed to `cargo run --example` (e.g. "client"), or
+    /// for multi-file examples, the directory name.
+    pub fn name(&self) -> &str {
+        let rest = self.path.strip_prefix("examples/").unwrap_or(&self.path);
+        match rest.split_once('/') {
+            Some((dir, _)) => dir,
+            None => rest.strip_suffix(".rs").unwrap_or(rest),
+        }
+    }
+
+    /// The example's `//!` doc comment, or failing that its leading `//` comment.
+    pub fn summary(&self) -> String {
+        let lines: Vec<&str> = self
+            .content
+            .lines()
+            .map(str::trim)
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| line.starts_with("//"))
+            .map(|line| {
+                line.trim_start_matches("//!")
+                    .trim_start_matches("//")
+                    .trim()
+            })
+            .take_while(|line| !line.is_empty())
+            .collect();
+        lines.join(" ")
+    }
+}
+
+/// Download the `.crate` archive (gzipped tarball) of a published crate version.
+pub async fn fetch_crate_archive(
+    client: &HttpClient,
+    crate_name: &str,
+    version: &str,
+) -> Result<Vec<u8>, Error> {
+    let url = format!("https://static.crates.io/crates/{crate_name}/{crate_name}-{version}.crate");
+    tracing::info!("Downloading crate archive from {url}");
+
+    let _slot = client.slot(&url).await?;
+    let response = client.get(&url).send().await?;
+    if matches!(
+        response.status(),
+        reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::FORBIDDEN
+    ) {
+        return Err(Error::CrateNotFound(format!("{crate_name} v{version}")));
+    }
+    let bytes = response.error_for_status()?.bytes().await?;
+    Ok(bytes.to_vec())
+}
+
+/// Extract the `.rs` files under `examples/` from a `.crate` archive.
+///
+/// Archive entries are prefixed with `{name}-{version}/`, which is stripped.
+pub fn examples_from_archive(archive: &[u8]) -> Result<Vec<ExampleFile>, Error> {
+    let read_error = |e: std::io::Error| Error::Other(format!("Failed to read crate archive: {e}"));
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    let mut files = Vec::new();
+    for entry in tar.entries().map_err(read_error)? {
+        let mut entry = entry.map_err(read_error)?;
+        let path = entry
+            .path()
+            .map_err(read_error)?
+            .to_string_lossy()
+            .into_owned();
+        let Some((_, relative)) = path.split_once('/') else {
+            continue;
+        };
+        if !is_example_source(relative) {
+            continue;
+        }
+        let mut content = String::new();
+        if entry.read_to_string(&mut content).is_ok() {
+            files.push(ExampleFile {
+                path: relative.to_string(),
+                content,
+            });
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Read the `.rs` files under `{package_dir}/examples/`.
+pub fn examples_from_dir(package_dir: &Path) -> Vec<ExampleFile> {
+    let mut files = Vec::new();
+    let mut pending = vec![package_dir.join("examples")];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(package_dir) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            if is_example_source(&relative)
+                && let Ok(content) = std::fs::read_to_string(&path)
+            {
+                files.push(ExampleFile {
+                    path: relative,
+                    content,
+                });
+            }
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+fn is_example_source(relative: &str) -> bool {
+    relative.starts_with("examples/") && relative.ends_with(".rs")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn example(path: &str, content: &str) -> ExampleFile {
+        ExampleFile {
+            path: path.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    // ========== ExampleFile tests ==========
+
+    #[test]
+    fn name_handles_single_and_multi_file_examples() {
+        assert_eq!(example("examples/client.rs", "").name(), "client");
+        assert_eq!(example("examples/server/main.rs", "").name(), "server");
+    }
+
+    #[test]
+    fn summary_reads_leading_comment() {
+        let file = example(
+            "examples/client.rs",
+            "//! A simple HTTP client.\n//! Sends one request.\n//!\n//! Details.\nfn main() {}",
+        );
+        assert_eq!(file.summary(), "A simple HTTP client. Sends one request.");
+        assert_eq!(example("examples/x.rs", "fn main() {}").summary(), "");
+    }
+
+    // ========== extraction tests ==========
+
+    #[test]
+    fn examples_from_archive_keeps_example_sources() {
+        let mut tar = tar::Builder::new(Vec::new());
+        for (path, content) in [
+            ("demo-1.0.0/Cargo.toml", "[package]"),
+            ("demo-1.0.0/src/lib.rs", "pub fn f() {}"),
+            ("demo-1.0.0/examples/basic.rs", "fn main() {}"),
+            ("demo-1.0.0/examples/multi/main.rs", "mod util;"),
+            ("demo-1.0.0/examples/README.md", "# Examples"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(&tar.into_inner().unwrap()).unwrap();
+        let archive = gz.finish().unwrap();
+
+        let files = examples_from_archive(&archive).unwrap();
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["examples/basic.rs", "examples/multi/main.rs"]);
+        assert_eq!(files[0].content, "fn main() {}");
+    }
+
+    #[test]
+    fn examples_from_dir_walks_subdirectories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("examples/multi")).unwrap();
+        std::fs::write(dir.path().join("examples/basic.rs"), "fn main() {}").unwrap();
+        std::fs::write(dir.path().join("examples/multi/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(dir.path().join("examples/notes.txt"), "").unwrap();
+
+        let files = examples_from_dir(dir.path());
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["examples/basic.rs", "examples/multi/main.rs"]);
+        assert!(examples_from_dir(&dir.path().join("missing")).is_empty());
+    }
+}
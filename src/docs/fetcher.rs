@@ -1,18 +1,32 @@
+use std::io::Read;
+use std::ops::RangeInclusive;
+
+use serde::Deserialize;
+
 use crate::error::Error;
+use crate::http::HttpClient;
 
 /// Fetch the raw zstd-compressed rustdoc JSON bytes from docs.rs.
 ///
 /// The URL pattern is: `https://docs.rs/crate/{name}/{version}/json`
-/// Returns the raw compressed bytes without any processing.
+/// Returns zstd-compressed bytes: docs.rs serves a `.json.zst` file, and bodies that
+/// arrive gzipped or as plain JSON instead are re-encoded (see `zstd_body`).
+#[tracing::instrument(name = "fetch", skip(client))]
 pub async fn fetch_raw_bytes(
-    client: &reqwest::Client,
+    client: &HttpClient,
     crate_name: &str,
     version: &str,
 ) -> Result<Vec<u8>, Error> {
     let url = format!("https://docs.rs/crate/{crate_name}/{version}/json");
     tracing::info!("Fetching rustdoc JSON from {url}");
 
-    let response = client.get(&url).send().await?;
+    // reqwest decodes a gzip `Content-Encoding` itself; a zstd one is left for `zstd_body`
+    let _slot = client.slot(&url).await?;
+    let response = client
+        .get(&url)
+        .header(reqwest::header::ACCEPT_ENCODING, "zstd, gzip")
+        .send()
+        .await?;
 
     let status = response.status();
     if status == reqwest::StatusCode::NOT_FOUND {
@@ -23,8 +37,97 @@ pub async fn fetch_raw_bytes(
     }
 
     let response = response.error_for_status()?;
+    if let Some(encoding) = response.headers().get(reqwest::header::CONTENT_ENCODING) {
+        tracing::debug!("{url} has Content-Encoding {encoding:?}");
+    }
     let bytes = response.bytes().await?;
-    Ok(bytes.to_vec())
+    zstd_body(bytes.to_vec(), &url)
+}
+
+/// How a response body is compressed, told apart by its magic bytes rather than
+/// headers, which mirrors and proxies don't set consistently.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BodyEncoding {
+    Zstd,
+    Gzip,
+    Json,
+    Unknown,
+}
+
+pub fn sniff_encoding(bytes: &[u8]) -> BodyEncoding {
+    match bytes {
+        [0x28, 0xB5, 0x2F, 0xFD, ..] => BodyEncoding::Zstd,
+        [0x1F, 0x8B, ..] => BodyEncoding::Gzip,
+        _ if bytes.trim_ascii_start().starts_with(b"{") => BodyEncoding::Json,
+        _ => BodyEncoding::Unknown,
+    }
+}
+
+/// A fetched rustdoc JSON body as zstd, the form `decode_raw_bytes` and the disk
+/// cache expect, whether it arrived as zstd, gzip, or uncompressed JSON.
+fn zstd_body(bytes: Vec<u8>, url: &str) -> Result<Vec<u8>, Error> {
+    let json = match sniff_encoding(&bytes) {
+        BodyEncoding::Zstd => return Ok(bytes),
+        BodyEncoding::Gzip => {
+            let mut json = Vec::new();
+            flate2::read::GzDecoder::new(bytes.as_slice())
+                .read_to_end(&mut json)
+                .map_err(Error::Gzip)?;
+            json
+        }
+        BodyEncoding::Json => bytes,
+        BodyEncoding::Unknown => {
+            let start = String::from_utf8_lossy(&bytes[..bytes.len().min(40)]).into_owned();
+            return Err(Error::UnknownEncoding {
+                url: url.to_string(),
+                description: format!("neither zstd, gzip, nor JSON (starts with {start:?})"),
+            });
+        }
+    };
+    tracing::info!("{url} was not zstd-compressed, re-encoding it for the cache");
+    Ok(zstd::stream::encode_all(json.as_slice(), 3)?)
+}
+
+/// One docs.rs build of a crate version, from `builds.json`.
+#[derive(Debug, Deserialize)]
+pub struct BuildInfo {
+    #[serde(default)]
+    pub rustc_version: String,
+    #[serde(default)]
+    pub docsrs_version: String,
+    /// "success", "failure", or "in_progress".
+    #[serde(deserialize_with = "build_status")]
+    pub build_status: String,
+    #[serde(default, alias = "created_at")]
+    pub build_time: Option<String>,
+}
+
+/// Older docs.rs responses report `build_status` as a bool.
+fn build_status<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Bool(true) => "success".to_string(),
+        serde_json::Value::Bool(false) => "failure".to_string(),
+        serde_json::Value::String(status) => status,
+        other => other.to_string(),
+    })
+}
+
+/// Fetch the docs.rs builds of a crate version, newest first.
+pub async fn fetch_builds(
+    client: &HttpClient,
+    crate_name: &str,
+    version: &str,
+) -> Result<Vec<BuildInfo>, Error> {
+    let url = format!("https://docs.rs/crate/{crate_name}/{version}/builds.json");
+    tracing::info!("Fetching docs.rs builds from {url}");
+
+    let _slot = client.slot(&url).await?;
+    let response = client.get(&url).send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(Error::CrateNotFound(format!("{crate_name} v{version}")));
+    }
+    let bytes = response.error_for_status()?.bytes().await?;
+    Ok(serde_json::from_slice(&bytes)?)
 }
 
 /// Decode raw zstd-compressed rustdoc JSON bytes into a `rustdoc_types::Crate`.
@@ -36,8 +139,19 @@ pub fn decode_raw_bytes(
     version: &str,
 ) -> Result<rustdoc_types::Crate, Error> {
     let decompressed = zstd::stream::decode_all(bytes).map_err(Error::Zstd)?;
+    decode_json_bytes(&decompressed, crate_name, version)
+}
 
-    let mut value: serde_json::Value = serde_json::from_slice(&decompressed)?;
+/// Decode uncompressed rustdoc JSON bytes into a `rustdoc_types::Crate`.
+///
+/// Normalizes across format versions, then deserializes. Used directly for
+/// locally built docs, which `cargo rustdoc` writes uncompressed.
+pub fn decode_json_bytes(
+    json: &[u8],
+    crate_name: &str,
+    version: &str,
+) -> Result<rustdoc_types::Crate, Error> {
+    let mut value: serde_json::Value = serde_json::from_slice(json)?;
 
     let format_version = value
         .get("format_version")
@@ -47,7 +161,15 @@ pub fn decode_raw_bytes(
 
     normalize_for_v56(&mut value, format_version);
 
-    let krate: rustdoc_types::Crate = serde_json::from_value(value)?;
+    let krate: rustdoc_types::Crate = if format_version > u64::from(NEWEST_KNOWN_FORMAT) {
+        tracing::warn!(
+            "{crate_name} v{version} uses rustdoc JSON format {format_version}, newer than \
+             {NEWEST_KNOWN_FORMAT}; parsing best-effort"
+        );
+        decode_lenient(value)?
+    } else {
+        serde_json::from_value(value)?
+    };
     tracing::info!(
         "Parsed rustdoc JSON for {crate_name} v{version}: {} items",
         krate.index.len()
@@ -55,56 +177,192 @@ pub fn decode_raw_bytes(
     Ok(krate)
 }
 
-/// Normalize a rustdoc JSON value so it deserializes with `rustdoc-types` 0.56
-/// (format version 56).
-///
-/// Format differences we handle:
+/// The newest rustdoc JSON format version the adapters below are known to handle.
+/// Newer formats are decoded best-effort, and responses say the docs may be incomplete.
+pub const NEWEST_KNOWN_FORMAT: u32 = 57;
+
+/// Deserialize a crate in a format we don't know, dropping entries of `index`,
+/// `paths`, and `external_crates` that don't fit the format-56 model instead of
+/// failing the whole crate.
+fn decode_lenient(mut value: serde_json::Value) -> Result<rustdoc_types::Crate, Error> {
+    let mut skipped = retain_valid::<rustdoc_types::Item>(&mut value, "index");
+    skipped += retain_valid::<rustdoc_types::ItemSummary>(&mut value, "paths");
+    skipped += retain_valid::<rustdoc_types::ExternalCrate>(&mut value, "external_crates");
+    if skipped > 0 {
+        tracing::warn!("Skipped {skipped} entries that don't deserialize");
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Remove the entries of the map at `key` that don't deserialize as `T`, returning
+/// how many were removed.
+fn retain_valid<T: serde::de::DeserializeOwned>(value: &mut serde_json::Value, key: &str) -> usize {
+    let Some(serde_json::Value::Object(map)) = value.get_mut(key) else {
+        return 0;
+    };
+    let before = map.len();
+    map.retain(|_, entry| serde_json::from_value::<T>(entry.clone()).is_ok());
+    before - map.len()
+}
+
+/// One conversion from a range of rustdoc JSON formats into the format-56 model of
+/// `rustdoc-types` 0.56, which the parser works on.
+struct FormatAdapter {
+    /// Format versions the adapter applies to.
+    formats: RangeInclusive<u64>,
+    /// What the adapter changes, for logs.
+    description: &'static str,
+    /// Whether it drops data the format carries (logged so the loss isn't silent).
+    lossy: bool,
+    apply: fn(&mut serde_json::Value),
+}
+
+/// Adapters in the order they run. Format differences we handle:
 /// - **53 -> 54**: `Item.attrs` changed from `Vec<String>` to `Vec<Attribute>` (tagged enum).
-///   We don't use attrs, so we empty all attrs arrays.
+///   Older source strings become `Attribute::Other`; formats 54-57 decode as they are.
 /// - **55 -> 56**: `Crate.target: Target` added; `Attribute::MacroExport` variant added.
 ///   We inject a dummy target for older formats.
 /// - **56 -> 57**: `ExternalCrate.path: PathBuf` added. We strip it since 0.56 doesn't expect it.
-fn normalize_for_v56(value: &mut serde_json::Value, format_version: u64) {
-    // For all versions: empty attrs arrays (format changed 53->54, we don't use them)
-    strip_attrs(value);
-
-    // For format < 56: inject a dummy target (Crate.target was added in format 56)
-    if format_version < 56 {
-        inject_dummy_target(value);
-    }
+/// - **Newer than 57**: attributes the format-56 model can't read are rewritten as
+///   source text where we know their shape, and dropped otherwise.
+const FORMAT_ADAPTERS: [FormatAdapter; 4] = [
+    FormatAdapter {
+        formats: 0..=53,
+        description: "attrs source strings wrapped as Attribute::Other",
+        lossy: false,
+        apply: normalize_attrs,
+    },
+    FormatAdapter {
+        formats: NEWEST_KNOWN_FORMAT as u64 + 1..=u64::MAX,
+        description: "attrs the format-56 model can't read rewritten as source text, unknown ones dropped",
+        lossy: true,
+        apply: normalize_attrs,
+    },
+    FormatAdapter {
+        formats: 0..=55,
+        description: "placeholder Crate.target injected",
+        lossy: false,
+        apply: inject_dummy_target,
+    },
+    FormatAdapter {
+        formats: 57..=u64::MAX,
+        description: "ExternalCrate.path dropped",
+        lossy: true,
+        apply: strip_external_crate_paths,
+    },
+];
+
+/// The adapters that apply to `format_version`, in order.
+fn adapters_for(format_version: u64) -> impl Iterator<Item = &'static FormatAdapter> {
+    FORMAT_ADAPTERS
+        .iter()
+        .filter(move |adapter| adapter.formats.contains(&format_version))
+}
 
-    // For format 57+: strip ExternalCrate.path (doesn't exist in 0.56)
-    if format_version >= 57 {
-        strip_external_crate_paths(value);
+/// Normalize a rustdoc JSON value so it deserializes with `rustdoc-types` 0.56
+/// (format version 56), by running every adapter for its format version.
+fn normalize_for_v56(value: &mut serde_json::Value, format_version: u64) {
+    for adapter in adapters_for(format_version) {
+        if adapter.lossy {
+            tracing::info!(
+                "Format {format_version} conversion drops data: {}",
+                adapter.description
+            );
+        } else {
+            tracing::debug!(
+                "Format {format_version} conversion: {}",
+                adapter.description
+            );
+        }
+        (adapter.apply)(value);
     }
 }
 
-/// Recursively replace all `"attrs"` arrays with `[]`.
+/// Recursively make every `"attrs"` array readable as format-56 `Attribute`s.
 ///
 /// The `attrs` field changed from `Vec<String>` (format <= 53) to `Vec<Attribute>`
-/// (format >= 54). Since we never use attrs, emptying them avoids deserialization
-/// errors regardless of format version.
-fn strip_attrs(value: &mut serde_json::Value) {
+/// (format >= 54), and later formats may add variants or change their shape.
+/// Attributes that already deserialize are left alone; the rest are converted to
+/// `{"other": "#[...]"}` source text (which every format deserializes) when
+/// `attr_source()` can recover it, and dropped otherwise.
+fn normalize_attrs(value: &mut serde_json::Value) {
     match value {
         serde_json::Value::Object(map) => {
-            if let Some(attrs) = map.get_mut("attrs")
-                && attrs.is_array()
-            {
-                *attrs = serde_json::Value::Array(Vec::new());
+            if let Some(serde_json::Value::Array(attrs)) = map.get_mut("attrs") {
+                *attrs = attrs
+                    .iter()
+                    .filter_map(|attr| {
+                        if serde_json::from_value::<rustdoc_types::Attribute>(attr.clone()).is_ok()
+                        {
+                            Some(attr.clone())
+                        } else {
+                            attr_source(attr).map(|src| serde_json::json!({ "other": src }))
+                        }
+                    })
+                    .collect();
             }
             for v in map.values_mut() {
-                strip_attrs(v);
+                normalize_attrs(v);
             }
         }
         serde_json::Value::Array(arr) => {
             for v in arr.iter_mut() {
-                strip_attrs(v);
+                normalize_attrs(v);
             }
         }
         _ => {}
     }
 }
 
+/// Recover the source form (e.g. `#[repr(C)]`) of a single attribute in any format.
+fn attr_source(attr: &serde_json::Value) -> Option<String> {
+    match attr {
+        // Format <= 53 stores source text; unit variants (e.g. "non_exhaustive") are bare tags
+        serde_json::Value::String(s) if s.starts_with("#[") => Some(s.clone()),
+        serde_json::Value::String(tag) => Some(format!("#[{tag}]")),
+        serde_json::Value::Object(map) => {
+            let (tag, inner) = map.iter().next()?;
+            match tag.as_str() {
+                "other" => inner.as_str().map(str::to_string),
+                "must_use" => match inner.get("reason").and_then(|r| r.as_str()) {
+                    Some(reason) => Some(format!("#[must_use = {reason:?}]")),
+                    None => Some("#[must_use]".to_string()),
+                },
+                "repr" => Some(repr_source(inner)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Render a structured `AttributeRepr` back into `#[repr(...)]` source form.
+fn repr_source(repr: &serde_json::Value) -> String {
+    let mut parts = Vec::new();
+    match repr.get("kind").and_then(|k| k.as_str()) {
+        Some("c") => parts.push("C".to_string()),
+        Some("rust") | None => {}
+        Some(other) => parts.push(other.to_string()),
+    }
+    if let Some(int) = repr.get("int").and_then(|i| i.as_str()) {
+        parts.push(int.to_string());
+    }
+    if let Some(align) = repr.get("align").and_then(|a| a.as_u64()) {
+        parts.push(format!("align({align})"));
+    }
+    if let Some(packed) = repr.get("packed").and_then(|p| p.as_u64()) {
+        parts.push(if packed == 1 {
+            "packed".to_string()
+        } else {
+            format!("packed({packed})")
+        });
+    }
+    if parts.is_empty() {
+        parts.push("Rust".to_string());
+    }
+    format!("#[repr({})]", parts.join(", "))
+}
+
 /// Inject a dummy `target` field into the root if not present.
 ///
 /// Format version 56 added `Crate.target: Target` which older formats lack.
@@ -142,19 +400,51 @@ mod tests {
     use super::*;
     use serde_json::json;
 
-    // ========== strip_attrs tests ==========
+    // ========== builds.json tests ==========
 
     #[test]
-    fn strip_attrs_empties_top_level_array() {
+    fn build_info_accepts_string_and_bool_statuses() {
+        let builds: Vec<BuildInfo> = serde_json::from_value(json!([
+            {
+                "id": 2,
+                "rustc_version": "rustc 1.80.0-nightly (abc 2024-05-01)",
+                "docsrs_version": "docsrs 0.6.0 (def 2024-05-02)",
+                "build_status": "success",
+                "build_time": "2024-05-03T10:00:00Z"
+            },
+            {
+                "id": 1,
+                "rustc_version": "rustc 1.60.0-nightly",
+                "docsrs_version": "docsrs 0.6.0",
+                "build_status": false
+            }
+        ]))
+        .unwrap();
+        assert_eq!(builds[0].build_status, "success");
+        assert_eq!(
+            builds[0].build_time.as_deref(),
+            Some("2024-05-03T10:00:00Z")
+        );
+        assert_eq!(builds[1].build_status, "failure");
+        assert_eq!(builds[1].build_time, None);
+    }
+
+    // ========== normalize_attrs tests ==========
+
+    #[test]
+    fn normalize_attrs_wraps_top_level_source_strings() {
         let mut value = json!({
             "attrs": ["#[derive(Debug)]", "#[allow(unused)]"]
         });
-        strip_attrs(&mut value);
-        assert_eq!(value["attrs"], json!([]));
+        normalize_attrs(&mut value);
+        assert_eq!(
+            value["attrs"],
+            json!([{"other": "#[derive(Debug)]"}, {"other": "#[allow(unused)]"}])
+        );
     }
 
     #[test]
-    fn strip_attrs_empties_nested_in_items() {
+    fn normalize_attrs_rewrites_attrs_nested_in_items() {
         // Simulates the real rustdoc JSON structure: items inside the index have attrs
         let mut value = json!({
             "index": {
@@ -170,53 +460,122 @@ mod tests {
                 }
             }
         });
-        strip_attrs(&mut value);
-        assert_eq!(value["index"]["0:3"]["attrs"], json!([]));
-        assert_eq!(value["index"]["0:5"]["attrs"], json!([]));
+        normalize_attrs(&mut value);
+        assert_eq!(
+            value["index"]["0:3"]["attrs"],
+            json!([{"other": "#[derive(Debug)]"}])
+        );
+        assert_eq!(
+            value["index"]["0:5"]["attrs"],
+            json!([{"other": "#[inline]"}])
+        );
     }
 
     #[test]
-    fn strip_attrs_handles_format_54_tagged_enum_attrs() {
-        // Format 54+ uses tagged enum attrs like {"Attribute": "derive"}
-        // These are still arrays, so strip_attrs should empty them
+    fn normalize_attrs_drops_unknown_tagged_attrs() {
+        // Variants the format-56 model lacks, with no known source form
         let mut value = json!({
             "attrs": [
                 {"Derive": "Debug"},
                 {"Other": {"value": "#[serde(rename)]"}}
             ]
         });
-        strip_attrs(&mut value);
+        normalize_attrs(&mut value);
         assert_eq!(value["attrs"], json!([]));
     }
 
     #[test]
-    fn strip_attrs_leaves_non_array_attrs_alone() {
+    fn normalize_attrs_leaves_non_array_attrs_alone() {
         // If "attrs" is not an array (hypothetical), don't touch it
         let mut value = json!({ "attrs": "not-an-array" });
-        strip_attrs(&mut value);
+        normalize_attrs(&mut value);
         assert_eq!(value["attrs"], json!("not-an-array"));
     }
 
     #[test]
-    fn strip_attrs_recurses_into_arrays() {
+    fn normalize_attrs_recurses_into_arrays() {
         // Items can appear inside arrays (e.g., in some JSON structures)
         let mut value = json!([
-            { "attrs": ["a"] },
-            { "attrs": ["b", "c"] }
+            { "attrs": ["#[inline]"] },
+            { "attrs": [{"Derive": "Debug"}] }
         ]);
-        strip_attrs(&mut value);
-        assert_eq!(value[0]["attrs"], json!([]));
+        normalize_attrs(&mut value);
+        assert_eq!(value[0]["attrs"], json!([{"other": "#[inline]"}]));
         assert_eq!(value[1]["attrs"], json!([]));
     }
 
     #[test]
-    fn strip_attrs_no_attrs_key_is_noop() {
+    fn normalize_attrs_no_attrs_key_is_noop() {
         let mut value = json!({"name": "foo", "inner": {}});
         let original = value.clone();
-        strip_attrs(&mut value);
+        normalize_attrs(&mut value);
         assert_eq!(value, original);
     }
 
+    #[test]
+    fn normalize_attrs_keeps_format_53_source_strings() {
+        let mut value = json!({
+            "attrs": ["#[non_exhaustive]", "#[derive(Debug)]", "#[repr(C)]", "#[must_use]", "#[doc(hidden)]"]
+        });
+        normalize_attrs(&mut value);
+        assert_eq!(
+            value["attrs"],
+            json!([
+                {"other": "#[non_exhaustive]"},
+                {"other": "#[derive(Debug)]"},
+                {"other": "#[repr(C)]"},
+                {"other": "#[must_use]"},
+                {"other": "#[doc(hidden)]"}
+            ])
+        );
+    }
+
+    #[test]
+    fn normalize_attrs_keeps_attrs_the_model_reads() {
+        let attrs = json!([
+            "non_exhaustive",
+            "automatically_derived",
+            {"must_use": {"reason": "futures do nothing unless polled"}},
+            {"repr": {"kind": "c", "align": null, "packed": null, "int": "u8"}},
+            {"export_name": "run"},
+            {"other": "#[inline]"}
+        ]);
+        let mut value = json!({ "attrs": attrs.clone() });
+        normalize_attrs(&mut value);
+        assert_eq!(value["attrs"], attrs);
+    }
+
+    #[test]
+    fn normalize_attrs_translates_attrs_of_newer_shapes() {
+        let mut value = json!({
+            "attrs": [
+                {"must_use": {"reason": ["read it"]}},
+                {"repr": {"kind": "scalable", "align": null, "packed": null, "int": null}},
+                {"optimize": "speed"}
+            ]
+        });
+        normalize_attrs(&mut value);
+        assert_eq!(
+            value["attrs"],
+            json!([
+                {"other": "#[must_use]"},
+                {"other": "#[repr(scalable)]"}
+            ])
+        );
+    }
+
+    #[test]
+    fn repr_source_renders_int_only_and_packed() {
+        assert_eq!(
+            repr_source(&json!({"kind": "rust", "align": null, "packed": null, "int": "i32"})),
+            "#[repr(i32)]"
+        );
+        assert_eq!(
+            repr_source(&json!({"kind": "c", "align": null, "packed": 1, "int": null})),
+            "#[repr(C, packed)]"
+        );
+    }
+
     // ========== strip_external_crate_paths tests ==========
 
     #[test]
@@ -297,7 +656,18 @@ mod tests {
     // ========== normalize_for_v56 integration tests ==========
 
     #[test]
-    fn normalize_v53_strips_attrs_and_injects_target() {
+    fn adapters_for_selects_adapters_by_format() {
+        let lossy = |format| adapters_for(format).filter(|a| a.lossy).count();
+        assert_eq!(adapters_for(53).count(), 2);
+        assert_eq!(lossy(53), 0);
+        assert_eq!(adapters_for(56).count(), 0);
+        assert_eq!(adapters_for(57).count(), 1);
+        assert_eq!(adapters_for(58).count(), 2);
+        assert_eq!(lossy(58), 2);
+    }
+
+    #[test]
+    fn normalize_v53_wraps_attrs_and_injects_target() {
         let mut value = json!({
             "format_version": 53,
             "index": {
@@ -309,8 +679,11 @@ mod tests {
         });
         normalize_for_v56(&mut value, 53);
 
-        // attrs should be emptied
-        assert_eq!(value["index"]["0"]["attrs"], json!([]));
+        // attrs become Attribute::Other source text
+        assert_eq!(
+            value["index"]["0"]["attrs"],
+            json!([{"other": "#[derive(Debug)]"}])
+        );
         // external_crates should be untouched (no path to strip, and version < 57)
         assert_eq!(value["external_crates"]["1"]["name"], json!("std"));
         // target should be injected
@@ -318,28 +691,27 @@ mod tests {
     }
 
     #[test]
-    fn normalize_v56_strips_attrs_only() {
+    fn normalize_v56_leaves_the_document_alone() {
         let mut value = json!({
             "format_version": 56,
             "index": {
-                "0:1": { "attrs": [{"Other": "#[cfg(test)]"}], "name": "Bar" }
+                "0:1": { "attrs": [{"other": "#[cfg(test)]"}, "macro_export"], "name": "Bar" }
             },
             "external_crates": {
                 "1": { "name": "core" }
             }
         });
+        let original = value.clone();
         normalize_for_v56(&mut value, 56);
-
-        assert_eq!(value["index"]["0:1"]["attrs"], json!([]));
-        assert_eq!(value["external_crates"]["1"]["name"], json!("core"));
+        assert_eq!(value, original);
     }
 
     #[test]
-    fn normalize_v57_strips_attrs_and_external_crate_paths() {
+    fn normalize_v57_strips_external_crate_paths_only() {
         let mut value = json!({
             "format_version": 57,
             "index": {
-                "0:1": { "attrs": [{"MacroExport": null}], "name": "Baz" }
+                "0:1": { "attrs": ["macro_export"], "name": "Baz" }
             },
             "external_crates": {
                 "1": { "name": "std", "path": "/rustc/library/std" },
@@ -348,8 +720,8 @@ mod tests {
         });
         normalize_for_v56(&mut value, 57);
 
-        // attrs emptied
-        assert_eq!(value["index"]["0:1"]["attrs"], json!([]));
+        // attrs untouched
+        assert_eq!(value["index"]["0:1"]["attrs"], json!(["macro_export"]));
         // paths stripped from external_crates
         assert!(value["external_crates"]["1"].get("path").is_none());
         assert!(value["external_crates"]["2"].get("path").is_none());
@@ -468,7 +840,7 @@ mod tests {
     #[test]
     fn roundtrip_v53_with_string_attrs_deserializes() {
         // Format 53 used plain string attrs -- the tagged enum Attribute in 0.56
-        // would fail to deserialize these, but strip_attrs empties them first
+        // would fail to deserialize these, but normalize_attrs rewrites them first
         let mut value = minimal_rustdoc_json(53);
         value["index"]["1"]["attrs"] = json!(["#[derive(Debug)]", "#[allow(unused)]"]);
 
@@ -495,6 +867,34 @@ mod tests {
         assert!(krate.external_crates.values().any(|c| c.name == "std"));
     }
 
+    #[test]
+    fn roundtrip_v58_drops_only_unknown_attrs() {
+        // Future formats may add attribute variants 0.56 doesn't know about
+        let mut value = minimal_rustdoc_json(58);
+        value["index"]["1"]["attrs"] = json!([
+            "non_exhaustive",
+            {"some_future_attr": {"value": 1}}
+        ]);
+
+        normalize_for_v56(&mut value, 58);
+        let krate: rustdoc_types::Crate = serde_json::from_value(value)
+            .expect("v58 JSON with unknown attrs should deserialize after normalization");
+        let item = &krate.index[&rustdoc_types::Id(1)];
+        assert_eq!(item.attrs, vec![rustdoc_types::Attribute::NonExhaustive]);
+    }
+
+    #[test]
+    fn decode_lenient_skips_items_of_unknown_shape() {
+        let mut value = minimal_rustdoc_json(99);
+        value["index"]["1"]["inner"] = json!({"some_future_kind": {}});
+
+        normalize_for_v56(&mut value, 99);
+        assert!(serde_json::from_value::<rustdoc_types::Crate>(value.clone()).is_err());
+        let krate = decode_lenient(value).expect("lenient decoding should keep valid items");
+        assert_eq!(krate.index.len(), 1);
+        assert_eq!(krate.format_version, 99);
+    }
+
     #[test]
     fn roundtrip_v53_without_normalization_fails() {
         // v53 JSON with string attrs and no target field should fail without normalization
@@ -566,4 +966,32 @@ mod tests {
         let result = decode_raw_bytes(&compressed, "test_crate", "1.0.0");
         assert!(result.is_err());
     }
+
+    // ========== zstd_body tests ==========
+
+    #[test]
+    fn zstd_body_accepts_zstd_gzip_and_plain_json() {
+        use std::io::Write;
+
+        let value = minimal_rustdoc_json(56);
+        let json = serde_json::to_vec(&value).unwrap();
+        let url = "https://docs.rs/crate/test_crate/1.0.0/json";
+
+        let zstd = zstd_compress_json(&value);
+        assert_eq!(zstd_body(zstd.clone(), url).unwrap(), zstd);
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(&json).unwrap();
+        for body in [gz.finish().unwrap(), json.clone()] {
+            let normalized = zstd_body(body, url).unwrap();
+            assert_eq!(sniff_encoding(&normalized), BodyEncoding::Zstd);
+            assert!(decode_raw_bytes(&normalized, "test_crate", "1.0.0").is_ok());
+        }
+    }
+
+    #[test]
+    fn zstd_body_rejects_html() {
+        let err = zstd_body(b"<!DOCTYPE html><html>".to_vec(), "https://docs.rs/x").unwrap_err();
+        assert!(err.to_string().contains("neither zstd, gzip, nor JSON"));
+    }
 }
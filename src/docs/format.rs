@@ -0,0 +1,159 @@
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd, html};
+
+/// Markdown extensions the renderers use: `render.rs` writes tables and strikethrough.
+fn parser(markdown: &str) -> Parser<'_> {
+    Parser::new_ext(
+        markdown,
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+    )
+}
+
+/// Markdown output as an HTML fragment, for clients embedding it in web pages. Raw
+/// HTML in the markdown is escaped, so generics like `Vec<T>` in doc text show up as
+/// written and docs can't inject markup.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let events = parser(markdown).map(|event| match event {
+        Event::Html(text) | Event::InlineHtml(text) => Event::Text(text),
+        event => event,
+    });
+    let mut out = String::new();
+    html::push_html(&mut out, events);
+    out
+}
+
+/// Markdown output as plain text: no heading markers, code fences, backticks, or
+/// emphasis. List items keep a `-` or number (indented two spaces per level), link
+/// targets follow their text in parentheses, and table cells are separated by ` | `.
+pub fn markdown_to_plain(markdown: &str) -> String {
+    let mut out = String::new();
+    // The next number of each open list, `None` for bullet lists
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    // Target and start of the text of each open link
+    let mut links: Vec<(String, usize)> = Vec::new();
+    let mut first_cell = true;
+
+    for event in parser(markdown) {
+        match event {
+            Event::Start(Tag::List(first)) => {
+                start_line(&mut out);
+                lists.push(first);
+            }
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+                if lists.is_empty() {
+                    blank_line(&mut out);
+                }
+            }
+            Event::Start(Tag::Item) => {
+                start_line(&mut out);
+                out.push_str(&"  ".repeat(lists.len().saturating_sub(1)));
+                match lists.last_mut() {
+                    Some(Some(number)) => {
+                        out.push_str(&format!("{number}. "));
+                        *number += 1;
+                    }
+                    _ => out.push_str("- "),
+                }
+            }
+            Event::End(TagEnd::Item | TagEnd::TableHead | TagEnd::TableRow) => start_line(&mut out),
+            Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::CodeBlock
+                | TagEnd::HtmlBlock
+                | TagEnd::BlockQuote(_)
+                | TagEnd::Table,
+            ) => {
+                if lists.is_empty() {
+                    blank_line(&mut out);
+                } else {
+                    start_line(&mut out);
+                }
+            }
+            Event::Start(Tag::TableHead | Tag::TableRow) => first_cell = true,
+            Event::Start(Tag::TableCell) => {
+                if !first_cell {
+                    out.push_str(" | ");
+                }
+                first_cell = false;
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                links.push((dest_url.to_string(), out.len()))
+            }
+            Event::End(TagEnd::Link) => {
+                if let Some((url, start)) = links.pop()
+                    && out[start..] != url
+                {
+                    out.push_str(&format!(" ({url})"));
+                }
+            }
+            Event::Text(text) | Event::Code(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                out.push_str(&text)
+            }
+            Event::SoftBreak | Event::HardBreak => out.push('\n'),
+            Event::Rule => blank_line(&mut out),
+            _ => {}
+        }
+    }
+
+    let lines: Vec<&str> = out.lines().map(str::trim_end).collect();
+    lines.join("\n").trim().to_string()
+}
+
+fn start_line(out: &mut String) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+fn blank_line(out: &mut String) {
+    start_line(out);
+    if !out.is_empty() && !out.ends_with("\n\n") {
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== markdown_to_plain tests ==========
+
+    #[test]
+    fn markdown_to_plain_drops_markdown_syntax() {
+        let markdown = "## serde::Serialize\n\n_Also re-exported as `serde::ser::Serialize`_\n\n\
+                        ```rust\npub trait Serialize {\n    fn serialize(&self);\n}\n```\n\n\
+                        A **data structure** that can be serialized.\n";
+        assert_eq!(
+            markdown_to_plain(markdown),
+            "serde::Serialize\n\nAlso re-exported as serde::ser::Serialize\n\n\
+             pub trait Serialize {\n    fn serialize(&self);\n}\n\n\
+             A data structure that can be serialized."
+        );
+    }
+
+    #[test]
+    fn markdown_to_plain_keeps_list_markers_links_and_table_cells() {
+        let markdown = "- [struct] `Config` — see [docs](https://docs.rs/x)\n\
+                        - nested:\n  1. first\n  2. <https://docs.rs>\n\n\
+                        | Kind | Count |\n|---|---|\n| struct | 3 |\n";
+        assert_eq!(
+            markdown_to_plain(markdown),
+            "- [struct] Config — see docs (https://docs.rs/x)\n- nested:\n  1. first\n  2. https://docs.rs\n\n\
+             Kind | Count\nstruct | 3"
+        );
+    }
+
+    // ========== markdown_to_html tests ==========
+
+    #[test]
+    fn markdown_to_html_renders_markdown_and_escapes_raw_html() {
+        let html =
+            markdown_to_html("## Vec<T>\n\nA `Vec` of <b>T</b>.\n\n```rust\nfn f() {}\n```\n");
+        assert_eq!(
+            html,
+            "<h2>Vec&lt;T&gt;</h2>\n<p>A <code>Vec</code> of &lt;b&gt;T&lt;/b&gt;.</p>\n\
+             <pre><code class=\"language-rust\">fn f() {}\n</code></pre>\n"
+        );
+    }
+}
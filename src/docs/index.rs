@@ -1,10 +1,18 @@
 use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+use super::construct::return_type;
+use super::parser::doc_headings;
+use super::suggest::{NameIndex, levenshtein, path_distance};
 
 /// In-memory indexed representation of a crate's documentation.
 /// All signatures are pre-rendered to strings during parsing, so the
 /// original rustdoc_types::Crate is dropped after index construction.
 pub struct CrateIndex {
+    /// Name of the library target, as used in item paths (e.g. "foo" for package `foo-rs`).
     pub crate_name: String,
+    /// Package name on crates.io and docs.rs.
+    pub package_name: String,
     pub version: String,
     /// All indexed items, keyed by their fully qualified path (e.g. "serde::Serialize").
     pub items: HashMap<String, IndexedItem>,
@@ -12,8 +20,43 @@ pub struct CrateIndex {
     pub modules: HashMap<String, Vec<String>>,
     /// Impl blocks: type path → list of impl blocks.
     pub impl_blocks: HashMap<String, Vec<ImplBlock>>,
+    /// Auto-trait impls (`Send`, `Sync`, `Unpin`, ...): type path → impls, including
+    /// negative ones. Kept out of `impl_blocks`.
+    pub auto_traits: HashMap<String, Vec<AutoTraitImpl>>,
     /// Root module items (items at the crate root).
     pub root_items: Vec<String>,
+    /// Definition paths that differ from an item's public path, and further public
+    /// re-export paths → the public path.
+    pub aliases: HashMap<String, String>,
+    /// Public re-exports of items from other crates, by their path in this crate.
+    pub foreign_reexports: HashMap<String, ForeignReexport>,
+    /// Trigram index over item names, for "did you mean" suggestions.
+    pub name_index: NameIndex,
+    /// Target triple the docs were built for ("unknown" before format version 56).
+    pub target: String,
+    /// Rustdoc JSON format version the docs were built with.
+    pub format_version: u32,
+    /// Time spent building this index from the rustdoc JSON.
+    pub parse_time: Duration,
+    /// Whether parsing ran over its time budget, leaving later items with only the
+    /// first sentence of their docs.
+    pub partial: bool,
+    /// Set when the docs came from an older cached copy because fetching failed.
+    pub stale: Option<StaleDocs>,
+    /// When the rustdoc JSON was fetched or built, for docs that can change under the
+    /// same version ("latest" and local builds).
+    pub fetched_at: Option<SystemTime>,
+}
+
+/// Why docs were served from an older cached copy, and from when.
+#[derive(Debug, Clone)]
+pub struct StaleDocs {
+    /// The version asked for (e.g. "latest"), which the cached copy may not match.
+    pub requested: String,
+    /// When the cached copy was written, if the filesystem reports it.
+    pub cached_at: Option<SystemTime>,
+    /// The error fetching from docs.rs failed with.
+    pub error: String,
 }
 
 /// A single documented item in the crate.
@@ -28,16 +71,90 @@ pub struct IndexedItem {
     pub kind: ItemKind,
     /// The rendered signature (e.g. `pub trait Serialize { ... }`).
     pub signature: String,
+    /// Usage-relevant attributes in source form (e.g. `#[non_exhaustive]`, `#[repr(C)]`).
+    pub attrs: Vec<String>,
+    /// Declared visibility (only non-`pub` in indexes built with private items).
+    pub visibility: Visibility,
+    /// Other paths this item is reachable at (e.g. its definition path behind a re-export).
+    pub aliases: Vec<String>,
+    /// Further public paths the item is re-exported at, besides `path`.
+    pub reexports: Vec<String>,
     /// The short one-line doc summary.
     pub short_doc: String,
     /// Full documentation text.
     pub doc: String,
+    /// Set if the item is marked `#[deprecated]`.
+    pub deprecation: Option<Deprecation>,
+    /// `#[stable]`/`#[unstable]` status (standard library items only).
+    pub stability: Option<Stability>,
+    /// Predicate of the item's `#[doc(cfg(...))]`, e.g. `feature = "full"`.
+    pub cfg: Option<String>,
+    /// Whether this is an `unsafe fn` or `unsafe trait`.
+    pub is_unsafe: bool,
+    /// For functions: whether it is an `async fn` or returns a future or stream.
+    pub async_kind: Option<AsyncKind>,
+    /// Whether the item is `#[doc(hidden)]`, directly or through an enclosing module.
+    pub is_hidden: bool,
+    /// Where the item is defined, if rustdoc recorded it.
+    pub span: Option<SourceSpan>,
+    /// Markdown headings in the documentation (e.g. "Panics", "Cancellation safety").
+    pub headings: Vec<String>,
+    /// Types and traits from other crates (the standard library aside) named in the
+    /// item's signature, fields, or variants.
+    pub external_refs: Vec<ExternalRef>,
     /// Kind-specific detail (struct fields, enum variants, trait methods, etc.)
     pub detail: ItemDetail,
     /// The parent module path (empty string for root items).
     pub parent_module: String,
 }
 
+impl IndexedItem {
+    /// Whether the item has a doc comment (for `documented_only`).
+    pub fn is_documented(&self) -> bool {
+        !self.doc.trim().is_empty()
+    }
+}
+
+/// A source location: a file path relative to the crate root (as rustdoc records it)
+/// and the 1-based lines the item spans.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub file: String,
+    pub line: usize,
+    pub end_line: usize,
+}
+
+/// A type or trait from another crate, as named in a signature (e.g. `Bytes`) and
+/// where that crate defines it (e.g. `bytes::bytes::Bytes`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalRef {
+    pub name: String,
+    pub path: String,
+    /// The defining crate's name as rustdoc records it (e.g. `bytes`).
+    pub crate_name: String,
+}
+
+/// A public re-export of an item defined in another crate (e.g. `pub use bytes::Bytes`
+/// in `actix_web::web`). The item itself has no entry in this crate's index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignReexport {
+    /// Path in this crate (e.g. `actix_web::web::Bytes`).
+    pub path: String,
+    /// Where the defining crate has it (e.g. `bytes::bytes::Bytes`).
+    pub target_path: String,
+    /// The defining crate's name as rustdoc records it (e.g. `bytes`).
+    pub crate_name: String,
+    /// `None` for kinds the index doesn't track (e.g. primitives).
+    pub kind: Option<ItemKind>,
+}
+
+/// Where a primitive type is indexed. Primitives share their path with the module of
+/// the same name (`core::str`), so they go under `primitive`, where `core::primitive::str`
+/// names the type itself.
+pub fn primitive_path(crate_name: &str, name: &str) -> String {
+    format!("{crate_name}::primitive::{name}")
+}
+
 /// The kind of a documented item.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ItemKind {
@@ -50,7 +167,11 @@ pub enum ItemKind {
     Constant,
     Static,
     Macro,
+    AttributeMacro,
+    DeriveMacro,
     Union,
+    /// A primitive type documented by std or core (`str`, `u64`, `slice`, ...)
+    Primitive,
 }
 
 impl std::fmt::Display for ItemKind {
@@ -65,7 +186,69 @@ impl std::fmt::Display for ItemKind {
             ItemKind::Constant => write!(f, "const"),
             ItemKind::Static => write!(f, "static"),
             ItemKind::Macro => write!(f, "macro"),
+            ItemKind::AttributeMacro => write!(f, "attr"),
+            ItemKind::DeriveMacro => write!(f, "derive"),
             ItemKind::Union => write!(f, "union"),
+            ItemKind::Primitive => write!(f, "primitive"),
+        }
+    }
+}
+
+/// The order items are listed in (`sort` of `lookup_crate_items` and `search_crate`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemOrder {
+    /// By name, whatever the kind.
+    Alpha,
+    /// Modules first, then grouped by kind, by name within each kind.
+    Kind,
+    /// Longest doc comment first, so the best-documented items lead.
+    DocLength,
+}
+
+impl ItemOrder {
+    pub fn compare(self, a: &IndexedItem, b: &IndexedItem) -> std::cmp::Ordering {
+        let by_name = || a.name.cmp(&b.name).then_with(|| a.path.cmp(&b.path));
+        match self {
+            ItemOrder::Alpha => a
+                .name
+                .to_lowercase()
+                .cmp(&b.name.to_lowercase())
+                .then_with(by_name),
+            ItemOrder::Kind => {
+                let a_is_mod = a.kind == ItemKind::Module;
+                let b_is_mod = b.kind == ItemKind::Module;
+                b_is_mod
+                    .cmp(&a_is_mod)
+                    .then_with(|| a.kind.to_string().cmp(&b.kind.to_string()))
+                    .then_with(by_name)
+            }
+            ItemOrder::DocLength => b.doc.len().cmp(&a.doc.len()).then_with(by_name),
+        }
+    }
+}
+
+/// Declared visibility of an item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Visibility {
+    /// `pub`
+    Public,
+    /// `pub(crate)`
+    Crate,
+    /// `pub(in path)`
+    Restricted(String),
+    /// No visibility modifier: private items, plus trait items and enum variants
+    /// that inherit their parent's visibility.
+    Private,
+}
+
+impl Visibility {
+    /// Source-form prefix for signatures, including the trailing space (empty if private).
+    pub fn prefix(&self) -> String {
+        match self {
+            Visibility::Public => "pub ".to_string(),
+            Visibility::Crate => "pub(crate) ".to_string(),
+            Visibility::Restricted(path) => format!("pub(in {path}) "),
+            Visibility::Private => String::new(),
         }
     }
 }
@@ -82,6 +265,12 @@ pub struct ItemDetail {
     pub methods: Vec<MethodInfo>,
     /// For structs/enums: whether it derives common traits.
     pub derives: Vec<String>,
+    /// For declarative macros: the matcher of each arm (e.g. `($x:expr)`).
+    pub macro_arms: Vec<String>,
+    /// For declarative macros: invocation lines taken from the doc examples.
+    pub macro_usages: Vec<String>,
+    /// For derive macros: helper attributes accepted inside the annotated item.
+    pub helper_attrs: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -106,6 +295,124 @@ pub struct MethodInfo {
     pub signature: String,
     pub doc: String,
     pub is_required: bool,
+    pub is_unsafe: bool,
+    pub async_kind: Option<AsyncKind>,
+    pub is_hidden: bool,
+    pub deprecation: Option<Deprecation>,
+    pub stability: Option<Stability>,
+    /// Where the method is defined, which for a provided trait method includes its
+    /// default body.
+    pub span: Option<SourceSpan>,
+}
+
+/// Details of a `#[deprecated]` attribute.
+#[derive(Debug, Clone)]
+pub struct Deprecation {
+    /// Version the item was deprecated in (e.g. "1.2.0").
+    pub since: Option<String>,
+    /// Migration note (e.g. "use `bar` instead").
+    pub note: Option<String>,
+}
+
+/// Stability of a standard library item, from its `#[stable]`/`#[unstable]` attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stability {
+    /// Stable since this Rust version (e.g. "1.36.0").
+    Stable { since: String },
+    /// Nightly-only behind `feature`, with its tracking issue number if any.
+    Unstable {
+        feature: String,
+        issue: Option<String>,
+    },
+}
+
+/// How a function takes part in async code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncKind {
+    /// An `async fn`.
+    AsyncFn,
+    /// Returns a future (`impl Future`, `BoxFuture`, a type implementing `Future`, ...).
+    Future,
+    /// Returns a stream (`impl Stream`, `BoxStream`, ...).
+    Stream,
+}
+
+/// Return type names that mark a future, then a stream.
+const FUTURE_TYPES: &[&str] = &[
+    "Future",
+    "IntoFuture",
+    "TryFuture",
+    "BoxFuture",
+    "LocalBoxFuture",
+];
+const STREAM_TYPES: &[&str] = &["Stream", "TryStream", "BoxStream", "LocalBoxStream"];
+
+impl AsyncKind {
+    /// Classify a function by its `async` keyword and rendered return type. The first
+    /// future or stream type named in `returns` wins, so `impl Future<Output = BoxStream>`
+    /// is a future.
+    pub fn classify(is_async: bool, returns: &str) -> Option<AsyncKind> {
+        if is_async {
+            return Some(AsyncKind::AsyncFn);
+        }
+        returns
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .find_map(|name| {
+                if FUTURE_TYPES.contains(&name) {
+                    Some(AsyncKind::Future)
+                } else if STREAM_TYPES.contains(&name) {
+                    Some(AsyncKind::Stream)
+                } else {
+                    None
+                }
+            })
+    }
+}
+
+/// Async API surface of a crate, for the async inventory.
+pub struct AsyncReport<'a> {
+    /// Free functions, with how each is async.
+    pub functions: Vec<(&'a IndexedItem, AsyncKind)>,
+    /// Trait and inherent methods, with the trait or type path.
+    pub methods: Vec<(&'a str, &'a MethodInfo, AsyncKind)>,
+}
+
+/// Unsafe API surface of a crate, for the unsafe report.
+pub struct UnsafeReport<'a> {
+    /// Free `unsafe fn`s.
+    pub functions: Vec<&'a IndexedItem>,
+    /// Unsafe traits and traits with unsafe methods, with those methods.
+    pub traits: Vec<(&'a IndexedItem, Vec<&'a MethodInfo>)>,
+    /// Unsafe inherent methods of types, with the type path.
+    pub methods: Vec<(&'a str, &'a MethodInfo)>,
+}
+
+/// Where methods with a given name come from.
+pub struct MethodOrigins<'a> {
+    /// Traits in this crate declaring the method.
+    pub trait_defs: Vec<(&'a IndexedItem, &'a MethodInfo)>,
+    /// Trait names (possibly from other crates) whose impls here provide the
+    /// method, with the implementing type paths.
+    pub trait_impls: Vec<(String, Vec<&'a str>)>,
+    /// Types with an inherent method of that name.
+    pub inherent: Vec<(&'a str, &'a MethodInfo)>,
+}
+
+/// A likely error type, with the signals that identified it.
+pub struct ErrorType<'a> {
+    pub item: &'a IndexedItem,
+    /// Has an `impl Error for ...` block.
+    pub implements_error: bool,
+    /// Number of signatures returning `Result<_, Name>` (matched by name).
+    pub result_uses: usize,
+}
+
+/// A deprecated item or method, for the deprecation report.
+pub struct DeprecatedEntry<'a> {
+    pub path: String,
+    /// Kind label as shown in listings (e.g. "struct"; "method" for methods).
+    pub kind: String,
+    pub deprecation: &'a Deprecation,
 }
 
 /// An impl block associated with a type.
@@ -115,25 +422,60 @@ pub struct ImplBlock {
     pub header: String,
     /// Trait being implemented, if any.
     pub trait_name: Option<String>,
+    /// Full path of the trait, e.g. "core::iter::traits::iterator::Iterator".
+    pub trait_path: Option<String>,
+    /// Provided methods of the trait this impl doesn't override.
+    pub provided_methods: Vec<String>,
     /// Methods in this impl block.
     pub methods: Vec<MethodInfo>,
 }
 
+/// An impl of an auto trait, written in the crate or derived by rustdoc.
+#[derive(Debug, Clone)]
+pub struct AutoTraitImpl {
+    /// e.g. "Send"
+    pub trait_name: String,
+    /// `impl !Send for ...`
+    pub is_negative: bool,
+    /// Bounds the impl requires (e.g. "T: Send"); empty if unconditional.
+    pub bounds: Vec<String>,
+    /// e.g. "impl<T> Send for Wrapper<T> where T: Send"
+    pub header: String,
+    /// Derived by rustdoc rather than written in the crate.
+    pub is_synthetic: bool,
+}
+
+/// Outcome of resolving a user-supplied item path.
+pub enum ItemLookup<'a> {
+    /// The path resolved to a single item.
+    Found(&'a IndexedItem),
+    /// The path loosely matches several items (sorted by path).
+    Ambiguous(Vec<&'a IndexedItem>),
+    NotFound,
+}
+
 /// Result of a search query.
 pub struct SearchResult {
     pub item: IndexedItem,
-    pub score: SearchScore,
+    /// For methods: the path of the type whose impl block defines it.
+    pub owner: Option<String>,
+    /// The doc heading the query matched, if that's how the item matched.
+    pub heading: Option<String>,
+    /// Composite ranking: the match tier adjusted by path, kind, and doc signals.
+    pub rank: i32,
 }
 
-/// How well a search result matches.
+/// How well a search result matches: the base tier of its rank.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SearchScore {
     /// Query exactly matches the item name.
-    Exact = 4,
+    Exact = 5,
     /// Item name starts with the query.
-    Prefix = 3,
+    Prefix = 4,
     /// Item name contains the query.
-    NameContains = 2,
+    NameContains = 3,
+    /// A markdown heading in the docs contains the query.
+    HeadingContains = 2,
     /// The item path contains the query.
     PathContains = 1,
     /// The doc text contains the query.
@@ -142,42 +484,81 @@ pub enum SearchScore {
 
 impl CrateIndex {
     /// Search within the crate for items matching the query.
-    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
-        let query_lower = query.to_lowercase();
+    /// Matching ignores case unless `case_sensitive` is set. `#[doc(hidden)]` items and
+    /// methods (and methods of hidden types) are left out unless `include_hidden` is set,
+    /// and `documented_only` leaves out those without a doc comment.
+    #[tracing::instrument(name = "search", skip(self), fields(crate_name = self.package_name))]
+    pub fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        case_sensitive: bool,
+        include_hidden: bool,
+        documented_only: bool,
+    ) -> Vec<SearchResult> {
+        let fold = |s: &str| {
+            if case_sensitive {
+                s.to_string()
+            } else {
+                s.to_lowercase()
+            }
+        };
+        let query = fold(query);
         let mut results: Vec<SearchResult> = self
             .items
             .values()
-            .filter_map(|item| {
-                let name_lower = item.name.to_lowercase();
-                let path_lower = item.path.to_lowercase();
-                let doc_lower = item.doc.to_lowercase();
-
-                let score = if name_lower == query_lower {
-                    SearchScore::Exact
-                } else if name_lower.starts_with(&query_lower) {
-                    SearchScore::Prefix
-                } else if name_lower.contains(&query_lower) {
-                    SearchScore::NameContains
-                } else if path_lower.contains(&query_lower) {
-                    SearchScore::PathContains
-                } else if doc_lower.contains(&query_lower) {
-                    SearchScore::DocContains
-                } else {
-                    return None;
-                };
-
-                Some(SearchResult {
-                    item: item.clone(),
-                    score,
-                })
-            })
+            .filter_map(|item| search_match(item, &query, &fold, None))
             .collect();
 
-        // Sort by score (highest first), then alphabetically by path
+        // Methods declared by traits, including their provided-method docs
+        for item in self.items.values() {
+            if item.kind != ItemKind::Trait {
+                continue;
+            }
+            for method in &item.detail.methods {
+                let method_item = method_item(&item.path, method);
+                results.extend(search_match(&method_item, &query, &fold, Some(&item.path)));
+            }
+        }
+
+        // Methods live in impl blocks rather than the item map
+        for (type_path, blocks) in &self.impl_blocks {
+            for block in blocks {
+                for method in &block.methods {
+                    let item = method_item(type_path, method);
+                    if let Some(mut result) = search_match(&item, &query, &fold, Some(type_path)) {
+                        // Trait impl methods repeat across every implementing type
+                        if block.trait_name.is_some() {
+                            result.rank -= 20;
+                        }
+                        results.push(result);
+                    }
+                }
+            }
+        }
+
+        if !include_hidden {
+            results.retain(|r| {
+                !r.item.is_hidden
+                    && !r
+                        .owner
+                        .as_ref()
+                        .and_then(|owner| self.items.get(owner))
+                        .is_some_and(|owner| owner.is_hidden)
+            });
+        }
+        if documented_only {
+            results.retain(|r| r.item.is_documented());
+        }
+
+        // Sort by rank (highest first), then alphabetically by path, owner, and
+        // signature, so ties don't depend on map order
         results.sort_by(|a, b| {
-            b.score
-                .cmp(&a.score)
+            b.rank
+                .cmp(&a.rank)
                 .then_with(|| a.item.path.cmp(&b.item.path))
+                .then_with(|| a.owner.cmp(&b.owner))
+                .then_with(|| a.item.signature.cmp(&b.item.signature))
         });
 
         results.truncate(limit);
@@ -200,28 +581,166 @@ impl CrateIndex {
             .filter_map(|path| self.items.get(path))
             .collect();
 
-        // Sort: modules first, then by kind, then by name
-        items.sort_by(|a, b| {
-            let a_is_mod = a.kind == ItemKind::Module;
-            let b_is_mod = b.kind == ItemKind::Module;
-            b_is_mod
-                .cmp(&a_is_mod)
-                .then_with(|| a.kind.to_string().cmp(&b.kind.to_string()))
-                .then_with(|| a.name.cmp(&b.name))
-        });
+        // Sort: modules first, then by kind, then by name and path
+        items.sort_by(|a, b| ItemOrder::Kind.compare(a, b));
 
         items
     }
 
     /// Look up a specific item by path.
     pub fn get_item(&self, item_path: &str) -> Option<&IndexedItem> {
+        match self.resolve_item(item_path) {
+            ItemLookup::Found(item) => Some(item),
+            ItemLookup::Ambiguous(_) | ItemLookup::NotFound => None,
+        }
+    }
+
+    /// Resolve a user-supplied item path, falling back to case-insensitive and
+    /// then suffix matching (e.g. "Mutex" → `tokio::sync::Mutex`) when no full
+    /// path matches. Several fallback matches yield `Ambiguous`.
+    pub fn resolve_item(&self, item_path: &str) -> ItemLookup<'_> {
+        if let Some(item) = self.get_item_by_path(item_path) {
+            return ItemLookup::Found(item);
+        }
+
+        let mut candidates = self.insensitive_matches(item_path);
+        if candidates.is_empty() {
+            candidates = self.suffix_matches(item_path);
+        }
+        match candidates.len() {
+            0 => ItemLookup::NotFound,
+            1 => ItemLookup::Found(candidates[0]),
+            _ => {
+                candidates.sort_by(|a, b| a.path.cmp(&b.path));
+                ItemLookup::Ambiguous(candidates)
+            }
+        }
+    }
+
+    /// Look up an item by its full path, relative path, or definition path.
+    pub fn get_item_by_path(&self, item_path: &str) -> Option<&IndexedItem> {
         // Try exact match first
         if let Some(item) = self.items.get(item_path) {
             return Some(item);
         }
+        // A bare primitive name (`str`, `u64`) means the type, not its module
+        if !item_path.contains("::")
+            && let Some(item) = self
+                .items
+                .get(&primitive_path(&self.crate_name, item_path))
+                .filter(|item| item.kind == ItemKind::Primitive)
+        {
+            return Some(item);
+        }
         // Try with crate name prefix
         let full_path = format!("{}::{}", self.crate_name, item_path);
-        self.items.get(&full_path)
+        if let Some(item) = self.items.get(&full_path) {
+            return Some(item);
+        }
+        // Try a path written with the package name of a renamed lib target
+        if let Some(rest) = self.strip_package_prefix(item_path)
+            && let Some(item) = self.items.get(&format!("{}::{rest}", self.crate_name))
+        {
+            return Some(item);
+        }
+        // Try definition-path aliases
+        if let Some(item) = self
+            .resolve_alias(item_path)
+            .and_then(|path| self.items.get(path))
+        {
+            return Some(item);
+        }
+        None
+    }
+
+    /// The re-export of another crate's item at `item_path` (full, crate-relative, or
+    /// written with the package name).
+    pub fn foreign_reexport(&self, item_path: &str) -> Option<&ForeignReexport> {
+        let relative = self.strip_package_prefix(item_path).unwrap_or(item_path);
+        self.foreign_reexports.get(relative).or_else(|| {
+            self.foreign_reexports
+                .get(&format!("{}::{relative}", self.crate_name))
+        })
+    }
+
+    /// Re-exports of other crates' items directly in a module (the crate root if
+    /// `module_path` is `None`), sorted by path.
+    pub fn module_reexports(&self, module_path: Option<&str>) -> Vec<&ForeignReexport> {
+        let module = module_path.unwrap_or(&self.crate_name);
+        let mut reexports: Vec<&ForeignReexport> = self
+            .foreign_reexports
+            .values()
+            .filter(|r| {
+                r.path
+                    .rsplit_once("::")
+                    .is_some_and(|(parent, _)| parent == module)
+            })
+            .collect();
+        reexports.sort_by(|a, b| a.path.cmp(&b.path));
+        reexports
+    }
+
+    /// Items re-exported directly in a module (the crate root if `module_path` is
+    /// `None`) but listed under a canonical path elsewhere, sorted by path.
+    pub fn module_local_reexports(&self, module_path: Option<&str>) -> Vec<&IndexedItem> {
+        let module = module_path.unwrap_or(&self.crate_name);
+        let mut items: Vec<&IndexedItem> = self
+            .items
+            .values()
+            .filter(|item| {
+                item.reexports.iter().any(|path| {
+                    path.rsplit_once("::")
+                        .is_some_and(|(parent, _)| parent == module)
+                })
+            })
+            .collect();
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+        items
+    }
+
+    /// The rest of `path` if it starts with the package name (hyphens as underscores)
+    /// where that differs from the lib name, e.g. "foo_rs::Bar" for lib `foo`.
+    pub fn strip_package_prefix<'a>(&self, path: &'a str) -> Option<&'a str> {
+        let package = self.package_name.replace('-', "_");
+        if package == self.crate_name {
+            return None;
+        }
+        let (first, rest) = path.split_once("::")?;
+        (first.replace('-', "_") == package).then_some(rest)
+    }
+
+    /// Items whose path ends with `item_path` at a segment boundary, preferring
+    /// exact-case matches over case-insensitive ones.
+    fn suffix_matches(&self, item_path: &str) -> Vec<&IndexedItem> {
+        let suffix = format!("::{}", item_path.trim_start_matches("::"));
+        let exact: Vec<&IndexedItem> = self
+            .items
+            .values()
+            .filter(|item| item.path.ends_with(&suffix))
+            .collect();
+        if !exact.is_empty() {
+            return exact;
+        }
+
+        let folded = fold_path(&suffix);
+        self.items
+            .values()
+            .filter(|item| fold_path(&item.path).ends_with(&folded))
+            .collect()
+    }
+
+    /// Items whose path matches `item_path` ignoring case and hyphen/underscore
+    /// differences (e.g. "hashmap" → `HashMap`).
+    fn insensitive_matches(&self, item_path: &str) -> Vec<&IndexedItem> {
+        let wanted = fold_path(item_path);
+        let wanted_full = fold_path(&format!("{}::{}", self.crate_name, item_path));
+        self.items
+            .values()
+            .filter(|item| {
+                let path = fold_path(&item.path);
+                path == wanted || path == wanted_full
+            })
+            .collect()
     }
 
     /// Get impl blocks for a type.
@@ -235,62 +754,1624 @@ impl CrateIndex {
         if let Some(impls) = self.impl_blocks.get(&full_path) {
             result.extend(impls.iter());
         }
+        // A bare primitive name, also in alloc, which has impls but no primitive items
+        if result.is_empty()
+            && !item_path.contains("::")
+            && let Some(impls) = self
+                .impl_blocks
+                .get(&primitive_path(&self.crate_name, item_path))
+        {
+            result.extend(impls.iter());
+        }
+        // Fall back to the impls of whatever item the path resolves to
+        if result.is_empty()
+            && let Some(impls) = self
+                .get_item(item_path)
+                .and_then(|item| self.impl_blocks.get(&item.path))
+        {
+            result.extend(impls.iter());
+        }
         result
     }
 
-    /// Suggest similar item paths using Levenshtein distance.
-    pub fn suggest_similar(&self, query: &str, max_suggestions: usize) -> Vec<String> {
-        let query_lower = query.to_lowercase();
-        let mut scored: Vec<(String, usize)> = self
+    /// Public items named `name` (e.g. "DateTime"), or whose path ends with it when it
+    /// has several segments (e.g. "de::Deserializer"), sorted by path. Falls back to a
+    /// case-insensitive match.
+    pub fn find_definitions(&self, name: &str) -> Vec<&IndexedItem> {
+        let last = name.rsplit("::").next().unwrap_or(name);
+        let suffix = format!("::{name}");
+        let matches = |fold: &dyn Fn(&str) -> String| {
+            let (last, suffix) = (fold(last), fold(&suffix));
+            let mut found: Vec<&IndexedItem> = self
+                .items
+                .values()
+                .filter(|item| item.visibility == Visibility::Public && !item.is_hidden)
+                .filter(|item| fold(&item.name) == last && fold(&item.path).ends_with(&suffix))
+                .collect();
+            found.sort_by(|a, b| a.path.cmp(&b.path));
+            found
+        };
+        let exact = matches(&|s: &str| s.to_string());
+        if exact.is_empty() {
+            matches(&|s: &str| s.to_lowercase())
+        } else {
+            exact
+        }
+    }
+
+    /// The methods of the trait at `trait_path`, which may be a definition path such as
+    /// "core::iter::traits::iterator::Iterator": falls back to the crate's only trait
+    /// with that name.
+    pub fn trait_methods(&self, trait_path: &str) -> Option<&[MethodInfo]> {
+        let is_trait = |item: &&IndexedItem| item.kind == ItemKind::Trait;
+        let item = match self.get_item_by_path(trait_path).filter(is_trait) {
+            Some(item) => item,
+            None => {
+                let name = trait_path.rsplit("::").next().unwrap_or(trait_path);
+                let mut traits = self
+                    .items
+                    .values()
+                    .filter(is_trait)
+                    .filter(|i| i.name == name);
+                let item = traits.next()?;
+                if traits.next().is_some() {
+                    return None;
+                }
+                item
+            }
+        };
+        Some(&item.detail.methods)
+    }
+
+    /// Map a definition path (with or without the crate prefix) to its public path.
+    fn resolve_alias(&self, item_path: &str) -> Option<&String> {
+        self.aliases.get(item_path).or_else(|| {
+            self.aliases
+                .get(&format!("{}::{}", self.crate_name, item_path))
+        })
+    }
+
+    /// All items of one kind across the crate, sorted by path.
+    pub fn items_of_kind(&self, kind: &ItemKind) -> Vec<&IndexedItem> {
+        let mut items: Vec<&IndexedItem> = self
             .items
-            .keys()
-            .map(|path| {
-                // Compare against both the full path and just the item name
-                let name = path.rsplit("::").next().unwrap_or(path);
-                let name_lower = name.to_lowercase();
-                let path_lower = path.to_lowercase();
-                let d1 = levenshtein(&query_lower, &name_lower);
-                let d2 = levenshtein(&query_lower, &path_lower);
-                (path.clone(), d1.min(d2))
+            .values()
+            .filter(|item| &item.kind == kind)
+            .collect();
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+        items
+    }
+
+    /// Every deprecated public item, plus deprecated inherent and trait methods
+    /// of public types and traits, sorted by path.
+    pub fn deprecated_items(&self) -> Vec<DeprecatedEntry<'_>> {
+        let mut entries = Vec::new();
+        for item in self.items.values() {
+            if item.visibility != Visibility::Public {
+                continue;
+            }
+            if let Some(deprecation) = &item.deprecation {
+                entries.push(DeprecatedEntry {
+                    path: item.path.clone(),
+                    kind: item.kind.to_string(),
+                    deprecation,
+                });
+            }
+
+            let inherent = self
+                .impl_blocks
+                .get(&item.path)
+                .into_iter()
+                .flatten()
+                .filter(|block| block.trait_name.is_none())
+                .flat_map(|block| &block.methods);
+            for method in item.detail.methods.iter().chain(inherent) {
+                if let Some(deprecation) = &method.deprecation {
+                    entries.push(DeprecatedEntry {
+                        path: format!("{}::{}", item.path, method.name),
+                        kind: "method".to_string(),
+                        deprecation,
+                    });
+                }
+            }
+        }
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        entries
+    }
+
+    /// Collect the crate's public unsafe functions, traits, and inherent methods,
+    /// each sorted by path.
+    pub fn unsafe_report(&self) -> UnsafeReport<'_> {
+        let mut report = UnsafeReport {
+            functions: Vec::new(),
+            traits: Vec::new(),
+            methods: Vec::new(),
+        };
+        for item in self.items.values() {
+            if item.visibility != Visibility::Public {
+                continue;
+            }
+            match item.kind {
+                ItemKind::Function if item.is_unsafe => report.functions.push(item),
+                ItemKind::Trait => {
+                    let methods: Vec<&MethodInfo> =
+                        item.detail.methods.iter().filter(|m| m.is_unsafe).collect();
+                    if item.is_unsafe || !methods.is_empty() {
+                        report.traits.push((item, methods));
+                    }
+                }
+                _ => {}
+            }
+
+            let inherent = self
+                .impl_blocks
+                .get(&item.path)
+                .into_iter()
+                .flatten()
+                .filter(|block| block.trait_name.is_none())
+                .flat_map(|block| &block.methods)
+                .filter(|m| m.is_unsafe);
+            report
+                .methods
+                .extend(inherent.map(|m| (item.path.as_str(), m)));
+        }
+        report.functions.sort_by(|a, b| a.path.cmp(&b.path));
+        report.traits.sort_by(|a, b| a.0.path.cmp(&b.0.path));
+        report
+            .methods
+            .sort_by(|a, b| a.0.cmp(b.0).then_with(|| a.1.name.cmp(&b.1.name)));
+        report
+    }
+
+    /// Collect the crate's public async functions and methods and those returning futures
+    /// or streams, each sorted by path. Besides `async_kind`, a function counts when it
+    /// returns a type of this crate that implements `Future` or `Stream` (e.g. a
+    /// `JoinHandle`).
+    pub fn async_report(&self) -> AsyncReport<'_> {
+        // By type name; of types sharing a name, the first by path wins
+        let mut types: Vec<(&String, &Vec<ImplBlock>)> = self.impl_blocks.iter().collect();
+        types.sort_unstable_by_key(|(type_path, _)| *type_path);
+        let mut futures = HashMap::new();
+        for (type_path, blocks) in types {
+            let name = type_path.rsplit("::").next().unwrap_or(type_path);
+            for block in blocks {
+                let kind = match block.trait_name.as_deref() {
+                    Some("Future") => AsyncKind::Future,
+                    Some("Stream") => AsyncKind::Stream,
+                    _ => continue,
+                };
+                futures.entry(name).or_insert(kind);
+            }
+        }
+        let kind_of = |async_kind: Option<AsyncKind>, signature: &str| {
+            async_kind.or_else(|| {
+                let returns = return_type(signature)?;
+                let name = returns.split('<').next().unwrap_or(returns);
+                futures
+                    .get(name.rsplit("::").next().unwrap_or(name))
+                    .copied()
+            })
+        };
+
+        let mut report = AsyncReport {
+            functions: Vec::new(),
+            methods: Vec::new(),
+        };
+        for item in self.items.values() {
+            if item.visibility != Visibility::Public || item.is_hidden {
+                continue;
+            }
+            match item.kind {
+                ItemKind::Function => {
+                    if let Some(kind) = kind_of(item.async_kind, &item.signature) {
+                        report.functions.push((item, kind));
+                    }
+                }
+                ItemKind::Trait => {
+                    for method in &item.detail.methods {
+                        if let Some(kind) = kind_of(method.async_kind, &method.signature) {
+                            report.methods.push((item.path.as_str(), method, kind));
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            let inherent = self
+                .impl_blocks
+                .get(&item.path)
+                .into_iter()
+                .flatten()
+                .filter(|block| block.trait_name.is_none())
+                .flat_map(|block| &block.methods)
+                .filter(|m| !m.is_hidden);
+            for method in inherent {
+                if let Some(kind) = kind_of(method.async_kind, &method.signature) {
+                    report.methods.push((item.path.as_str(), method, kind));
+                }
+            }
+        }
+        report.functions.sort_by(|a, b| a.0.path.cmp(&b.0.path));
+        report
+            .methods
+            .sort_by(|a, b| a.0.cmp(b.0).then_with(|| a.1.name.cmp(&b.1.name)));
+        report
+    }
+
+    /// Find which traits (or inherent impls) define a method called `method_name`.
+    /// A `Type::method` path is reduced to its last segment.
+    pub fn method_origins(&self, method_name: &str) -> MethodOrigins<'_> {
+        let name = method_name.rsplit("::").next().unwrap_or(method_name);
+        let mut origins = MethodOrigins {
+            trait_defs: Vec::new(),
+            trait_impls: Vec::new(),
+            inherent: Vec::new(),
+        };
+
+        for item in self.items.values() {
+            if item.kind == ItemKind::Trait
+                && let Some(method) = item.detail.methods.iter().find(|m| m.name == name)
+            {
+                origins.trait_defs.push((item, method));
+            }
+        }
+
+        let mut by_trait: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (type_path, blocks) in &self.impl_blocks {
+            for block in blocks {
+                let Some(method) = block.methods.iter().find(|m| m.name == name) else {
+                    continue;
+                };
+                match &block.trait_name {
+                    Some(trait_name) => by_trait.entry(trait_name).or_default().push(type_path),
+                    None => origins.inherent.push((type_path, method)),
+                }
+            }
+        }
+        origins.trait_impls = by_trait
+            .into_iter()
+            .map(|(trait_name, mut types)| {
+                types.sort();
+                types.dedup();
+                (trait_name.to_string(), types)
             })
             .collect();
 
-        scored.sort_by_key(|(_, d)| *d);
-        scored.truncate(max_suggestions);
+        origins.trait_defs.sort_by(|a, b| a.0.path.cmp(&b.0.path));
+        origins
+            .trait_impls
+            .sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+        origins.inherent.sort_by(|a, b| a.0.cmp(b.0));
+        origins
+    }
+
+    /// Find the crate's error structs and enums: public types that implement
+    /// `Error`, are named `*Error`, or appear as the error type of a `Result` in
+    /// some signature. Sorted with the strongest signals first.
+    pub fn error_types(&self) -> Vec<ErrorType<'_>> {
+        let result_uses = self.result_error_names();
+        let mut found: Vec<ErrorType<'_>> = self
+            .items
+            .values()
+            .filter(|item| {
+                matches!(item.kind, ItemKind::Struct | ItemKind::Enum)
+                    && item.visibility == Visibility::Public
+            })
+            .filter_map(|item| {
+                let implements_error = self
+                    .impl_blocks
+                    .get(&item.path)
+                    .into_iter()
+                    .flatten()
+                    .any(|block| block.trait_name.as_deref() == Some("Error"));
+                let uses = result_uses.get(item.name.as_str()).copied().unwrap_or(0);
+                let named_error = item.name.ends_with("Error");
+                (implements_error || named_error || uses > 0).then_some(ErrorType {
+                    item,
+                    implements_error,
+                    result_uses: uses,
+                })
+            })
+            .collect();
+        found.sort_by(|a, b| {
+            b.implements_error
+                .cmp(&a.implements_error)
+                .then_with(|| b.result_uses.cmp(&a.result_uses))
+                .then_with(|| a.item.path.cmp(&b.item.path))
+        });
+        found
+    }
+
+    /// Count, by type name, how often each type is the error of a `Result` in
+    /// item and method signatures.
+    fn result_error_names(&self) -> HashMap<&str, usize> {
+        let method_sigs = self
+            .impl_blocks
+            .values()
+            .flatten()
+            .flat_map(|block| &block.methods)
+            .map(|m| m.signature.as_str());
+        let mut counts = HashMap::new();
+        for sig in self
+            .items
+            .values()
+            .map(|item| item.signature.as_str())
+            .chain(method_sigs)
+        {
+            for name in result_error_types(sig) {
+                *counts.entry(name).or_default() += 1;
+            }
+        }
+        counts
+    }
+
+    /// Find items whose path matches a glob pattern (e.g. `sync::*`, `net::Tcp*`).
+    ///
+    /// `*` matches within a single path segment. Patterns without the crate name
+    /// prefix are matched relative to the crate root. Results are sorted by path.
+    pub fn glob_items(&self, pattern: &str) -> Vec<&IndexedItem> {
+        let prefix = format!("{}::", self.crate_name);
+        let pattern = if pattern.starts_with(&prefix) {
+            pattern.to_string()
+        } else {
+            format!("{prefix}{pattern}")
+        };
+
+        let mut items: Vec<&IndexedItem> = self
+            .items
+            .values()
+            .filter(|item| glob_match(&pattern, &item.path))
+            .collect();
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+        items
+    }
+
+    /// Crate-relative paths starting with `prefix` (case-insensitive), for argument
+    /// completion. Shallower paths come first, then alphabetical order.
+    pub fn complete_paths(&self, prefix: &str, modules_only: bool) -> Vec<String> {
+        let crate_prefix = format!("{}::", self.crate_name);
+        let prefix = prefix.strip_prefix(&crate_prefix).unwrap_or(prefix);
+        let prefix = prefix.to_lowercase();
+
+        let mut paths: Vec<String> = self
+            .items
+            .values()
+            .filter(|item| !modules_only || item.kind == ItemKind::Module)
+            .filter_map(|item| item.path.strip_prefix(&crate_prefix))
+            .filter(|path| path.to_lowercase().starts_with(&prefix))
+            .map(str::to_string)
+            .collect();
+        paths.sort_by(|a, b| {
+            a.matches("::")
+                .count()
+                .cmp(&b.matches("::").count())
+                .then_with(|| a.cmp(b))
+        });
+        paths
+    }
+
+    /// Suggest similar item paths for a path that was not found.
+    ///
+    /// Candidates come from the trigram index over item names and are ranked by
+    /// per-segment Levenshtein distance, preferring items of the kind the query's
+    /// casing implies (CamelCase → types, SCREAMING_CASE → constants). A
+    /// method-looking path (`Type::method`) suggests that type's methods.
+    pub fn suggest_similar(&self, query: &str, max_suggestions: usize) -> Vec<Suggestion> {
+        let expected = ExpectedKind::of(query);
+
+        if expected == Some(ExpectedKind::Method)
+            && let Some((type_path, method)) = query.rsplit_once("::")
+            && let Some(owner) = self.get_item(type_path)
+        {
+            return self.suggest_methods(owner, &method.to_lowercase(), max_suggestions);
+        }
+
+        let segments: Vec<&str> = query.split("::").filter(|s| !s.is_empty()).collect();
+        let Some(last) = segments.last() else {
+            return Vec::new();
+        };
+        let mut scored: Vec<(&IndexedItem, usize, usize)> = self
+            .name_index
+            .candidates(last)
+            .into_iter()
+            .filter_map(|(path, name_distance)| {
+                let item = self.items.get(path)?;
+                let distance = path_distance(&segments, path, name_distance);
+                let penalty = match expected {
+                    Some(kind) if !kind.matches(&item.kind) => 2,
+                    _ => 0,
+                };
+                Some((item, distance, distance + penalty))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.path.cmp(&b.0.path)));
 
         // Only suggest if distance is reasonable (< half the query length + 3)
         let threshold = query.len() / 2 + 3;
+        scored
+            .into_iter()
+            .filter(|(_, d, _)| *d <= threshold)
+            .take(max_suggestions)
+            .map(|(item, _, _)| Suggestion {
+                path: item.path.clone(),
+                kind: item.kind.to_string(),
+                short_doc: item.short_doc.clone(),
+            })
+            .collect()
+    }
+
+    /// Suggest methods of `owner` (inherent, trait impl, or trait methods) close to `method`.
+    fn suggest_methods(
+        &self,
+        owner: &IndexedItem,
+        method: &str,
+        max_suggestions: usize,
+    ) -> Vec<Suggestion> {
+        // Inherent methods first, so they win ties against trait methods
+        let mut blocks: Vec<&ImplBlock> = self
+            .impl_blocks
+            .get(&owner.path)
+            .into_iter()
+            .flatten()
+            .collect();
+        blocks.sort_by_key(|block| block.trait_name.is_some());
+        let impl_methods = blocks.into_iter().flat_map(|block| &block.methods);
+        let mut seen = std::collections::HashSet::new();
+        let mut scored: Vec<(&MethodInfo, usize)> = owner
+            .detail
+            .methods
+            .iter()
+            .chain(impl_methods)
+            .filter(|m| seen.insert(m.name.as_str()))
+            .map(|m| (m, levenshtein(method, &m.name.to_lowercase())))
+            .collect();
+        scored.sort_by_key(|(_, d)| *d);
+
+        let threshold = method.len() / 2 + 3;
         scored
             .into_iter()
             .filter(|(_, d)| *d <= threshold)
-            .map(|(path, _)| path)
+            .take(max_suggestions)
+            .map(|(m, _)| Suggestion {
+                path: format!("{}::{}", owner.path, m.name),
+                kind: "method".to_string(),
+                short_doc: m.doc.lines().next().unwrap_or("").to_string(),
+            })
             .collect()
     }
 }
 
-/// Simple Levenshtein distance implementation.
-fn levenshtein(a: &str, b: &str) -> usize {
-    let a_len = a.len();
-    let b_len = b.len();
+/// A "did you mean" suggestion for a path that was not found.
+pub struct Suggestion {
+    pub path: String,
+    /// Kind label as shown in listings (e.g. "struct"; "method" for methods).
+    pub kind: String,
+    pub short_doc: String,
+}
+
+/// The kind of item a query's spelling implies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExpectedKind {
+    /// CamelCase name: struct, enum, trait, type alias, or union.
+    Type,
+    /// SCREAMING_CASE name: constant or static.
+    Constant,
+    /// snake_case name: function, module, or macro.
+    Value,
+    /// `name!`: macro.
+    Macro,
+    /// snake_case name under a CamelCase segment (e.g. `Vec::push`).
+    Method,
+}
+
+impl ExpectedKind {
+    /// Infer the expected kind from the last segment(s) of a query path.
+    fn of(query: &str) -> Option<Self> {
+        let mut segments = query.rsplit("::");
+        let last = segments.next()?;
+        let parent = segments.next();
+
+        if last.ends_with('!') {
+            return Some(ExpectedKind::Macro);
+        }
+        let first = last.chars().next()?;
+        let has_lower = last.chars().any(|c| c.is_lowercase());
+        if first.is_uppercase() {
+            if has_lower {
+                Some(ExpectedKind::Type)
+            } else {
+                Some(ExpectedKind::Constant)
+            }
+        } else if first.is_lowercase() || first == '_' {
+            let parent_is_type = parent
+                .and_then(|p| p.chars().next())
+                .is_some_and(|c| c.is_uppercase());
+            if parent_is_type {
+                Some(ExpectedKind::Method)
+            } else {
+                Some(ExpectedKind::Value)
+            }
+        } else {
+            None
+        }
+    }
+
+    fn matches(self, kind: &ItemKind) -> bool {
+        match self {
+            ExpectedKind::Type => matches!(
+                kind,
+                ItemKind::Struct
+                    | ItemKind::Enum
+                    | ItemKind::Trait
+                    | ItemKind::TypeAlias
+                    | ItemKind::Union
+                    | ItemKind::Primitive
+            ),
+            ExpectedKind::Constant => matches!(kind, ItemKind::Constant | ItemKind::Static),
+            ExpectedKind::Value => matches!(
+                kind,
+                ItemKind::Function
+                    | ItemKind::Module
+                    | ItemKind::Macro
+                    | ItemKind::AttributeMacro
+                    | ItemKind::DeriveMacro
+            ),
+            ExpectedKind::Macro => matches!(kind, ItemKind::Macro),
+            ExpectedKind::Method => matches!(kind, ItemKind::Function),
+        }
+    }
+}
+
+/// Score `item` against an already-folded `query`, returning `None` if it doesn't match.
+/// `owner` is the type path when `item` stands for a method.
+fn search_match(
+    item: &IndexedItem,
+    query: &str,
+    fold: &impl Fn(&str) -> String,
+    owner: Option<&str>,
+) -> Option<SearchResult> {
+    let name = fold(&item.name);
+    let path = fold(&item.path);
+    let heading = item.headings.iter().find(|h| fold(h).contains(query));
+
+    let score = if name == query {
+        SearchScore::Exact
+    } else if name.starts_with(query) {
+        SearchScore::Prefix
+    } else if name.contains(query) {
+        SearchScore::NameContains
+    } else if heading.is_some() {
+        SearchScore::HeadingContains
+    } else if owner.is_none() && path.contains(query) {
+        // A method's path only repeats its owner's, which matches on its own
+        SearchScore::PathContains
+    } else if fold(&item.doc).contains(query)
+        || (owner.is_some() && fold(&item.signature).contains(query))
+    {
+        SearchScore::DocContains
+    } else {
+        return None;
+    };
+
+    Some(SearchResult {
+        item: item.clone(),
+        owner: owner.map(str::to_string),
+        heading: heading
+            .filter(|_| score == SearchScore::HeadingContains)
+            .cloned(),
+        rank: search_rank(item, score, &path, query),
+    })
+}
+
+/// An item standing in for a method of `owner_path`, so methods can be searched
+/// and rendered like top-level items.
+fn method_item(owner_path: &str, method: &MethodInfo) -> IndexedItem {
+    IndexedItem {
+        path: format!("{owner_path}::{}", method.name),
+        name: method.name.clone(),
+        kind: ItemKind::Function,
+        signature: method.signature.clone(),
+        attrs: Vec::new(),
+        visibility: Visibility::Public,
+        aliases: Vec::new(),
+        reexports: Vec::new(),
+        short_doc: method.doc.lines().next().unwrap_or("").to_string(),
+        doc: method.doc.clone(),
+        deprecation: method.deprecation.clone(),
+        stability: method.stability.clone(),
+        cfg: None,
+        is_unsafe: method.is_unsafe,
+        async_kind: method.async_kind,
+        is_hidden: method.is_hidden,
+        span: None,
+        headings: doc_headings(&method.doc),
+        external_refs: Vec::new(),
+        detail: ItemDetail::default(),
+        parent_module: owner_path.to_string(),
+    }
+}
+
+/// Path segments that mark implementation details rather than public API.
+const INTERNAL_SEGMENTS: &[&str] = &["internal", "internals", "private", "imp", "sys", "detail"];
+
+/// Composite search rank: the match tier dominates, adjusted by
+/// - a bonus when a path segment equals the query exactly,
+/// - a penalty per module level and for internal-looking paths,
+/// - a bonus for "important" kinds (traits and types over constants),
+/// - a bonus for documented items.
+///
+/// `folded_path` and `query` are compared as given (already case-folded for
+/// case-insensitive searches).
+fn search_rank(item: &IndexedItem, score: SearchScore, folded_path: &str, query: &str) -> i32 {
+    let mut rank = score as i32 * 100;
+
+    if score < SearchScore::Exact && folded_path.split("::").any(|s| s == query) {
+        rank += 30;
+    }
+
+    let segments: Vec<&str> = item.path.split("::").collect();
+
+    // Depth below the crate root, not counting the item itself
+    let depth = segments.len().saturating_sub(2) as i32;
+    rank -= depth * 5;
+    if segments[1..]
+        .iter()
+        .any(|s| s.starts_with('_') || INTERNAL_SEGMENTS.contains(s))
+    {
+        rank -= 40;
+    }
+
+    rank += match item.kind {
+        ItemKind::Trait | ItemKind::Struct | ItemKind::Enum | ItemKind::Primitive => 15,
+        ItemKind::Function | ItemKind::Macro | ItemKind::AttributeMacro | ItemKind::DeriveMacro => {
+            10
+        }
+        ItemKind::Module => 8,
+        ItemKind::TypeAlias | ItemKind::Union => 5,
+        ItemKind::Constant | ItemKind::Static => 0,
+    };
+
+    if !item.short_doc.is_empty() {
+        rank += 10;
+    }
+    rank
+}
+
+/// Names of the error types in every `Result<T, E>` within a rendered signature
+/// (the last path segment of `E`, without generics).
+fn result_error_types(signature: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = signature;
+    while let Some(start) = rest.find("Result<") {
+        let args = &rest[start + "Result<".len()..];
+        let mut depth = 0usize;
+        let mut comma = None;
+        let mut end = args.len();
+        for (i, c) in args.char_indices() {
+            match c {
+                '<' | '(' | '[' => depth += 1,
+                ')' | ']' => depth = depth.saturating_sub(1),
+                '>' if depth == 0 => {
+                    end = i;
+                    break;
+                }
+                '>' => depth -= 1,
+                ',' if depth == 0 => comma = Some(i),
+                _ => {}
+            }
+        }
+        if let Some(comma) = comma {
+            let error = args[comma + 1..end].trim();
+            let error = error.split('<').next().unwrap_or(error);
+            let name = error.rsplit("::").next().unwrap_or(error);
+            if !name.is_empty() {
+                names.push(name);
+            }
+        }
+        rest = args;
+    }
+    names
+}
+
+/// Normalize a path for loose comparison: lowercase, with `-` treated as `_`.
+fn fold_path(path: &str) -> String {
+    path.to_lowercase().replace('-', "_")
+}
+
+/// Match `text` against a glob `pattern` where `*` matches any run of characters
+/// except `:`, so a wildcard never spans path segments.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((head, tail)) => {
+            let Some(rest) = text.strip_prefix(head) else {
+                return false;
+            };
+            // Try every split point the wildcard could consume up to the next ':'
+            let limit = rest.find(':').unwrap_or(rest.len());
+            (0..=limit)
+                .filter(|&i| rest.is_char_boundary(i))
+                .any(|i| glob_match(tail, &rest[i..]))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_item(path: &str, kind: ItemKind) -> IndexedItem {
+        let (parent, name) = path.rsplit_once("::").unwrap_or(("", path));
+        IndexedItem {
+            path: path.to_string(),
+            name: name.to_string(),
+            kind,
+            signature: String::new(),
+            attrs: Vec::new(),
+            visibility: Visibility::Public,
+            aliases: Vec::new(),
+            reexports: Vec::new(),
+            short_doc: String::new(),
+            doc: String::new(),
+            deprecation: None,
+            stability: None,
+            cfg: None,
+            is_unsafe: false,
+            async_kind: None,
+            is_hidden: false,
+            span: None,
+            headings: Vec::new(),
+            external_refs: Vec::new(),
+            detail: ItemDetail::default(),
+            parent_module: parent.to_string(),
+        }
+    }
+
+    fn test_index(items: &[(&str, ItemKind)]) -> CrateIndex {
+        let mut index = CrateIndex {
+            crate_name: "my_crate".to_string(),
+            package_name: "my-crate".to_string(),
+            version: "1.0.0".to_string(),
+            items: items
+                .iter()
+                .map(|(path, kind)| (path.to_string(), test_item(path, kind.clone())))
+                .collect(),
+            modules: HashMap::new(),
+            impl_blocks: HashMap::new(),
+            auto_traits: HashMap::new(),
+            root_items: Vec::new(),
+            aliases: HashMap::new(),
+            foreign_reexports: HashMap::new(),
+            name_index: NameIndex::default(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            format_version: 56,
+            parse_time: Duration::ZERO,
+            partial: false,
+            stale: None,
+            fetched_at: None,
+        };
+        index.name_index = NameIndex::build(index.items.values());
+        index
+    }
+
+    // ========== get_item tests ==========
+
+    #[test]
+    fn get_item_ignores_case() {
+        let index = test_index(&[("my_crate::collections::HashMap", ItemKind::Struct)]);
+
+        let item = index.get_item("collections::hashmap").unwrap();
+        assert_eq!(item.path, "my_crate::collections::HashMap");
+        assert!(index.get_item("MY-CRATE::Collections::HASHMAP").is_some());
+    }
+
+    #[test]
+    fn get_item_insensitive_match_must_be_unique() {
+        let index = test_index(&[
+            ("my_crate::Foo", ItemKind::Struct),
+            ("my_crate::foo", ItemKind::Function),
+        ]);
+
+        assert_eq!(index.get_item("foo").unwrap().kind, ItemKind::Function);
+        assert!(index.get_item("FOO").is_none());
+        assert!(matches!(
+            index.resolve_item("FOO"),
+            ItemLookup::Ambiguous(items) if items.len() == 2
+        ));
+    }
+
+    #[test]
+    fn get_item_resolves_unique_suffix() {
+        let index = test_index(&[
+            ("my_crate::sync::Mutex", ItemKind::Struct),
+            ("my_crate::sync::mpsc::Sender", ItemKind::Struct),
+        ]);
+
+        assert_eq!(
+            index.get_item("Mutex").unwrap().path,
+            "my_crate::sync::Mutex"
+        );
+        assert_eq!(
+            index.get_item("mpsc::Sender").unwrap().path,
+            "my_crate::sync::mpsc::Sender"
+        );
+        assert_eq!(
+            index
+                .get_item("other_name::sync::Mutex")
+                .map(|i| i.path.as_str()),
+            None
+        );
+        assert!(index.get_item("utex").is_none());
+    }
+
+    #[test]
+    fn get_item_accepts_package_name_of_renamed_lib() {
+        let mut index = test_index(&[("my_crate::sync::Mutex", ItemKind::Struct)]);
+        assert!(index.get_item("my_crate_rs::sync::Mutex").is_none());
+
+        index.package_name = "my-crate-rs".to_string();
+        assert_eq!(
+            index.get_item("my-crate-rs::sync::Mutex").unwrap().path,
+            "my_crate::sync::Mutex"
+        );
+        assert_eq!(index.strip_package_prefix("my_crate::sync"), None);
+    }
+
+    #[test]
+    fn resolve_item_reports_ambiguous_suffix() {
+        let index = test_index(&[
+            ("my_crate::io::Error", ItemKind::Struct),
+            ("my_crate::fmt::Error", ItemKind::Struct),
+        ]);
+
+        match index.resolve_item("Error") {
+            ItemLookup::Ambiguous(items) => {
+                let paths: Vec<&str> = items.iter().map(|i| i.path.as_str()).collect();
+                assert_eq!(paths, ["my_crate::fmt::Error", "my_crate::io::Error"]);
+            }
+            _ => panic!("expected an ambiguous lookup"),
+        }
+        assert!(index.get_item("Error").is_none());
+    }
+
+    // ========== search tests ==========
+
+    fn search_paths(index: &CrateIndex, query: &str) -> Vec<String> {
+        index
+            .search(query, 10, false, false, false)
+            .into_iter()
+            .map(|r| r.item.path)
+            .collect()
+    }
+
+    #[test]
+    fn search_boosts_heading_matches_over_prose() {
+        let mut index = test_index(&[
+            ("my_crate::Receiver::recv", ItemKind::Function),
+            ("my_crate::Sender::send", ItemKind::Function),
+        ]);
+        let recv = index.items.get_mut("my_crate::Receiver::recv").unwrap();
+        recv.doc = "Receive a value.\n\n# Cancel safety\n\nThis method is cancel safe.".to_string();
+        recv.headings = vec!["Cancel safety".to_string()];
+        let send = index.items.get_mut("my_crate::Sender::send").unwrap();
+        send.doc = "Send a value. Unlike `recv`, mind cancel safety here.".to_string();
+
+        let results = index.search("cancel safety", 10, false, false, false);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].item.path, "my_crate::Receiver::recv");
+        assert_eq!(results[0].heading.as_deref(), Some("Cancel safety"));
+        assert_eq!(results[1].heading, None);
+    }
+
+    #[test]
+    fn search_skips_hidden_items_unless_included() {
+        let mut index = test_index(&[
+            ("my_crate::spawn", ItemKind::Function),
+            ("my_crate::__private::spawn_inner", ItemKind::Function),
+        ]);
+        index
+            .items
+            .get_mut("my_crate::__private::spawn_inner")
+            .unwrap()
+            .is_hidden = true;
+
+        let results = index.search("spawn", 10, false, false, false);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].item.path, "my_crate::spawn");
+        assert_eq!(index.search("spawn", 10, false, true, false).len(), 2);
+    }
+
+    #[test]
+    fn search_skips_undocumented_items_if_asked() {
+        let mut index = test_index(&[
+            ("my_crate::spawn", ItemKind::Function),
+            ("my_crate::spawn_blocking", ItemKind::Function),
+        ]);
+        index.items.get_mut("my_crate::spawn").unwrap().doc = "Spawns a task.".to_string();
+        index.items.get_mut("my_crate::spawn_blocking").unwrap().doc = " \n".to_string();
+
+        assert_eq!(index.search("spawn", 10, false, false, false).len(), 2);
+        let results = index.search("spawn", 10, false, false, true);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].item.path, "my_crate::spawn");
+    }
+
+    #[test]
+    fn search_case_sensitive_distinguishes_case() {
+        let index = test_index(&[
+            ("my_crate::io::Read", ItemKind::Trait),
+            ("my_crate::fs::read", ItemKind::Function),
+        ]);
+
+        assert_eq!(index.search("Read", 10, false, false, false).len(), 2);
+        let results = index.search("Read", 10, true, false, false);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].item.path, "my_crate::io::Read");
+    }
+
+    #[test]
+    fn search_prefers_important_kinds_and_shallow_paths() {
+        let index = test_index(&[
+            ("my_crate::BUFFER", ItemKind::Constant),
+            ("my_crate::Buffer", ItemKind::Struct),
+            ("my_crate::io::util::Buffer", ItemKind::Struct),
+        ]);
+
+        assert_eq!(
+            search_paths(&index, "buffer"),
+            [
+                "my_crate::Buffer",
+                "my_crate::io::util::Buffer",
+                "my_crate::BUFFER"
+            ]
+        );
+    }
+
+    #[test]
+    fn search_penalizes_internal_paths() {
+        let index = test_index(&[
+            ("my_crate::__private::Reader", ItemKind::Struct),
+            ("my_crate::io::Reader", ItemKind::Struct),
+        ]);
+
+        assert_eq!(search_paths(&index, "Reader")[0], "my_crate::io::Reader");
+    }
+
+    #[test]
+    fn search_boosts_exact_path_segment_matches() {
+        let index = test_index(&[
+            ("my_crate::sync::Mutex", ItemKind::Struct),
+            ("my_crate::syncing::Mutex", ItemKind::Struct),
+        ]);
 
-    if a_len == 0 {
-        return b_len;
+        assert_eq!(search_paths(&index, "sync")[0], "my_crate::sync::Mutex");
     }
-    if b_len == 0 {
-        return a_len;
+
+    // ========== deprecated_items tests ==========
+
+    #[test]
+    fn deprecated_items_includes_methods_and_skips_private_items() {
+        let deprecation = Deprecation {
+            since: Some("1.2.0".to_string()),
+            note: Some("use `new_api` instead".to_string()),
+        };
+        let mut index = test_index(&[
+            ("my_crate::old_api", ItemKind::Function),
+            ("my_crate::hidden_api", ItemKind::Function),
+            ("my_crate::Widget", ItemKind::Struct),
+        ]);
+        index
+            .items
+            .get_mut("my_crate::old_api")
+            .unwrap()
+            .deprecation = Some(deprecation.clone());
+        let hidden = index.items.get_mut("my_crate::hidden_api").unwrap();
+        hidden.deprecation = Some(deprecation.clone());
+        hidden.visibility = Visibility::Crate;
+        index.impl_blocks.insert(
+            "my_crate::Widget".to_string(),
+            vec![ImplBlock {
+                header: "impl Widget".to_string(),
+                trait_name: None,
+                trait_path: None,
+                provided_methods: Vec::new(),
+                methods: vec![MethodInfo {
+                    name: "draw".to_string(),
+                    signature: "pub fn draw(&self)".to_string(),
+                    doc: String::new(),
+                    is_required: false,
+                    is_unsafe: false,
+                    async_kind: None,
+                    is_hidden: false,
+                    deprecation: Some(deprecation),
+                    stability: None,
+                    span: None,
+                }],
+            }],
+        );
+
+        let entries = index.deprecated_items();
+        let paths: Vec<(&str, &str)> = entries
+            .iter()
+            .map(|e| (e.path.as_str(), e.kind.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("my_crate::Widget::draw", "method"),
+                ("my_crate::old_api", "fn")
+            ]
+        );
     }
 
-    let mut prev: Vec<usize> = (0..=b_len).collect();
-    let mut curr = vec![0; b_len + 1];
+    // ========== unsafe_report tests ==========
+
+    fn test_method(name: &str, is_unsafe: bool) -> MethodInfo {
+        MethodInfo {
+            name: name.to_string(),
+            signature: format!("fn {name}()"),
+            doc: String::new(),
+            is_required: true,
+            is_unsafe,
+            async_kind: None,
+            is_hidden: false,
+            deprecation: None,
+            stability: None,
+            span: None,
+        }
+    }
+
+    #[test]
+    fn unsafe_report_collects_functions_traits_and_methods() {
+        let mut index = test_index(&[
+            ("my_crate::danger", ItemKind::Function),
+            ("my_crate::safe", ItemKind::Function),
+            ("my_crate::RawAlloc", ItemKind::Trait),
+            ("my_crate::Plain", ItemKind::Trait),
+            ("my_crate::Buffer", ItemKind::Struct),
+        ]);
+        index.items.get_mut("my_crate::danger").unwrap().is_unsafe = true;
+        index
+            .items
+            .get_mut("my_crate::RawAlloc")
+            .unwrap()
+            .detail
+            .methods = vec![test_method("alloc", true), test_method("size", false)];
+        index
+            .items
+            .get_mut("my_crate::Plain")
+            .unwrap()
+            .detail
+            .methods = vec![test_method("run", false)];
+        index.impl_blocks.insert(
+            "my_crate::Buffer".to_string(),
+            vec![ImplBlock {
+                header: "impl Buffer".to_string(),
+                trait_name: None,
+                trait_path: None,
+                provided_methods: Vec::new(),
+                methods: vec![
+                    test_method("get_unchecked", true),
+                    test_method("get", false),
+                ],
+            }],
+        );
+
+        let report = index.unsafe_report();
+        assert_eq!(report.functions.len(), 1);
+        assert_eq!(report.functions[0].path, "my_crate::danger");
+        assert_eq!(report.traits.len(), 1);
+        assert_eq!(report.traits[0].0.path, "my_crate::RawAlloc");
+        assert_eq!(report.traits[0].1.len(), 1);
+        assert_eq!(report.methods.len(), 1);
+        assert_eq!(report.methods[0].0, "my_crate::Buffer");
+        assert_eq!(report.methods[0].1.name, "get_unchecked");
+    }
+
+    // ========== async_report tests ==========
+
+    #[test]
+    fn async_kind_classifies_by_keyword_and_return_type() {
+        assert_eq!(AsyncKind::classify(true, "u8"), Some(AsyncKind::AsyncFn));
+        assert_eq!(
+            AsyncKind::classify(false, "impl Future<Output = BoxStream<'static, u8>>"),
+            Some(AsyncKind::Future)
+        );
+        assert_eq!(
+            AsyncKind::classify(false, "Pin<Box<dyn Stream<Item = u8> + Send>>"),
+            Some(AsyncKind::Stream)
+        );
+        assert_eq!(AsyncKind::classify(false, "FutureResult<u8>"), None);
+    }
+
+    #[test]
+    fn async_report_includes_types_implementing_future() {
+        let mut index = test_index(&[
+            ("my_crate::fetch", ItemKind::Function),
+            ("my_crate::spawn", ItemKind::Function),
+            ("my_crate::parse", ItemKind::Function),
+            ("my_crate::JoinHandle", ItemKind::Struct),
+        ]);
+        index.items.get_mut("my_crate::fetch").unwrap().async_kind = Some(AsyncKind::AsyncFn);
+        index.items.get_mut("my_crate::spawn").unwrap().signature =
+            "pub fn spawn<F>(future: F) -> JoinHandle<F::Output>".to_string();
+        index.items.get_mut("my_crate::parse").unwrap().signature =
+            "pub fn parse(s: &str) -> u8".to_string();
+        let mut lines = test_method("lines", false);
+        lines.async_kind = Some(AsyncKind::Stream);
+        index.impl_blocks.insert(
+            "my_crate::JoinHandle".to_string(),
+            vec![
+                ImplBlock {
+                    header: "impl Future for JoinHandle".to_string(),
+                    trait_name: Some("Future".to_string()),
+                    trait_path: None,
+                    provided_methods: Vec::new(),
+                    methods: Vec::new(),
+                },
+                ImplBlock {
+                    header: "impl JoinHandle".to_string(),
+                    trait_name: None,
+                    trait_path: None,
+                    provided_methods: Vec::new(),
+                    methods: vec![lines, test_method("abort", false)],
+                },
+            ],
+        );
+
+        let report = index.async_report();
+        let functions: Vec<(&str, AsyncKind)> = report
+            .functions
+            .iter()
+            .map(|(item, kind)| (item.path.as_str(), *kind))
+            .collect();
+        assert_eq!(
+            functions,
+            [
+                ("my_crate::fetch", AsyncKind::AsyncFn),
+                ("my_crate::spawn", AsyncKind::Future)
+            ]
+        );
+        assert_eq!(report.methods.len(), 1);
+        assert_eq!(report.methods[0].1.name, "lines");
+    }
 
-    for (i, a_ch) in a.chars().enumerate() {
-        curr[0] = i + 1;
-        for (j, b_ch) in b.chars().enumerate() {
-            let cost = if a_ch == b_ch { 0 } else { 1 };
-            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+    #[test]
+    fn async_report_resolves_shared_type_names_by_path() {
+        let mut index = test_index(&[("my_crate::watch", ItemKind::Function)]);
+        index.items.get_mut("my_crate::watch").unwrap().signature =
+            "pub fn watch() -> Handle".to_string();
+        for (path, trait_name) in [
+            ("my_crate::b::Handle", "Stream"),
+            ("my_crate::a::Handle", "Future"),
+        ] {
+            index.impl_blocks.insert(
+                path.to_string(),
+                vec![ImplBlock {
+                    header: format!("impl {trait_name} for Handle"),
+                    trait_name: Some(trait_name.to_string()),
+                    trait_path: None,
+                    provided_methods: Vec::new(),
+                    methods: Vec::new(),
+                }],
+            );
         }
-        std::mem::swap(&mut prev, &mut curr);
+
+        let report = index.async_report();
+        assert_eq!(report.functions[0].1, AsyncKind::Future);
+    }
+
+    // ========== error_types tests ==========
+
+    #[test]
+    fn result_error_types_finds_error_argument() {
+        assert_eq!(
+            result_error_types("pub fn open(p: &Path) -> Result<File, io::Error>"),
+            ["Error"]
+        );
+        assert_eq!(
+            result_error_types("pub fn f() -> Result<Vec<(u8, u8)>, ParseError<'static>>"),
+            ["ParseError"]
+        );
+        assert!(result_error_types("pub fn f() -> io::Result<()>").is_empty());
+        assert_eq!(
+            result_error_types("pub type Result<T> = std::result::Result<T, Error>"),
+            ["Error"]
+        );
     }
 
-    prev[b_len]
+    #[test]
+    fn error_types_ranks_by_signals() {
+        let mut index = test_index(&[
+            ("my_crate::Error", ItemKind::Enum),
+            ("my_crate::ParseError", ItemKind::Struct),
+            ("my_crate::Failure", ItemKind::Struct),
+            ("my_crate::Widget", ItemKind::Struct),
+            ("my_crate::parse", ItemKind::Function),
+        ]);
+        index.items.get_mut("my_crate::parse").unwrap().signature =
+            "pub fn parse(s: &str) -> Result<Widget, Failure>".to_string();
+        index.impl_blocks.insert(
+            "my_crate::Error".to_string(),
+            vec![ImplBlock {
+                header: "impl Error for Error".to_string(),
+                trait_name: Some("Error".to_string()),
+                trait_path: None,
+                provided_methods: Vec::new(),
+                methods: Vec::new(),
+            }],
+        );
+
+        let found: Vec<&str> = index
+            .error_types()
+            .iter()
+            .map(|e| e.item.path.as_str())
+            .collect();
+        assert_eq!(
+            found,
+            [
+                "my_crate::Error",
+                "my_crate::Failure",
+                "my_crate::ParseError"
+            ]
+        );
+    }
+
+    // ========== method_origins tests ==========
+
+    #[test]
+    fn method_origins_finds_traits_impls_and_inherent_methods() {
+        let mut index = test_index(&[
+            ("my_crate::Stream", ItemKind::Trait),
+            ("my_crate::Events", ItemKind::Struct),
+            ("my_crate::Poller", ItemKind::Struct),
+        ]);
+        index
+            .items
+            .get_mut("my_crate::Stream")
+            .unwrap()
+            .detail
+            .methods = vec![test_method("poll_next", true)];
+        index.impl_blocks.insert(
+            "my_crate::Events".to_string(),
+            vec![ImplBlock {
+                header: "impl Stream for Events".to_string(),
+                trait_name: Some("Stream".to_string()),
+                trait_path: None,
+                provided_methods: Vec::new(),
+                methods: vec![test_method("poll_next", false)],
+            }],
+        );
+        index.impl_blocks.insert(
+            "my_crate::Poller".to_string(),
+            vec![ImplBlock {
+                header: "impl Poller".to_string(),
+                trait_name: None,
+                trait_path: None,
+                provided_methods: Vec::new(),
+                methods: vec![test_method("poll_next", false)],
+            }],
+        );
+
+        let origins = index.method_origins("Events::poll_next");
+        assert_eq!(origins.trait_defs.len(), 1);
+        assert_eq!(origins.trait_defs[0].0.path, "my_crate::Stream");
+        assert_eq!(
+            origins.trait_impls,
+            [("Stream".to_string(), vec!["my_crate::Events"])]
+        );
+        assert_eq!(origins.inherent.len(), 1);
+        assert_eq!(origins.inherent[0].0, "my_crate::Poller");
+    }
+
+    #[test]
+    fn search_finds_methods_in_impl_blocks() {
+        let mut index = test_index(&[("my_crate::sync::Mutex", ItemKind::Struct)]);
+        let mut method = test_method("try_lock", false);
+        method.doc = "Attempts to acquire the lock without waiting.".to_string();
+        index.impl_blocks.insert(
+            "my_crate::sync::Mutex".to_string(),
+            vec![ImplBlock {
+                header: "impl Mutex".to_string(),
+                trait_name: None,
+                trait_path: None,
+                provided_methods: Vec::new(),
+                methods: vec![method],
+            }],
+        );
+
+        let results = index.search("try_lock", 10, false, false, false);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].item.path, "my_crate::sync::Mutex::try_lock");
+        assert_eq!(results[0].owner.as_deref(), Some("my_crate::sync::Mutex"));
+        assert_eq!(
+            index
+                .search("without waiting", 10, false, false, false)
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn search_covers_trait_method_docs_and_headings() {
+        let mut index = test_index(&[("my_crate::StreamExt", ItemKind::Trait)]);
+        let mut next = test_method("next", false);
+        next.doc =
+            "Returns the next item.\n\n# Cancel safety\n\nThis method is cancel safe.".to_string();
+        index
+            .items
+            .get_mut("my_crate::StreamExt")
+            .unwrap()
+            .detail
+            .methods = vec![next];
+
+        let results = index.search("cancel safety", 10, false, false, false);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].item.path, "my_crate::StreamExt::next");
+        assert_eq!(results[0].owner.as_deref(), Some("my_crate::StreamExt"));
+        assert_eq!(results[0].heading.as_deref(), Some("Cancel safety"));
+    }
+
+    // ========== find_definitions tests ==========
+
+    #[test]
+    fn find_definitions_matches_name_or_path_suffix() {
+        let index = test_index(&[
+            ("my_crate::de::Deserializer", ItemKind::Trait),
+            ("my_crate::json::Deserializer", ItemKind::Struct),
+            ("my_crate::Deserialize", ItemKind::Trait),
+        ]);
+
+        assert_eq!(index.find_definitions("Deserializer").len(), 2);
+        let found = index.find_definitions("de::Deserializer");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, "my_crate::de::Deserializer");
+        assert_eq!(index.find_definitions("deserialize").len(), 1);
+        assert!(index.find_definitions("Serializer").is_empty());
+    }
+
+    // ========== ItemOrder tests ==========
+
+    #[test]
+    fn item_order_sorts_by_name_kind_or_doc_length() {
+        let mut items = vec![
+            test_item("my_crate::spawn", ItemKind::Function),
+            test_item("my_crate::Runtime", ItemKind::Struct),
+            test_item("my_crate::sync", ItemKind::Module),
+            test_item("my_crate::Builder", ItemKind::Struct),
+        ];
+        items[0].doc = "Spawns a task.".to_string();
+        items[3].doc = "Builds a runtime with custom settings.".to_string();
+        let order = |items: &mut Vec<IndexedItem>, order: ItemOrder| {
+            items.sort_by(|a, b| order.compare(a, b));
+            items.iter().map(|i| i.name.clone()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            order(&mut items, ItemOrder::Alpha),
+            ["Builder", "Runtime", "spawn", "sync"]
+        );
+        assert_eq!(
+            order(&mut items, ItemOrder::Kind),
+            ["sync", "spawn", "Builder", "Runtime"]
+        );
+        assert_eq!(
+            order(&mut items, ItemOrder::DocLength),
+            ["Builder", "spawn", "Runtime", "sync"]
+        );
+    }
+
+    // ========== module_local_reexports tests ==========
+
+    #[test]
+    fn module_local_reexports_lists_items_once_under_canonical_path() {
+        let mut index = test_index(&[
+            ("my_crate::Client", ItemKind::Struct),
+            ("my_crate::connect", ItemKind::Function),
+        ]);
+        if let Some(client) = index.items.get_mut("my_crate::Client") {
+            client.reexports = vec!["my_crate::prelude::Client".to_string()];
+        }
+
+        let prelude = index.module_local_reexports(Some("my_crate::prelude"));
+        assert_eq!(prelude.len(), 1);
+        assert_eq!(prelude[0].path, "my_crate::Client");
+        assert!(index.module_local_reexports(None).is_empty());
+    }
+
+    // ========== foreign_reexport tests ==========
+
+    #[test]
+    fn foreign_reexport_resolves_relative_and_package_paths() {
+        let mut index = test_index(&[("my_crate::web", ItemKind::Module)]);
+        for (path, target) in [
+            ("my_crate::web::Bytes", "bytes::bytes::Bytes"),
+            ("my_crate::Buf", "bytes::buf::Buf"),
+        ] {
+            index.foreign_reexports.insert(
+                path.to_string(),
+                ForeignReexport {
+                    path: path.to_string(),
+                    target_path: target.to_string(),
+                    crate_name: "bytes".to_string(),
+                    kind: Some(ItemKind::Struct),
+                },
+            );
+        }
+
+        let found = index.foreign_reexport("web::Bytes").unwrap();
+        assert_eq!(found.target_path, "bytes::bytes::Bytes");
+        assert!(index.foreign_reexport("my_crate::web::Bytes").is_some());
+        assert!(index.foreign_reexport("web::Buf").is_none());
+
+        let root: Vec<&str> = index
+            .module_reexports(None)
+            .iter()
+            .map(|r| r.path.as_str())
+            .collect();
+        assert_eq!(root, ["my_crate::Buf"]);
+        assert_eq!(index.module_reexports(Some("my_crate::web")).len(), 1);
+    }
+
+    // ========== trait_methods tests ==========
+
+    #[test]
+    fn trait_methods_falls_back_to_the_unique_trait_name() {
+        let mut index = test_index(&[
+            ("my_crate::iter::Iterator", ItemKind::Trait),
+            ("my_crate::Read", ItemKind::Trait),
+            ("my_crate::io::Read", ItemKind::Trait),
+        ]);
+        index
+            .items
+            .get_mut("my_crate::iter::Iterator")
+            .unwrap()
+            .detail
+            .methods = vec![test_method("next", true), test_method("map", false)];
+
+        let methods = index
+            .trait_methods("my_crate::iter::traits::iterator::Iterator")
+            .unwrap();
+        assert_eq!(methods.len(), 2);
+        assert!(index.trait_methods("my_crate::iter::Iterator").is_some());
+        assert!(index.trait_methods("my_crate::other::Read").is_none());
+        assert!(index.trait_methods("my_crate::Missing").is_none());
+    }
+
+    // ========== suggest_similar tests ==========
+
+    fn suggestion_paths(index: &CrateIndex, query: &str) -> Vec<String> {
+        index
+            .suggest_similar(query, 5)
+            .into_iter()
+            .map(|s| s.path)
+            .collect()
+    }
+
+    #[test]
+    fn suggest_similar_prefers_implied_kind() {
+        let index = test_index(&[
+            ("my_crate::Sender", ItemKind::Struct),
+            ("my_crate::sender", ItemKind::Function),
+        ]);
+
+        assert_eq!(suggestion_paths(&index, "Sendr")[0], "my_crate::Sender");
+        assert_eq!(suggestion_paths(&index, "sendr")[0], "my_crate::sender");
+    }
+
+    #[test]
+    fn suggest_similar_suggests_methods_for_method_paths() {
+        let mut index = test_index(&[("my_crate::Widget", ItemKind::Struct)]);
+        index.impl_blocks.insert(
+            "my_crate::Widget".to_string(),
+            vec![ImplBlock {
+                header: "impl Widget".to_string(),
+                trait_name: None,
+                trait_path: None,
+                provided_methods: Vec::new(),
+                methods: vec![MethodInfo {
+                    name: "render".to_string(),
+                    signature: "pub fn render(&self)".to_string(),
+                    doc: "Draw the widget.".to_string(),
+                    is_required: false,
+                    is_unsafe: false,
+                    async_kind: None,
+                    is_hidden: false,
+                    deprecation: None,
+                    stability: None,
+                    span: None,
+                }],
+            }],
+        );
+
+        let suggestions = index.suggest_similar("Widget::rendr", 5);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].path, "my_crate::Widget::render");
+        assert_eq!(suggestions[0].kind, "method");
+        assert_eq!(suggestions[0].short_doc, "Draw the widget.");
+    }
+
+    #[test]
+    fn suggest_similar_does_not_depend_on_index_order() {
+        // More equally close names than the trigram index keeps candidates for
+        let paths: Vec<String> = (0..80).map(|i| format!("my_crate::Item{i:02}")).collect();
+        let kinds: Vec<(&str, ItemKind)> = paths
+            .iter()
+            .map(|path| (path.as_str(), ItemKind::Struct))
+            .collect();
+        let mut index = test_index(&kinds);
+        let forward: Vec<IndexedItem> = paths.iter().map(|p| index.items[p].clone()).collect();
+
+        index.name_index = NameIndex::build(forward.iter());
+        let first = suggestion_paths(&index, "Itemm");
+        index.name_index = NameIndex::build(forward.iter().rev());
+        assert_eq!(suggestion_paths(&index, "Itemm"), first);
+        assert_eq!(first[0], "my_crate::Item00");
+    }
+
+    #[test]
+    fn suggest_similar_matches_per_segment() {
+        let index = test_index(&[
+            ("my_crate::sync::Mutex", ItemKind::Struct),
+            ("my_crate::lock::Mutex", ItemKind::Struct),
+            ("my_crate::sync::Barrier", ItemKind::Struct),
+        ]);
+
+        let paths = suggestion_paths(&index, "sync::Mutx");
+        assert_eq!(paths[0], "my_crate::sync::Mutex");
+        assert!(!paths.contains(&"my_crate::sync::Barrier".to_string()));
+    }
+
+    // ========== complete_paths tests ==========
+
+    #[test]
+    fn complete_paths_matches_relative_prefix_shallowest_first() {
+        let index = test_index(&[
+            ("my_crate::sync", ItemKind::Module),
+            ("my_crate::sync::Mutex", ItemKind::Struct),
+            ("my_crate::sync::mpsc", ItemKind::Module),
+            ("my_crate::sync::mpsc::Sender", ItemKind::Struct),
+            ("my_crate::Sink", ItemKind::Trait),
+        ]);
+
+        assert_eq!(
+            index.complete_paths("s", false),
+            [
+                "Sink",
+                "sync",
+                "sync::Mutex",
+                "sync::mpsc",
+                "sync::mpsc::Sender"
+            ]
+        );
+        assert_eq!(
+            index.complete_paths("my_crate::sync::m", false),
+            ["sync::Mutex", "sync::mpsc", "sync::mpsc::Sender"]
+        );
+        assert_eq!(index.complete_paths("sync", true), ["sync", "sync::mpsc"]);
+    }
+
+    // ========== glob_match tests ==========
+
+    #[test]
+    fn glob_match_star_matches_single_segment() {
+        assert!(glob_match("tokio::sync::*", "tokio::sync::Mutex"));
+        assert!(!glob_match("tokio::sync::*", "tokio::sync::mpsc::Sender"));
+        assert!(!glob_match("tokio::sync::*", "tokio::net::TcpStream"));
+    }
+
+    #[test]
+    fn glob_match_prefix_and_suffix_wildcards() {
+        assert!(glob_match("tokio::net::Tcp*", "tokio::net::TcpStream"));
+        assert!(glob_match("tokio::net::Tcp*", "tokio::net::Tcp"));
+        assert!(glob_match("tokio::*::*Stream", "tokio::net::TcpStream"));
+        assert!(!glob_match("tokio::net::Tcp*", "tokio::net::UdpSocket"));
+    }
+
+    #[test]
+    fn glob_match_without_wildcard_is_exact() {
+        assert!(glob_match("serde::Serialize", "serde::Serialize"));
+        assert!(!glob_match("serde::Serialize", "serde::Serializer"));
+    }
 }
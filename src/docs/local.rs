@@ -0,0 +1,537 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cargo_lock::Version;
+
+use crate::error::Error;
+use crate::workspace::{LocalPackage, dependency_lib_name};
+
+/// Cargo features to build a crate's docs with, instead of the single combination
+/// docs.rs builds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureSet {
+    pub features: Vec<String>,
+    pub no_default_features: bool,
+}
+
+impl FeatureSet {
+    /// Parse a comma- or space-separated feature list, as taken by `cargo --features`.
+    pub fn parse(features: &str, no_default_features: bool) -> Self {
+        let mut features: Vec<String> = features
+            .split([',', ' '])
+            .filter(|f| !f.is_empty())
+            .map(str::to_string)
+            .collect();
+        features.sort();
+        features.dedup();
+        Self {
+            features,
+            no_default_features,
+        }
+    }
+
+    /// Short description for versions and messages, e.g. "features: rt, macros; no default".
+    pub fn describe(&self) -> String {
+        let features = if self.features.is_empty() {
+            "features: none".to_string()
+        } else {
+            format!("features: {}", self.features.join(", "))
+        };
+        if self.no_default_features {
+            format!("{features}; no default")
+        } else {
+            features
+        }
+    }
+
+    /// The feature flags of a cargo command.
+    fn cargo_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if !self.features.is_empty() {
+            args.push("--features".to_string());
+            args.push(self.features.join(","));
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".to_string());
+        }
+        args
+    }
+}
+
+/// Build rustdoc JSON for a local package and return the uncompressed JSON bytes.
+///
+/// Runs `cargo +nightly rustdoc -p {name}@{version} --lib -Z unstable-options --output-format json`
+/// (JSON output is still nightly-only) from the workspace root, so dependencies
+/// resolve through the workspace's Cargo.lock (cargo clones git dependencies at
+/// their pinned revision if needed), then reads `{target_dir}/doc/{lib_name}.json`.
+pub async fn build_rustdoc_json(
+    package: &LocalPackage,
+    workspace_root: &Path,
+    target_dir: &Path,
+    document_private: bool,
+    features: Option<&FeatureSet>,
+) -> Result<Vec<u8>, Error> {
+    let build_error = |message: String| Error::LocalBuild {
+        crate_name: package.name.clone(),
+        message,
+    };
+
+    let Some(lib_name) = &package.lib_name else {
+        return Err(build_error("package has no library target".to_string()));
+    };
+
+    let mut cmd = tokio::process::Command::new("cargo");
+    cmd.current_dir(workspace_root)
+        .arg("+nightly")
+        .arg("rustdoc")
+        .arg("-p")
+        .arg(format!("{}@{}", package.name, package.version))
+        .args(["--lib", "-Z", "unstable-options", "--output-format", "json"]);
+    if let Some(features) = features {
+        cmd.args(features.cargo_args());
+    }
+    if document_private {
+        cmd.args(["--", "--document-private-items"]);
+    }
+
+    tracing::info!(
+        "Building rustdoc JSON for {}@{} ({:?})",
+        package.name,
+        package.version,
+        package.source
+    );
+    run_rustdoc(
+        cmd,
+        &target_dir.join("doc").join(format!("{lib_name}.json")),
+    )
+    .await
+    .map_err(build_error)
+}
+
+/// The rustdoc JSON an earlier JSON doc build (`cargo rustdoc --output-format json`, or
+/// this server's own) left at `{target_dir}/doc/{lib_name}.json`, with when it was
+/// written, if that is no older than any file of the package. Blocking.
+pub fn prebuilt_rustdoc_json(
+    package: &LocalPackage,
+    target_dir: &Path,
+) -> Option<(PathBuf, SystemTime)> {
+    let lib_name = package.lib_name.as_ref()?;
+    let path = target_dir.join("doc").join(format!("{lib_name}.json"));
+    let built_at = std::fs::metadata(&path).ok()?.modified().ok()?;
+    let newest_source = newest_source_change(package.manifest_path.parent()?)
+        .inspect_err(|e| tracing::debug!("Failed to check sources of {}: {e}", package.name))
+        .ok()?;
+    (built_at >= newest_source).then_some((path, built_at))
+}
+
+/// When a file of a package directory last changed, skipping the same entries as
+/// `source_hash`.
+fn newest_source_change(package_dir: &Path) -> std::io::Result<SystemTime> {
+    let mut files = Vec::new();
+    collect_source_files(package_dir, package_dir, &mut files)?;
+    let mut newest = UNIX_EPOCH;
+    for rel in &files {
+        newest = newest.max(std::fs::metadata(package_dir.join(rel))?.modified()?);
+    }
+    Ok(newest)
+}
+
+/// Build rustdoc JSON for a crates.io crate with a custom feature set.
+///
+/// docs.rs builds one feature combination, so this generates a scratch project under
+/// `scratch_root` depending on just `crate_name` (pinned to `version`, any version for
+/// "latest") with `features`, and runs `cargo +nightly rustdoc -p {crate_name}` there.
+/// Each feature set gets its own target directory, so concurrent builds of one crate
+/// with different features don't read each other's JSON. The JSON file is named after
+/// the lib target, which `cargo metadata` gives (it can differ from the package name).
+pub async fn build_with_features(
+    crate_name: &str,
+    version: &str,
+    features: &FeatureSet,
+    scratch_root: &Path,
+) -> Result<Vec<u8>, Error> {
+    let build_error = |message: String| Error::LocalBuild {
+        crate_name: crate_name.to_string(),
+        message,
+    };
+    // Both end up in a directory name and the scratch manifest
+    check_scratch_dependency(crate_name, version).map_err(build_error)?;
+
+    let project_dir = scratch_root.join(format!(
+        "{crate_name}-{}-{:016x}",
+        version,
+        fnv1a(FNV_OFFSET, features.describe().as_bytes())
+    ));
+    tokio::fs::create_dir_all(&project_dir)
+        .await
+        .map_err(|e| build_error(format!("failed to create {}: {e}", project_dir.display())))?;
+    for (file, contents) in [
+        (
+            "Cargo.toml",
+            scratch_manifest(crate_name, version, features),
+        ),
+        ("lib.rs", String::new()),
+    ] {
+        tokio::fs::write(project_dir.join(file), contents)
+            .await
+            .map_err(|e| build_error(format!("failed to write {file}: {e}")))?;
+    }
+
+    let target_dir = project_dir.join("target");
+    let mut cmd = tokio::process::Command::new("cargo");
+    cmd.current_dir(&project_dir)
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .arg("+nightly")
+        .arg("rustdoc")
+        .arg("-p")
+        .arg(crate_name)
+        .args(["--lib", "-Z", "unstable-options", "--output-format", "json"]);
+
+    tracing::info!(
+        "Building rustdoc JSON for {crate_name} v{version} ({})",
+        features.describe()
+    );
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| build_error(format!("failed to run cargo: {e}")))?;
+    check_rustdoc_output(&output).map_err(build_error)?;
+
+    let metadata_query = (
+        project_dir.clone(),
+        crate_name.to_string(),
+        (version != "latest").then(|| version.to_string()),
+    );
+    let lib_name = tokio::task::spawn_blocking(move || {
+        let (project_dir, crate_name, version) = metadata_query;
+        dependency_lib_name(&project_dir, &crate_name, version.as_deref())
+    })
+    .await
+    .ok()
+    .flatten()
+    .unwrap_or_else(|| crate_name.replace('-', "_"));
+    read_rustdoc_json(&target_dir.join("doc").join(format!("{lib_name}.json")))
+        .await
+        .map_err(build_error)
+}
+
+/// Reject a crate name that isn't a crates.io identifier and a version that is neither
+/// "latest", a semver version, nor a partial one like "0.8".
+fn check_scratch_dependency(crate_name: &str, version: &str) -> Result<(), String> {
+    let valid_name = !crate_name.is_empty()
+        && crate_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_name {
+        return Err(format!("invalid crate name {crate_name:?}"));
+    }
+    let partial_version = || {
+        let parts: Vec<&str> = version.split('.').collect();
+        parts.len() <= 3
+            && parts
+                .iter()
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+    };
+    if version != "latest" && Version::parse(version).is_err() && !partial_version() {
+        return Err(format!("invalid version {version:?}"));
+    }
+    Ok(())
+}
+
+/// Manifest of a scratch project that only depends on `crate_name` with `features`.
+fn scratch_manifest(crate_name: &str, version: &str, features: &FeatureSet) -> String {
+    let requirement = if version == "latest" {
+        "*".to_string()
+    } else {
+        format!("={version}")
+    };
+    let feature_list: Vec<String> = features.features.iter().map(|f| format!("{f:?}")).collect();
+    format!(
+        "[package]\nname = \"docsrs-mcp-scratch\"\nversion = \"0.0.0\"\nedition = \"2021\"\n\n\
+         [lib]\npath = \"lib.rs\"\n\n\
+         [dependencies]\n{crate_name} = {{ version = \"{requirement}\", default-features = {}, features = [{}] }}\n",
+        !features.no_default_features,
+        feature_list.join(", ")
+    )
+}
+
+/// Run a `cargo rustdoc` command and read the JSON it writes to `json_path`.
+/// Errors are messages for `Error::LocalBuild`, ending with the tail of cargo's output.
+async fn run_rustdoc(
+    mut cmd: tokio::process::Command,
+    json_path: &Path,
+) -> Result<Vec<u8>, String> {
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| format!("failed to run cargo: {e}"))?;
+    check_rustdoc_output(&output)?;
+    read_rustdoc_json(json_path).await
+}
+
+/// Fail with the tail of cargo's output if a `cargo rustdoc` run failed.
+fn check_rustdoc_output(output: &std::process::Output) -> Result<(), String> {
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let tail: Vec<&str> = stderr.lines().rev().take(10).collect();
+        let tail: Vec<&str> = tail.into_iter().rev().collect();
+        return Err(format!(
+            "cargo rustdoc failed (a nightly toolchain is required: `rustup toolchain install nightly`):\n{}",
+            tail.join("\n")
+        ));
+    }
+
+    Ok(())
+}
+
+async fn read_rustdoc_json(json_path: &Path) -> Result<Vec<u8>, String> {
+    tokio::fs::read(json_path)
+        .await
+        .map_err(|e| format!("failed to read {}: {e}", json_path.display()))
+}
+
+/// Suffix of a cached local build's version key for the build options that change
+/// its output: private items (`-private`) and a custom feature set (`-features-{hash}`).
+pub fn build_key_suffix(document_private: bool, features: Option<&FeatureSet>) -> String {
+    let mut suffix = String::new();
+    if document_private {
+        suffix.push_str("-private");
+    }
+    if let Some(features) = features {
+        suffix.push_str(&format!(
+            "-features-{:016x}",
+            fnv1a(FNV_OFFSET, features.describe().as_bytes())
+        ));
+    }
+    suffix
+}
+
+/// Hash the sources of a package directory, for keying cached local builds.
+///
+/// Covers every file's relative path and contents, skipping `target/` and hidden
+/// entries (e.g. `.git`). Uses FNV-1a so the hash is stable across Rust releases.
+pub fn source_hash(package_dir: &Path) -> std::io::Result<u64> {
+    let mut files = Vec::new();
+    collect_source_files(package_dir, package_dir, &mut files)?;
+    files.sort();
+
+    let mut hash = FNV_OFFSET;
+    for rel in &files {
+        hash = fnv1a(hash, rel.to_string_lossy().as_bytes());
+        hash = fnv1a(hash, &[0]);
+        hash = fnv1a(hash, &std::fs::read(package_dir.join(rel))?);
+    }
+    Ok(hash)
+}
+
+fn collect_source_files(
+    root: &Path,
+    dir: &Path,
+    files: &mut Vec<std::path::PathBuf>,
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "target" {
+            continue;
+        }
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_source_files(root, &path, files)?;
+        } else if let Ok(rel) = path.strip_prefix(root) {
+            files.push(rel.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+pub const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Continue an FNV-1a hash (start from `FNV_OFFSET`) over `bytes`.
+pub fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        hash ^= u64::from(b);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_package(dir: &Path, lib_rs: &str) {
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("Cargo.toml"), "[package]\nname = \"p\"\n").unwrap();
+        std::fs::write(dir.join("src/lib.rs"), lib_rs).unwrap();
+    }
+
+    // ========== FeatureSet tests ==========
+
+    #[test]
+    fn feature_set_parses_and_describes_features() {
+        let features = FeatureSet::parse("rt, macros,rt", true);
+        assert_eq!(features.features, ["macros", "rt"]);
+        assert_eq!(features.describe(), "features: macros, rt; no default");
+        assert_eq!(
+            features.cargo_args(),
+            ["--features", "macros,rt", "--no-default-features"]
+        );
+        assert_eq!(FeatureSet::parse("", false).describe(), "features: none");
+    }
+
+    #[test]
+    fn scratch_manifest_pins_version_and_features() {
+        let features = FeatureSet::parse("rt,macros", true);
+        let manifest = scratch_manifest("tokio", "1.38.0", &features);
+        assert!(manifest.contains(
+            r#"tokio = { version = "=1.38.0", default-features = false, features = ["macros", "rt"] }"#
+        ));
+        let manifest = scratch_manifest("tokio", "latest", &FeatureSet::default());
+        assert!(manifest.contains(r#"version = "*", default-features = true, features = [] }"#));
+    }
+
+    #[tokio::test]
+    async fn build_with_features_rejects_names_and_versions_outside_the_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let scratch_root = dir.path().join("scratch");
+        for (crate_name, version) in [
+            ("../x", "1.0.0"),
+            ("tokio/../../x", "latest"),
+            ("", "1.0.0"),
+            ("tokio", r#"1.0" , path = "/tmp"#),
+            ("tokio", "../1.0"),
+            ("tokio", "1.0.0.0"),
+        ] {
+            let result =
+                build_with_features(crate_name, version, &FeatureSet::default(), &scratch_root)
+                    .await;
+            assert!(
+                matches!(result, Err(Error::LocalBuild { .. })),
+                "{crate_name} {version}"
+            );
+        }
+        // Nothing was written, inside the scratch root or next to it
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        for (crate_name, version) in [
+            ("tokio-util", "0.7.11"),
+            ("serde_json", "latest"),
+            ("rand", "0.8"),
+            ("wasm_bindgen", "0.2.93-alpha.1"),
+        ] {
+            assert_eq!(check_scratch_dependency(crate_name, version), Ok(()));
+        }
+    }
+
+    // ========== prebuilt_rustdoc_json tests ==========
+
+    #[test]
+    fn prebuilt_rustdoc_json_is_used_only_when_newer_than_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        write_package(dir.path(), "pub fn a() {}");
+        let target_dir = dir.path().join("target");
+        std::fs::create_dir_all(target_dir.join("doc")).unwrap();
+        let json_path = target_dir.join("doc/my_crate.json");
+        std::fs::write(&json_path, "{}").unwrap();
+        let package = LocalPackage {
+            name: "my-crate".to_string(),
+            version: "0.1.0".to_string(),
+            manifest_path: dir.path().join("Cargo.toml"),
+            lib_name: Some("my_crate".to_string()),
+            source: crate::workspace::PackageSource::Member,
+        };
+        let set_modified = |path: &Path, secs: u64| {
+            std::fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(UNIX_EPOCH + std::time::Duration::from_secs(secs))
+                .unwrap();
+        };
+        set_modified(&dir.path().join("Cargo.toml"), 1_000);
+        set_modified(&dir.path().join("src/lib.rs"), 1_000);
+        set_modified(&json_path, 2_000);
+
+        let (path, built_at) = prebuilt_rustdoc_json(&package, &target_dir).unwrap();
+        assert_eq!(path, json_path);
+        assert_eq!(built_at, UNIX_EPOCH + std::time::Duration::from_secs(2_000));
+
+        // Edited since the docs were built
+        set_modified(&dir.path().join("src/lib.rs"), 3_000);
+        assert_eq!(prebuilt_rustdoc_json(&package, &target_dir), None);
+
+        let no_lib = LocalPackage {
+            lib_name: None,
+            ..package
+        };
+        assert_eq!(prebuilt_rustdoc_json(&no_lib, &target_dir), None);
+    }
+
+    // ========== build_key_suffix tests ==========
+
+    #[test]
+    fn build_key_suffix_separates_build_options() {
+        let rt = FeatureSet::parse("rt", false);
+        let suffixes = [
+            build_key_suffix(false, None),
+            build_key_suffix(true, None),
+            build_key_suffix(false, Some(&rt)),
+            build_key_suffix(true, Some(&rt)),
+            build_key_suffix(false, Some(&FeatureSet::parse("rt", true))),
+            build_key_suffix(false, Some(&FeatureSet::parse("macros", false))),
+        ];
+        assert_eq!(suffixes[0], "");
+        assert_eq!(suffixes[1], "-private");
+        assert!(suffixes[3].starts_with("-private-features-"));
+        for (i, a) in suffixes.iter().enumerate() {
+            for b in &suffixes[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+        // Feature order doesn't matter
+        assert_eq!(
+            build_key_suffix(false, Some(&FeatureSet::parse("rt,macros", false))),
+            build_key_suffix(false, Some(&FeatureSet::parse("macros rt", false)))
+        );
+    }
+
+    // ========== source_hash tests ==========
+
+    #[test]
+    fn source_hash_is_deterministic() {
+        let dir = tempfile::tempdir().unwrap();
+        write_package(dir.path(), "pub fn a() {}");
+
+        assert_eq!(
+            source_hash(dir.path()).unwrap(),
+            source_hash(dir.path()).unwrap()
+        );
+    }
+
+    #[test]
+    fn source_hash_changes_with_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        write_package(dir.path(), "pub fn a() {}");
+        let before = source_hash(dir.path()).unwrap();
+
+        std::fs::write(dir.path().join("src/lib.rs"), "pub fn b() {}").unwrap();
+        assert_ne!(before, source_hash(dir.path()).unwrap());
+    }
+
+    #[test]
+    fn source_hash_ignores_target_and_hidden_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        write_package(dir.path(), "pub fn a() {}");
+        let before = source_hash(dir.path()).unwrap();
+
+        std::fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        std::fs::write(dir.path().join("target/debug/out"), "build output").unwrap();
+        std::fs::create_dir_all(dir.path().join(".git")).unwrap();
+        std::fs::write(dir.path().join(".git/HEAD"), "ref").unwrap();
+
+        assert_eq!(before, source_hash(dir.path()).unwrap());
+    }
+}
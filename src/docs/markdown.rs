@@ -0,0 +1,694 @@
+use super::doctests::fence_mode;
+
+/// HTML elements recognized in doc comments. Anything else in angle brackets (e.g. a
+/// stray `Vec<T>` or an `<https://…>` autolink) is left untouched.
+const HTML_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "article",
+    "b",
+    "big",
+    "blockquote",
+    "br",
+    "caption",
+    "center",
+    "code",
+    "dd",
+    "del",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "font",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "ins",
+    "kbd",
+    "li",
+    "ol",
+    "p",
+    "picture",
+    "pre",
+    "s",
+    "script",
+    "section",
+    "small",
+    "source",
+    "span",
+    "strike",
+    "strong",
+    "style",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "tt",
+    "u",
+    "ul",
+    "var",
+];
+
+/// Convert raw HTML in a doc comment to markdown.
+///
+/// Emphasis, code, links, images, lists, headings, and tables become their markdown
+/// equivalents; layout elements (`<div>`, `<span>`, `<details>`, …) are stripped but
+/// keep their content, and `<script>`/`<style>` are dropped entirely. Common entities
+/// are decoded. Fenced code blocks and inline code spans are copied unchanged.
+pub fn html_to_markdown(doc: &str) -> String {
+    if !doc.contains('<') && !doc.contains('&') {
+        return doc.to_string();
+    }
+
+    let mut converter = Converter::default();
+    let mut text = String::new();
+    let mut fence: Option<&str> = None;
+    for line in doc.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        match fence {
+            Some(marker) => {
+                converter.push_raw(line);
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+            }
+            None if trimmed.starts_with("```") || trimmed.starts_with("~~~") => {
+                converter.convert(&std::mem::take(&mut text));
+                converter.push_raw(line);
+                fence = Some(&trimmed[..3]);
+            }
+            None => text.push_str(line),
+        }
+    }
+    converter.convert(&text);
+    converter.finish()
+}
+
+/// A table being collected from `<tr>`/`<td>` tags.
+#[derive(Default)]
+struct Table {
+    rows: Vec<Vec<String>>,
+    cell: Option<String>,
+}
+
+#[derive(Default)]
+struct Converter {
+    out: String,
+    table: Option<Table>,
+    /// Targets of the open `<a>` tags (`None` for anchors without `href`).
+    links: Vec<Option<String>>,
+    /// Open lists: `None` for `<ul>`, the next number for `<ol>`.
+    lists: Vec<Option<usize>>,
+}
+
+impl Converter {
+    /// Append text, into the current table cell if inside one.
+    fn push(&mut self, s: &str) {
+        match self.table.as_mut() {
+            Some(Table {
+                cell: Some(cell), ..
+            }) => cell.push_str(s),
+            Some(_) => {} // text between cells
+            None => self.out.push_str(s),
+        }
+    }
+
+    /// Append code copied verbatim.
+    fn push_raw(&mut self, s: &str) {
+        if self.table.is_none() {
+            self.out.push_str(s);
+        }
+    }
+
+    /// Convert HTML in markdown text (outside fenced code blocks).
+    fn convert(&mut self, text: &str) {
+        let mut rest = text;
+        while let Some(i) = rest.find(['<', '`', '&']) {
+            self.push(&rest[..i]);
+            rest = &rest[i..];
+            rest = match rest.as_bytes()[0] {
+                b'`' => self.code_span(rest),
+                b'&' => self.entity(rest),
+                _ => self.tag(rest),
+            };
+        }
+        self.push(rest);
+    }
+
+    /// Copy an inline code span; returns the text after it.
+    fn code_span<'a>(&mut self, text: &'a str) -> &'a str {
+        let ticks = text.len() - text.trim_start_matches('`').len();
+        let end = text[ticks..]
+            .find(&text[..ticks])
+            .map(|i| ticks + i + ticks)
+            .unwrap_or(ticks);
+        self.push(&text[..end]);
+        &text[end..]
+    }
+
+    /// Decode an entity like `&lt;` or `&#39;`; returns the text after it.
+    fn entity<'a>(&mut self, text: &'a str) -> &'a str {
+        let decoded = text[1..].split_once(';').and_then(|(name, _)| {
+            let ch = match name {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => ' ',
+                _ => {
+                    let code = name.strip_prefix('#')?;
+                    let code = match code.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => code.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((ch, name.len() + 2))
+        });
+        match decoded {
+            Some((ch, len)) => {
+                self.push(ch.encode_utf8(&mut [0; 4]));
+                &text[len..]
+            }
+            None => {
+                self.push("&");
+                &text[1..]
+            }
+        }
+    }
+
+    /// Convert the tag at the start of `text`; returns the text after it.
+    fn tag<'a>(&mut self, text: &'a str) -> &'a str {
+        if let Some(comment) = text.strip_prefix("<!--") {
+            return comment.split_once("-->").map_or("", |(_, rest)| rest);
+        }
+        let Some((name, attrs, closing, rest)) = parse_tag(text) else {
+            self.push("<");
+            return &text[1..];
+        };
+        if !closing && matches!(name.as_str(), "script" | "style") {
+            let end = format!("</{name}");
+            return rest
+                .find(&end)
+                .and_then(|i| rest[i..].find('>').map(|j| &rest[i + j + 1..]))
+                .unwrap_or("");
+        }
+        self.element(&name, attrs, closing);
+        rest
+    }
+
+    fn element(&mut self, name: &str, attrs: &str, closing: bool) {
+        match (name, closing) {
+            ("b" | "strong", _) => self.push("**"),
+            ("i" | "em", _) => self.push("*"),
+            ("code" | "tt" | "kbd" | "var", _) => self.push("`"),
+            ("del" | "s" | "strike", _) => self.push("~~"),
+            ("sup", false) => self.push("^"),
+            ("br", _) => self.push("\n"),
+            ("hr", _) => self.push("\n\n---\n\n"),
+            ("pre", _) => self.push("\n```\n"),
+            ("summary", false) => self.push("\n\n**"),
+            ("summary", true) => self.push("**\n\n"),
+            ("dt", false) => self.push("\n\n**"),
+            ("dt", true) => self.push("**\n"),
+            (
+                "p" | "div" | "section" | "article" | "figure" | "figcaption" | "center"
+                | "details" | "blockquote" | "dl" | "dd" | "caption",
+                _,
+            ) => self.push("\n\n"),
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", false) => {
+                let level = usize::from(name.as_bytes()[1] - b'0');
+                self.push(&format!("\n\n{} ", "#".repeat(level)));
+            }
+            ("h1" | "h2" | "h3" | "h4" | "h5" | "h6", true) => self.push("\n\n"),
+            ("a", false) => {
+                let href = attr(attrs, "href").map(str::to_string);
+                if href.is_some() {
+                    self.push("[");
+                }
+                self.links.push(href);
+            }
+            ("a", true) => {
+                if let Some(Some(href)) = self.links.pop() {
+                    self.push(&format!("]({href})"));
+                }
+            }
+            ("img", _) => {
+                if let Some(src) = attr(attrs, "src") {
+                    let alt = attr(attrs, "alt").unwrap_or("");
+                    self.push(&format!("![{alt}]({src})"));
+                }
+            }
+            ("ul", false) => self.open_list(None),
+            ("ol", false) => self.open_list(Some(1)),
+            ("ul" | "ol", true) => {
+                self.lists.pop();
+                self.push("\n\n");
+            }
+            ("li", false) => {
+                let depth = self.lists.len().saturating_sub(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}.", *n - 1)
+                    }
+                    _ => "-".to_string(),
+                };
+                self.push(&format!("\n{}{marker} ", "  ".repeat(depth)));
+            }
+            ("table", false) => self.table = Some(Table::default()),
+            ("table", true) => self.close_table(),
+            ("tr", false) => {
+                if let Some(table) = self.table.as_mut() {
+                    table.rows.push(Vec::new());
+                }
+            }
+            ("td" | "th", false) => {
+                if let Some(table) = self.table.as_mut() {
+                    table.cell = Some(String::new());
+                }
+            }
+            ("td" | "th", true) => {
+                if let Some(table) = self.table.as_mut()
+                    && let Some(cell) = table.cell.take()
+                {
+                    let cell = cell.split_whitespace().collect::<Vec<_>>().join(" ");
+                    let cell = cell.replace('|', "\\|");
+                    match table.rows.last_mut() {
+                        Some(row) => row.push(cell),
+                        None => table.rows.push(vec![cell]),
+                    }
+                }
+            }
+            // Layout-only elements: keep the content
+            _ => {}
+        }
+    }
+
+    fn open_list(&mut self, start: Option<usize>) {
+        if self.lists.is_empty() {
+            self.push("\n");
+        }
+        self.lists.push(start);
+    }
+
+    /// Emit the collected table as a markdown table, the first row as its header.
+    fn close_table(&mut self) {
+        let Some(table) = self.table.take() else {
+            return;
+        };
+        let rows: Vec<Vec<String>> = table.rows.into_iter().filter(|r| !r.is_empty()).collect();
+        let Some(columns) = rows.iter().map(Vec::len).max() else {
+            return;
+        };
+        let mut lines = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            let mut cells = row.clone();
+            cells.resize(columns, String::new());
+            lines.push(format!("| {} |", cells.join(" | ")));
+            if i == 0 {
+                lines.push(format!("|{}", " --- |".repeat(columns)));
+            }
+        }
+        self.push(&format!("\n\n{}\n\n", lines.join("\n")));
+    }
+
+    /// The converted text, with trailing spaces and runs of blank lines removed.
+    fn finish(self) -> String {
+        let mut lines: Vec<&str> = Vec::new();
+        for line in self.out.lines().map(str::trim_end) {
+            let blank_run = line.is_empty() && lines.last().is_none_or(|l| l.is_empty());
+            if !blank_run {
+                lines.push(line);
+            }
+        }
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        lines.join("\n")
+    }
+}
+
+/// Split a tag at the start of `text` into (lowercase name, attributes, is-closing, rest),
+/// if it names a known HTML element.
+fn parse_tag(text: &str) -> Option<(String, &str, bool, &str)> {
+    let inner = &text[1..];
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(inner) => (true, inner),
+        None => (false, inner),
+    };
+    let name_len = inner
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(inner.len());
+    let name = inner[..name_len].to_ascii_lowercase();
+    if !HTML_TAGS.contains(&name.as_str()) {
+        return None;
+    }
+    let after_name = &inner[name_len..];
+    if !after_name.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>') {
+        return None;
+    }
+    let end = after_name.find('>')?;
+    let attrs = after_name[..end].trim_end_matches('/').trim();
+    Some((name, attrs, closing, &after_name[end + 1..]))
+}
+
+/// The value of attribute `name` in a tag's attribute text.
+fn attr<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attrs;
+    while let Some(i) = rest.find(name) {
+        let before_ok = rest[..i]
+            .chars()
+            .next_back()
+            .is_none_or(char::is_whitespace);
+        let after = rest[i + name.len()..].trim_start();
+        if before_ok && let Some(value) = after.strip_prefix('=') {
+            let value = value.trim_start();
+            return match value.chars().next()? {
+                quote @ ('"' | '\'') => value[1..].split(quote).next(),
+                _ => value.split_whitespace().next(),
+            };
+        }
+        rest = &rest[i + name.len()..];
+    }
+    None
+}
+
+/// Fit a doc comment into rendered tool output.
+///
+/// Headings are shifted so the doc's top level becomes `top_heading` (e.g. `###` under
+/// the item's `##` title), relative `.html` links are resolved against `base_url` (the
+/// docs.rs directory of the item's page, if it has one), and Rust code fences without
+/// a language get an explicit `rust` tag.
+pub fn normalize_doc(doc: &str, top_heading: usize, base_url: Option<&str>) -> String {
+    let mut in_fence = false;
+    let mut min_level = None;
+    for line in doc.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence && let Some(level) = heading_level(trimmed) {
+            min_level = Some(min_level.map_or(level, |min: usize| min.min(level)));
+        }
+    }
+    let shift = min_level.map_or(0, |min| top_heading.saturating_sub(min));
+
+    let mut lines = Vec::new();
+    let mut in_fence = false;
+    for line in doc.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            lines.push(if in_fence {
+                line.to_string()
+            } else {
+                tag_rust_fence(line)
+            });
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            lines.push(line.to_string());
+            continue;
+        }
+        let line = match heading_level(trimmed) {
+            Some(level) if shift > 0 => {
+                format!(
+                    "{}{}",
+                    "#".repeat((level + shift).min(6)),
+                    &trimmed[level..]
+                )
+            }
+            _ => line.to_string(),
+        };
+        lines.push(match base_url {
+            Some(base_url) => resolve_links(&line, base_url),
+            None => line,
+        });
+    }
+    lines.join("\n")
+}
+
+/// The leading paragraphs of a long doc that fit in `max_chars` (at least the first
+/// one), and how many characters were left out. `None` if the doc already fits.
+///
+/// Paragraphs are separated by blank lines outside code fences, so a code block is
+/// never cut in half.
+pub fn summarize_doc(doc: &str, max_chars: usize) -> Option<(String, usize)> {
+    let total = doc.chars().count();
+    if total <= max_chars {
+        return None;
+    }
+
+    let mut paragraphs: Vec<Vec<&str>> = vec![Vec::new()];
+    let mut in_fence = false;
+    for line in doc.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if !in_fence && trimmed.is_empty() {
+            paragraphs.push(Vec::new());
+        } else if let Some(paragraph) = paragraphs.last_mut() {
+            paragraph.push(line);
+        }
+    }
+
+    let mut kept: Vec<String> = Vec::new();
+    let mut kept_chars = 0;
+    for paragraph in paragraphs.iter().filter(|p| !p.is_empty()) {
+        let text = paragraph.join("\n");
+        let chars = text.chars().count() + 2;
+        if !kept.is_empty() && kept_chars + chars > max_chars {
+            break;
+        }
+        kept_chars += chars;
+        kept.push(text);
+    }
+    let summary = kept.join("\n\n");
+    let left_out = total.saturating_sub(summary.chars().count());
+    (left_out > 0).then_some((summary, left_out))
+}
+
+/// The level of an ATX heading line (`## Examples` → 2).
+fn heading_level(line: &str) -> Option<usize> {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[hashes..];
+    ((1..=6).contains(&hashes) && (rest.is_empty() || rest.starts_with(' '))).then_some(hashes)
+}
+
+/// Add a `rust` tag to an opening code fence that rustdoc treats as Rust (no language,
+/// or only doctest attributes like `no_run`).
+fn tag_rust_fence(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    let marker_len = trimmed.len() - trimmed.trim_start_matches(['`', '~']).len();
+    let (marker, info) = trimmed.split_at(marker_len);
+    let info = info.trim();
+    if info.is_empty() {
+        format!("{indent}{marker}rust")
+    } else if fence_mode(info).is_some() && !info.split([',', ' ']).any(|tag| tag == "rust") {
+        format!("{indent}{marker}rust,{info}")
+    } else {
+        line.to_string()
+    }
+}
+
+/// Resolve relative `.html` targets of inline links and link definitions in a line,
+/// skipping inline code.
+fn resolve_links(line: &str, base_url: &str) -> String {
+    if let Some(rest) = line.trim_start().strip_prefix('[')
+        && let Some((label, target)) = rest.split_once("]: ")
+        && let Some(url) = resolve_url(target.trim(), base_url)
+    {
+        return format!("[{label}]: {url}");
+    }
+
+    let mut out = String::with_capacity(line.len());
+    for (i, segment) in line.split('`').enumerate() {
+        if i > 0 {
+            out.push('`');
+        }
+        if i % 2 == 1 {
+            out.push_str(segment);
+            continue;
+        }
+        let mut rest = segment;
+        while let Some(start) = rest.find("](") {
+            let target_start = start + 2;
+            let target_len = rest[target_start..]
+                .find([')', ' '])
+                .unwrap_or(rest.len() - target_start);
+            let target = &rest[target_start..target_start + target_len];
+            out.push_str(&rest[..target_start]);
+            match resolve_url(target, base_url) {
+                Some(url) => out.push_str(&url),
+                None => out.push_str(target),
+            }
+            rest = &rest[target_start + target_len..];
+        }
+        out.push_str(rest);
+    }
+    out
+}
+
+/// The absolute URL of a relative rustdoc page link (`../struct.Foo.html#method.bar`),
+/// or `None` for absolute URLs, anchors, and intra-doc paths (`crate::Foo`).
+fn resolve_url(target: &str, base_url: &str) -> Option<String> {
+    let page = target.split(['#', '?']).next().unwrap_or(target);
+    if target.contains("://")
+        || target.starts_with('#')
+        || !(page.ends_with(".html") || page.ends_with('/'))
+    {
+        return None;
+    }
+    let host_end = base_url.find("://")? + 3;
+    let host_end = host_end
+        + base_url[host_end..]
+            .find('/')
+            .unwrap_or(base_url.len() - host_end);
+    let origin = &base_url[..host_end];
+    if target.starts_with('/') {
+        return Some(format!("{origin}{target}"));
+    }
+    let mut segments: Vec<&str> = base_url[host_end..]
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+    for part in target.split('/') {
+        match part {
+            ".." => {
+                segments.pop();
+            }
+            "." => {}
+            part => segments.push(part),
+        }
+    }
+    Some(format!("{origin}/{}", segments.join("/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== html_to_markdown tests ==========
+
+    #[test]
+    fn inline_html_becomes_markdown() {
+        assert_eq!(
+            html_to_markdown(
+                "Use <b>this</b> or <code>that</code>, see <a href=\"https://x.rs\">docs</a>.<br>x<sup>2</sup> &lt;= 4"
+            ),
+            "Use **this** or `that`, see [docs](https://x.rs).\nx^2 <= 4"
+        );
+        assert_eq!(
+            html_to_markdown("<img src=\"logo.png\" alt=\"Logo\" width=\"20\"/>"),
+            "![Logo](logo.png)"
+        );
+    }
+
+    #[test]
+    fn layout_tags_are_stripped_keeping_content() {
+        assert_eq!(
+            html_to_markdown(
+                "<div class=\"warning\">\n\nCareful: <span style=\"x\">this</span> blocks.\n\n</div>\n<!-- hidden -->\n<script>alert(1)</script>Done."
+            ),
+            "Careful: this blocks.\n\nDone."
+        );
+    }
+
+    #[test]
+    fn tables_and_lists_are_converted() {
+        assert_eq!(
+            html_to_markdown(
+                "<table>\n<tr><th>Feature</th><th>Default</th></tr>\n<tr><td><code>std</code></td><td>yes</td></tr>\n</table>"
+            ),
+            "| Feature | Default |\n| --- | --- |\n| `std` | yes |"
+        );
+        assert_eq!(
+            html_to_markdown("<ol><li>one</li><li>two<ul><li>nested</li></ul></li></ol>"),
+            "1. one\n2. two\n  - nested"
+        );
+    }
+
+    #[test]
+    fn code_and_non_html_brackets_are_untouched() {
+        let doc = "Returns `Option<B>` for Vec<T>, see <https://docs.rs>.\n\n```rust\nlet x: Vec<b> = \"<div>\".into(); // &amp;\n```";
+        assert_eq!(html_to_markdown(doc), doc);
+    }
+
+    // ========== normalize_doc tests ==========
+
+    #[test]
+    fn headings_shift_below_the_output_title() {
+        let doc = "Intro\n\n# Examples\n\n```\n# use std::io;\n```\n\n## Details";
+        assert_eq!(
+            normalize_doc(doc, 3, None),
+            "Intro\n\n### Examples\n\n```rust\n# use std::io;\n```\n\n#### Details"
+        );
+        assert_eq!(
+            normalize_doc("### Already deep", 3, None),
+            "### Already deep"
+        );
+    }
+
+    #[test]
+    fn rust_fences_get_tagged() {
+        assert_eq!(tag_rust_fence("```no_run"), "```rust,no_run");
+        assert_eq!(tag_rust_fence("  ~~~"), "  ~~~rust");
+        assert_eq!(tag_rust_fence("```rust,ignore"), "```rust,ignore");
+        assert_eq!(tag_rust_fence("```toml"), "```toml");
+    }
+
+    #[test]
+    fn relative_links_resolve_against_docs_rs() {
+        let base = "https://docs.rs/tokio/1.40.0/tokio/sync/";
+        assert_eq!(
+            normalize_doc(
+                "See [Mutex](struct.Mutex.html#method.lock), [spawn](../fn.spawn.html), \
+                 [`RwLock`](crate::sync::RwLock), and `[x](y.html)`.\n\n[net]: ../net/index.html",
+                3,
+                Some(base)
+            ),
+            "See [Mutex](https://docs.rs/tokio/1.40.0/tokio/sync/struct.Mutex.html#method.lock), \
+             [spawn](https://docs.rs/tokio/1.40.0/tokio/fn.spawn.html), [`RwLock`](crate::sync::RwLock), \
+             and `[x](y.html)`.\n\n[net]: https://docs.rs/tokio/1.40.0/tokio/net/index.html"
+        );
+    }
+
+    // ========== summarize_doc tests ==========
+
+    #[test]
+    fn summarize_doc_keeps_leading_paragraphs() {
+        let doc = "First paragraph.\n\nSecond paragraph.\n\nThird paragraph that is long.";
+        assert_eq!(summarize_doc(doc, 100), None);
+        let (summary, left_out) = summarize_doc(doc, 40).unwrap();
+        assert_eq!(summary, "First paragraph.\n\nSecond paragraph.");
+        assert_eq!(left_out, doc.chars().count() - summary.chars().count());
+        let (summary, _) = summarize_doc(doc, 5).unwrap();
+        assert_eq!(summary, "First paragraph.");
+    }
+
+    #[test]
+    fn summarize_doc_does_not_split_code_blocks() {
+        let doc = "Intro.\n\n```rust\nlet a = 1;\n\nlet b = 2;\n```\n\nOutro paragraph here.";
+        let (summary, _) = summarize_doc(doc, 45).unwrap();
+        assert_eq!(summary, "Intro.\n\n```rust\nlet a = 1;\n\nlet b = 2;\n```");
+    }
+}
@@ -1,5 +1,25 @@
+pub mod bound_error;
 pub mod cache;
+pub mod cfg;
+pub mod construct;
+pub mod corpus;
+pub mod crates_io;
+pub mod diff;
+pub mod doctests;
+pub mod examples;
 pub mod fetcher;
+pub mod format;
 pub mod index;
+pub mod local;
+pub mod markdown;
 pub mod parser;
 pub mod render;
+pub mod signature;
+pub mod snippet;
+pub mod source;
+pub mod stats;
+pub mod std_docs;
+pub mod suggest;
+pub mod templates;
+pub mod thread_safety;
+pub mod tree;
@@ -1,13 +1,20 @@
 use rustdoc_types::{
-    Crate, Enum, Function, GenericArg, GenericArgs, GenericBound, GenericParamDef,
-    GenericParamDefKind, Id, Impl, Item, ItemEnum, Path as RustdocPath, Struct, StructKind, Trait,
-    Type, Union, Variant, VariantKind,
+    Attribute, Crate, Enum, Function, GenericArg, GenericArgs, GenericBound, GenericParamDef,
+    GenericParamDefKind, Id, Impl, Item, ItemEnum, MacroKind, Path as RustdocPath, Struct,
+    StructKind, Trait, Type, Union, Variant, VariantKind,
 };
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
 
+use super::cfg::parse_doc_cfg;
+use super::doctests::extract_doctests;
 use super::index::{
-    CrateIndex, FieldInfo, ImplBlock, IndexedItem, ItemDetail, ItemKind, MethodInfo, VariantInfo,
+    AsyncKind, AutoTraitImpl, CrateIndex, Deprecation, ExternalRef, FieldInfo, ForeignReexport,
+    ImplBlock, IndexedItem, ItemDetail, ItemKind, MethodInfo, SourceSpan, Stability, VariantInfo,
+    Visibility, primitive_path,
 };
+use super::suggest::NameIndex;
 
 /// Convert a `rustdoc_types::Crate` into a `CrateIndex`.
 ///
@@ -15,19 +22,46 @@ use super::index::{
 /// - Path resolution uses `crate_data.paths` directly (Id → ItemSummary with path: Vec<String>)
 /// - Items are discovered by iterating ALL entries in `crate_data.index`
 /// - Module membership is determined by dropping the last path component
-pub fn parse_crate(krate: &Crate, crate_name: &str, version: &str) -> CrateIndex {
-    let ctx = ParseContext { krate };
+///
+/// With a `budget`, items parsed after it runs out keep only the first sentence of
+/// their docs and the index is marked `partial`.
+#[tracing::instrument(name = "parse", skip(krate, budget), fields(crate_name = package_name))]
+pub fn parse_crate(
+    krate: &Crate,
+    package_name: &str,
+    version: &str,
+    budget: Option<Duration>,
+) -> CrateIndex {
+    let started = Instant::now();
+    let ctx = ParseContext {
+        krate,
+        deadline: budget.map(|budget| started + budget),
+        partial: Cell::new(false),
+    };
+    let lib_name = lib_name(krate).unwrap_or_else(|| package_name.replace('-', "_"));
+    let crate_name = lib_name.as_str();
 
     let mut index = CrateIndex {
         crate_name: crate_name.to_string(),
+        package_name: package_name.to_string(),
         version: version.to_string(),
         items: HashMap::new(),
         modules: HashMap::new(),
         impl_blocks: HashMap::new(),
+        auto_traits: HashMap::new(),
         root_items: Vec::new(),
+        aliases: HashMap::new(),
+        foreign_reexports: HashMap::new(),
+        name_index: NameIndex::default(),
+        target: krate.target.triple.clone(),
+        format_version: krate.format_version,
+        parse_time: Duration::ZERO,
+        partial: false,
+        stale: None,
+        fetched_at: None,
     };
 
-    // Build a path map from Id → fully qualified path string using krate.paths
+    // Build a path map from Id → fully qualified definition path using krate.paths
     let mut path_map: HashMap<Id, String> = HashMap::new();
     for (id, summary) in &krate.paths {
         if !summary.path.is_empty() {
@@ -35,10 +69,33 @@ pub fn parse_crate(krate: &Crate, crate_name: &str, version: &str) -> CrateIndex
         }
     }
 
+    // Canonical paths: the shortest public path where one exists (definition paths
+    // often go through private modules), otherwise the definition path.
+    let mut canonical_map = path_map.clone();
+    let (public, reexport_paths) = public_paths(krate, crate_name);
+    canonical_map.extend(public);
+    index.foreign_reexports = foreign_reexports(krate, &canonical_map);
+
+    // Items are walked in id order rather than `HashMap` order, so module listings,
+    // impl blocks, and the winner of two items at one path are the same on every run
+    let mut items: Vec<(&Id, &Item)> = krate.index.iter().collect();
+    items.sort_unstable_by_key(|(id, _)| **id);
+
+    // Methods are indexed with their impl blocks, even where rustdoc gives them a module
+    // path (the inlined primitive methods of std)
+    let impl_members: HashSet<Id> = items
+        .iter()
+        .filter_map(|(_, item)| match &item.inner {
+            ItemEnum::Impl(impl_) => Some(impl_.items.iter().copied()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
     // Phase 1: Iterate ALL items in krate.index and index named, non-impl items.
     // For each item, look up its path in krate.paths. If not in paths, skip it
     // (it's likely a sub-item like a struct field or variant, handled via parent).
-    for (id, item) in &krate.index {
+    for &(id, item) in &items {
         // Skip impl blocks (handled in phase 2)
         if matches!(&item.inner, ItemEnum::Impl(_)) {
             continue;
@@ -50,12 +107,15 @@ pub fn parse_crate(krate: &Crate, crate_name: &str, version: &str) -> CrateIndex
         };
 
         // Skip sub-items that are children of other items (fields, variants, etc.)
-        if matches!(&item.inner, ItemEnum::StructField(_) | ItemEnum::Variant(_)) {
+        if matches!(&item.inner, ItemEnum::StructField(_) | ItemEnum::Variant(_))
+            || impl_members.contains(id)
+        {
             continue;
         }
 
         // Look up the item's path via krate.paths
-        let item_path = match path_map.get(id) {
+        let item_path = match canonical_map.get(id) {
+            _ if matches!(item.inner, ItemEnum::Primitive(_)) => primitive_path(crate_name, name),
             Some(p) => p.clone(),
             None => {
                 // Item not in paths table — this can happen for re-exports or
@@ -71,13 +131,31 @@ pub fn parse_crate(krate: &Crate, crate_name: &str, version: &str) -> CrateIndex
             None => crate_name.to_string(),
         };
 
-        if let Some(indexed) = ctx.index_item(item, name, &item_path, &parent_module) {
+        if let Some(mut indexed) = ctx.index_item(item, name, &item_path, &parent_module) {
             let kind = indexed.kind.clone();
 
-            // Track in parent module
-            if parent_module == crate_name {
+            // Keep the definition path as an alias when it differs from the public path
+            if let Some(def_path) = path_map.get(id)
+                && kind != ItemKind::Primitive
+                && *def_path != item_path
+            {
+                index.aliases.insert(def_path.clone(), item_path.clone());
+                indexed.aliases.push(def_path.clone());
+            }
+
+            // Further public re-exports resolve to the same item instead of repeating it
+            for path in reexport_paths.get(id).into_iter().flatten() {
+                if *path != item_path && !indexed.reexports.contains(path) {
+                    index.aliases.insert(path.clone(), item_path.clone());
+                    indexed.reexports.push(path.clone());
+                }
+            }
+            indexed.reexports.sort();
+
+            // Track in parent module (the crate root module is not its own child)
+            if parent_module == crate_name && item_path != crate_name {
                 index.root_items.push(item_path.clone());
-            } else {
+            } else if parent_module != crate_name {
                 index
                     .modules
                     .entry(parent_module.to_string())
@@ -94,18 +172,46 @@ pub fn parse_crate(krate: &Crate, crate_name: &str, version: &str) -> CrateIndex
         }
     }
 
+    // Items inside a `#[doc(hidden)]` module are hidden too
+    let hidden_modules: Vec<String> = index
+        .items
+        .values()
+        .filter(|item| item.is_hidden && item.kind == ItemKind::Module)
+        .map(|item| format!("{}::", item.path))
+        .collect();
+    if !hidden_modules.is_empty() {
+        for item in index.items.values_mut() {
+            if hidden_modules.iter().any(|m| item.path.starts_with(m)) {
+                item.is_hidden = true;
+            }
+        }
+    }
+
     // Phase 2: Process all impl blocks
-    for item in krate.index.values() {
+    // Impls for primitive types (`impl str`, `impl<T> [T]`) have no path to go by; in the
+    // standard library they belong to the primitive, also in alloc, which has no
+    // primitive items of its own
+    let is_std = STD_CRATES.contains(&crate_name);
+    for (_, item) in &items {
         if let ItemEnum::Impl(impl_) = &item.inner {
-            ctx.process_impl(impl_, &path_map, &mut index);
+            let primitive = is_std
+                .then(|| primitive_name(&impl_.for_))
+                .flatten()
+                .map(|name| primitive_path(crate_name, name));
+            ctx.process_impl(impl_, primitive.as_deref(), &canonical_map, &mut index);
         }
     }
 
+    index.name_index = NameIndex::build(index.items.values());
+    index.parse_time = started.elapsed();
+    index.partial = ctx.partial.get();
+
     tracing::info!(
-        "Indexed {} items, {} modules, {} impl block groups for {crate_name}",
+        "Indexed {} items, {} modules, {} impl block groups for {crate_name} in {:?}",
         index.items.len(),
         index.modules.len(),
         index.impl_blocks.len(),
+        index.parse_time,
     );
 
     index
@@ -113,9 +219,30 @@ pub fn parse_crate(krate: &Crate, crate_name: &str, version: &str) -> CrateIndex
 
 struct ParseContext<'a> {
     krate: &'a Crate,
+    /// When the parse budget runs out, if there is one.
+    deadline: Option<Instant>,
+    /// Set once the deadline has passed.
+    partial: Cell<bool>,
 }
 
 impl<'a> ParseContext<'a> {
+    /// An item's docs, or only their first sentence once the parse budget is spent.
+    fn doc(&self, item: &Item) -> String {
+        let doc = item.docs.as_deref().unwrap_or_default();
+        if !self.partial.get()
+            && self
+                .deadline
+                .is_some_and(|deadline| Instant::now() > deadline)
+        {
+            self.partial.set(true);
+        }
+        if self.partial.get() {
+            first_sentence(doc)
+        } else {
+            doc.to_string()
+        }
+    }
+
     /// Convert a single rustdoc Item into an IndexedItem.
     fn index_item(
         &self,
@@ -151,19 +278,25 @@ impl<'a> ParseContext<'a> {
             }
             ItemEnum::TypeAlias(ta) => {
                 let sig = format!(
-                    "pub type {name}{} = {}",
+                    "{}type {name}{} = {}",
+                    visibility_prefix(&item.visibility),
                     render_generics_from_item(item),
                     render_type(&ta.type_)
                 );
                 (ItemKind::TypeAlias, sig, ItemDetail::default())
             }
             ItemEnum::Constant { type_, const_: _ } => {
-                let sig = format!("pub const {name}: {}", render_type(type_));
+                let sig = format!(
+                    "{}const {name}: {}",
+                    visibility_prefix(&item.visibility),
+                    render_type(type_)
+                );
                 (ItemKind::Constant, sig, ItemDetail::default())
             }
             ItemEnum::Static(s) => {
                 let sig = format!(
-                    "pub static {}{name}: {}",
+                    "{}static {}{name}: {}",
+                    visibility_prefix(&item.visibility),
                     if s.is_mutable { "mut " } else { "" },
                     render_type(&s.type_)
                 );
@@ -175,13 +308,35 @@ impl<'a> ParseContext<'a> {
                 } else {
                     mac.clone()
                 };
-                (ItemKind::Macro, sig, ItemDetail::default())
+                let detail = ItemDetail {
+                    macro_arms: macro_arms(mac),
+                    macro_usages: macro_usages(item.docs.as_deref().unwrap_or(""), name),
+                    ..Default::default()
+                };
+                (ItemKind::Macro, sig, detail)
+            }
+            ItemEnum::ProcMacro(pm) => {
+                let (kind, sig) = match pm.kind {
+                    MacroKind::Bang => (ItemKind::Macro, format!("{name}!(/* proc-macro */)")),
+                    MacroKind::Attr => (ItemKind::AttributeMacro, format!("#[{name}]")),
+                    MacroKind::Derive => (ItemKind::DeriveMacro, format!("#[derive({name})]")),
+                };
+                let detail = ItemDetail {
+                    helper_attrs: pm.helpers.clone(),
+                    ..Default::default()
+                };
+                (kind, sig, detail)
             }
             ItemEnum::Union(u) => {
                 let sig = self.render_union_signature(name, u, item);
                 let detail = self.union_detail(u);
                 (ItemKind::Union, sig, detail)
             }
+            ItemEnum::Primitive(_) => (
+                ItemKind::Primitive,
+                primitive_signature(name),
+                ItemDetail::default(),
+            ),
             // Skip items we don't index (imports, extern crates, fields, variants, etc.)
             other => {
                 tracing::trace!("Skipping {name} ({:?})", std::mem::discriminant(other));
@@ -189,27 +344,156 @@ impl<'a> ParseContext<'a> {
             }
         };
 
-        let doc = item.docs.clone().unwrap_or_default();
+        let doc = self.doc(item);
         let short_doc = first_sentence(&doc);
+        let headings = doc_headings(&doc);
+        let is_unsafe = match &item.inner {
+            ItemEnum::Function(f) => f.header.is_unsafe,
+            ItemEnum::Trait(t) => t.is_unsafe,
+            _ => false,
+        };
+        let async_kind = match &item.inner {
+            ItemEnum::Function(f) => async_kind(f),
+            _ => None,
+        };
 
         Some(IndexedItem {
             path: item_path.to_string(),
             name: name.to_string(),
             kind,
             signature,
+            attrs: item
+                .attrs
+                .iter()
+                .map(render_attribute)
+                .filter(|attr| SHOWN_ATTRS.iter().any(|prefix| attr.starts_with(prefix)))
+                .collect(),
+            visibility: convert_visibility(&item.visibility),
+            aliases: Vec::new(),
+            reexports: Vec::new(),
             short_doc,
             doc,
+            deprecation: convert_deprecation(item),
+            stability: convert_stability(item),
+            cfg: convert_doc_cfg(item),
+            is_unsafe,
+            async_kind,
+            is_hidden: is_doc_hidden(item),
+            span: source_span(item),
+            headings,
+            external_refs: self.external_refs(item),
             detail,
             parent_module: parent_module.to_string(),
         })
     }
 
+    // ========== External type references ==========
+
+    /// Types and traits from other crates named in an item's signature, fields, or
+    /// variants, resolved through `krate.paths` and `krate.external_crates`.
+    fn external_refs(&self, item: &Item) -> Vec<ExternalRef> {
+        let mut paths = Vec::new();
+        self.item_paths(item, &mut paths);
+
+        let mut refs: Vec<ExternalRef> = Vec::new();
+        for path in paths {
+            let Some(summary) = self.krate.paths.get(&path.id).filter(|s| s.crate_id != 0) else {
+                continue;
+            };
+            let Some(krate) = self.krate.external_crates.get(&summary.crate_id) else {
+                continue;
+            };
+            if STD_CRATES.contains(&krate.name.as_str()) {
+                continue;
+            }
+            let full_path = summary.path.join("::");
+            if refs.iter().any(|r| r.path == full_path) {
+                continue;
+            }
+            refs.push(ExternalRef {
+                name: path
+                    .path
+                    .rsplit("::")
+                    .next()
+                    .unwrap_or(&path.path)
+                    .to_string(),
+                path: full_path,
+                crate_name: krate.name.clone(),
+            });
+        }
+        refs.sort_by(|a, b| a.path.cmp(&b.path));
+        refs
+    }
+
+    fn item_paths<'k>(&'k self, item: &'k Item, out: &mut Vec<&'k RustdocPath>) {
+        match &item.inner {
+            ItemEnum::Function(f) => {
+                generics_paths(&f.generics, out);
+                for (_, ty) in &f.sig.inputs {
+                    type_paths(ty, out);
+                }
+                if let Some(ty) = &f.sig.output {
+                    type_paths(ty, out);
+                }
+            }
+            ItemEnum::Struct(s) => {
+                generics_paths(&s.generics, out);
+                let fields: Vec<Id> = match &s.kind {
+                    StructKind::Unit => Vec::new(),
+                    StructKind::Tuple(fields) => fields.iter().flatten().copied().collect(),
+                    StructKind::Plain { fields, .. } => fields.clone(),
+                };
+                self.field_paths(&fields, out);
+            }
+            ItemEnum::Union(u) => {
+                generics_paths(&u.generics, out);
+                self.field_paths(&u.fields, out);
+            }
+            ItemEnum::Enum(e) => {
+                generics_paths(&e.generics, out);
+                for id in &e.variants {
+                    let Some(ItemEnum::Variant(variant)) =
+                        self.krate.index.get(id).map(|v| &v.inner)
+                    else {
+                        continue;
+                    };
+                    let fields: Vec<Id> = match &variant.kind {
+                        VariantKind::Plain => Vec::new(),
+                        VariantKind::Tuple(fields) => fields.iter().flatten().copied().collect(),
+                        VariantKind::Struct { fields, .. } => fields.clone(),
+                    };
+                    self.field_paths(&fields, out);
+                }
+            }
+            ItemEnum::Trait(t) => {
+                generics_paths(&t.generics, out);
+                bounds_paths(&t.bounds, out);
+            }
+            ItemEnum::TypeAlias(ta) => {
+                generics_paths(&ta.generics, out);
+                type_paths(&ta.type_, out);
+            }
+            ItemEnum::Constant { type_, .. } => type_paths(type_, out),
+            ItemEnum::Static(s) => type_paths(&s.type_, out),
+            _ => {}
+        }
+    }
+
+    fn field_paths<'k>(&'k self, field_ids: &[Id], out: &mut Vec<&'k RustdocPath>) {
+        for id in field_ids {
+            if let Some(ItemEnum::StructField(ty)) = self.krate.index.get(id).map(|f| &f.inner) {
+                type_paths(ty, out);
+            }
+        }
+    }
+
     // ========== Signature rendering ==========
 
     fn render_struct_signature(&self, name: &str, s: &Struct, item: &Item) -> String {
+        let vis = visibility_prefix(&item.visibility);
         let generics = render_generics_from_item(item);
         match &s.kind {
-            StructKind::Unit => format!("pub struct {name}{generics};"),
+            StructKind::Unit => format!("{vis}struct {name}{generics};"),
             StructKind::Tuple(fields) => {
                 let fields_str: Vec<String> = fields
                     .iter()
@@ -219,14 +503,18 @@ impl<'a> ParseContext<'a> {
                             .index
                             .get(id)
                             .and_then(|item| match &item.inner {
-                                ItemEnum::StructField(ty) => Some(render_type(ty)),
+                                ItemEnum::StructField(ty) => Some(format!(
+                                    "{}{}",
+                                    visibility_prefix(&item.visibility),
+                                    render_type(ty)
+                                )),
                                 _ => None,
                             })
                             .unwrap_or_else(|| "_".to_string()),
                         None => "_".to_string(),
                     })
                     .collect();
-                format!("pub struct {name}{generics}({});", fields_str.join(", "))
+                format!("{vis}struct {name}{generics}({});", fields_str.join(", "))
             }
             StructKind::Plain {
                 fields,
@@ -234,9 +522,9 @@ impl<'a> ParseContext<'a> {
             } => {
                 if fields.is_empty() {
                     if *has_stripped_fields {
-                        format!("pub struct {name}{generics} {{ /* private fields */ }}")
+                        format!("{vis}struct {name}{generics} {{ /* private fields */ }}")
                     } else {
-                        format!("pub struct {name}{generics} {{}}")
+                        format!("{vis}struct {name}{generics} {{}}")
                     }
                 } else {
                     let fields_str = self.render_fields(fields);
@@ -245,16 +533,17 @@ impl<'a> ParseContext<'a> {
                     } else {
                         ""
                     };
-                    format!("pub struct {name}{generics} {{\n{fields_str}{private}}}",)
+                    format!("{vis}struct {name}{generics} {{\n{fields_str}{private}}}",)
                 }
             }
         }
     }
 
     fn render_enum_signature(&self, name: &str, e: &Enum, item: &Item) -> String {
+        let vis = visibility_prefix(&item.visibility);
         let generics = render_generics_from_item(item);
         if e.variants.is_empty() {
-            return format!("pub enum {name}{generics} {{}}");
+            return format!("{vis}enum {name}{generics} {{}}");
         }
 
         let variants: Vec<String> = e
@@ -277,7 +566,7 @@ impl<'a> ParseContext<'a> {
             ""
         };
         format!(
-            "pub enum {name}{generics} {{\n{}{stripped_line}\n}}",
+            "{vis}enum {name}{generics} {{\n{}{stripped_line}\n}}",
             variants.join("\n")
         )
     }
@@ -319,6 +608,7 @@ impl<'a> ParseContext<'a> {
     }
 
     fn render_trait_signature(&self, name: &str, t: &Trait, item: &Item) -> String {
+        let vis = visibility_prefix(&item.visibility);
         let generics = render_generics_from_item(item);
         let bounds = if t.bounds.is_empty() {
             String::new()
@@ -327,25 +617,29 @@ impl<'a> ParseContext<'a> {
             format!(": {}", bounds_str.join(" + "))
         };
 
+        let unsafety = if t.is_unsafe { "unsafe " } else { "" };
         let methods = self.collect_trait_methods(t);
         if methods.is_empty() {
-            format!("pub trait {name}{generics}{bounds} {{}}")
+            format!("{vis}{unsafety}trait {name}{generics}{bounds} {{}}")
         } else {
             let method_sigs: Vec<String> = methods
                 .iter()
                 .map(|m| format!("    {};", m.signature))
                 .collect();
             format!(
-                "pub trait {name}{generics}{bounds} {{\n{}\n}}",
+                "{vis}{unsafety}trait {name}{generics}{bounds} {{\n{}\n}}",
                 method_sigs.join("\n")
             )
         }
     }
 
-    fn render_function_signature(&self, name: &str, func: &Function, _item: &Item) -> String {
+    fn render_function_signature(&self, name: &str, func: &Function, item: &Item) -> String {
         let header = &func.header;
         let mut parts = Vec::new();
-        parts.push("pub".to_string());
+        let vis = visibility_prefix(&item.visibility);
+        if !vis.is_empty() {
+            parts.push(vis.trim_end().to_string());
+        }
         if header.is_const {
             parts.push("const".to_string());
         }
@@ -383,8 +677,9 @@ impl<'a> ParseContext<'a> {
     }
 
     fn render_union_signature(&self, name: &str, _u: &Union, item: &Item) -> String {
+        let vis = visibility_prefix(&item.visibility);
         let generics = render_generics_from_item(item);
-        format!("pub union {name}{generics} {{ ... }}")
+        format!("{vis}union {name}{generics} {{ ... }}")
     }
 
     fn render_fields(&self, fields: &[Id]) -> String {
@@ -394,9 +689,11 @@ impl<'a> ParseContext<'a> {
                 let item = self.krate.index.get(id)?;
                 let name = item.name.as_ref()?;
                 match &item.inner {
-                    ItemEnum::StructField(ty) => {
-                        Some(format!("    pub {name}: {},\n", render_type(ty)))
-                    }
+                    ItemEnum::StructField(ty) => Some(format!(
+                        "    {}{name}: {},\n",
+                        visibility_prefix(&item.visibility),
+                        render_type(ty)
+                    )),
                     _ => None,
                 }
             })
@@ -418,7 +715,7 @@ impl<'a> ParseContext<'a> {
                         ItemEnum::StructField(ty) => Some(FieldInfo {
                             name: i.to_string(),
                             type_str: render_type(ty),
-                            doc: item.docs.clone().unwrap_or_default(),
+                            doc: self.doc(item),
                         }),
                         _ => None,
                     }
@@ -443,7 +740,7 @@ impl<'a> ParseContext<'a> {
                     ItemEnum::Variant(v) => Some(VariantInfo {
                         name: name.clone(),
                         signature: self.render_variant(name, v),
-                        doc: item.docs.clone().unwrap_or_default(),
+                        doc: self.doc(item),
                     }),
                     _ => None,
                 }
@@ -481,7 +778,7 @@ impl<'a> ParseContext<'a> {
                     ItemEnum::StructField(ty) => Some(FieldInfo {
                         name: name.clone(),
                         type_str: render_type(ty),
-                        doc: item.docs.clone().unwrap_or_default(),
+                        doc: self.doc(item),
                     }),
                     _ => None,
                 }
@@ -502,8 +799,14 @@ impl<'a> ParseContext<'a> {
                         Some(MethodInfo {
                             name: name.clone(),
                             signature: sig,
-                            doc: item.docs.clone().unwrap_or_default(),
+                            doc: self.doc(item),
                             is_required,
+                            is_unsafe: f.header.is_unsafe,
+                            async_kind: async_kind(f),
+                            is_hidden: is_doc_hidden(item),
+                            deprecation: convert_deprecation(item),
+                            stability: convert_stability(item),
+                            span: source_span(item),
                         })
                     }
                     _ => None,
@@ -512,33 +815,42 @@ impl<'a> ParseContext<'a> {
             .collect()
     }
 
-    /// Process an impl block and attach it to the implementing type.
-    fn process_impl(&self, impl_: &Impl, path_map: &HashMap<Id, String>, index: &mut CrateIndex) {
-        let type_path = match &impl_.for_ {
-            Type::ResolvedPath(path) => resolve_path(path, path_map),
+    /// Process an impl block and attach it to the implementing type. `primitive` is
+    /// the path of the primitive type the impl is for, if it isn't for a named type.
+    fn process_impl(
+        &self,
+        impl_: &Impl,
+        primitive: Option<&str>,
+        path_map: &HashMap<Id, String>,
+        index: &mut CrateIndex,
+    ) {
+        let (type_path, type_name) = match (&impl_.for_, primitive) {
+            (Type::ResolvedPath(path), _) => {
+                let Some(type_path) = resolve_path(path, path_map) else {
+                    return;
+                };
+                let name = type_path
+                    .rsplit("::")
+                    .next()
+                    .unwrap_or(&type_path)
+                    .to_string();
+                (type_path, name)
+            }
+            (for_, Some(primitive)) => (primitive.to_string(), render_type(for_)),
             _ => return,
         };
 
-        let Some(type_path) = type_path else {
-            return;
-        };
-
-        let trait_name = impl_
+        let trait_path = impl_
             .trait_
             .as_ref()
-            .and_then(|p| resolve_path(p, path_map))
-            .map(|p| p.rsplit("::").next().unwrap_or(&p).to_string());
-
-        let header = if let Some(ref tn) = trait_name {
-            format!(
-                "impl {tn} for {}",
-                type_path.rsplit("::").next().unwrap_or(&type_path)
-            )
-        } else {
-            format!(
-                "impl {}",
-                type_path.rsplit("::").next().unwrap_or(&type_path)
-            )
+            .and_then(|p| resolve_path(p, path_map));
+        let trait_name = trait_path
+            .as_deref()
+            .map(|p| p.rsplit("::").next().unwrap_or(p).to_string());
+
+        let header = match trait_name {
+            Some(ref tn) => format!("impl {tn} for {type_name}"),
+            None => format!("impl {type_name}"),
         };
 
         let methods: Vec<MethodInfo> = impl_
@@ -553,8 +865,14 @@ impl<'a> ParseContext<'a> {
                         Some(MethodInfo {
                             name: name.clone(),
                             signature: sig,
-                            doc: item.docs.clone().unwrap_or_default(),
+                            doc: self.doc(item),
                             is_required: false,
+                            is_unsafe: f.header.is_unsafe,
+                            async_kind: async_kind(f),
+                            is_hidden: is_doc_hidden(item),
+                            deprecation: convert_deprecation(item),
+                            stability: convert_stability(item),
+                            span: source_span(item),
                         })
                     }
                     _ => None,
@@ -562,25 +880,269 @@ impl<'a> ParseContext<'a> {
             })
             .collect();
 
-        // Skip empty auto-trait impls
-        if methods.is_empty() && trait_name.is_some() {
-            let tn = trait_name.as_deref().unwrap_or("");
-            let boring = ["Send", "Sync", "Unpin", "UnwindSafe", "RefUnwindSafe"];
-            if boring.contains(&tn) {
-                return;
-            }
+        // Auto-trait impls are kept apart for `is_thread_safe`, not listed as impls
+        if methods.is_empty()
+            && let Some(tn) = trait_name.as_deref()
+            && AUTO_TRAITS.contains(&tn)
+        {
+            let auto = auto_trait_impl(tn, impl_);
+            index.auto_traits.entry(type_path).or_default().push(auto);
+            return;
         }
 
+        let mut provided_methods = impl_.provided_trait_methods.clone();
+        provided_methods.sort_unstable();
+
         let block = ImplBlock {
             header,
             trait_name,
+            trait_path,
             methods,
+            provided_methods,
         };
 
         index.impl_blocks.entry(type_path).or_default().push(block);
     }
 }
 
+/// Auto traits, whose impls are recorded as `AutoTraitImpl`s.
+const AUTO_TRAITS: [&str; 5] = ["Send", "Sync", "Unpin", "UnwindSafe", "RefUnwindSafe"];
+
+/// An auto-trait impl with the bounds it requires, from generic parameters and the
+/// where clause.
+fn auto_trait_impl(trait_name: &str, impl_: &Impl) -> AutoTraitImpl {
+    let mut bounds: Vec<String> = impl_
+        .generics
+        .params
+        .iter()
+        .filter_map(|p| match &p.kind {
+            GenericParamDefKind::Type { bounds, .. } if !bounds.is_empty() => {
+                let b: Vec<String> = bounds.iter().map(render_generic_bound).collect();
+                Some(format!("{}: {}", p.name, b.join(" + ")))
+            }
+            _ => None,
+        })
+        .collect();
+    let where_clause = render_where_clause(&impl_.generics.where_predicates);
+    bounds.extend(
+        where_clause
+            .trim_start_matches("\nwhere\n")
+            .split(",\n")
+            .map(str::trim)
+            .filter(|bound| !bound.is_empty())
+            .map(str::to_string),
+    );
+
+    let params: Vec<&str> = impl_
+        .generics
+        .params
+        .iter()
+        .map(|p| p.name.as_str())
+        .collect();
+    let generics = if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    };
+    let negation = if impl_.is_negative { "!" } else { "" };
+    let mut header = format!(
+        "impl{generics} {negation}{trait_name} for {}",
+        render_type(&impl_.for_)
+    );
+    if !bounds.is_empty() {
+        header.push_str(&format!(" where {}", bounds.join(", ")));
+    }
+
+    AutoTraitImpl {
+        trait_name: trait_name.to_string(),
+        is_negative: impl_.is_negative,
+        bounds,
+        header,
+        is_synthetic: impl_.is_synthetic,
+    }
+}
+
+/// Compute the shortest public path of every local item reachable from the crate root,
+/// and the other public paths items are re-exported at.
+///
+/// Walks the module tree breadth-first, following public `use` re-exports (including
+/// globs) and skipping stripped (private) modules, so an item defined in a private
+/// module gets the path under which it is re-exported.
+fn public_paths(
+    krate: &Crate,
+    crate_name: &str,
+) -> (HashMap<Id, String>, HashMap<Id, Vec<String>>) {
+    let mut paths: HashMap<Id, String> = HashMap::new();
+    let mut others: HashMap<Id, Vec<String>> = HashMap::new();
+    let mut expanded: HashSet<(Id, String)> = HashSet::new();
+    let mut queue: VecDeque<(Id, String)> = VecDeque::new();
+
+    paths.insert(krate.root, crate_name.to_string());
+    queue.push_back((krate.root, crate_name.to_string()));
+
+    while let Some((module_id, module_path)) = queue.pop_front() {
+        if !expanded.insert((module_id, module_path.clone())) {
+            continue;
+        }
+        let Some(ItemEnum::Module(module)) = krate.index.get(&module_id).map(|i| &i.inner) else {
+            continue;
+        };
+
+        for child_id in &module.items {
+            let Some(child) = krate.index.get(child_id) else {
+                continue;
+            };
+            if !matches!(child.visibility, rustdoc_types::Visibility::Public) {
+                continue;
+            }
+
+            let (target_id, path) = match &child.inner {
+                ItemEnum::Use(u) => {
+                    let Some(target_id) = u.id else {
+                        continue;
+                    };
+                    if u.is_glob {
+                        // `pub use inner::*` lists inner's items under this module's path
+                        queue.push_back((target_id, module_path.clone()));
+                        continue;
+                    }
+                    (target_id, format!("{module_path}::{}", u.name))
+                }
+                ItemEnum::Module(m) if m.is_stripped => continue,
+                _ => {
+                    let Some(name) = &child.name else {
+                        continue;
+                    };
+                    (*child_id, format!("{module_path}::{name}"))
+                }
+            };
+
+            // Only local items have an index entry; external re-exports are skipped
+            let Some(target) = krate.index.get(&target_id) else {
+                continue;
+            };
+            if paths.contains_key(&target_id) {
+                others.entry(target_id).or_default().push(path);
+                continue;
+            }
+            if matches!(target.inner, ItemEnum::Module(_)) {
+                queue.push_back((target_id, path.clone()));
+            }
+            paths.insert(target_id, path);
+        }
+    }
+
+    (paths, others)
+}
+
+/// Public `pub use` re-exports of items from other crates, keyed by their path in
+/// this crate. Glob re-exports are left out: their items aren't listed in the JSON.
+fn foreign_reexports(
+    krate: &Crate,
+    canonical_map: &HashMap<Id, String>,
+) -> HashMap<String, ForeignReexport> {
+    let mut reexports = HashMap::new();
+    for (module_id, module) in &krate.index {
+        let ItemEnum::Module(module) = &module.inner else {
+            continue;
+        };
+        let Some(module_path) = canonical_map.get(module_id) else {
+            continue;
+        };
+        for child in module.items.iter().filter_map(|id| krate.index.get(id)) {
+            let ItemEnum::Use(u) = &child.inner else {
+                continue;
+            };
+            if u.is_glob || !matches!(child.visibility, rustdoc_types::Visibility::Public) {
+                continue;
+            }
+            let Some(target_id) = u.id.filter(|id| !krate.index.contains_key(id)) else {
+                continue;
+            };
+            let Some(summary) = krate.paths.get(&target_id).filter(|s| s.crate_id != 0) else {
+                continue;
+            };
+            let Some(external) = krate.external_crates.get(&summary.crate_id) else {
+                continue;
+            };
+            let path = format!("{module_path}::{}", u.name);
+            reexports.insert(
+                path.clone(),
+                ForeignReexport {
+                    path,
+                    target_path: summary.path.join("::"),
+                    crate_name: external.name.clone(),
+                    kind: summary_kind(summary.kind),
+                },
+            );
+        }
+    }
+    reexports
+}
+
+/// The index's kind for a `krate.paths` entry, if it's one the index tracks.
+fn summary_kind(kind: rustdoc_types::ItemKind) -> Option<ItemKind> {
+    use rustdoc_types::ItemKind as K;
+    match kind {
+        K::Module => Some(ItemKind::Module),
+        K::Struct => Some(ItemKind::Struct),
+        K::Enum => Some(ItemKind::Enum),
+        K::Trait | K::TraitAlias => Some(ItemKind::Trait),
+        K::Function => Some(ItemKind::Function),
+        K::TypeAlias => Some(ItemKind::TypeAlias),
+        K::Constant => Some(ItemKind::Constant),
+        K::Static => Some(ItemKind::Static),
+        K::Macro => Some(ItemKind::Macro),
+        K::ProcAttribute => Some(ItemKind::AttributeMacro),
+        K::ProcDerive => Some(ItemKind::DeriveMacro),
+        K::Union => Some(ItemKind::Union),
+        _ => None,
+    }
+}
+
+/// The primitive an impl's self type belongs to, named as rustdoc names its primitive
+/// items (`impl<T> [T]` → "slice", `impl<T> *const T` → "pointer").
+fn primitive_name(ty: &Type) -> Option<&str> {
+    Some(match ty {
+        Type::Primitive(name) => name,
+        Type::Slice(_) => "slice",
+        Type::Array { .. } => "array",
+        Type::RawPointer { .. } => "pointer",
+        Type::BorrowedRef { .. } => "reference",
+        Type::Tuple(types) if types.is_empty() => "unit",
+        Type::Tuple(_) => "tuple",
+        Type::FunctionPointer(_) => "fn",
+        _ => return None,
+    })
+}
+
+/// How a primitive type is written, for its signature: `str`, `[T]`, `*const T`, ...
+fn primitive_signature(name: &str) -> String {
+    match name {
+        "slice" => "[T]",
+        "array" => "[T; N]",
+        "pointer" => "*const T\n*mut T",
+        "reference" => "&T\n&mut T",
+        "tuple" => "(T₁, T₂, …, Tₙ)",
+        "unit" => "()",
+        "never" => "!",
+        "fn" => "fn(T₁, …, Tₙ) -> R",
+        other => other,
+    }
+    .to_string()
+}
+
+/// Whether a function is an `async fn` or returns a future or stream.
+fn async_kind(func: &Function) -> Option<AsyncKind> {
+    let returns = func
+        .sig
+        .output
+        .as_ref()
+        .map(render_type)
+        .unwrap_or_default();
+    AsyncKind::classify(func.header.is_async, &returns)
+}
+
 /// Resolve a rustdoc Path to a fully qualified string using the path map.
 fn resolve_path(path: &RustdocPath, path_map: &HashMap<Id, String>) -> Option<String> {
     path_map
@@ -589,6 +1151,126 @@ fn resolve_path(path: &RustdocPath, path_map: &HashMap<Id, String>) -> Option<St
         .or_else(|| Some(path.path.clone()))
 }
 
+/// Crates whose types need no follow-up hint.
+const STD_CRATES: [&str; 4] = ["std", "core", "alloc", "proc_macro"];
+
+/// Collect the type and trait paths a type mentions, including generic arguments.
+fn type_paths<'k>(ty: &'k Type, out: &mut Vec<&'k RustdocPath>) {
+    match ty {
+        Type::ResolvedPath(path) => {
+            out.push(path);
+            args_paths(path.args.as_deref(), out);
+        }
+        Type::DynTrait(dyn_trait) => {
+            for poly in &dyn_trait.traits {
+                out.push(&poly.trait_);
+                args_paths(poly.trait_.args.as_deref(), out);
+            }
+        }
+        Type::FunctionPointer(fp) => {
+            for (_, ty) in &fp.sig.inputs {
+                type_paths(ty, out);
+            }
+            if let Some(ty) = &fp.sig.output {
+                type_paths(ty, out);
+            }
+        }
+        Type::Tuple(types) => types.iter().for_each(|ty| type_paths(ty, out)),
+        Type::Slice(type_)
+        | Type::Array { type_, .. }
+        | Type::Pat { type_, .. }
+        | Type::RawPointer { type_, .. }
+        | Type::BorrowedRef { type_, .. } => type_paths(type_, out),
+        Type::ImplTrait(bounds) => bounds_paths(bounds, out),
+        Type::QualifiedPath {
+            args,
+            self_type,
+            trait_,
+            ..
+        } => {
+            type_paths(self_type, out);
+            if let Some(trait_) = trait_ {
+                out.push(trait_);
+                args_paths(trait_.args.as_deref(), out);
+            }
+            args_paths(args.as_deref(), out);
+        }
+        Type::Generic(_) | Type::Primitive(_) | Type::Infer => {}
+    }
+}
+
+fn args_paths<'k>(args: Option<&'k GenericArgs>, out: &mut Vec<&'k RustdocPath>) {
+    match args {
+        Some(GenericArgs::AngleBracketed { args, constraints }) => {
+            for arg in args {
+                if let GenericArg::Type(ty) = arg {
+                    type_paths(ty, out);
+                }
+            }
+            for c in constraints {
+                args_paths(c.args.as_deref(), out);
+                match &c.binding {
+                    rustdoc_types::AssocItemConstraintKind::Equality(
+                        rustdoc_types::Term::Type(ty),
+                    ) => type_paths(ty, out),
+                    rustdoc_types::AssocItemConstraintKind::Constraint(bounds) => {
+                        bounds_paths(bounds, out)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Some(GenericArgs::Parenthesized { inputs, output }) => {
+            inputs.iter().for_each(|ty| type_paths(ty, out));
+            if let Some(ty) = output {
+                type_paths(ty, out);
+            }
+        }
+        Some(GenericArgs::ReturnTypeNotation) | None => {}
+    }
+}
+
+fn bounds_paths<'k>(bounds: &'k [GenericBound], out: &mut Vec<&'k RustdocPath>) {
+    for bound in bounds {
+        if let GenericBound::TraitBound { trait_, .. } = bound {
+            out.push(trait_);
+            args_paths(trait_.args.as_deref(), out);
+        }
+    }
+}
+
+fn generics_paths<'k>(generics: &'k rustdoc_types::Generics, out: &mut Vec<&'k RustdocPath>) {
+    for param in &generics.params {
+        match &param.kind {
+            GenericParamDefKind::Type {
+                bounds, default, ..
+            } => {
+                bounds_paths(bounds, out);
+                if let Some(ty) = default {
+                    type_paths(ty, out);
+                }
+            }
+            GenericParamDefKind::Const { type_, .. } => type_paths(type_, out),
+            GenericParamDefKind::Lifetime { .. } => {}
+        }
+    }
+    for predicate in &generics.where_predicates {
+        match predicate {
+            rustdoc_types::WherePredicate::BoundPredicate { type_, bounds, .. } => {
+                type_paths(type_, out);
+                bounds_paths(bounds, out);
+            }
+            rustdoc_types::WherePredicate::EqPredicate { lhs, rhs } => {
+                type_paths(lhs, out);
+                if let rustdoc_types::Term::Type(ty) = rhs {
+                    type_paths(ty, out);
+                }
+            }
+            rustdoc_types::WherePredicate::LifetimePredicate { .. } => {}
+        }
+    }
+}
+
 // ========== Type rendering (free functions) ==========
 
 pub fn render_type(ty: &Type) -> String {
@@ -835,6 +1517,80 @@ fn render_where_clause(predicates: &[rustdoc_types::WherePredicate]) -> String {
     format!("\nwhere\n    {}", clauses.join(",\n    "))
 }
 
+fn convert_visibility(vis: &rustdoc_types::Visibility) -> Visibility {
+    match vis {
+        rustdoc_types::Visibility::Public => Visibility::Public,
+        rustdoc_types::Visibility::Crate => Visibility::Crate,
+        rustdoc_types::Visibility::Restricted { path, .. } => Visibility::Restricted(path.clone()),
+        rustdoc_types::Visibility::Default => Visibility::Private,
+    }
+}
+
+/// Source-form visibility prefix for a signature (e.g. `pub(crate) `).
+fn visibility_prefix(vis: &rustdoc_types::Visibility) -> String {
+    convert_visibility(vis).prefix()
+}
+
+/// Attributes shown with an item: those that affect how an API must be used, or
+/// whether it is public API at all (`#[doc(hidden)]`), matched by source prefix.
+/// Stability and `#[doc(cfg(..))]` are read into their own fields instead.
+const SHOWN_ATTRS: [&str; 4] = ["#[non_exhaustive", "#[must_use", "#[repr(", "#[doc(hidden"];
+
+/// Render an attribute in source form. Attributes of formats before 54 arrive
+/// from the fetcher as `Attribute::Other` source text.
+fn render_attribute(attr: &Attribute) -> String {
+    match attr {
+        Attribute::NonExhaustive => "#[non_exhaustive]".to_string(),
+        Attribute::MustUse { reason: Some(r) } => format!("#[must_use = {r:?}]"),
+        Attribute::MustUse { reason: None } => "#[must_use]".to_string(),
+        Attribute::MacroExport => "#[macro_export]".to_string(),
+        Attribute::ExportName(name) => format!("#[export_name = {name:?}]"),
+        Attribute::LinkSection(name) => format!("#[link_section = {name:?}]"),
+        Attribute::AutomaticallyDerived => "#[automatically_derived]".to_string(),
+        Attribute::Repr(repr) => render_repr(repr),
+        Attribute::NoMangle => "#[no_mangle]".to_string(),
+        Attribute::TargetFeature { enable } => {
+            let features: Vec<String> = enable.iter().map(|f| format!("enable = {f:?}")).collect();
+            format!("#[target_feature({})]", features.join(", "))
+        }
+        Attribute::Other(src) => src.clone(),
+    }
+}
+
+/// Render a structured `#[repr(...)]` back into source form.
+fn render_repr(repr: &rustdoc_types::AttributeRepr) -> String {
+    let mut parts = Vec::new();
+    match repr.kind {
+        rustdoc_types::ReprKind::Rust => {}
+        rustdoc_types::ReprKind::C => parts.push("C".to_string()),
+        rustdoc_types::ReprKind::Transparent => parts.push("transparent".to_string()),
+        rustdoc_types::ReprKind::Simd => parts.push("simd".to_string()),
+    }
+    parts.extend(repr.int.clone());
+    if let Some(align) = repr.align {
+        parts.push(format!("align({align})"));
+    }
+    if let Some(packed) = repr.packed {
+        parts.push(if packed == 1 {
+            "packed".to_string()
+        } else {
+            format!("packed({packed})")
+        });
+    }
+    if parts.is_empty() {
+        parts.push("Rust".to_string());
+    }
+    format!("#[repr({})]", parts.join(", "))
+}
+
+/// Whether an item carries `#[doc(hidden)]`.
+fn is_doc_hidden(item: &Item) -> bool {
+    item.attrs
+        .iter()
+        .map(render_attribute)
+        .any(|attr| attr.starts_with("#[doc(hidden"))
+}
+
 /// Extract the first sentence from a documentation string.
 fn first_sentence(doc: &str) -> String {
     let trimmed = doc.trim();
@@ -859,3 +1615,788 @@ fn first_sentence(doc: &str) -> String {
     }
     trimmed[..end].trim().to_string()
 }
+
+/// Extract the matcher of each arm from a declarative macro's source.
+///
+/// Rustdoc renders `macro_rules!` bodies as `(matcher) => { ... };`, so each
+/// matcher is the first delimited group of an arm. For `macro` (2.0) items with
+/// a single arm, the parameter list is the matcher.
+fn macro_arms(source: &str) -> Vec<String> {
+    let Some(rules) = source.find("macro_rules!") else {
+        // Macros 2.0: `pub macro name($x:expr) { ... }`
+        return source
+            .find('(')
+            .and_then(|open| Some(&source[open..=matching_delim(source, open)?]))
+            .map(|m| vec![collapse_whitespace(m)])
+            .unwrap_or_default();
+    };
+
+    let Some(body_open) = source[rules..].find(['{', '(', '[']).map(|i| i + rules) else {
+        return Vec::new();
+    };
+    let Some(body_close) = matching_delim(source, body_open) else {
+        return Vec::new();
+    };
+    let body = &source[body_open + 1..body_close];
+
+    let mut arms = Vec::new();
+    let mut pos = 0;
+    while let Some(open) = body[pos..].find(['{', '(', '[']).map(|i| i + pos) {
+        let Some(close) = matching_delim(body, open) else {
+            break;
+        };
+        arms.push(collapse_whitespace(&body[open..=close]));
+
+        // Skip past `=>` and the transcriber group
+        let Some(arrow) = body[close..].find("=>").map(|i| i + close) else {
+            break;
+        };
+        let Some(t_open) = body[arrow..].find(['{', '(', '[']).map(|i| i + arrow) else {
+            break;
+        };
+        let Some(t_close) = matching_delim(body, t_open) else {
+            break;
+        };
+        pos = t_close + 1;
+    }
+    arms
+}
+
+/// Find the index of the delimiter closing the one at `open`, respecting nesting
+/// and skipping delimiters inside string and char literals.
+fn matching_delim(s: &str, open: usize) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut depth = 0usize;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            b'"' => i = string_end(s, i)?,
+            // Lifetimes and labels (`'a`) start the same way but aren't skipped
+            b'\'' => i = char_literal_end(s, i).unwrap_or(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// The index of the quote closing the string literal opened at `quote`, or of the
+/// last `#` for a raw string (`r#"..."#`).
+fn string_end(s: &str, quote: usize) -> Option<usize> {
+    let before = &s[..quote];
+    let hashes = before.len() - before.trim_end_matches('#').len();
+    let prefix = &before[..quote - hashes];
+    let is_raw = prefix.strip_suffix('r').is_some_and(|rest| {
+        !rest
+            .trim_end_matches(['b', 'c'])
+            .ends_with(|c: char| c.is_alphanumeric() || c == '_')
+    });
+    if is_raw {
+        let closing = format!("\"{}", "#".repeat(hashes));
+        return s[quote + 1..]
+            .find(&closing)
+            .map(|i| quote + i + closing.len());
+    }
+    let mut escaped = false;
+    for (i, b) in s.bytes().enumerate().skip(quote + 1) {
+        match b {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The index of the quote closing the char literal opened at `quote`, or `None`
+/// if the quote starts a lifetime or label instead.
+fn char_literal_end(s: &str, quote: usize) -> Option<usize> {
+    let rest = &s[quote + 1..];
+    let len = if rest.starts_with('\\') {
+        // An escape: `'\n'`, `'\''`, `'\u{1F600}'`
+        rest.get(2..)?.find('\'')? + 2
+    } else {
+        rest.chars().next()?.len_utf8()
+    };
+    rest[len..].starts_with('\'').then_some(quote + 1 + len)
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Collect lines invoking `name!` from the Rust code blocks in a doc string.
+///
+/// Hidden doctest lines (prefixed with `# `) are skipped. Returns at most five
+/// distinct lines.
+fn macro_usages(doc: &str, name: &str) -> Vec<String> {
+    let needle = format!("{name}!");
+    // `my_vec!` isn't an invocation of `vec!`
+    let invokes = |line: &str| {
+        line.match_indices(&needle)
+            .any(|(i, _)| !line[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_'))
+    };
+    let mut usages: Vec<String> = Vec::new();
+    for block in code_blocks(doc) {
+        for line in block.lines() {
+            let line = line.trim();
+            if line.starts_with('#') || !invokes(line) {
+                continue;
+            }
+            if !usages.iter().any(|u| u == line) {
+                usages.push(line.to_string());
+            }
+            if usages.len() == 5 {
+                return usages;
+            }
+        }
+    }
+    usages
+}
+
+/// Extract the contents of the Rust code blocks in a markdown doc string.
+pub(crate) fn code_blocks(doc: &str) -> Vec<String> {
+    extract_doctests(doc)
+        .into_iter()
+        .map(|doctest| doctest.code)
+        .collect()
+}
+
+fn source_span(item: &Item) -> Option<SourceSpan> {
+    item.span.as_ref().map(|span| SourceSpan {
+        file: span.filename.display().to_string(),
+        line: span.begin.0,
+        end_line: span.end.0,
+    })
+}
+
+fn convert_deprecation(item: &Item) -> Option<Deprecation> {
+    item.deprecation.as_ref().map(|d| Deprecation {
+        since: d.since.clone(),
+        note: d.note.clone(),
+    })
+}
+
+/// The name of the crate's lib target, which can differ from the package name
+/// (e.g. `lib.name = "foo"` in package `foo-rs`), from the root module.
+fn lib_name(krate: &Crate) -> Option<String> {
+    krate
+        .index
+        .get(&krate.root)
+        .and_then(|root| root.name.clone())
+        .or_else(|| krate.paths.get(&krate.root)?.path.first().cloned())
+}
+
+/// Read an item's `#[stable]`/`#[unstable]` attribute (kept by the fetcher for std docs).
+fn convert_stability(item: &Item) -> Option<Stability> {
+    item.attrs
+        .iter()
+        .map(render_attribute)
+        .find_map(|attr| parse_stability(&attr))
+}
+
+/// Read an item's `#[doc(cfg(...))]` predicate; several are combined with `all(...)`.
+fn convert_doc_cfg(item: &Item) -> Option<String> {
+    let predicates: Vec<String> = item
+        .attrs
+        .iter()
+        .map(render_attribute)
+        .filter_map(|attr| parse_doc_cfg(&attr))
+        .collect();
+    match predicates.len() {
+        0 => None,
+        1 => predicates.into_iter().next(),
+        _ => Some(format!("all({})", predicates.join(", "))),
+    }
+}
+
+/// Parse a stability attribute, either in source form
+/// (`#[stable(feature = "rust1", since = "1.0.0")]`, `#[unstable(feature = "x", issue = "1")]`)
+/// or as rustdoc's HIR debug printing (`#[attr = Stability { level: Stable { since:
+/// Version(RustcVersion { major: 1, minor: 36, patch: 0 }) ... }, feature: "x" }]`).
+fn parse_stability(attr: &str) -> Option<Stability> {
+    let string_arg = |key: &str| {
+        let rest = attr.split_once(key)?.1.trim_start();
+        let rest = rest.strip_prefix([':', '='])?.trim_start();
+        let value = rest.strip_prefix('"')?.split('"').next()?;
+        Some(value.to_string())
+    };
+    let number_arg = |key: &str| -> Option<&str> {
+        let rest = attr.split_once(key)?.1;
+        Some(&rest[..rest.find(|c: char| !c.is_ascii_digit())?])
+    };
+
+    if attr.starts_with("#[stable(") {
+        return Some(Stability::Stable {
+            since: string_arg("since")?,
+        });
+    }
+    if attr.starts_with("#[unstable(") {
+        return Some(Stability::Unstable {
+            feature: string_arg("feature")?,
+            issue: string_arg("issue").filter(|issue| issue != "none"),
+        });
+    }
+    if attr.starts_with("#[attr = Stability") {
+        if attr.contains("Unstable") {
+            return Some(Stability::Unstable {
+                feature: string_arg("feature")?,
+                issue: number_arg("issue: Some(").map(str::to_string),
+            });
+        }
+        let since = ["major: ", "minor: ", "patch: "]
+            .map(|part| number_arg(part).unwrap_or("0"))
+            .join(".");
+        return Some(Stability::Stable { since });
+    }
+    None
+}
+
+/// Extract the text of markdown ATX headings (`# Panics`, `## Cancellation safety`)
+/// from a doc comment, ignoring `#` lines inside code fences (hidden doctest lines).
+pub(crate) fn doc_headings(doc: &str) -> Vec<String> {
+    let mut headings = Vec::new();
+    let mut in_fence = false;
+
+    for line in doc.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let hashes = trimmed.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&hashes)
+            && let Some(text) = trimmed[hashes..].strip_prefix(' ')
+        {
+            let text = text.trim().trim_end_matches('#').trim();
+            if !text.is_empty() {
+                headings.push(text.to_string());
+            }
+        }
+    }
+    headings
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+
+    // ========== parse_crate tests ==========
+
+    fn json_item(id: u32, name: Option<&str>, inner: Value) -> Value {
+        json!({
+            "id": id, "crate_id": 0, "name": name, "span": null, "visibility": "public",
+            "docs": null, "links": {}, "attrs": [], "deprecation": null, "inner": inner,
+        })
+    }
+
+    /// A crate with struct `Widget` and one inherent impl per method name, the impl of
+    /// `methods[i]` having the id `100 + 2 * i`.
+    fn crate_with_impls(methods: &[&str]) -> Crate {
+        let generics = json!({ "params": [], "where_predicates": [] });
+        let impl_ids: Vec<u32> = (0..methods.len() as u32).map(|i| 100 + 2 * i).collect();
+        let mut index = serde_json::Map::new();
+        index.insert(
+            "0".to_string(),
+            json_item(
+                0,
+                Some("my_crate"),
+                json!({ "module": { "is_crate": true, "items": [1], "is_stripped": false } }),
+            ),
+        );
+        index.insert(
+            "1".to_string(),
+            json_item(
+                1,
+                Some("Widget"),
+                json!({ "struct": { "kind": "unit", "generics": generics, "impls": impl_ids } }),
+            ),
+        );
+        for (method, impl_id) in methods.iter().zip(&impl_ids) {
+            let function = json!({ "function": {
+                "sig": { "inputs": [], "output": null, "is_c_variadic": false },
+                "generics": generics,
+                "header": { "is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust" },
+                "has_body": true,
+            } });
+            index.insert(
+                (impl_id + 1).to_string(),
+                json_item(impl_id + 1, Some(method), function),
+            );
+            let impl_ = json!({ "impl": {
+                "is_unsafe": false, "generics": generics, "provided_trait_methods": [],
+                "trait": null,
+                "for": { "resolved_path": { "path": "Widget", "id": 1, "args": null } },
+                "items": [impl_id + 1], "is_negative": false, "is_synthetic": false,
+                "blanket_impl": null,
+            } });
+            index.insert(impl_id.to_string(), json_item(*impl_id, None, impl_));
+        }
+        serde_json::from_value(json!({
+            "root": 0, "crate_version": "1.0.0", "includes_private": false, "index": index,
+            "paths": {
+                "0": { "crate_id": 0, "path": ["my_crate"], "kind": "module" },
+                "1": { "crate_id": 0, "path": ["my_crate", "Widget"], "kind": "struct" },
+            },
+            "external_crates": {},
+            "target": { "triple": "x86_64-unknown-linux-gnu", "target_features": [] },
+            "format_version": 56,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_crate_keeps_impl_blocks_in_id_order() {
+        let methods = [
+            "new", "len", "push", "pop", "clear", "iter", "get", "insert",
+        ];
+        let index = parse_crate(&crate_with_impls(&methods), "my_crate", "1.0.0", None);
+        let parsed: Vec<&str> = index.impl_blocks["my_crate::Widget"]
+            .iter()
+            .flat_map(|block| &block.methods)
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(parsed, methods);
+    }
+
+    #[test]
+    fn parse_crate_shows_usage_attributes_in_source_form() {
+        let mut krate = crate_with_impls(&[]);
+        krate.index.get_mut(&rustdoc_types::Id(1)).unwrap().attrs = vec![
+            Attribute::AutomaticallyDerived,
+            Attribute::Repr(rustdoc_types::AttributeRepr {
+                kind: rustdoc_types::ReprKind::C,
+                align: None,
+                packed: None,
+                int: Some("u8".to_string()),
+            }),
+            Attribute::NonExhaustive,
+            Attribute::Other("#[inline]".to_string()),
+            Attribute::Other("#[doc(hidden)]".to_string()),
+        ];
+        let index = parse_crate(&krate, "my_crate", "1.0.0", None);
+        let widget = &index.items["my_crate::Widget"];
+        assert_eq!(
+            widget.attrs,
+            ["#[repr(C, u8)]", "#[non_exhaustive]", "#[doc(hidden)]"]
+        );
+        assert!(widget.is_hidden);
+    }
+
+    #[test]
+    fn parse_crate_picks_the_shortest_public_path() {
+        let module = |id: u32, name: &str, items: &[u32], is_stripped: bool| {
+            json_item(
+                id,
+                Some(name),
+                json!({ "module": { "is_crate": false, "items": items, "is_stripped": is_stripped } }),
+            )
+        };
+        let reexport = |id: u32, source: &str| {
+            json_item(
+                id,
+                None,
+                json!({ "use": { "source": source, "name": "Widget", "id": 10, "is_glob": false } }),
+            )
+        };
+        // `Widget` is defined in the private `imp` and re-exported at three depths,
+        // the deepest reached first in declaration order
+        let krate: Crate = serde_json::from_value(json!({
+            "root": 0, "crate_version": "1.0.0", "includes_private": false,
+            "index": {
+                "0": json_item(0, Some("my_crate"), json!({ "module": {
+                    "is_crate": true, "items": [1, 2, 5], "is_stripped": false,
+                } })),
+                "1": module(1, "imp", &[10], true),
+                "2": module(2, "outer", &[3, 6], false),
+                "3": module(3, "inner", &[4], false),
+                "4": reexport(4, "crate::imp::Widget"),
+                "5": reexport(5, "imp::Widget"),
+                "6": reexport(6, "crate::imp::Widget"),
+                "10": json_item(10, Some("Widget"), json!({ "struct": {
+                    "kind": "unit",
+                    "generics": { "params": [], "where_predicates": [] },
+                    "impls": [],
+                } })),
+            },
+            "paths": {
+                "0": { "crate_id": 0, "path": ["my_crate"], "kind": "module" },
+                "10": { "crate_id": 0, "path": ["my_crate", "imp", "Widget"], "kind": "struct" },
+            },
+            "external_crates": {},
+            "target": { "triple": "x86_64-unknown-linux-gnu", "target_features": [] },
+            "format_version": 56,
+        }))
+        .unwrap();
+        let index = parse_crate(&krate, "my_crate", "1.0.0", None);
+
+        let widget = &index.items["my_crate::Widget"];
+        assert_eq!(
+            widget.reexports,
+            ["my_crate::outer::Widget", "my_crate::outer::inner::Widget"]
+        );
+        assert!(!index.items.contains_key("my_crate::outer::inner::Widget"));
+        assert!(!index.items.contains_key("my_crate::imp::Widget"));
+        for path in ["my_crate::outer::inner::Widget", "my_crate::imp::Widget"] {
+            assert_eq!(index.get_item(path).unwrap().path, "my_crate::Widget");
+        }
+    }
+
+    #[test]
+    fn parse_crate_renders_restricted_visibility() {
+        for (visibility, expected, prefix) in [
+            (
+                rustdoc_types::Visibility::Public,
+                Visibility::Public,
+                "pub ",
+            ),
+            (
+                rustdoc_types::Visibility::Crate,
+                Visibility::Crate,
+                "pub(crate) ",
+            ),
+            (
+                rustdoc_types::Visibility::Restricted {
+                    parent: rustdoc_types::Id(0),
+                    path: "crate::inner".to_string(),
+                },
+                Visibility::Restricted("crate::inner".to_string()),
+                "pub(in crate::inner) ",
+            ),
+            (rustdoc_types::Visibility::Default, Visibility::Private, ""),
+        ] {
+            let mut krate = crate_with_impls(&[]);
+            krate
+                .index
+                .get_mut(&rustdoc_types::Id(1))
+                .unwrap()
+                .visibility = visibility;
+            let index = parse_crate(&krate, "my_crate", "1.0.0", None);
+            let widget = &index.items["my_crate::Widget"];
+            assert_eq!(widget.visibility, expected);
+            assert_eq!(widget.signature, format!("{prefix}struct Widget;"));
+        }
+    }
+
+    #[test]
+    fn parse_crate_indexes_primitives_with_their_impls() {
+        let generics = json!({ "params": [], "where_predicates": [] });
+        let method = |name: &str, id: u32| {
+            let function = json!({ "function": {
+                "sig": { "inputs": [], "output": null, "is_c_variadic": false },
+                "generics": generics,
+                "header": { "is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust" },
+                "has_body": true,
+            } });
+            json_item(id, Some(name), function)
+        };
+        let impl_for = |id: u32, for_: Value, method_id: u32| {
+            let impl_ = json!({ "impl": {
+                "is_unsafe": false, "generics": generics, "provided_trait_methods": [],
+                "trait": null, "for": for_, "items": [method_id], "is_negative": false,
+                "is_synthetic": false, "blanket_impl": null,
+            } });
+            json_item(id, None, impl_)
+        };
+        let krate: Crate = serde_json::from_value(json!({
+            "root": 0, "crate_version": null, "includes_private": false,
+            "index": {
+                "0": json_item(0, Some("core"), json!({ "module": {
+                    "is_crate": true, "items": [1, 2, 3], "is_stripped": false,
+                } })),
+                "1": json_item(1, Some("str"), json!({ "module": {
+                    "is_crate": false, "items": [], "is_stripped": false,
+                } })),
+                "2": json_item(2, Some("str"), json!({ "primitive": { "name": "str", "impls": [10] } })),
+                "3": json_item(3, Some("slice"), json!({ "primitive": { "name": "slice", "impls": [] } })),
+                "10": impl_for(10, json!({ "primitive": "str" }), 11),
+                "11": method("len", 11),
+                "12": impl_for(12, json!({ "slice": { "generic": "T" } }), 13),
+                "13": method("first", 13),
+            },
+            "paths": {
+                "0": { "crate_id": 0, "path": ["core"], "kind": "module" },
+                "1": { "crate_id": 0, "path": ["core", "str"], "kind": "module" },
+                "2": { "crate_id": 0, "path": ["core", "str"], "kind": "primitive" },
+                "3": { "crate_id": 0, "path": ["core", "slice"], "kind": "primitive" },
+                "11": { "crate_id": 0, "path": ["core", "str", "len"], "kind": "function" },
+            },
+            "external_crates": {},
+            "target": { "triple": "x86_64-unknown-linux-gnu", "target_features": [] },
+            "format_version": 56,
+        }))
+        .unwrap();
+        let index = parse_crate(&krate, "core", "1.0.0", None);
+
+        assert_eq!(index.items["core::str"].kind, ItemKind::Module);
+        assert_eq!(
+            index.items["core::primitive::str"].kind,
+            ItemKind::Primitive
+        );
+        assert_eq!(index.items["core::primitive::slice"].signature, "[T]");
+        // Methods stay with their impl even where rustdoc gives them a module path
+        assert!(!index.items.contains_key("core::str::len"));
+        let str_impls = &index.impl_blocks["core::primitive::str"];
+        assert_eq!(str_impls[0].header, "impl str");
+        assert_eq!(str_impls[0].methods[0].name, "len");
+        assert_eq!(
+            index.impl_blocks["core::primitive::slice"][0].header,
+            "impl [T]"
+        );
+
+        // A bare primitive name means the type, a crate path the module
+        assert_eq!(index.get_item("str").unwrap().path, "core::primitive::str");
+        assert_eq!(index.get_item("core::str").unwrap().kind, ItemKind::Module);
+        assert_eq!(index.get_impl_blocks("slice").len(), 1);
+    }
+
+    #[test]
+    fn parse_crate_indexes_proc_macros_by_kind() {
+        let proc_macro = |id: u32, name: &str, kind: &str, helpers: &[&str]| {
+            json_item(
+                id,
+                Some(name),
+                json!({ "proc_macro": { "kind": kind, "helpers": helpers } }),
+            )
+        };
+        let krate: Crate = serde_json::from_value(json!({
+            "root": 0, "crate_version": "1.0.0", "includes_private": false,
+            "index": {
+                "0": json_item(0, Some("my_macros"), json!({ "module": {
+                    "is_crate": true, "items": [1, 2, 3], "is_stripped": false,
+                } })),
+                "1": proc_macro(1, "Builder", "derive", &["builder", "builder_field"]),
+                "2": proc_macro(2, "route", "attr", &[]),
+                "3": proc_macro(3, "sql", "bang", &[]),
+            },
+            "paths": {
+                "0": { "crate_id": 0, "path": ["my_macros"], "kind": "module" },
+                "1": { "crate_id": 0, "path": ["my_macros", "Builder"], "kind": "proc_derive" },
+                "2": { "crate_id": 0, "path": ["my_macros", "route"], "kind": "proc_attribute" },
+                "3": { "crate_id": 0, "path": ["my_macros", "sql"], "kind": "macro" },
+            },
+            "external_crates": {},
+            "target": { "triple": "x86_64-unknown-linux-gnu", "target_features": [] },
+            "format_version": 56,
+        }))
+        .unwrap();
+        let index = parse_crate(&krate, "my_macros", "1.0.0", None);
+
+        let derive = &index.items["my_macros::Builder"];
+        assert_eq!(derive.kind, ItemKind::DeriveMacro);
+        assert_eq!(derive.signature, "#[derive(Builder)]");
+        assert_eq!(derive.detail.helper_attrs, ["builder", "builder_field"]);
+
+        let attr = &index.items["my_macros::route"];
+        assert_eq!(attr.kind, ItemKind::AttributeMacro);
+        assert_eq!(attr.signature, "#[route]");
+        assert!(attr.detail.helper_attrs.is_empty());
+
+        let bang = &index.items["my_macros::sql"];
+        assert_eq!(bang.kind, ItemKind::Macro);
+        assert_eq!(bang.signature, "sql!(/* proc-macro */)");
+        assert!(bang.detail.helper_attrs.is_empty());
+    }
+
+    // ========== parse_stability tests ==========
+
+    #[test]
+    fn parses_source_stability_attributes() {
+        assert_eq!(
+            parse_stability(r#"#[stable(feature = "rust1", since = "1.0.0")]"#),
+            Some(Stability::Stable {
+                since: "1.0.0".to_string()
+            })
+        );
+        assert_eq!(
+            parse_stability(r#"#[unstable(feature = "allocator_api", issue = "32838")]"#),
+            Some(Stability::Unstable {
+                feature: "allocator_api".to_string(),
+                issue: Some("32838".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_stability(r#"#[unstable(feature = "rustc_private", issue = "none")]"#),
+            Some(Stability::Unstable {
+                feature: "rustc_private".to_string(),
+                issue: None,
+            })
+        );
+        assert_eq!(parse_stability("#[must_use]"), None);
+    }
+
+    #[test]
+    fn parses_hir_stability_attributes() {
+        let stable = r#"#[attr = Stability {stability: Stability { level: Stable { since: Version(RustcVersion { major: 1, minor: 36, patch: 0 }), allowed_through_unstable_modules: None }, feature: "futures_api" }, span: ..}]"#;
+        assert_eq!(
+            parse_stability(stable),
+            Some(Stability::Stable {
+                since: "1.36.0".to_string()
+            })
+        );
+        let unstable = r#"#[attr = Stability {stability: Stability { level: Unstable { reason: None, issue: Some(32838), is_soft: false }, feature: "allocator_api" }, span: ..}]"#;
+        assert_eq!(
+            parse_stability(unstable),
+            Some(Stability::Unstable {
+                feature: "allocator_api".to_string(),
+                issue: Some("32838".to_string()),
+            })
+        );
+    }
+
+    // ========== macro_arms tests ==========
+
+    #[test]
+    fn macro_arms_extracts_each_matcher() {
+        let source = "macro_rules! vec {\n    () => { ... };\n    ($elem:expr; $n:expr) => { ... };\n    ($($x:expr),+ $(,)?) => { ... };\n}";
+        assert_eq!(
+            macro_arms(source),
+            vec!["()", "($elem:expr; $n:expr)", "($($x:expr),+ $(,)?)"]
+        );
+    }
+
+    #[test]
+    fn macro_arms_handles_bracket_and_brace_matchers() {
+        let source = "macro_rules! m {\n    [$x:ident] => { ... };\n    {$($t:tt)*} => { ... };\n}";
+        assert_eq!(macro_arms(source), vec!["[$x:ident]", "{$($t:tt)*}"]);
+    }
+
+    #[test]
+    fn macro_arms_collapses_multiline_matchers() {
+        let source = "macro_rules! select {\n    (\n        $($bind:pat = $fut:expr => $handler:expr,)+\n    ) => { ... };\n}";
+        assert_eq!(
+            macro_arms(source),
+            vec!["( $($bind:pat = $fut:expr => $handler:expr,)+ )"]
+        );
+    }
+
+    #[test]
+    fn macro_arms_macros_2_0_single_arm() {
+        assert_eq!(
+            macro_arms("pub macro assert_matches($left:expr, $right:pat) { ... }"),
+            vec!["($left:expr, $right:pat)"]
+        );
+    }
+
+    #[test]
+    fn macro_arms_skips_delimiters_in_literals() {
+        let source = r##"macro_rules! m {
+    (open "(" $x:expr) => { ... };
+    (char ')' $y:expr) => { ... };
+    (escaped "\")" '\'' $z:expr) => { ... };
+    (raw r#"}"# $w:expr) => { ... };
+    (life $l:lifetime 'a) => { ... };
+}"##;
+        assert_eq!(
+            macro_arms(source),
+            vec![
+                r#"(open "(" $x:expr)"#,
+                "(char ')' $y:expr)",
+                r#"(escaped "\")" '\'' $z:expr)"#,
+                r##"(raw r#"}"# $w:expr)"##,
+                "(life $l:lifetime 'a)",
+            ]
+        );
+    }
+
+    #[test]
+    fn macro_arms_empty_source() {
+        assert!(macro_arms("").is_empty());
+    }
+
+    // ========== code_blocks / doc_headings / macro_usages tests ==========
+
+    #[test]
+    fn code_blocks_skips_non_rust_fences() {
+        let doc = "Intro\n\n```\nlet a = 1;\n```\n\n```toml\n[dependencies]\n```\n\n```rust,no_run\nlet b = 2;\n```";
+        assert_eq!(code_blocks(doc), vec!["let a = 1;\n", "let b = 2;\n"]);
+    }
+
+    #[test]
+    fn doc_headings_skips_code_fences() {
+        let doc = "Intro\n\n# Examples\n\n```\n# use std::io;\n```\n\n## Cancellation safety ##\n\n#not a heading";
+        assert_eq!(doc_headings(doc), vec!["Examples", "Cancellation safety"]);
+    }
+
+    #[test]
+    fn macro_usages_collects_invocations_and_skips_hidden_lines() {
+        let doc = "```\n# let hidden = vec![0];\nlet v = vec![1, 2, 3];\nlet w = vec![0; 5];\nlet v = vec![1, 2, 3];\n```";
+        assert_eq!(
+            macro_usages(doc, "vec"),
+            vec!["let v = vec![1, 2, 3];", "let w = vec![0; 5];"]
+        );
+    }
+
+    #[test]
+    fn macro_usages_requires_an_identifier_boundary() {
+        let doc = "```
+let a = my_vec![1];
+let b = smallvec![2];
+let c = std::vec![3];
+let d = (vec![4], my_vec![5]);
+```";
+        assert_eq!(
+            macro_usages(doc, "vec"),
+            vec!["let c = std::vec![3];", "let d = (vec![4], my_vec![5]);"]
+        );
+    }
+
+    // ========== type_paths tests ==========
+
+    fn resolved(path: &str, id: u32, args: Vec<Type>) -> Type {
+        Type::ResolvedPath(RustdocPath {
+            path: path.to_string(),
+            id: Id(id),
+            args: Some(Box::new(GenericArgs::AngleBracketed {
+                args: args.into_iter().map(GenericArg::Type).collect(),
+                constraints: Vec::new(),
+            })),
+        })
+    }
+
+    #[test]
+    fn type_paths_walks_references_and_generic_arguments() {
+        // &Result<Vec<Bytes>, Box<dyn Error>>
+        let error = Type::DynTrait(rustdoc_types::DynTrait {
+            traits: vec![rustdoc_types::PolyTrait {
+                trait_: RustdocPath {
+                    path: "Error".to_string(),
+                    id: Id(4),
+                    args: None,
+                },
+                generic_params: Vec::new(),
+            }],
+            lifetime: None,
+        });
+        let ty = Type::BorrowedRef {
+            lifetime: None,
+            is_mutable: false,
+            type_: Box::new(resolved(
+                "Result",
+                0,
+                vec![
+                    resolved("Vec", 1, vec![resolved("bytes::Bytes", 2, Vec::new())]),
+                    resolved("Box", 3, vec![error]),
+                ],
+            )),
+        };
+
+        let mut paths = Vec::new();
+        type_paths(&ty, &mut paths);
+        let names: Vec<&str> = paths.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(names, ["Result", "Vec", "bytes::Bytes", "Box", "Error"]);
+    }
+}
@@ -1,22 +1,101 @@
-use super::index::{CrateIndex, ImplBlock, IndexedItem, ItemKind, SearchResult};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime};
 
-/// Render a module listing (for `lookup_crate_items`).
-pub fn render_crate_items(index: &CrateIndex, module_path: Option<&str>) -> String {
-    let items = index.get_module_items(module_path);
+use super::bound_error::BoundError;
+use super::cache::CacheEntry;
+use super::cfg::{FeatureMap, FeatureModule, describe_cfg};
+use super::construct::{BuilderInfo, ConstructionGuide, has_receiver, mentions_type, return_type};
+use super::crates_io::{CrateSummary, Dependent, VersionInfo};
+use super::diff::{ApiChange, ApiDiff};
+use super::doctests::{DoctestGroup, DoctestMode};
+use super::examples::ExampleFile;
+use super::fetcher::{BuildInfo, NEWEST_KNOWN_FORMAT};
+use super::index::{
+    AsyncKind, AsyncReport, CrateIndex, DeprecatedEntry, Deprecation, ErrorType, ForeignReexport,
+    ImplBlock, IndexedItem, ItemKind, ItemOrder, MethodInfo, MethodOrigins, SearchResult,
+    SourceSpan, Stability, UnsafeReport,
+};
+use super::markdown::{html_to_markdown, normalize_doc, summarize_doc};
+use super::signature::{condensed_signature, pretty_signature};
+use super::snippet::Reference;
+use super::stats::{ApiSurface, CrateStats};
+use super::std_docs::KeywordDoc;
+use super::thread_safety::{AutoTraitStatus, ThreadSafety, Verdict};
+use super::tree::ModuleNode;
+use crate::cargo_lock::LockDiff;
+use crate::workspace::rust_version_exceeds;
+
+/// Doc comments longer than this (in characters) are cut to their leading paragraphs
+/// by `render_item()` unless `full_doc` is set.
+const SUMMARY_DOC_CHARS: usize = 2000;
+
+/// Layout of rendered signatures and method listings, set per server and per tool call.
+#[derive(Debug, Clone, Copy)]
+pub struct RenderOptions {
+    /// Column width that long signatures are wrapped to.
+    pub width: usize,
+    /// Wrap long function signatures to one parameter per line.
+    pub wrap_signatures: bool,
+    /// Put a method's one-line summary on the same line as its signature in listings.
+    pub inline_docs: bool,
+    /// Show an item's whole doc comment instead of the leading paragraphs of a long one.
+    pub full_doc: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            width: 80,
+            wrap_signatures: true,
+            inline_docs: false,
+            full_doc: false,
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn signature(&self, signature: &str) -> String {
+        if self.wrap_signatures {
+            pretty_signature(signature, self.width)
+        } else {
+            signature.to_string()
+        }
+    }
+}
+
+/// Render a module listing (for `lookup_crate_items`), keeping only items matching `filter`.
+///
+/// Items are grouped under a heading per kind in `ItemOrder::Kind` order; any other
+/// order gives one list with each item's kind in brackets.
+#[tracing::instrument(name = "render", skip_all)]
+pub fn render_crate_items(
+    index: &CrateIndex,
+    module_path: Option<&str>,
+    include_signatures: bool,
+    order: ItemOrder,
+    filter: impl Fn(&IndexedItem) -> bool,
+) -> String {
+    let mut items = index.get_module_items(module_path);
+    items.retain(|item| filter(item));
+    items.sort_by(|a, b| order.compare(a, b));
+    let mut local_reexports = index.module_local_reexports(module_path);
+    local_reexports.retain(|item| filter(item));
+    let reexports = index.module_reexports(module_path);
 
     let header = match module_path {
         Some(path) => format!("## {path}\n"),
         None => format!("## {} v{}\n", index.crate_name, index.version),
     };
 
-    if items.is_empty() {
+    if items.is_empty() && local_reexports.is_empty() && reexports.is_empty() {
         let suggestion = match module_path {
             Some(path) => {
                 let suggestions = index.suggest_similar(path, 5);
                 if suggestions.is_empty() {
                     String::new()
                 } else {
-                    format!("\nDid you mean one of: {}?", suggestions.join(", "))
+                    let paths: Vec<&str> = suggestions.iter().map(|s| s.path.as_str()).collect();
+                    format!("\nDid you mean one of: {}?", paths.join(", "))
                 }
             }
             None => String::new(),
@@ -24,69 +103,1889 @@ pub fn render_crate_items(index: &CrateIndex, module_path: Option<&str>) -> Stri
         return format!("{header}\nNo items found.{suggestion}");
     }
 
-    let mut sections: Vec<String> = Vec::new();
-    let mut current_kind: Option<ItemKind> = None;
+    let mut sections: Vec<String> = Vec::new();
+    let mut current_kind: Option<ItemKind> = None;
+
+    if order != ItemOrder::Kind && !items.is_empty() {
+        sections.push("\n### Items\n".to_string());
+    }
+    for item in &items {
+        if order == ItemOrder::Kind && current_kind.as_ref() != Some(&item.kind) {
+            current_kind = Some(item.kind.clone());
+            sections.push(format!("\n### {}\n", kind_label_plural(&item.kind)));
+        }
+        let kind_prefix = if order == ItemOrder::Kind {
+            String::new()
+        } else {
+            format!("[{}] ", item.kind)
+        };
+
+        let doc_suffix = if item.short_doc.is_empty() {
+            String::new()
+        } else {
+            format!(" — {}", item.short_doc)
+        };
+
+        let label = if include_signatures {
+            listing_signature(item).unwrap_or_else(|| item.name.clone())
+        } else {
+            item.name.clone()
+        };
+        // How much a submodule holds, so it's clear which ones are worth opening
+        let count_suffix = if item.kind == ItemKind::Module {
+            let path = Some(item.path.as_str());
+            let listed = index
+                .get_module_items(path)
+                .into_iter()
+                .chain(index.module_local_reexports(path))
+                .filter(|child| filter(child))
+                .count()
+                + index.module_reexports(path).len();
+            match listed {
+                1 => " (1 item)".to_string(),
+                n => format!(" ({n} items)"),
+            }
+        } else {
+            String::new()
+        };
+        sections.push(format!(
+            "- {kind_prefix}`{label}`{count_suffix}{doc_suffix}"
+        ));
+    }
+
+    if !local_reexports.is_empty() {
+        sections.push("\n### Re-exports\n".to_string());
+        for item in local_reexports {
+            sections.push(format!(
+                "- [{}] `{}` → `{}`",
+                item.kind, item.name, item.path
+            ));
+        }
+    }
+
+    if !reexports.is_empty() {
+        sections.push("\n### Re-exports from other crates\n".to_string());
+        for reexport in reexports {
+            let name = reexport.path.rsplit("::").next().unwrap_or(&reexport.path);
+            let kind = reexport
+                .kind
+                .as_ref()
+                .map(|kind| format!("[{kind}] "))
+                .unwrap_or_default();
+            sections.push(format!(
+                "- {kind}`{name}` → `{}` (crate `{}`)",
+                reexport.target_path, reexport.crate_name
+            ));
+        }
+    }
+
+    format!("{header}{}", sections.join("\n"))
+}
+
+/// Maximum length of a signature shown in an item listing.
+const LISTING_SIGNATURE_CHARS: usize = 120;
+
+/// An item's signature condensed to one line for listings (`include_signatures`):
+/// a function's full signature, or a type's shape. `None` for modules, which list
+/// their contents instead.
+fn listing_signature(item: &IndexedItem) -> Option<String> {
+    (item.kind != ItemKind::Module && !item.signature.is_empty())
+        .then(|| condensed_signature(&item.signature, LISTING_SIGNATURE_CHARS))
+}
+
+/// Render the items matching a glob pattern as a compact listing.
+pub fn render_glob_matches(
+    index: &CrateIndex,
+    pattern: &str,
+    items: &[&IndexedItem],
+    include_signatures: bool,
+) -> String {
+    if items.is_empty() {
+        return format!(
+            "No items matching `{pattern}` in {} v{}.",
+            index.crate_name, index.version
+        );
+    }
+
+    let mut parts = Vec::new();
+    parts.push(format!(
+        "## Items matching `{pattern}` in {} v{} ({} found)\n",
+        index.crate_name,
+        index.version,
+        items.len()
+    ));
+    for item in items {
+        let doc_suffix = if item.short_doc.is_empty() {
+            String::new()
+        } else {
+            format!(" — {}", item.short_doc)
+        };
+        parts.push(format!(
+            "- [{}] `{}`{}{doc_suffix}",
+            item.kind,
+            item.path,
+            reexport_suffix(item)
+        ));
+        if include_signatures && let Some(signature) = listing_signature(item) {
+            parts.push(format!("  `{signature}`"));
+        }
+    }
+
+    parts.join("\n")
+}
+
+/// Render every item of one kind (for `list_items_by_kind`), up to `limit` entries.
+/// `more_token` is the `read_more` token for the entries past the limit.
+pub fn render_items_by_kind(
+    index: &CrateIndex,
+    kind: &ItemKind,
+    items: &[&IndexedItem],
+    limit: usize,
+    more_token: Option<&str>,
+) -> String {
+    let label = kind_label_plural(kind);
+    if items.is_empty() {
+        return format!(
+            "No {label} found in {} v{}.",
+            index.crate_name, index.version
+        );
+    }
+
+    let mut parts = Vec::new();
+    parts.push(format!(
+        "## {label} in {} v{} ({} total)\n",
+        index.crate_name,
+        index.version,
+        items.len()
+    ));
+    parts.push(render_item_entries(&items[..limit.min(items.len())]));
+    if items.len() > limit {
+        parts.push(format!(
+            "\n_…and {} more ({}raise `limit` to see them)._",
+            items.len() - limit,
+            read_more_hint(more_token)
+        ));
+    }
+
+    parts.join("\n")
+}
+
+/// One `- `path` — summary` line per item, as listed by `list_items_by_kind`.
+pub fn render_item_entries(items: &[&IndexedItem]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            let also = reexport_suffix(item);
+            if item.short_doc.is_empty() {
+                format!("- `{}`{also}", item.path)
+            } else {
+                format!("- `{}`{also} — {}", item.path, item.short_doc)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// " (also `a::X`, `b::X`)" for an item re-exported at further public paths, so it
+/// is listed once under its canonical path.
+fn reexport_suffix(item: &IndexedItem) -> String {
+    if item.reexports.is_empty() {
+        return String::new();
+    }
+    let paths: Vec<String> = item.reexports.iter().map(|p| format!("`{p}`")).collect();
+    format!(" (also {})", paths.join(", "))
+}
+
+/// "call `read_more` with `token: "…"`, or " when a continuation token exists.
+fn read_more_hint(token: Option<&str>) -> String {
+    token
+        .map(|token| format!("call `read_more` with `token: \"{token}\"`, or "))
+        .unwrap_or_default()
+}
+
+/// A one-line note that Cargo.lock also has `others` (version, `from-lock:` selector)
+/// of the crate shown.
+pub fn render_locked_versions_note(index: &CrateIndex, others: &[(&str, String)]) -> String {
+    let others: Vec<String> = others
+        .iter()
+        .map(|(version, selector)| format!("{version} (`version: \"from-lock:{selector}\"`)"))
+        .collect();
+    format!(
+        "_Showing {} v{}. Cargo.lock also has {}._",
+        index.package_name,
+        index.version,
+        others.join(", ")
+    )
+}
+
+/// A warning for docs in a rustdoc JSON format newer than the server knows, which were
+/// decoded best-effort.
+pub fn render_format_note(index: &CrateIndex) -> Option<String> {
+    (index.format_version > NEWEST_KNOWN_FORMAT).then(|| {
+        format!(
+            "_Note: docs may be incomplete (format {}). They use a rustdoc JSON format newer \
+             than this server supports ({NEWEST_KNOWN_FORMAT}), so items it couldn't read \
+             were skipped._",
+            index.format_version
+        )
+    })
+}
+
+/// A note for docs served from an older cached copy because docs.rs failed.
+pub fn render_stale_note(index: &CrateIndex) -> Option<String> {
+    let stale = index.stale.as_ref()?;
+    let age = stale
+        .cached_at
+        .and_then(|cached_at| SystemTime::now().duration_since(cached_at).ok())
+        .map(|age| format!(" from {}", describe_age(age)))
+        .unwrap_or_default();
+    let requested = if stale.requested == index.version {
+        String::new()
+    } else {
+        format!(", not the requested {}", stale.requested)
+    };
+    Some(format!(
+        "_Note: serving stale docs{age} (cached v{}{requested}) because docs.rs could not be \
+         reached: {}. Call `refresh_crate` to retry._",
+        index.version, stale.error
+    ))
+}
+
+/// When the docs behind "latest" or a local build were fetched or built.
+pub fn render_fetched_note(index: &CrateIndex) -> Option<String> {
+    let fetched_at = index.fetched_at?;
+    let age = SystemTime::now()
+        .duration_since(fetched_at)
+        .unwrap_or_default();
+    let verb = if index.version == "latest" {
+        "fetched"
+    } else {
+        "built"
+    };
+    Some(format!("_Docs {verb} {}._", describe_age(age)))
+}
+
+/// A note for an index cut short by the parse budget, whose full version is still
+/// being built.
+pub fn render_partial_note(index: &CrateIndex) -> Option<String> {
+    index.partial.then(|| {
+        "_Note: partial index. Parsing these docs ran over the parse budget \
+         (`--parse-budget-ms`), so some items only have their first doc sentence. The full \
+         index is being built in the background; call again shortly for complete docs._"
+            .to_string()
+    })
+}
+
+/// Render the deprecation report (for `list_deprecated_items`).
+pub fn render_deprecated(index: &CrateIndex, entries: &[DeprecatedEntry<'_>]) -> String {
+    if entries.is_empty() {
+        return format!(
+            "No deprecated items in {} v{}.",
+            index.crate_name, index.version
+        );
+    }
+
+    let mut parts = Vec::new();
+    parts.push(format!(
+        "## Deprecated items in {} v{} ({} total)\n",
+        index.crate_name,
+        index.version,
+        entries.len()
+    ));
+    for entry in entries {
+        parts.push(format!(
+            "- [{}] `{}`{}",
+            entry.kind,
+            entry.path,
+            deprecation_suffix(entry.deprecation)
+        ));
+    }
+    parts.join("\n")
+}
+
+/// Render the async API inventory (for `list_async_items`): `async fn`s, then functions
+/// returning futures, then those returning streams.
+pub fn render_async_report(index: &CrateIndex, report: &AsyncReport<'_>) -> String {
+    let header = format!("## Async API in {} v{}\n", index.crate_name, index.version);
+    if report.functions.is_empty() && report.methods.is_empty() {
+        return format!(
+            "{header}\nNo public async functions or methods returning futures or streams."
+        );
+    }
+
+    let sections = [
+        (AsyncKind::AsyncFn, "`async fn`s"),
+        (AsyncKind::Future, "Returning Futures"),
+        (AsyncKind::Stream, "Returning Streams"),
+    ];
+    let mut parts = vec![header];
+    for (kind, title) in sections {
+        let functions: Vec<&IndexedItem> = report
+            .functions
+            .iter()
+            .filter(|(_, k)| *k == kind)
+            .map(|(item, _)| *item)
+            .collect();
+        let methods: Vec<(&str, &MethodInfo)> = report
+            .methods
+            .iter()
+            .filter(|(_, _, k)| *k == kind)
+            .map(|(owner, method, _)| (*owner, *method))
+            .collect();
+        if functions.is_empty() && methods.is_empty() {
+            continue;
+        }
+        parts.push(format!(
+            "### {title} ({})\n",
+            functions.len() + methods.len()
+        ));
+        for item in functions {
+            if item.short_doc.is_empty() {
+                parts.push(format!("- `{}`", item.path));
+            } else {
+                parts.push(format!("- `{}` — {}", item.path, item.short_doc));
+            }
+        }
+        for (owner, method) in methods {
+            parts.push(format!("- `{owner}` — `{}`", method.signature));
+        }
+        parts.push(String::new());
+    }
+
+    parts.join("\n")
+}
+
+/// Render the `Send`/`Sync`/`Unpin` status of a type with the impl behind each, and
+/// what it means for moving and sharing values across threads (for `is_thread_safe`).
+pub fn render_thread_safety(item: &IndexedItem, safety: &ThreadSafety<'_>) -> String {
+    let mut parts = vec![format!("## Thread safety of `{}`\n", item.path)];
+    let verdict = |verdict: Verdict| match verdict {
+        Verdict::Yes => "**yes**".to_string(),
+        Verdict::If(bounds) => {
+            let bounds: Vec<String> = bounds.iter().map(|b| format!("`{b}`")).collect();
+            format!("**only if** {}", bounds.join(", "))
+        }
+        Verdict::No => "**no**".to_string(),
+        Verdict::Unknown => "**unknown** (no `Send`/`Sync` impls recorded)".to_string(),
+    };
+    parts.push(format!(
+        "- Move to another thread (`thread::spawn`, channels; needs `Send`): {}",
+        verdict(safety.can_move())
+    ));
+    parts.push(format!(
+        "- Share across threads behind `Arc` (needs `Send + Sync`): {}\n",
+        verdict(safety.can_share())
+    ));
+
+    parts.push("### Auto traits\n".to_string());
+    for (trait_name, status) in [
+        ("Send", safety.send),
+        ("Sync", safety.sync),
+        ("Unpin", safety.unpin),
+    ] {
+        let answer = match status {
+            AutoTraitStatus::Yes(_) => "yes",
+            AutoTraitStatus::Conditional(_) => "conditionally",
+            AutoTraitStatus::No(_) => "no",
+            AutoTraitStatus::Unknown => "unknown",
+        };
+        let cited = match status.cited() {
+            Some(i) if i.is_synthetic => format!(" — `{}` (auto)", i.header),
+            Some(i) => format!(" — `{}` (written in the crate)", i.header),
+            None => String::new(),
+        };
+        parts.push(format!("- **{trait_name}**: {answer}{cited}"));
+    }
+
+    parts.join("\n")
+}
+
+/// Render the module tree with the cfg each module and gated item requires, after a
+/// summary of the features named (for `feature_map`).
+pub fn render_feature_map(index: &CrateIndex, map: &FeatureMap<'_>) -> String {
+    let header = format!(
+        "## Feature-gated API of {} v{}\n",
+        index.crate_name, index.version
+    );
+    let Some(root) = &map.root else {
+        return format!("{header}\nNo root module found.");
+    };
+    if map.features.is_empty() && !has_cfg(root) {
+        return format!(
+            "{header}\nNo item is marked with `#[doc(cfg(...))]`, so the docs don't say which \
+             features unlock what. Everything listed was built with the features docs.rs used."
+        );
+    }
+
+    let mut parts = vec![header];
+    if !map.features.is_empty() {
+        parts.push("### Features\n".to_string());
+        for (feature, count) in &map.features {
+            let plural = if *count == 1 { "" } else { "s" };
+            parts.push(format!("- `{feature}` — gates {count} item{plural}"));
+        }
+        parts.push(String::new());
+    }
+    parts.push("### Modules\n".to_string());
+    push_feature_module(&mut parts, root, 0);
+    parts.join("\n")
+}
+
+/// Whether a module or anything under it has a cfg.
+fn has_cfg(module: &FeatureModule<'_>) -> bool {
+    module.module.cfg.is_some()
+        || !module.gated_items.is_empty()
+        || module.submodules.iter().any(has_cfg)
+}
+
+fn push_feature_module(parts: &mut Vec<String>, module: &FeatureModule<'_>, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let requires = module
+        .module
+        .cfg
+        .as_deref()
+        .map(|cfg| format!(" — requires {}", describe_cfg(cfg)))
+        .unwrap_or_default();
+    let plural = if module.item_count == 1 { "" } else { "s" };
+    parts.push(format!(
+        "{indent}- `{}` ({} item{plural}){requires}",
+        module.module.path, module.item_count
+    ));
+    for item in &module.gated_items {
+        let cfg = item.cfg.as_deref().unwrap_or_default();
+        parts.push(format!(
+            "{indent}  - {} `{}` — requires {}",
+            item.kind,
+            item.name,
+            describe_cfg(cfg)
+        ));
+    }
+    for submodule in &module.submodules {
+        push_feature_module(parts, submodule, depth + 1);
+    }
+}
+
+/// Render where a name is defined across crates (for `which_crate_defines`), with how
+/// many crates were searched and how many Cargo.lock crates are left to load.
+pub fn render_definitions(
+    name: &str,
+    definitions: &[(&CrateIndex, Vec<&IndexedItem>)],
+    searched: usize,
+    failed: &[String],
+    unloaded: usize,
+) -> String {
+    let mut parts = vec![format!("## Where `{name}` is defined\n")];
+    if definitions.is_empty() {
+        parts.push(format!(
+            "Not found in the {searched} loaded crate{}.\n",
+            if searched == 1 { "" } else { "s" }
+        ));
+    }
+    for (index, items) in definitions {
+        parts.push(format!("### {} v{}\n", index.package_name, index.version));
+        for item in items {
+            let relative = item
+                .path
+                .strip_prefix(&format!("{}::", index.crate_name))
+                .unwrap_or(&item.path);
+            let summary = if item.short_doc.is_empty() {
+                String::new()
+            } else {
+                format!(" — {}", item.short_doc)
+            };
+            parts.push(format!("- {} `{}`{summary}", item.kind, item.path));
+            parts.push(format!(
+                "  → `lookup_item` with `crate_name: \"{}\"`, `item_path: \"{relative}\"`",
+                index.package_name
+            ));
+        }
+        parts.push(String::new());
+    }
+    if !failed.is_empty() {
+        parts.push(format!("Could not load: {}\n", failed.join(", ")));
+    }
+    if unloaded > 0 {
+        parts.push(format!(
+            "_{unloaded} Cargo.lock crate{} not searched yet: call again to load and search more._",
+            if unloaded == 1 { " is" } else { "s are" }
+        ));
+    }
+    parts.join("\n")
+}
+
+/// Render the unsafe API report (for `list_unsafe_items`).
+pub fn render_unsafe_report(index: &CrateIndex, report: &UnsafeReport<'_>) -> String {
+    let header = format!("## Unsafe API in {} v{}\n", index.crate_name, index.version);
+    if report.functions.is_empty() && report.traits.is_empty() && report.methods.is_empty() {
+        return format!("{header}\nNo public unsafe functions, traits, or methods.");
+    }
+
+    let mut parts = vec![header];
+    if !report.functions.is_empty() {
+        parts.push(format!(
+            "### Unsafe Functions ({})\n",
+            report.functions.len()
+        ));
+        for item in &report.functions {
+            let doc_suffix = if item.short_doc.is_empty() {
+                String::new()
+            } else {
+                format!(" — {}", item.short_doc)
+            };
+            parts.push(format!("- `{}`{doc_suffix}", item.path));
+        }
+        parts.push(String::new());
+    }
+
+    if !report.traits.is_empty() {
+        parts.push(format!("### Traits ({})\n", report.traits.len()));
+        for (item, methods) in &report.traits {
+            let mut notes = Vec::new();
+            if item.is_unsafe {
+                notes.push("unsafe to implement".to_string());
+            }
+            if !methods.is_empty() {
+                let names: Vec<String> = methods.iter().map(|m| format!("`{}`", m.name)).collect();
+                notes.push(format!("unsafe methods: {}", names.join(", ")));
+            }
+            parts.push(format!("- `{}` — {}", item.path, notes.join("; ")));
+        }
+        parts.push(String::new());
+    }
+
+    if !report.methods.is_empty() {
+        parts.push(format!("### Unsafe Methods ({})\n", report.methods.len()));
+        for (type_path, method) in &report.methods {
+            parts.push(format!("- `{type_path}` — `{}`", method.signature));
+        }
+    }
+
+    parts.join("\n")
+}
+
+/// Render the crate's likely error types with their variants (for `list_error_types`).
+pub fn render_error_types(index: &CrateIndex, errors: &[ErrorType<'_>]) -> String {
+    if errors.is_empty() {
+        return format!(
+            "No error types found in {} v{}.",
+            index.crate_name, index.version
+        );
+    }
+
+    let mut parts = Vec::new();
+    parts.push(format!(
+        "## Error types in {} v{} ({} found)\n",
+        index.crate_name,
+        index.version,
+        errors.len()
+    ));
+    for error in errors {
+        let item = error.item;
+        parts.push(format!("### `{}` ({})\n", item.path, item.kind));
+        if !item.short_doc.is_empty() {
+            parts.push(format!("{}\n", item.short_doc));
+        }
+
+        let mut signals = Vec::new();
+        if error.implements_error {
+            signals.push("implements `Error`".to_string());
+        }
+        if error.result_uses > 0 {
+            let plural = if error.result_uses == 1 { "" } else { "s" };
+            signals.push(format!(
+                "returned in {} `Result`{plural}",
+                error.result_uses
+            ));
+        }
+        if signals.is_empty() {
+            signals.push("named `*Error`".to_string());
+        }
+        parts.push(format!("_{}_\n", signals.join(", ")));
+
+        if item.attrs.iter().any(|a| a.starts_with("#[non_exhaustive")) {
+            parts.push("`#[non_exhaustive]`: match with a wildcard arm.\n".to_string());
+        }
+        for v in &item.detail.variants {
+            let doc = if v.doc.is_empty() {
+                String::new()
+            } else {
+                format!(" — {}", doc_line(&v.doc))
+            };
+            let signature = v.signature.trim().trim_end_matches(',');
+            parts.push(format!("- `{signature}`{doc}"));
+        }
+        if !item.detail.variants.is_empty() {
+            parts.push(String::new());
+        }
+    }
+
+    parts.join("\n")
+}
+
+/// Render a step-by-step construction guide for each builder of `target` (for `find_builder`).
+pub fn render_builders(target: &IndexedItem, builders: &[BuilderInfo<'_>]) -> String {
+    if builders.is_empty() {
+        return format!(
+            "No builder found for `{}`. Try `how_to_construct` for other ways to create it.",
+            target.path
+        );
+    }
+
+    let mut parts = Vec::new();
+    parts.push(format!("## Building `{}`\n", target.path));
+    for info in builders {
+        let builder = info.builder;
+        parts.push(format!("### Via `{}`\n", builder.path));
+        if !builder.short_doc.is_empty() {
+            parts.push(format!("{}\n", builder.short_doc));
+        }
+
+        parts.push("1. Create the builder:".to_string());
+        for m in &info.entry_points {
+            parts.push(format!(
+                "   - `{}::{}` — `{}`",
+                target.name, m.name, m.signature
+            ));
+        }
+        for m in &info.constructors {
+            parts.push(format!(
+                "   - `{}::{}` — `{}`",
+                builder.name, m.name, m.signature
+            ));
+        }
+        if info.entry_points.is_empty() && info.constructors.is_empty() {
+            parts.push(
+                "   - _(no constructor found; check `Default` or `lookup_impl_block`)_".to_string(),
+            );
+        }
+
+        parts.push("2. Configure (chainable):".to_string());
+        for m in &info.setters {
+            parts.push(format!("   - `{}`", m.signature));
+        }
+        if info.setters.is_empty() {
+            parts.push("   - _(no setters found)_".to_string());
+        }
+
+        parts.push("3. Build:".to_string());
+        for m in &info.finishers {
+            parts.push(format!("   - `{}`", m.signature));
+        }
+        if info.finishers.is_empty() {
+            parts.push("   - _(no method returning the target found)_".to_string());
+        }
+
+        let start = match (info.entry_points.first(), info.constructors.first()) {
+            (Some(m), _) => Some(format!("{}::{}()", target.name, m.name)),
+            (None, Some(m)) => Some(format!("{}::{}()", builder.name, m.name)),
+            (None, None) => None,
+        };
+        if let (Some(start), Some(finish)) = (start, info.finishers.first()) {
+            let mut example = format!("let value = {start}");
+            for m in info.setters.iter().take(3) {
+                example.push_str(&format!("\n    .{}(/* ... */)", m.name));
+            }
+            example.push_str(&format!("\n    .{}()", finish.name));
+            if return_type(&finish.signature).is_some_and(|ty| ty.contains("Result<")) {
+                example.push('?');
+            }
+            parts.push(format!("\n```rust\n{example};\n```"));
+        }
+        parts.push(String::new());
+    }
+
+    parts.join("\n")
+}
+
+/// Render every way to create a value of `target` (for `how_to_construct`).
+pub fn render_construction_guide(target: &IndexedItem, guide: &ConstructionGuide<'_>) -> String {
+    let name = &target.name;
+    let mut parts = Vec::new();
+    parts.push(format!("## Ways to create `{}`\n", target.path));
+
+    if guide.default {
+        parts.push(format!(
+            "### Default\n\n```rust\nlet value = {name}::default();\n```\n"
+        ));
+    }
+
+    if !guide.constructors.is_empty() {
+        parts.push("### Constructors\n".to_string());
+        for m in &guide.constructors {
+            let doc = if m.doc.is_empty() {
+                String::new()
+            } else {
+                format!("\n  {}", doc_line(&m.doc))
+            };
+            parts.push(format!("- `{name}::{}` — `{}`{doc}", m.name, m.signature));
+        }
+        parts.push(String::new());
+    }
+
+    if !guide.conversions.is_empty() {
+        parts.push("### Conversions\n".to_string());
+        for c in &guide.conversions {
+            let example = match c.trait_name.as_str() {
+                "FromStr" => format!("\"...\".parse::<{name}>()"),
+                "TryFrom" => format!("{name}::try_from(/* {} */)", c.source),
+                _ => format!("{name}::from(/* {} */)", c.source),
+            };
+            let trait_ref = match c.trait_name.as_str() {
+                "FromStr" => c.trait_name.clone(),
+                _ => format!("{}<{}>", c.trait_name, c.source),
+            };
+            parts.push(format!("- `{trait_ref}`: `{example}`"));
+        }
+        parts.push(String::new());
+    }
+
+    if !guide.builders.is_empty() {
+        parts.push("### Builders\n".to_string());
+        for info in &guide.builders {
+            let entry = info
+                .entry_points
+                .first()
+                .map(|m| format!("{name}::{}()", m.name))
+                .or_else(|| {
+                    info.constructors
+                        .first()
+                        .map(|m| format!("{}::{}()", info.builder.name, m.name))
+                });
+            let finish = info.finishers.first().map(|m| format!(".{}()", m.name));
+            match (entry, finish) {
+                (Some(entry), Some(finish)) => parts.push(format!(
+                    "- `{}`: `{entry}` … `{finish}` (see `find_builder`)",
+                    info.builder.path
+                )),
+                _ => parts.push(format!("- `{}` (see `find_builder`)", info.builder.path)),
+            }
+        }
+        parts.push(String::new());
+    }
+
+    if let Some(literal) = &guide.literal {
+        parts.push(format!(
+            "### Struct Literal\n\n```rust\nlet value = {literal};\n```\n"
+        ));
+    }
+    if target.kind == ItemKind::Enum && !target.detail.variants.is_empty() {
+        parts.push("### Variants\n".to_string());
+        let variants: Vec<String> = target
+            .detail
+            .variants
+            .iter()
+            .map(|v| format!("`{name}::{}`", v.name))
+            .collect();
+        parts.push(format!("{}\n", variants.join(", ")));
+    }
+
+    if !guide.producers.is_empty() {
+        parts.push("### Returned By\n".to_string());
+        for (path, signature) in guide.producers.iter().take(10) {
+            parts.push(format!("- `{path}` — `{signature}`"));
+        }
+        if guide.producers.len() > 10 {
+            parts.push(format!("- _…and {} more_", guide.producers.len() - 10));
+        }
+        parts.push(String::new());
+    }
+
+    if parts.len() == 1 {
+        parts.push(
+            "No constructors, conversions, or builders found. The type may only be \
+             obtainable from other crates' APIs or through trait methods."
+                .to_string(),
+        );
+    }
+    parts.join("\n")
+}
+
+/// Render where a method name comes from (for `find_method_origin`).
+pub fn render_method_origins(
+    index: &CrateIndex,
+    method_name: &str,
+    origins: &MethodOrigins<'_>,
+) -> String {
+    let mut parts = Vec::new();
+    parts.push(format!(
+        "## Origins of `{method_name}` in {} v{}\n",
+        index.crate_name, index.version
+    ));
+
+    if !origins.trait_defs.is_empty() {
+        parts.push("### Defined by Traits\n".to_string());
+        for (item, method) in &origins.trait_defs {
+            let role = if method.is_required {
+                "required"
+            } else {
+                "provided"
+            };
+            parts.push(format!(
+                "- `{}` ({role}) — `{}`",
+                item.path, method.signature
+            ));
+            if !method.doc.is_empty() {
+                parts.push(format!("  {}", doc_line(&method.doc)));
+            }
+        }
+        parts.push(String::new());
+    }
+
+    if !origins.trait_impls.is_empty() {
+        parts.push("### Provided by Trait Impls\n".to_string());
+        for (trait_name, types) in &origins.trait_impls {
+            let shown: Vec<String> = types.iter().take(5).map(|t| format!("`{t}`")).collect();
+            let more = if types.len() > 5 {
+                format!(" and {} more", types.len() - 5)
+            } else {
+                String::new()
+            };
+            parts.push(format!(
+                "- trait `{trait_name}`, implemented for {}{more}",
+                shown.join(", ")
+            ));
+        }
+        parts.push(String::new());
+    }
+
+    if !origins.inherent.is_empty() {
+        parts.push("### Inherent Methods\n".to_string());
+        for (type_path, method) in &origins.inherent {
+            parts.push(format!("- `{type_path}` — `{}`", method.signature));
+        }
+        parts.push(String::new());
+    }
+
+    if parts.len() == 1 {
+        parts.push(format!(
+            "No trait or type in this crate defines `{method_name}`. It may come from a \
+             trait in another crate; try `search_crate` on that crate."
+        ));
+    }
+    parts.join("\n")
+}
+
+/// Render crates.io search results (for `find_crates`).
+pub fn render_crate_search(query: &str, crates: &[CrateSummary], total: u64) -> String {
+    if crates.is_empty() {
+        return format!("No crates found on crates.io for \"{query}\".");
+    }
+
+    let mut parts = Vec::new();
+    parts.push(format!(
+        "## crates.io results for \"{query}\" ({} of {total})\n",
+        crates.len()
+    ));
+    for krate in crates {
+        let recent = krate
+            .recent_downloads
+            .map(|n| format!(", {} recent", format_count(n)))
+            .unwrap_or_default();
+        let updated = krate.updated_at.get(..10).unwrap_or(&krate.updated_at);
+        parts.push(format!(
+            "- **{}** v{} — {} downloads{recent}, updated {updated}",
+            krate.name,
+            krate.version(),
+            format_count(krate.downloads)
+        ));
+        if let Some(description) = krate.description.as_deref().map(str::trim)
+            && !description.is_empty()
+        {
+            parts.push(format!("  {}", first_line(description)));
+        }
+        if let Some(repository) = &krate.repository {
+            parts.push(format!("  {repository}"));
+        }
+    }
+    parts.push(String::new());
+    parts.push(
+        "Use `lookup_crate_items` with a crate name to explore its documentation.".to_string(),
+    );
+    parts.join("\n")
+}
+
+/// Render the dependents of a crate (for `reverse_dependencies`).
+pub fn render_reverse_dependencies(
+    crate_name: &str,
+    dependents: &[Dependent],
+    total: u64,
+) -> String {
+    if dependents.is_empty() {
+        return format!("No crates on crates.io depend on {crate_name}.");
+    }
+
+    let mut parts = Vec::new();
+    parts.push(format!(
+        "## Crates depending on {crate_name} ({} of {total}, most downloaded first)\n",
+        dependents.len()
+    ));
+    for dep in dependents {
+        let mut notes = Vec::new();
+        if dep.kind != "normal" {
+            notes.push(format!("{}-dependency", dep.kind));
+        }
+        if dep.optional {
+            notes.push("optional".to_string());
+        }
+        let notes = if notes.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", notes.join(", "))
+        };
+        parts.push(format!(
+            "- **{}** v{} — requires `{}`{notes}, {} downloads",
+            dep.name,
+            dep.version,
+            dep.req,
+            format_count(dep.downloads)
+        ));
+    }
+    parts.join("\n")
+}
+
+/// Maximum entries shown per section of an API diff.
+const MAX_DIFF_ENTRIES: usize = 40;
+
+/// Render newer versions and the API changes since the pinned version (for `upgrade_report`).
+///
+/// `diff` is `None` when there is nothing to upgrade to, and `Err` with a reason
+/// when the docs of either version could not be loaded. MSRVs newer than
+/// `toolchain` (from the workspace's `rust-toolchain.toml`) are flagged.
+pub fn render_upgrade_report(
+    crate_name: &str,
+    pinned: &str,
+    pinned_info: Option<&VersionInfo>,
+    newer: &[&VersionInfo],
+    target: Option<&VersionInfo>,
+    diff: Option<Result<ApiDiff, String>>,
+    toolchain: Option<&str>,
+) -> String {
+    let mut parts = Vec::new();
+    parts.push(format!("## Upgrade report for {crate_name} v{pinned}\n"));
+
+    if let Some(info) = pinned_info {
+        if info.yanked {
+            parts.push(format!(
+                "**Warning:** v{pinned} has been yanked from crates.io.\n"
+            ));
+        }
+        if let Some(msrv) = &info.rust_version {
+            parts.push(format!("MSRV: {}\n", msrv_label(msrv, toolchain)));
+        }
+    }
+
+    if newer.is_empty() {
+        parts.push(format!("v{pinned} is the newest published version."));
+        return parts.join("\n");
+    }
+
+    parts.push(format!("### Newer Versions ({})\n", newer.len()));
+    for version in newer {
+        let date = version.created_at.get(..10).unwrap_or(&version.created_at);
+        let yanked = if version.yanked { " (yanked)" } else { "" };
+        let msrv = version
+            .rust_version
+            .as_deref()
+            .map(|msrv| format!(", MSRV {}", msrv_label(msrv, toolchain)))
+            .unwrap_or_default();
+        parts.push(format!("- v{} — {date}{msrv}{yanked}", version.num));
+    }
+    parts.push(String::new());
+
+    let Some(target) = target else {
+        parts.push("Every newer version is yanked or a pre-release.".to_string());
+        return parts.join("\n");
+    };
+    parts.push(format!("### API Changes v{pinned} → v{}\n", target.num));
+    match diff {
+        Some(Ok(diff)) if diff.is_empty() => {
+            parts.push("No changes to the public API.".to_string());
+        }
+        Some(Ok(diff)) => push_api_diff(&mut parts, &diff),
+        Some(Err(reason)) => parts.push(format!("API diff unavailable: {reason}")),
+        None => {}
+    }
+    parts.join("\n")
+}
+
+/// Render the public API changes between two versions of a crate.
+pub fn render_api_diff(crate_name: &str, old: &str, new: &str, diff: &ApiDiff) -> String {
+    let mut parts = vec![format!("## API Changes in {crate_name} v{old} → v{new}\n")];
+    if diff.is_empty() {
+        parts.push("No changes to the public API.".to_string());
+    } else {
+        push_api_diff(&mut parts, diff);
+    }
+    parts.join("\n")
+}
+
+/// Render the crates available without a download (for `list_cached_crates`).
+///
+/// `memory` holds (crate, version, item count) for loaded indexes; `disk` is
+/// `None` when the disk cache is disabled.
+pub fn render_cached_crates(
+    memory: &[(String, String, usize)],
+    disk: Option<&[CacheEntry]>,
+    now: SystemTime,
+) -> String {
+    let mut parts = vec!["## Cached crates\n".to_string()];
+    parts.push(format!("### In memory ({})\n", memory.len()));
+    if memory.is_empty() {
+        parts.push("No crates loaded yet.".to_string());
+    }
+    for (name, version, items) in memory {
+        parts.push(format!(
+            "- {name} {version} — {} items",
+            format_count(*items as u64)
+        ));
+    }
+    parts.push(String::new());
+
+    let Some(disk) = disk else {
+        parts.push("### On disk\n\nDisk cache disabled.".to_string());
+        return parts.join("\n");
+    };
+    let total: u64 = disk.iter().map(|e| e.size).sum();
+    parts.push(format!(
+        "### On disk ({}, {})\n",
+        disk.len(),
+        format_size(total)
+    ));
+    if disk.is_empty() {
+        parts.push("Nothing cached on disk.".to_string());
+    }
+    for entry in disk {
+        let age = entry
+            .modified
+            .and_then(|modified| now.duration_since(modified).ok())
+            .map(|age| format!(", {} old", format_age(age)))
+            .unwrap_or_default();
+        parts.push(format!(
+            "- {} {} — {}{age}",
+            entry.crate_name,
+            entry.version,
+            format_size(entry.size)
+        ));
+    }
+    parts.join("\n")
+}
+
+/// Render what changed when the project was re-read (for `reload_project`).
+pub fn render_project_reload(
+    has_lock: bool,
+    diff: &LockDiff,
+    added_packages: &[&str],
+    removed_packages: &[&str],
+) -> String {
+    let mut parts = vec!["## Project reloaded\n".to_string()];
+    if !has_lock {
+        parts.push("No Cargo.lock found; versions default to \"latest\".\n".to_string());
+    }
+    if diff.is_empty() && added_packages.is_empty() && removed_packages.is_empty() {
+        parts.push("No changes to pinned versions or local packages.".to_string());
+        return parts.join("\n");
+    }
+
+    if !diff.changed.is_empty() {
+        parts.push(format!("### Version Changes ({})\n", diff.changed.len()));
+        for (name, old, new) in &diff.changed {
+            parts.push(format!("- {name}: {old} → {new}"));
+        }
+        parts.push(String::new());
+    }
+    for (title, entries) in [("Added", &diff.added), ("Removed", &diff.removed)] {
+        if entries.is_empty() {
+            continue;
+        }
+        parts.push(format!("### {title} Dependencies ({})\n", entries.len()));
+        for (name, version) in entries {
+            parts.push(format!("- {name} {version}"));
+        }
+        parts.push(String::new());
+    }
+    for (title, packages) in [("Added", added_packages), ("Removed", removed_packages)] {
+        if packages.is_empty() {
+            continue;
+        }
+        parts.push(format!("### {title} Local Packages ({})\n", packages.len()));
+        for name in packages {
+            parts.push(format!("- {name}"));
+        }
+        parts.push(String::new());
+    }
+    parts.join("\n")
+}
+
+/// What `document_workspace` did for one workspace member.
+pub enum MemberDocs {
+    Indexed(std::sync::Arc<CrateIndex>),
+    /// Not documented, and why (e.g. no library target).
+    Skipped(String),
+    Failed(String),
+}
+
+/// Render the members `document_workspace` documented, with how to query each, then
+/// the skipped and failed ones.
+pub fn render_workspace_docs(members: &[(String, MemberDocs)]) -> String {
+    let indexed: Vec<(&str, &CrateIndex)> = members
+        .iter()
+        .filter_map(|(name, docs)| match docs {
+            MemberDocs::Indexed(index) => Some((name.as_str(), index.as_ref())),
+            _ => None,
+        })
+        .collect();
+    let mut parts = vec![format!(
+        "## Workspace documented ({} of {} members)\n",
+        indexed.len(),
+        members.len()
+    )];
+    if indexed.is_empty() {
+        parts.push("No member's docs could be built.".to_string());
+    }
+    for (name, index) in &indexed {
+        parts.push(format!(
+            "- `{}` {} — {} items, query with `crate_name: \"{name}\"`",
+            index.crate_name,
+            index.version,
+            format_count(index.items.len() as u64)
+        ));
+    }
+    parts.push(String::new());
+
+    for (title, failed) in [("Skipped", false), ("Failed", true)] {
+        let entries: Vec<String> = members
+            .iter()
+            .filter_map(|(name, docs)| match docs {
+                MemberDocs::Skipped(reason) if !failed => Some(format!("- {name}: {reason}")),
+                MemberDocs::Failed(error) if failed => Some(format!("- {name}: {error}")),
+                _ => None,
+            })
+            .collect();
+        if !entries.is_empty() {
+            parts.push(format!("### {title} ({})\n", entries.len()));
+            parts.extend(entries);
+            parts.push(String::new());
+        }
+    }
+    parts.join("\n").trim_end().to_string()
+}
+
+/// Render item counts, documentation coverage, and parse time (for `crate_stats`).
+pub fn render_crate_stats(index: &CrateIndex, stats: &CrateStats<'_>) -> String {
+    let mut parts = Vec::new();
+    parts.push(format!(
+        "## Statistics for {} v{}\n",
+        index.crate_name, index.version
+    ));
+    parts.push(format!(
+        "- Items: {} ({} modules)",
+        index.items.len(),
+        index.modules.len()
+    ));
+    parts.push(format!(
+        "- Documented: {}/{} public items ({:.1}%)",
+        stats.documented,
+        stats.public_items,
+        stats.coverage()
+    ));
+    parts.push(format!(
+        "- Impl blocks: {} ({} trait impls), {} methods",
+        stats.impl_blocks, stats.trait_impls, stats.methods
+    ));
+    parts.push(format!(
+        "- Parse time: {:.1} ms{}",
+        index.parse_time.as_secs_f64() * 1000.0,
+        if index.partial {
+            " (over budget, partial index)"
+        } else {
+            ""
+        }
+    ));
+    parts.push(String::new());
+
+    parts.push("### Items by Kind\n".to_string());
+    for (kind, count) in &stats.kinds {
+        parts.push(format!("- {}: {count}", kind_label_plural(kind)));
+    }
+    parts.push(String::new());
+
+    if !stats.largest_modules.is_empty() {
+        parts.push("### Largest Modules\n".to_string());
+        for (path, count) in &stats.largest_modules {
+            parts.push(format!("- `{path}` — {count} items"));
+        }
+    }
+    parts.join("\n")
+}
+
+/// Render a module hierarchy as an indented tree (for `crate_tree`), each module
+/// followed by its direct items per kind when `item_counts` is set.
+pub fn render_module_tree(index: &CrateIndex, tree: &ModuleNode<'_>, item_counts: bool) -> String {
+    let mut lines = vec![module_tree_label(tree, tree.path, item_counts)];
+    push_module_tree_children(&mut lines, tree, "", item_counts);
+    format!(
+        "## Module tree of {} v{} ({} modules)\n\n```text\n{}\n```",
+        index.crate_name,
+        index.version,
+        tree.module_count(),
+        lines.join("\n")
+    )
+}
+
+fn push_module_tree_children(
+    lines: &mut Vec<String>,
+    node: &ModuleNode<'_>,
+    prefix: &str,
+    item_counts: bool,
+) {
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i + 1 == node.children.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        lines.push(format!(
+            "{prefix}{branch}{}",
+            module_tree_label(child, child.name, item_counts)
+        ));
+        push_module_tree_children(lines, child, &format!("{prefix}{indent}"), item_counts);
+    }
+}
+
+/// "net — 2 structs, 1 function", or just the name without `item_counts`.
+fn module_tree_label(node: &ModuleNode<'_>, name: &str, item_counts: bool) -> String {
+    if !item_counts || node.item_counts.is_empty() {
+        return name.to_string();
+    }
+    let counts: Vec<String> = node
+        .item_counts
+        .iter()
+        .map(|(kind, count)| {
+            let label = if *count == 1 {
+                kind_label(kind).to_string()
+            } else {
+                kind_label_plural(kind)
+            };
+            format!("{count} {}", label.to_lowercase())
+        })
+        .collect();
+    format!("{name} — {}", counts.join(", "))
+}
+
+/// Public API size below which a crate counts as small, and from which it counts as large.
+const SMALL_API: usize = 50;
+const LARGE_API: usize = 500;
+
+/// Render the public API size and shape of a crate (for `api_surface`).
+pub fn render_api_surface(index: &CrateIndex, surface: &ApiSurface) -> String {
+    let total = surface.total();
+    let size = if total < SMALL_API {
+        "small"
+    } else if total < LARGE_API {
+        "medium"
+    } else {
+        "large"
+    };
+    let mut parts = vec![
+        format!("## Public API of {} v{}\n", index.crate_name, index.version),
+        format!("**{total}** public items and methods — a {size} API surface.\n"),
+    ];
+
+    parts.push("### Shape\n".to_string());
+    let percent = |count: usize| {
+        let items = total - surface.methods;
+        if items == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / items as f64
+        }
+    };
+    parts.push(format!(
+        "- Generic items: {} ({:.0}% of items)",
+        surface.generic_items,
+        percent(surface.generic_items)
+    ));
+    parts.push(format!("- Inherent methods: {}", surface.methods));
+    parts.push(format!(
+        "- Unsafe functions, traits, and methods: {}",
+        surface.unsafe_items
+    ));
+    parts.push(format!(
+        "- Re-exported items: {} (public path differs from the definition)",
+        surface.reexports
+    ));
+    parts.push(format!("- Deprecated: {}", surface.deprecated));
+    parts.push(format!(
+        "- `#[non_exhaustive]` types: {}",
+        surface.non_exhaustive
+    ));
+    parts.push(format!(
+        "- `#[doc(hidden)]` public items (not counted above): {}",
+        surface.hidden
+    ));
+    parts.push(String::new());
+
+    parts.push("### Items by Kind\n".to_string());
+    for (kind, count) in &surface.kinds {
+        parts.push(format!("- {}: {count}", kind_label_plural(kind)));
+    }
+    parts.join("\n")
+}
+
+/// Render docs.rs build metadata for a crate version (for `docs_build_info`).
+///
+/// Each source is fetched independently, so a failed one is reported inline
+/// without hiding the others.
+pub fn render_build_info(
+    crate_name: &str,
+    version: &str,
+    builds: Result<Vec<BuildInfo>, String>,
+    index: Result<&CrateIndex, String>,
+    release: Option<&VersionInfo>,
+) -> String {
+    let mut parts = Vec::new();
+    parts.push(format!(
+        "## docs.rs build info for {crate_name} v{version}\n"
+    ));
+
+    parts.push("### Builds\n".to_string());
+    match builds {
+        Ok(builds) if builds.is_empty() => parts.push("No builds recorded.".to_string()),
+        Ok(builds) => {
+            for build in &builds {
+                let time = build
+                    .build_time
+                    .as_deref()
+                    .map(|t| format!(" at {}", t.get(..10).unwrap_or(t)))
+                    .unwrap_or_default();
+                parts.push(format!(
+                    "- **{}**{time} — {} / {}",
+                    build.build_status, build.rustc_version, build.docsrs_version
+                ));
+            }
+        }
+        Err(e) => parts.push(format!("Build list unavailable: {e}")),
+    }
+    parts.push(String::new());
+
+    parts.push("### Rustdoc JSON\n".to_string());
+    match index {
+        Ok(index) => {
+            parts.push(format!("- Target: `{}`", index.target));
+            parts.push(format!("- Format version: {}", index.format_version));
+            parts.push(format!("- Items indexed: {}", index.items.len()));
+        }
+        Err(e) => parts.push(format!("Rustdoc JSON unavailable: {e}")),
+    }
+    parts.push(String::new());
+
+    if let Some(release) = release {
+        parts.push("### Features\n".to_string());
+        let defaults = release.features.get("default");
+        match defaults {
+            Some(defaults) if !defaults.is_empty() => {
+                parts.push(format!("Default: {}", defaults.join(", ")));
+            }
+            _ => parts.push("Default: (none)".to_string()),
+        }
+        let others: Vec<&str> = release
+            .features
+            .keys()
+            .map(String::as_str)
+            .filter(|name| {
+                *name != "default" && defaults.is_none_or(|d| !d.iter().any(|f| f == name))
+            })
+            .collect();
+        if !others.is_empty() {
+            parts.push(format!("Non-default: {}", others.join(", ")));
+        }
+        parts.push(String::new());
+    }
+
+    parts.push(
+        "docs.rs builds with default features for its default target unless the crate's \
+         `[package.metadata.docs.rs]` enables more (e.g. `all-features = true`). Items behind \
+         other features or `cfg`s for other targets may be missing from the JSON."
+            .to_string(),
+    );
+    parts.join("\n")
+}
+
+/// Render doctests grouped by item (for `list_doctests`), showing at most `limit` items.
+/// `more_token` is the `read_more` token for the items past the limit.
+pub fn render_doctests(
+    index: &CrateIndex,
+    groups: &[DoctestGroup<'_>],
+    limit: usize,
+    more_token: Option<&str>,
+) -> String {
+    if groups.is_empty() {
+        return format!(
+            "No matching doctests in {} v{}.",
+            index.crate_name, index.version
+        );
+    }
+
+    let total: usize = groups.iter().map(|g| g.doctests.len()).sum();
+    let mut parts = Vec::new();
+    parts.push(format!(
+        "## Doctests in {} v{} ({total} in {} items)\n",
+        index.crate_name,
+        index.version,
+        groups.len()
+    ));
+    parts.push(render_doctest_groups(&groups[..limit.min(groups.len())]));
+    if groups.len() > limit {
+        parts.push(format!(
+            "... and {} more items. Narrow with `item_path`, {}raise `limit`.",
+            groups.len() - limit,
+            read_more_hint(more_token)
+        ));
+    }
+    parts.join("\n")
+}
+
+/// The doctests of each group under a `### path` heading, as listed by `list_doctests`.
+pub fn render_doctest_groups(groups: &[DoctestGroup<'_>]) -> String {
+    let mut parts = Vec::new();
+    for group in groups {
+        parts.push(format!("### `{}`\n", group.path));
+        if !group.signature.is_empty() && !group.signature.contains('\n') {
+            parts.push(format!("`{}`\n", group.signature));
+        }
+        for doctest in &group.doctests {
+            if doctest.mode != DoctestMode::Run {
+                parts.push(format!("*{}*", doctest.mode));
+            }
+            parts.push("```rust".to_string());
+            parts.push(doctest.code.trim_end().to_string());
+            parts.push("```".to_string());
+        }
+        parts.push(String::new());
+    }
+    parts.join("\n")
+}
+
+/// Render what the paths a snippet references in one crate resolved to (for `resolve_snippet`).
+pub fn render_snippet_references(
+    index: &CrateIndex,
+    references: &[(&str, Reference<'_>)],
+) -> String {
+    let mut parts = Vec::new();
+    parts.push(format!("### {} v{}\n", index.crate_name, index.version));
+    for (path, reference) in references {
+        match reference {
+            Reference::Item(item) => {
+                parts.push(format!("- [{}] `{}`", item.kind, item.path));
+                push_signature_and_doc(&mut parts, &item.signature, &item.short_doc);
+            }
+            Reference::Method { owner, method } => {
+                parts.push(format!("- [method] `{}::{}`", owner.path, method.name));
+                push_signature_and_doc(&mut parts, &method.signature, &doc_line(&method.doc));
+            }
+            Reference::Ambiguous(candidates) => {
+                let paths: Vec<String> = candidates
+                    .iter()
+                    .take(5)
+                    .map(|item| format!("`{}`", item.path))
+                    .collect();
+                parts.push(format!("- `{path}` — ambiguous: {}", paths.join(", ")));
+            }
+            Reference::NotFound => {
+                let suggestions = index.suggest_similar(path, 3);
+                let hint = if suggestions.is_empty() {
+                    String::new()
+                } else {
+                    let paths: Vec<String> = suggestions
+                        .iter()
+                        .map(|s| format!("`{}`", s.path))
+                        .collect();
+                    format!(" (did you mean {}?)", paths.join(", "))
+                };
+                parts.push(format!("- `{path}` — not found{hint}"));
+            }
+        }
+    }
+    parts.push(String::new());
+    parts.join("\n")
+}
+
+fn push_signature_and_doc(parts: &mut Vec<String>, signature: &str, doc: &str) {
+    let signature = first_line(signature);
+    if !signature.is_empty() {
+        parts.push(format!("  `{signature}`"));
+    }
+    if !doc.is_empty() {
+        parts.push(format!("  {doc}"));
+    }
+}
+
+/// Maximum implementors listed beyond those related to the failing type.
+const MAX_OTHER_IMPLEMENTORS: usize = 20;
+
+/// Render an explanation of a trait-bound error (for `explain_bound_error`): the
+/// trait's docs and required methods, its implementors for the types in the error,
+/// and feature flags that may provide the missing impl.
+///
+/// `trait_docs` is the crate defining the trait with the trait itself, or the
+/// reason they could not be shown. `features` pairs crate names with feature
+/// flags that may enable the impl.
+pub fn render_bound_error(
+    error: &BoundError,
+    trait_docs: Result<(&CrateIndex, &IndexedItem), String>,
+    features: &[(String, Vec<&str>)],
+    options: &RenderOptions,
+) -> String {
+    let mut parts = Vec::new();
+    parts.push(format!(
+        "## `{}: {}` is not satisfied\n",
+        error.type_name, error.trait_path
+    ));
+    if let Some(required_by) = &error.required_by {
+        parts.push(format!("Required by a bound in `{required_by}`.\n"));
+    }
+
+    match trait_docs {
+        Ok((index, item)) => {
+            parts.push(format!(
+                "### Trait `{}` ({} v{})\n",
+                item.path, index.crate_name, index.version
+            ));
+            parts.push(format!(
+                "```rust\n{}\n```\n",
+                options.signature(&item.signature)
+            ));
+            if !item.doc.is_empty() {
+                parts.push(format!("{}\n", doc_body(index, item, 4)));
+            }
+            let required: Vec<_> = item
+                .detail
+                .methods
+                .iter()
+                .filter(|m| m.is_required)
+                .collect();
+            if !required.is_empty() {
+                parts.push("**Required methods:**".to_string());
+                for method in required {
+                    push_signature_bullet(&mut parts, &method.signature, "", options);
+                }
+                parts.push(String::new());
+            }
+            if let Some(derive) = index
+                .items
+                .values()
+                .find(|i| i.kind == ItemKind::DeriveMacro && i.name == item.name)
+            {
+                parts.push(format!(
+                    "Derive it with `#[derive({})]` (`{}`).\n",
+                    derive.name, derive.path
+                ));
+            }
+
+            let type_names = error.type_names();
+            let (related, other): (Vec<_>, Vec<_>) = index
+                .trait_implementors(&item.name)
+                .into_iter()
+                .partition(|(path, _)| {
+                    type_names.contains(&path.rsplit("::").next().unwrap_or(path))
+                });
+            if related.is_empty() {
+                parts.push(format!(
+                    "No implementations of `{}` in {} for {}.\n",
+                    item.name,
+                    index.crate_name,
+                    type_names
+                        .iter()
+                        .map(|name| format!("`{name}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            } else {
+                parts.push("### Implementations for types in the error\n".to_string());
+                for (_, block) in &related {
+                    parts.push(format!("- `{}`", block.header));
+                }
+                parts.push(String::new());
+            }
+            if !other.is_empty() {
+                parts.push(format!("### Other implementors ({})\n", other.len()));
+                for (_, block) in other.iter().take(MAX_OTHER_IMPLEMENTORS) {
+                    parts.push(format!("- `{}`", block.header));
+                }
+                if other.len() > MAX_OTHER_IMPLEMENTORS {
+                    parts.push(format!(
+                        "- _... and {} more_",
+                        other.len() - MAX_OTHER_IMPLEMENTORS
+                    ));
+                }
+                parts.push(String::new());
+            }
+        }
+        Err(reason) => parts.push(format!("{reason}\n")),
+    }
+
+    if !features.is_empty() {
+        parts.push("### Feature flags that may provide the impl\n".to_string());
+        for (crate_name, names) in features {
+            let names: Vec<String> = names.iter().map(|name| format!("`{name}`")).collect();
+            parts.push(format!("- {crate_name}: {}", names.join(", ")));
+        }
+        parts.push(String::new());
+    }
+
+    parts.join("\n")
+}
+
+/// Maximum lines shown of a single example file.
+const MAX_EXAMPLE_LINES: usize = 500;
+
+/// Render a crate's examples (for `get_crate_examples`): a listing, or the
+/// sources of the example named `example`.
+pub fn render_examples(
+    crate_name: &str,
+    version: &str,
+    files: &[ExampleFile],
+    example: Option<&str>,
+) -> String {
+    if files.is_empty() {
+        return format!("{crate_name} v{version} has no examples/ directory.");
+    }
+
+    let Some(example) = example else {
+        let mut parts = Vec::new();
+        parts.push(format!("## Examples of {crate_name} v{version}\n"));
+        for file in files {
+            let summary = file.summary();
+            let summary = if summary.is_empty() {
+                String::new()
+            } else {
+                format!(" — {summary}")
+            };
+            parts.push(format!(
+                "- `{}` (`{}`, {} lines){summary}",
+                file.name(),
+                file.path,
+                file.content.lines().count()
+            ));
+        }
+        parts.push(String::new());
+        parts.push("Pass `example` with a name to read its source.".to_string());
+        return parts.join("\n");
+    };
+
+    let selected: Vec<&ExampleFile> = files
+        .iter()
+        .filter(|f| f.name() == example || f.path == example || f.path.ends_with(example))
+        .collect();
+    if selected.is_empty() {
+        let mut names: Vec<&str> = files.iter().map(ExampleFile::name).collect();
+        names.dedup();
+        return format!(
+            "No example named `{example}` in {crate_name} v{version}. Available: {}",
+            names.join(", ")
+        );
+    }
+
+    let mut parts = Vec::new();
+    for file in selected {
+        parts.push(format!("## {}\n", file.path));
+        parts.push("```rust".to_string());
+        parts.extend(
+            file.content
+                .lines()
+                .take(MAX_EXAMPLE_LINES)
+                .map(str::to_string),
+        );
+        parts.push("```".to_string());
+        let total = file.content.lines().count();
+        if total > MAX_EXAMPLE_LINES {
+            parts.push(format!(
+                "({} more lines not shown)",
+                total - MAX_EXAMPLE_LINES
+            ));
+        }
+        parts.push(String::new());
+    }
+    parts.join("\n")
+}
+
+/// An MSRV, noting when it is newer than the workspace's pinned toolchain.
+fn msrv_label(msrv: &str, toolchain: Option<&str>) -> String {
+    match toolchain {
+        Some(toolchain) if rust_version_exceeds(msrv, toolchain) => {
+            format!("{msrv} (**exceeds toolchain {toolchain}**)")
+        }
+        _ => msrv.to_string(),
+    }
+}
+
+fn push_api_diff(parts: &mut Vec<String>, diff: &ApiDiff) {
+    push_diff_section(parts, "Added", &diff.added);
+    push_diff_section(parts, "Removed", &diff.removed);
+    push_diff_section(parts, "Changed", &diff.changed);
+}
+
+fn push_diff_section(parts: &mut Vec<String>, title: &str, changes: &[ApiChange]) {
+    if changes.is_empty() {
+        return;
+    }
+    parts.push(format!("#### {title} ({})\n", changes.len()));
+    for change in changes.iter().take(MAX_DIFF_ENTRIES) {
+        parts.push(format!("- [{}] `{}`", change.kind, change.path));
+        if let (Some(old), Some(new)) = (&change.old, &change.new) {
+            parts.push("  ```diff".to_string());
+            parts.extend(old.lines().map(|line| format!("  - {line}")));
+            parts.extend(new.lines().map(|line| format!("  + {line}")));
+            parts.push("  ```".to_string());
+        }
+    }
+    if changes.len() > MAX_DIFF_ENTRIES {
+        parts.push(format!(
+            "- ... and {} more",
+            changes.len() - MAX_DIFF_ENTRIES
+        ));
+    }
+    parts.push(String::new());
+}
+
+/// " (nightly only, feature `x`, tracking issue #N)" for an unstable item.
+fn unstable_suffix(stability: &Stability) -> String {
+    let Stability::Unstable { feature, issue } = stability else {
+        return String::new();
+    };
+    match issue {
+        Some(issue) => format!(" (nightly only, feature `{feature}`, tracking issue #{issue})"),
+        None => format!(" (nightly only, feature `{feature}`)"),
+    }
+}
 
-    for item in &items {
-        if current_kind.as_ref() != Some(&item.kind) {
-            current_kind = Some(item.kind.clone());
-            sections.push(format!("\n### {}s\n", kind_label(&item.kind)));
+/// A method's summary line, flagged when the method is nightly-only.
+fn method_doc(method: &MethodInfo) -> String {
+    match &method.stability {
+        Some(Stability::Unstable { feature, .. }) => {
+            format!("_Unstable (`{feature}`)_ {}", doc_line(&method.doc))
+                .trim_end()
+                .to_string()
         }
-
-        let doc_suffix = if item.short_doc.is_empty() {
-            String::new()
-        } else {
-            format!(" — {}", item.short_doc)
-        };
-
-        sections.push(format!("- `{}`{doc_suffix}", item.name));
+        _ => doc_line(&method.doc),
     }
+}
 
-    format!("{header}{}", sections.join("\n"))
+/// " since X: note" for a deprecation, omitting whichever parts are missing.
+fn deprecation_suffix(deprecation: &Deprecation) -> String {
+    let mut suffix = String::new();
+    if let Some(since) = &deprecation.since {
+        suffix.push_str(&format!(" since {since}"));
+    }
+    if let Some(note) = &deprecation.note {
+        suffix.push_str(&format!(": {note}"));
+    }
+    suffix
 }
 
 /// Render detailed info for a single item (for `lookup_item`).
-pub fn render_item(item: &IndexedItem) -> String {
+#[tracing::instrument(name = "render", skip_all, fields(item = item.path))]
+pub fn render_item(index: &CrateIndex, item: &IndexedItem, options: &RenderOptions) -> String {
     let mut parts = Vec::new();
 
     // Header
     parts.push(format!("## {}\n", item.path));
+    if !item.aliases.is_empty() {
+        let aliases: Vec<String> = item.aliases.iter().map(|a| format!("`{a}`")).collect();
+        parts.push(format!("_Defined at {}_\n", aliases.join(", ")));
+    }
+    if !item.reexports.is_empty() {
+        let reexports: Vec<String> = item.reexports.iter().map(|p| format!("`{p}`")).collect();
+        parts.push(format!("_Also re-exported as {}_\n", reexports.join(", ")));
+    }
+
+    if let Some(deprecation) = &item.deprecation {
+        parts.push(format!(
+            "**Deprecated**{}\n",
+            deprecation_suffix(deprecation)
+        ));
+    }
+
+    match &item.stability {
+        Some(Stability::Stable { since }) => parts.push(format!("**Stable** since Rust {since}\n")),
+        Some(stability @ Stability::Unstable { .. }) => {
+            parts.push(format!("**Unstable**{}\n", unstable_suffix(stability)))
+        }
+        None => {}
+    }
+
+    if let Some(cfg) = &item.cfg {
+        parts.push(format!("**Requires** {}\n", describe_cfg(cfg)));
+    }
+
+    if let Some(span) = &item.span {
+        let location = format!("`{}:{}`", span.file, span.line);
+        match docs_rs_source_url(index, span) {
+            Some(url) => parts.push(format!("Defined in {location} ([source]({url}))\n")),
+            None => parts.push(format!("Defined in {location}\n")),
+        }
+    }
 
-    // Signature
-    parts.push(format!("```rust\n{}\n```\n", item.signature));
+    // Signature, preceded by any usage-relevant attributes
+    let signature = options.signature(&item.signature);
+    if item.attrs.is_empty() {
+        parts.push(format!("```rust\n{signature}\n```\n"));
+    } else {
+        parts.push(format!(
+            "```rust\n{}\n{signature}\n```\n",
+            item.attrs.join("\n"),
+        ));
+    }
 
-    // Documentation
+    // Documentation, cut to its leading paragraphs when long
     if !item.doc.is_empty() {
-        parts.push(item.doc.clone());
+        push_doc_body(&mut parts, doc_body(index, item, 3), options);
         parts.push(String::new());
     }
 
     // Kind-specific details
     match item.kind {
-        ItemKind::Struct | ItemKind::Union => {
-            if !item.detail.fields.is_empty() {
-                parts.push("### Fields\n".to_string());
-                for f in &item.detail.fields {
-                    let doc = if f.doc.is_empty() {
-                        String::new()
-                    } else {
-                        format!(" — {}", first_line(&f.doc))
-                    };
-                    parts.push(format!("- `{}`: `{}`{doc}", f.name, f.type_str));
-                }
-                parts.push(String::new());
+        ItemKind::Struct | ItemKind::Union if !item.detail.fields.is_empty() => {
+            parts.push("### Fields\n".to_string());
+            for f in &item.detail.fields {
+                let doc = if f.doc.is_empty() {
+                    String::new()
+                } else {
+                    format!(" — {}", doc_line(&f.doc))
+                };
+                parts.push(format!("- `{}`: `{}`{doc}", f.name, f.type_str));
             }
+            parts.push(String::new());
         }
-        ItemKind::Enum => {
-            if !item.detail.variants.is_empty() {
-                parts.push("### Variants\n".to_string());
-                for v in &item.detail.variants {
-                    let doc = if v.doc.is_empty() {
-                        String::new()
-                    } else {
-                        format!("\n  {}", first_line(&v.doc))
-                    };
-                    parts.push(format!("- `{}`{doc}", v.name));
+        ItemKind::Enum if !item.detail.variants.is_empty() => {
+            parts.push("### Variants\n".to_string());
+            for v in &item.detail.variants {
+                let doc = if v.doc.is_empty() {
+                    String::new()
+                } else {
+                    format!("\n  {}", doc_line(&v.doc))
+                };
+                parts.push(format!("- `{}`{doc}", v.name));
+            }
+            parts.push(String::new());
+        }
+        ItemKind::Macro => {
+            if !item.detail.macro_arms.is_empty() {
+                parts.push("### Syntax\n".to_string());
+                let arms: Vec<String> = item
+                    .detail
+                    .macro_arms
+                    .iter()
+                    .map(|arm| format!("{}!{arm}", item.name))
+                    .collect();
+                parts.push(format!("```rust\n{}\n```\n", arms.join("\n")));
+            }
+
+            if !item.detail.macro_usages.is_empty() {
+                parts.push("### Usage\n".to_string());
+                for usage in &item.detail.macro_usages {
+                    parts.push(format!("- `{usage}`"));
                 }
                 parts.push(String::new());
             }
@@ -108,10 +2007,7 @@ pub fn render_item(item: &IndexedItem) -> String {
             if !required.is_empty() {
                 parts.push("### Required Methods\n".to_string());
                 for m in &required {
-                    parts.push(format!("- `{}`", m.signature));
-                    if !m.doc.is_empty() {
-                        parts.push(format!("  {}\n", first_line(&m.doc)));
-                    }
+                    push_signature_bullet(&mut parts, &m.signature, &method_doc(m), options);
                 }
                 parts.push(String::new());
             }
@@ -119,28 +2015,69 @@ pub fn render_item(item: &IndexedItem) -> String {
             if !provided.is_empty() {
                 parts.push("### Provided Methods\n".to_string());
                 for m in &provided {
-                    parts.push(format!("- `{}`", m.signature));
-                    if !m.doc.is_empty() {
-                        parts.push(format!("  {}\n", first_line(&m.doc)));
+                    push_signature_bullet(&mut parts, &m.signature, &method_doc(m), options);
+                }
+                parts.push(String::new());
+            }
+        }
+        ItemKind::Primitive => {
+            // Primitives are only reachable through their methods, so name them here
+            let mut methods: Vec<&str> = Vec::new();
+            for block in index.get_impl_blocks(&item.path) {
+                if block.trait_name.is_none() {
+                    for m in block.methods.iter().filter(|m| !m.is_hidden) {
+                        if !methods.contains(&m.name.as_str()) {
+                            methods.push(&m.name);
+                        }
                     }
                 }
+            }
+            if !methods.is_empty() {
+                parts.push("### Methods\n".to_string());
+                let names: Vec<String> = methods.iter().map(|m| format!("`{m}`")).collect();
+                parts.push(names.join(", "));
                 parts.push(String::new());
             }
+            parts.push(format!(
+                "_`lookup_impl_block` with `{}` lists the method signatures and trait impls._\n",
+                item.name
+            ));
+        }
+        ItemKind::DeriveMacro if !item.detail.helper_attrs.is_empty() => {
+            parts.push("### Helper Attributes\n".to_string());
+            for helper in &item.detail.helper_attrs {
+                parts.push(format!("- `#[{helper}]`"));
+            }
+            parts.push(String::new());
         }
         _ => {}
     }
 
+    // Where to follow types that come from dependencies
+    if !item.external_refs.is_empty() {
+        parts.push("### Types from other crates\n".to_string());
+        for r in &item.external_refs {
+            parts.push(format!(
+                "- `{}` is `{}` — see `lookup_item` in crate `{}`",
+                r.name, r.path, r.crate_name
+            ));
+        }
+        parts.push(String::new());
+    }
+
     parts.join("\n")
 }
 
 /// Render search results (for `search_crate`).
+#[tracing::instrument(name = "render", skip_all)]
 pub fn render_search_results(index: &CrateIndex, query: &str, results: &[SearchResult]) -> String {
     if results.is_empty() {
         let suggestions = index.suggest_similar(query, 5);
         let suggestion_text = if suggestions.is_empty() {
             String::new()
         } else {
-            format!("\n\nDid you mean: {}?", suggestions.join(", "))
+            let paths: Vec<&str> = suggestions.iter().map(|s| s.path.as_str()).collect();
+            format!("\n\nDid you mean: {}?", paths.join(", "))
         };
         return format!(
             "No results found for \"{query}\" in {} v{}.{suggestion_text}",
@@ -161,9 +2098,16 @@ pub fn render_search_results(index: &CrateIndex, query: &str, results: &[SearchR
         } else {
             format!(" — {}", item.short_doc)
         };
+        let heading_suffix = match &result.heading {
+            Some(heading) => format!(" (§ {heading})"),
+            None => String::new(),
+        };
+        let (kind, also) = match result.owner {
+            Some(_) => ("method".to_string(), String::new()),
+            None => (item.kind.to_string(), reexport_suffix(item)),
+        };
         parts.push(format!(
-            "- [{kind}] `{path}`{doc_suffix}",
-            kind = item.kind,
+            "- [{kind}] `{path}`{also}{doc_suffix}{heading_suffix}",
             path = item.path,
         ));
     }
@@ -172,7 +2116,23 @@ pub fn render_search_results(index: &CrateIndex, query: &str, results: &[SearchR
 }
 
 /// Render impl blocks for a type (for `lookup_impl_block`).
-pub fn render_impls(item_path: &str, impls: &[&ImplBlock]) -> String {
+///
+/// Types with many inherent methods get them grouped by category (constructors,
+/// conversion, iteration, async, operators). Trait impls are ordered by usefulness:
+/// the crate's own traits first, then well-known standard traits by group, then the
+/// rest alphabetically. Impls without methods (marker traits, auto traits) are listed
+/// on one line at the end.
+///
+/// `trait_methods` holds the methods of implemented traits by trait path, giving the
+/// signatures of the provided methods an impl inherits; without an entry they are
+/// listed by name.
+pub fn render_impls(
+    index: &CrateIndex,
+    item_path: &str,
+    impls: &[&ImplBlock],
+    trait_methods: &HashMap<String, Vec<MethodInfo>>,
+    options: &RenderOptions,
+) -> String {
     if impls.is_empty() {
         return format!("No implementations found for `{item_path}`.");
     }
@@ -186,40 +2146,359 @@ pub fn render_impls(item_path: &str, impls: &[&ImplBlock]) -> String {
 
     if !inherent.is_empty() {
         parts.push("### Inherent Methods\n".to_string());
-        for block in &inherent {
-            for m in &block.methods {
-                let doc = if m.doc.is_empty() {
-                    String::new()
-                } else {
-                    format!("\n  {}", first_line(&m.doc))
-                };
-                parts.push(format!("- `{}`{doc}", m.signature));
+        let methods: Vec<&MethodInfo> = inherent.iter().flat_map(|b| &b.methods).collect();
+        if methods.len() < MIN_GROUPED_METHODS {
+            for m in methods {
+                push_signature_bullet(&mut parts, &m.signature, &method_doc(m), options);
+            }
+            parts.push(String::new());
+        } else {
+            let type_name = item_path.rsplit("::").next().unwrap_or(item_path);
+            for category in METHOD_CATEGORIES {
+                let in_category: Vec<_> = methods
+                    .iter()
+                    .filter(|m| method_category(m, type_name) == category)
+                    .collect();
+                if in_category.is_empty() {
+                    continue;
+                }
+                parts.push(format!("**{category}**\n"));
+                for m in in_category {
+                    push_signature_bullet(&mut parts, &m.signature, &method_doc(m), options);
+                }
+                parts.push(String::new());
             }
         }
-        parts.push(String::new());
     }
 
     if !trait_impls.is_empty() {
         parts.push("### Trait Implementations\n".to_string());
-        for block in &trait_impls {
+        let (markers, mut with_methods): (Vec<&ImplBlock>, Vec<&ImplBlock>) = trait_impls
+            .iter()
+            .map(|b| **b)
+            .partition(|b| b.methods.is_empty() && b.provided_methods.is_empty());
+
+        let crate_traits: HashSet<&str> = index
+            .items
+            .values()
+            .filter(|item| item.kind == ItemKind::Trait)
+            .map(|item| item.name.as_str())
+            .collect();
+        with_methods.sort_by_cached_key(|b| {
+            let name = b.trait_name.as_deref().unwrap_or("");
+            (
+                trait_rank(name, &crate_traits),
+                name.to_string(),
+                b.header.clone(),
+            )
+        });
+
+        let mut current_group = None;
+        for block in with_methods {
+            let name = block.trait_name.as_deref().unwrap_or("");
+            if let (_, Some(group)) = trait_rank(name, &crate_traits)
+                && current_group != Some(group)
+            {
+                parts.push(format!("**{group}**\n"));
+                current_group = Some(group);
+            }
             parts.push(format!("#### {}\n", block.header));
-            if block.methods.is_empty() {
-                parts.push("  _(auto-derived, no custom methods)_\n".to_string());
-            } else {
-                for m in &block.methods {
-                    parts.push(format!("- `{}`", m.signature));
-                    if !m.doc.is_empty() {
-                        parts.push(format!("  {}\n", first_line(&m.doc)));
-                    }
-                }
+            for m in &block.methods {
+                push_signature_bullet(&mut parts, &m.signature, &method_doc(m), options);
             }
+            push_provided_methods(&mut parts, block, trait_methods, options);
             parts.push(String::new());
         }
+
+        if !markers.is_empty() {
+            let mut names: Vec<&str> = markers
+                .iter()
+                .filter_map(|b| b.trait_name.as_deref())
+                .collect();
+            names.sort_unstable();
+            names.dedup();
+            let names: Vec<String> = names.iter().map(|n| format!("`{n}`")).collect();
+            parts.push(format!(
+                "**Also implements** (no methods): {}\n",
+                names.join(", ")
+            ));
+        }
+    }
+
+    parts.join("\n")
+}
+
+/// The provided methods `block` inherits from its trait, marked "provided by trait":
+/// with signatures when the trait's methods are known, else by name.
+fn push_provided_methods(
+    parts: &mut Vec<String>,
+    block: &ImplBlock,
+    trait_methods: &HashMap<String, Vec<MethodInfo>>,
+    options: &RenderOptions,
+) {
+    if block.provided_methods.is_empty() {
+        return;
+    }
+    // Separate from the impl's own methods, if it has any
+    let gap = if block.methods.is_empty() { "" } else { "\n" };
+    let known = block
+        .trait_path
+        .as_ref()
+        .and_then(|path| trait_methods.get(path));
+    match known {
+        Some(methods) => {
+            let provided: Vec<&MethodInfo> = methods
+                .iter()
+                .filter(|m| !m.is_hidden && block.provided_methods.contains(&m.name))
+                .collect();
+            if provided.is_empty() {
+                return;
+            }
+            parts.push(format!("{gap}_Provided by trait ({}):_\n", provided.len()));
+            for m in provided {
+                push_signature_bullet(parts, &m.signature, &method_doc(m), options);
+            }
+        }
+        None => {
+            let names: Vec<String> = block
+                .provided_methods
+                .iter()
+                .map(|name| format!("`{name}`"))
+                .collect();
+            parts.push(format!("{gap}_Provided by trait:_ {}", names.join(", ")));
+        }
+    }
+}
+
+/// Inherent method count from which `render_impls` groups methods by category.
+const MIN_GROUPED_METHODS: usize = 10;
+
+/// Inherent method categories, in the order `render_impls` lists them.
+const METHOD_CATEGORIES: [&str; 6] = [
+    "Constructors",
+    "Conversion",
+    "Iteration",
+    "Async",
+    "Operators",
+    "Other methods",
+];
+
+/// Guess a method's category from its name and signature.
+fn method_category(method: &MethodInfo, type_name: &str) -> &'static str {
+    let name = method.name.as_str();
+    let returns = return_type(&method.signature).unwrap_or("");
+    let returns_self = mentions_type(returns, "Self") || mentions_type(returns, type_name);
+    if !has_receiver(&method.signature)
+        && (returns_self || name == "new" || name.starts_with("new_") || name == "builder")
+    {
+        "Constructors"
+    } else if method.signature.contains("async fn")
+        || returns.starts_with("impl Future")
+        || name.starts_with("poll_")
+    {
+        "Async"
+    } else if name.contains("iter")
+        || returns.contains("Iter")
+        || matches!(
+            name,
+            "drain" | "chunks" | "windows" | "lines" | "bytes" | "chars"
+        )
+    {
+        "Iteration"
+    } else if ["as_", "to_", "into_", "from_", "try_into_"]
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+    {
+        "Conversion"
+    } else if [
+        "checked_",
+        "wrapping_",
+        "saturating_",
+        "overflowing_",
+        "unchecked_",
+    ]
+    .iter()
+    .any(|prefix| name.starts_with(prefix))
+        || matches!(
+            name,
+            "add" | "sub" | "mul" | "div" | "rem" | "neg" | "pow" | "abs"
+        )
+    {
+        "Operators"
+    } else {
+        "Other methods"
+    }
+}
+
+/// Well-known standard library traits by group, in the order `render_impls` lists them.
+const STD_TRAIT_GROUPS: &[(&str, &[&str])] = &[
+    ("Common", &["Clone", "Default", "Drop"]),
+    (
+        "Formatting",
+        &[
+            "Debug", "Display", "Binary", "Octal", "LowerHex", "UpperHex", "LowerExp", "UpperExp",
+            "Pointer",
+        ],
+    ),
+    (
+        "Comparison",
+        &["PartialEq", "Eq", "PartialOrd", "Ord", "Hash"],
+    ),
+    (
+        "Conversion",
+        &[
+            "From",
+            "Into",
+            "TryFrom",
+            "TryInto",
+            "FromStr",
+            "ToString",
+            "AsRef",
+            "AsMut",
+            "Borrow",
+            "BorrowMut",
+            "ToOwned",
+            "Deref",
+            "DerefMut",
+        ],
+    ),
+    (
+        "Iteration",
+        &[
+            "Iterator",
+            "IntoIterator",
+            "DoubleEndedIterator",
+            "ExactSizeIterator",
+            "FromIterator",
+            "Extend",
+            "Sum",
+            "Product",
+        ],
+    ),
+    (
+        "Operators",
+        &[
+            "Add",
+            "Sub",
+            "Mul",
+            "Div",
+            "Rem",
+            "Neg",
+            "Not",
+            "BitAnd",
+            "BitOr",
+            "BitXor",
+            "Shl",
+            "Shr",
+            "AddAssign",
+            "SubAssign",
+            "MulAssign",
+            "DivAssign",
+            "RemAssign",
+            "BitAndAssign",
+            "BitOrAssign",
+            "BitXorAssign",
+            "ShlAssign",
+            "ShrAssign",
+            "Index",
+            "IndexMut",
+        ],
+    ),
+    ("Async", &["Future", "IntoFuture"]),
+    ("I/O", &["Read", "Write", "BufRead", "Seek"]),
+];
+
+/// Sort key of a trait impl: (0, None) for the crate's own traits, (1 + position, group)
+/// for well-known standard traits, and a last rank (with group "Other traits") for the rest.
+fn trait_rank(name: &str, crate_traits: &HashSet<&str>) -> (usize, Option<&'static str>) {
+    if crate_traits.contains(name) {
+        return (0, None);
+    }
+    let mut rank = 1;
+    for (group, traits) in STD_TRAIT_GROUPS {
+        if let Some(i) = traits.iter().position(|t| *t == name) {
+            return (rank + i, Some(group));
+        }
+        rank += traits.len();
+    }
+    (rank, Some("Other traits"))
+}
+
+/// The default bodies of a trait's provided methods, for `lookup_item` with
+/// `show_default_body`.
+pub fn render_default_bodies(item: &IndexedItem, bodies: &[(String, String)]) -> String {
+    if item.kind != ItemKind::Trait {
+        return "_`show_default_body` only applies to traits._".to_string();
+    }
+    let provided = item
+        .detail
+        .methods
+        .iter()
+        .filter(|m| !m.is_required)
+        .count();
+    if provided == 0 {
+        return format!("_`{}` has no provided methods._", item.name);
+    }
+    let mut parts = vec!["### Default Implementations\n".to_string()];
+    for (name, body) in bodies {
+        parts.push(format!("#### `{name}`\n\n```rust\n{body}\n```\n"));
+    }
+    if bodies.len() < provided {
+        parts.push(format!(
+            "_Source unavailable for {} of {provided} provided methods._",
+            provided - bodies.len()
+        ));
+    }
+    parts.join("\n").trim_end().to_string()
+}
+
+/// Push a rendered doc body, cut to its leading paragraphs unless `full_doc` is set.
+fn push_doc_body(parts: &mut Vec<String>, body: String, options: &RenderOptions) {
+    match summarize_doc(&body, SUMMARY_DOC_CHARS).filter(|_| !options.full_doc) {
+        Some((summary, left_out)) => {
+            parts.push(summary);
+            parts.push(format!(
+                "\n_Docs shortened: call again with `full_doc: true` for the rest ({} more chars)._",
+                format_count(left_out as u64)
+            ));
+        }
+        None => parts.push(body),
     }
+}
 
+/// The standard library's docs for a language keyword. Relative links point into the
+/// doc.rust-lang.org std docs of the same release (or nightly).
+pub fn render_keyword(index: &CrateIndex, keyword: &KeywordDoc, options: &RenderOptions) -> String {
+    let channel = if index.version.starts_with("nightly") {
+        "nightly"
+    } else {
+        &index.version
+    };
+    let base_url = format!("https://doc.rust-lang.org/{channel}/std/");
+    let mut parts = vec![
+        format!("## Keyword `{}`\n", keyword.keyword),
+        format!(
+            "_Rust language keyword, documented by {} v{}_\n",
+            index.crate_name, index.version
+        ),
+    ];
+    push_doc_body(
+        &mut parts,
+        normalize_doc(&keyword.doc, 3, Some(&base_url)),
+        options,
+    );
     parts.join("\n")
 }
 
+/// For a module path argument naming another kind of item.
+pub fn render_not_a_module(item: &IndexedItem) -> String {
+    format!(
+        "`{}` is a {}, not a module. Use `lookup_item` for its docs.",
+        item.path,
+        kind_label(&item.kind).to_lowercase()
+    )
+}
+
 /// Render a "not found" message with suggestions.
 pub fn render_not_found(index: &CrateIndex, item_path: &str) -> String {
     let suggestions = index.suggest_similar(item_path, 5);
@@ -230,7 +2509,14 @@ pub fn render_not_found(index: &CrateIndex, item_path: &str) -> String {
             "\n\nDid you mean one of:\n{}",
             suggestions
                 .iter()
-                .map(|s| format!("- `{s}`"))
+                .map(|s| {
+                    let doc_suffix = if s.short_doc.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" — {}", s.short_doc)
+                    };
+                    format!("- [{}] `{}`{doc_suffix}", s.kind, s.path)
+                })
                 .collect::<Vec<_>>()
                 .join("\n")
         )
@@ -241,6 +2527,67 @@ pub fn render_not_found(index: &CrateIndex, item_path: &str) -> String {
     )
 }
 
+/// Note above an item looked up through a re-export in another crate.
+pub fn render_reexport_note(reexport: &ForeignReexport, version: &str) -> String {
+    format!(
+        "_`{}` re-exports this item from crate `{}` v{version}._\n",
+        reexport.path, reexport.crate_name
+    )
+}
+
+/// Pointer to the defining crate for a re-export whose docs couldn't be loaded.
+pub fn render_foreign_reexport(reexport: &ForeignReexport, error: Option<&str>) -> String {
+    let mut text = format!(
+        "`{}` is a re-export of `{}` from crate `{}`, which is documented separately.\n\n\
+         Look it up with `lookup_item` (crate_name `{}`, item_path `{}`).",
+        reexport.path,
+        reexport.target_path,
+        reexport.crate_name,
+        reexport.crate_name,
+        reexport.target_path,
+    );
+    if let Some(error) = error {
+        text.push_str(&format!(
+            "\n\nLoading `{}` failed: {error}",
+            reexport.crate_name
+        ));
+    }
+    text
+}
+
+/// Render the candidates for an item path that matched several items, with
+/// guidance on picking one.
+pub fn render_ambiguous(
+    index: &CrateIndex,
+    item_path: &str,
+    candidates: &[&IndexedItem],
+) -> String {
+    let mut parts = Vec::new();
+    parts.push(format!(
+        "## `{item_path}` is ambiguous in {} v{}\n",
+        index.crate_name, index.version
+    ));
+    parts.push(format!("It matches {} items:\n", candidates.len()));
+    for item in candidates {
+        let doc_suffix = if item.short_doc.is_empty() {
+            String::new()
+        } else {
+            format!(" — {}", item.short_doc)
+        };
+        parts.push(format!("- [{}] `{}`{doc_suffix}", item.kind, item.path));
+    }
+
+    let prefix = format!("{}::", index.crate_name);
+    let example = candidates
+        .first()
+        .map(|item| item.path.strip_prefix(&prefix).unwrap_or(&item.path))
+        .unwrap_or(item_path);
+    parts.push(format!(
+        "\nRetry with one of these paths as `item_path` (the crate prefix is optional, e.g. `{example}`)."
+    ));
+    parts.join("\n")
+}
+
 fn kind_label(kind: &ItemKind) -> &'static str {
     match kind {
         ItemKind::Module => "Module",
@@ -252,10 +2599,480 @@ fn kind_label(kind: &ItemKind) -> &'static str {
         ItemKind::Constant => "Constant",
         ItemKind::Static => "Static",
         ItemKind::Macro => "Macro",
+        ItemKind::AttributeMacro => "Attribute Macro",
+        ItemKind::DeriveMacro => "Derive Macro",
         ItemKind::Union => "Union",
+        ItemKind::Primitive => "Primitive Type",
+    }
+}
+
+fn kind_label_plural(kind: &ItemKind) -> String {
+    match kind {
+        ItemKind::TypeAlias => "Type Aliases".to_string(),
+        other => format!("{}s", kind_label(other)),
     }
 }
 
+/// Push a signature as a list item, followed by its one-line `doc` (if any): inline code
+/// when it fits on one line, otherwise a wrapped code block inside the item.
+fn push_signature_bullet(
+    parts: &mut Vec<String>,
+    signature: &str,
+    doc: &str,
+    options: &RenderOptions,
+) {
+    let signature = options.signature(signature);
+    if signature.contains('\n') {
+        let code: Vec<String> = signature.lines().map(|line| format!("  {line}")).collect();
+        parts.push(format!("- ```rust\n{}\n  ```", code.join("\n")));
+    } else if options.inline_docs && !doc.is_empty() {
+        parts.push(format!("- `{signature}` — {doc}"));
+        return;
+    } else {
+        parts.push(format!("- `{signature}`"));
+    }
+    if !doc.is_empty() {
+        parts.push(format!("  {doc}"));
+    }
+}
+
+/// An item's doc comment as clean markdown whose top headings are level `top_heading`.
+pub(crate) fn doc_body(index: &CrateIndex, item: &IndexedItem, top_heading: usize) -> String {
+    normalize_doc(
+        &html_to_markdown(&item.doc),
+        top_heading,
+        docs_rs_dir(index, item).as_deref(),
+    )
+}
+
+/// The docs.rs directory holding an item's page, which relative doc links start from.
+/// `None` for locally built docs.
+fn docs_rs_dir(index: &CrateIndex, item: &IndexedItem) -> Option<String> {
+    index.version.parse::<cargo_lock::Version>().ok()?;
+    let module = if item.kind == ItemKind::Module {
+        &item.path
+    } else {
+        &item.parent_module
+    };
+    Some(format!(
+        "https://docs.rs/{}/{}/{}/",
+        index.package_name,
+        index.version,
+        module.replace("::", "/")
+    ))
+}
+
+/// The docs.rs source view of a span, for files under the crate's `src/` directory.
+/// `None` for locally built docs.
+fn docs_rs_source_url(index: &CrateIndex, span: &SourceSpan) -> Option<String> {
+    index.version.parse::<cargo_lock::Version>().ok()?;
+    let file = span.file.strip_prefix("src/")?;
+    Some(format!(
+        "https://docs.rs/{}/{}/src/{}/{file}.html#{}",
+        index.package_name, index.version, index.crate_name, span.line,
+    ))
+}
+
+/// The first line of a doc comment, with any HTML converted to markdown.
+fn doc_line(doc: &str) -> String {
+    html_to_markdown(first_line(doc))
+}
+
 fn first_line(s: &str) -> &str {
     s.lines().next().unwrap_or("")
 }
+
+/// Human-readable byte size (e.g. `2048` → `2.0 KiB`).
+pub fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1_024 => format!("{bytes} B"),
+        1_024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1_024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// Coarse age: the largest whole unit of minutes, hours, or days.
+fn format_age(age: Duration) -> String {
+    match age.as_secs() {
+        0..60 => "<1m".to_string(),
+        secs @ 60..3_600 => format!("{}m", secs / 60),
+        secs @ 3_600..86_400 => format!("{}h", secs / 3_600),
+        secs => format!("{}d", secs / 86_400),
+    }
+}
+
+/// Age in words (e.g. "12 days ago").
+fn describe_age(age: Duration) -> String {
+    let ago = |n: u64, unit: &str| format!("{n} {unit}{} ago", if n == 1 { "" } else { "s" });
+    match age.as_secs() {
+        0..60 => "just now".to_string(),
+        secs @ 60..3_600 => ago(secs / 60, "minute"),
+        secs @ 3_600..86_400 => ago(secs / 3_600, "hour"),
+        secs => ago(secs / 86_400, "day"),
+    }
+}
+
+/// Abbreviate a large count (e.g. `1234567` → `1.2M`).
+fn format_count(n: u64) -> String {
+    match n {
+        0..1_000 => n.to_string(),
+        1_000..1_000_000 => format!("{:.1}K", n as f64 / 1e3),
+        1_000_000..1_000_000_000 => format!("{:.1}M", n as f64 / 1e6),
+        _ => format!("{:.1}B", n as f64 / 1e9),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::docs::index::{ItemDetail, Visibility};
+    use crate::docs::suggest::NameIndex;
+
+    fn item(path: &str, kind: ItemKind) -> IndexedItem {
+        let (parent, name) = path.rsplit_once("::").unwrap();
+        IndexedItem {
+            path: path.to_string(),
+            name: name.to_string(),
+            kind,
+            signature: String::new(),
+            attrs: Vec::new(),
+            visibility: Visibility::Public,
+            aliases: Vec::new(),
+            reexports: Vec::new(),
+            short_doc: String::new(),
+            doc: String::new(),
+            deprecation: None,
+            stability: None,
+            cfg: None,
+            is_unsafe: false,
+            async_kind: None,
+            is_hidden: false,
+            span: None,
+            headings: Vec::new(),
+            external_refs: Vec::new(),
+            detail: ItemDetail::default(),
+            parent_module: parent.to_string(),
+        }
+    }
+
+    /// An index of `items`, with each listed in the module its path names.
+    fn test_index(items: Vec<IndexedItem>) -> CrateIndex {
+        let mut modules: HashMap<String, Vec<String>> = HashMap::new();
+        let mut root_items = Vec::new();
+        for item in &items {
+            if item.parent_module == "my_crate" {
+                root_items.push(item.path.clone());
+            } else {
+                modules
+                    .entry(item.parent_module.clone())
+                    .or_default()
+                    .push(item.path.clone());
+            }
+        }
+        let items: HashMap<String, IndexedItem> = items
+            .into_iter()
+            .map(|item| (item.path.clone(), item))
+            .collect();
+        CrateIndex {
+            crate_name: "my_crate".to_string(),
+            package_name: "my_crate".to_string(),
+            version: "1.0.0".to_string(),
+            name_index: NameIndex::build(items.values()),
+            items,
+            modules,
+            impl_blocks: HashMap::new(),
+            auto_traits: HashMap::new(),
+            root_items,
+            aliases: HashMap::new(),
+            foreign_reexports: HashMap::new(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            format_version: 56,
+            parse_time: Duration::ZERO,
+            partial: false,
+            stale: None,
+            fetched_at: None,
+        }
+    }
+
+    fn method(name: &str, signature: &str) -> MethodInfo {
+        MethodInfo {
+            name: name.to_string(),
+            signature: signature.to_string(),
+            doc: String::new(),
+            is_required: false,
+            is_unsafe: false,
+            async_kind: None,
+            is_hidden: false,
+            deprecation: None,
+            stability: None,
+            span: None,
+        }
+    }
+
+    /// An impl of `trait_name` (inherent if `None`) for `Widget` with `methods`.
+    fn impl_block(trait_name: Option<&str>, methods: Vec<MethodInfo>) -> ImplBlock {
+        ImplBlock {
+            header: match trait_name {
+                Some(name) => format!("impl {name} for Widget"),
+                None => "impl Widget".to_string(),
+            },
+            trait_name: trait_name.map(str::to_string),
+            trait_path: None,
+            provided_methods: Vec::new(),
+            methods,
+        }
+    }
+
+    /// The heading and group lines of a rendered response.
+    fn outline(text: &str) -> Vec<&str> {
+        text.lines()
+            .filter(|line| line.starts_with('#') || line.starts_with("**"))
+            .collect()
+    }
+
+    // ========== render_crate_items tests ==========
+
+    #[test]
+    fn render_crate_items_counts_listed_children_of_submodules() {
+        let mut hidden = item("my_crate::outer::inner::Internal", ItemKind::Struct);
+        hidden.is_hidden = true;
+        let index = test_index(vec![
+            item("my_crate::outer", ItemKind::Module),
+            item("my_crate::outer::inner", ItemKind::Module),
+            item("my_crate::outer::inner::Config", ItemKind::Struct),
+            item("my_crate::outer::inner::load", ItemKind::Function),
+            hidden,
+            item("my_crate::outer::empty", ItemKind::Module),
+            item("my_crate::outer::single", ItemKind::Module),
+            item("my_crate::outer::single::ONLY", ItemKind::Constant),
+        ]);
+        let visible = |item: &IndexedItem| !item.is_hidden;
+
+        let root = render_crate_items(&index, None, false, ItemOrder::Kind, visible);
+        assert!(root.contains("- `outer` (3 items)"), "{root}");
+
+        let outer = render_crate_items(
+            &index,
+            Some("my_crate::outer"),
+            false,
+            ItemOrder::Kind,
+            visible,
+        );
+        assert!(outer.contains("- `inner` (2 items)"), "{outer}");
+        assert!(outer.contains("- `single` (1 item)"), "{outer}");
+        assert!(outer.contains("- `empty` (0 items)"), "{outer}");
+
+        // Without the filter the hidden item counts again
+        let all = render_crate_items(
+            &index,
+            Some("my_crate::outer"),
+            false,
+            ItemOrder::Kind,
+            |_| true,
+        );
+        assert!(all.contains("- `inner` (3 items)"), "{all}");
+    }
+
+    // ========== output stability tests ==========
+
+    #[test]
+    fn rendering_does_not_depend_on_index_order() {
+        let items = || {
+            let mut items = vec![
+                item("my_crate::a", ItemKind::Module),
+                item("my_crate::b", ItemKind::Module),
+                item("my_crate::a::Handle", ItemKind::Struct),
+                item("my_crate::b::Handle", ItemKind::Struct),
+                item("my_crate::handle", ItemKind::Function),
+                item("my_crate::Handler", ItemKind::Trait),
+                item("my_crate::HANDLE_LIMIT", ItemKind::Constant),
+            ];
+            for item in &mut items {
+                item.short_doc = format!("The {} item.", item.name);
+            }
+            items
+        };
+        let render_all = |items: Vec<IndexedItem>, reverse: bool| {
+            let index = test_index(items);
+            let from = |ty: &str| ImplBlock {
+                header: format!("impl From<{ty}> for Widget"),
+                ..impl_block(
+                    Some("From"),
+                    vec![method("from", "fn from(value: T) -> Self")],
+                )
+            };
+            let mut blocks = [
+                from("u8"),
+                from("u16"),
+                impl_block(Some("Handler"), vec![method("handle", "fn handle(&self)")]),
+                impl_block(Some("Sync"), Vec::new()),
+                impl_block(Some("Send"), Vec::new()),
+            ];
+            if reverse {
+                blocks.reverse();
+            }
+            let impls: Vec<&ImplBlock> = blocks.iter().collect();
+            let options = RenderOptions::default();
+            [
+                render_crate_items(&index, None, false, ItemOrder::Kind, |_| true),
+                render_crate_items(&index, None, false, ItemOrder::DocLength, |_| true),
+                render_glob_matches(&index, "*", &index.glob_items("*"), false),
+                render_search_results(
+                    &index,
+                    "handle",
+                    &index.search("handle", 10, false, false, false),
+                ),
+                render_impls(
+                    &index,
+                    "my_crate::Widget",
+                    &impls,
+                    &HashMap::new(),
+                    &options,
+                ),
+            ]
+        };
+
+        let first = render_all(items(), false);
+
+        let mut reversed_items = items();
+        reversed_items.reverse();
+        assert_eq!(render_all(reversed_items, true), first);
+        // Crate traits lead, std groups follow, and ties on the trait name fall
+        // back to the impl header
+        assert_eq!(
+            outline(&first[4]),
+            vec![
+                "## Implementations for `my_crate::Widget`",
+                "### Trait Implementations",
+                "#### impl Handler for Widget",
+                "**Conversion**",
+                "#### impl From<u16> for Widget",
+                "#### impl From<u8> for Widget",
+                "**Also implements** (no methods): `Send`, `Sync`",
+            ]
+        );
+    }
+
+    // ========== render_impls tests ==========
+
+    #[test]
+    fn render_impls_orders_inherent_then_crate_then_std_traits() {
+        let index = test_index(vec![item("my_crate::Greet", ItemKind::Trait)]);
+        let fmt = || method("fmt", "fn fmt(&self, f: &mut Formatter<'_>) -> Result");
+        let blocks = [
+            impl_block(Some("Display"), vec![fmt()]),
+            impl_block(Some("Unpin"), Vec::new()),
+            impl_block(
+                Some("Serialize"),
+                vec![method("serialize", "fn serialize(&self)")],
+            ),
+            impl_block(Some("Greet"), vec![method("greet", "fn greet(&self)")]),
+            impl_block(None, vec![method("new", "pub fn new() -> Self")]),
+            impl_block(
+                Some("Clone"),
+                vec![method("clone", "fn clone(&self) -> Self")],
+            ),
+            impl_block(Some("Debug"), vec![fmt()]),
+            impl_block(Some("Send"), Vec::new()),
+        ];
+        let impls: Vec<&ImplBlock> = blocks.iter().collect();
+        let text = render_impls(
+            &index,
+            "my_crate::Widget",
+            &impls,
+            &HashMap::new(),
+            &RenderOptions::default(),
+        );
+        assert_eq!(
+            outline(&text),
+            [
+                "## Implementations for `my_crate::Widget`",
+                "### Inherent Methods",
+                "### Trait Implementations",
+                "#### impl Greet for Widget",
+                "**Common**",
+                "#### impl Clone for Widget",
+                "**Formatting**",
+                "#### impl Debug for Widget",
+                "#### impl Display for Widget",
+                "**Other traits**",
+                "#### impl Serialize for Widget",
+                "**Also implements** (no methods): `Send`, `Unpin`",
+            ]
+        );
+
+        let mut reversed = impls.clone();
+        reversed.reverse();
+        let text_reversed = render_impls(
+            &index,
+            "my_crate::Widget",
+            &reversed,
+            &HashMap::new(),
+            &RenderOptions::default(),
+        );
+        assert_eq!(outline(&text_reversed), outline(&text));
+    }
+
+    #[test]
+    fn render_impls_groups_inherent_methods_of_large_types_by_category() {
+        let index = test_index(Vec::new());
+        let methods = vec![
+            method("len", "pub fn len(&self) -> usize"),
+            method("new", "pub fn new() -> Self"),
+            method("iter", "pub fn iter(&self) -> Iter<'_>"),
+            method("as_str", "pub fn as_str(&self) -> &str"),
+            method("with_capacity", "pub fn with_capacity(n: usize) -> Widget"),
+            method(
+                "checked_add",
+                "pub fn checked_add(&self, n: usize) -> Option<Self>",
+            ),
+            method("into_inner", "pub fn into_inner(self) -> String"),
+            method("poll_ready", "pub fn poll_ready(&mut self) -> Poll<()>"),
+            method("clear", "pub fn clear(&mut self)"),
+            method("drain", "pub fn drain(&mut self) -> Drain<'_>"),
+        ];
+        let render = |methods: Vec<MethodInfo>| {
+            let block = impl_block(None, methods);
+            render_impls(
+                &index,
+                "my_crate::Widget",
+                &[&block],
+                &HashMap::new(),
+                &RenderOptions::default(),
+            )
+        };
+
+        let text = render(methods.clone());
+        assert_eq!(
+            outline(&text),
+            [
+                "## Implementations for `my_crate::Widget`",
+                "### Inherent Methods",
+                "**Constructors**",
+                "**Conversion**",
+                "**Iteration**",
+                "**Async**",
+                "**Operators**",
+                "**Other methods**",
+            ]
+        );
+        let position = |needle: &str| text.find(needle).unwrap();
+        assert!(position("**Constructors**") < position("fn new()"));
+        assert!(position("fn with_capacity") < position("**Conversion**"));
+        assert!(position("**Iteration**") < position("fn iter("));
+        assert!(position("fn drain(") < position("**Async**"));
+        assert!(position("**Other methods**") < position("fn len("));
+
+        // Types with few methods list them as they come
+        let text = render(methods[..3].to_vec());
+        assert_eq!(
+            outline(&text),
+            [
+                "## Implementations for `my_crate::Widget`",
+                "### Inherent Methods",
+            ]
+        );
+    }
+}
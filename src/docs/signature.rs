@@ -0,0 +1,261 @@
+/// Wrap a rendered signature so no function line exceeds `width` columns.
+///
+/// Lines declaring a function (including trait method lines) that are too long get
+/// one parameter per line, rustfmt-style; if the part before the parameters is still
+/// too long, its generic parameters are split the same way. Other lines, such as
+/// struct fields and `where` clauses (already one bound per line), are kept as is.
+pub fn pretty_signature(signature: &str, width: usize) -> String {
+    signature
+        .lines()
+        .map(|line| wrap_fn_line(line, width).unwrap_or_else(|| line.to_string()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A signature on one line for item listings: lines are joined, the visibility is
+/// dropped, and elided private fields become `..`. A `{ ... }` body that would take the
+/// line past `max` characters keeps only the members that fit, then `…`.
+pub fn condensed_signature(signature: &str, max: usize) -> String {
+    let line = signature
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| if line.starts_with("//") { ".." } else { line })
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(", }", " }");
+    let line = match line.strip_prefix("pub") {
+        Some(rest) if rest.starts_with(' ') => rest.trim_start().to_string(),
+        Some(rest) if rest.starts_with('(') => rest
+            .split_once(") ")
+            .map_or(line.clone(), |(_, rest)| rest.to_string()),
+        _ => line,
+    };
+    if line.chars().count() <= max {
+        return line;
+    }
+
+    let Some(open) = line.find(" { ").filter(|_| line.ends_with(" }")) else {
+        return line;
+    };
+    let mut condensed = line[..open + 2].to_string();
+    for member in split_members(&line[open + 3..line.len() - 2]) {
+        if condensed.chars().count() + member.chars().count() + 4 > max {
+            condensed.push_str(" … }");
+            return condensed;
+        }
+        condensed.push(' ');
+        condensed.push_str(member);
+    }
+    condensed.push_str(" }");
+    condensed
+}
+
+/// Split a body's members after each `,` or `;` outside any brackets, keeping the
+/// separators.
+fn split_members(body: &str) -> Vec<&str> {
+    let mut members = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut prev = ' ';
+    for (i, ch) in body.char_indices() {
+        match ch {
+            '(' | '[' | '{' | '<' => depth += 1,
+            '>' if prev == '-' => {}
+            ')' | ']' | '}' | '>' => depth = depth.saturating_sub(1),
+            ',' | ';' if depth == 0 => {
+                members.push(body[start..=i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        prev = ch;
+    }
+    members.push(body[start..].trim());
+    members.retain(|member| !member.is_empty());
+    members
+}
+
+/// Wrap one over-long `fn` line, or `None` if it fits or is not a function.
+fn wrap_fn_line(line: &str, width: usize) -> Option<String> {
+    if line.chars().count() <= width {
+        return None;
+    }
+    let indent = &line[..line.len() - line.trim_start().len()];
+    let fn_start = if line.trim_start().starts_with("fn ") {
+        indent.len()
+    } else {
+        line.find(" fn ")? + 1
+    };
+
+    // The parameter list opens right after the name and its generics
+    let name_end =
+        fn_start + 3 + line[fn_start + 3..].find(|c: char| !(c.is_alphanumeric() || c == '_'))?;
+    let generics_end = if line[name_end..].starts_with('<') {
+        Some(closing_delim(line, name_end)?)
+    } else {
+        None
+    };
+    let open = generics_end.map_or(name_end, |end| end + 1);
+    if !line[open..].starts_with('(') {
+        return None;
+    }
+    let close = closing_delim(line, open)?;
+    let params = split_top_level(&line[open + 1..close]);
+    if params.is_empty() {
+        return None;
+    }
+
+    let mut head = line[..open].to_string();
+    if let Some(end) = generics_end
+        && open > width
+    {
+        head = format!(
+            "{}<\n{}{}>",
+            &line[..name_end],
+            indented_list(&split_top_level(&line[name_end + 1..end]), indent),
+            indent
+        );
+    }
+    Some(format!(
+        "{head}(\n{}{indent}){}",
+        indented_list(&params, indent),
+        &line[close + 1..]
+    ))
+}
+
+/// One entry per line, indented one level past `indent`, each with a trailing comma.
+fn indented_list(entries: &[&str], indent: &str) -> String {
+    entries
+        .iter()
+        .map(|entry| format!("{indent}    {entry},\n"))
+        .collect()
+}
+
+/// Find the index of the delimiter closing the one at `open`, respecting nesting of
+/// `()`, `[]`, `{}`, and `<>` (the `>` of `->` doesn't count).
+fn closing_delim(s: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut prev = ' ';
+    for (i, ch) in s[open..].char_indices() {
+        match ch {
+            '(' | '[' | '{' | '<' => depth += 1,
+            '>' if prev == '-' => {}
+            ')' | ']' | '}' | '>' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+        prev = ch;
+    }
+    None
+}
+
+/// Split a comma-separated list at commas outside any brackets, trimming entries.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut prev = ' ';
+    for (i, ch) in s.char_indices() {
+        match ch {
+            '(' | '[' | '{' | '<' => depth += 1,
+            '>' if prev == '-' => {}
+            ')' | ']' | '}' | '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                entries.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+        prev = ch;
+    }
+    entries.push(s[start..].trim());
+    entries.retain(|entry| !entry.is_empty());
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== pretty_signature tests ==========
+
+    #[test]
+    fn short_signatures_are_unchanged() {
+        let sig = "pub fn new(name: &str) -> Self";
+        assert_eq!(pretty_signature(sig, 80), sig);
+        let sig = "pub struct Long {\n    pub field_with_a_long_name: HashMap<String, Vec<u8>>,\n}";
+        assert_eq!(pretty_signature(sig, 20), sig);
+    }
+
+    #[test]
+    fn long_functions_get_one_parameter_per_line() {
+        let sig = "pub fn spawn<F>(name: &str, task: F, callback: impl Fn(u8, u8) -> bool) -> JoinHandle<F::Output>\nwhere\n    F: Future + Send";
+        assert_eq!(
+            pretty_signature(sig, 60),
+            "pub fn spawn<F>(\n    name: &str,\n    task: F,\n    callback: impl Fn(u8, u8) -> bool,\n) -> JoinHandle<F::Output>\nwhere\n    F: Future + Send"
+        );
+    }
+
+    #[test]
+    fn trait_method_lines_keep_indent_and_wrap_generics() {
+        let sig = "pub trait Visitor {\n    fn visit_map<A: MapAccess<'de> + Send, B: Default>(self, map: A) -> Result<Self::Value, A::Error>;\n}";
+        assert_eq!(
+            pretty_signature(sig, 40),
+            "pub trait Visitor {\n    fn visit_map<\n        A: MapAccess<'de> + Send,\n        B: Default,\n    >(\n        self,\n        map: A,\n    ) -> Result<Self::Value, A::Error>;\n}"
+        );
+    }
+
+    #[test]
+    fn functions_without_parameters_are_unchanged() {
+        let sig = "pub fn a_function_with_a_rather_long_name() -> Result<Vec<String>, Error>";
+        assert_eq!(pretty_signature(sig, 20), sig);
+    }
+
+    // ========== condensed_signature tests ==========
+
+    #[test]
+    fn condensed_signature_joins_lines_and_drops_visibility() {
+        let sig =
+            "pub fn spawn<F>(future: F) -> JoinHandle<F::Output>\nwhere\n    F: Future + Send,";
+        assert_eq!(
+            condensed_signature(sig, 120),
+            "fn spawn<F>(future: F) -> JoinHandle<F::Output> where F: Future + Send,"
+        );
+        let sig = "pub struct Widget {\n    pub size: u32,\n\n    // ... private fields\n}";
+        assert_eq!(
+            condensed_signature(sig, 120),
+            "struct Widget { pub size: u32, .. }"
+        );
+        assert_eq!(
+            condensed_signature("pub(crate) enum Mode {\n    A,\n    B,\n}", 120),
+            "enum Mode { A, B }"
+        );
+    }
+
+    #[test]
+    fn condensed_signature_keeps_the_members_that_fit() {
+        let sig = "pub trait Greet {\n    fn name(self: &Self) -> String;\n    fn greet(self: &Self) -> String;\n}";
+        assert_eq!(
+            condensed_signature(sig, 50),
+            "trait Greet { fn name(self: &Self) -> String; … }"
+        );
+        assert_eq!(
+            condensed_signature(
+                "pub enum Level {\n    Low(Vec<(u8, u8)>),\n    High,\n}",
+                38
+            ),
+            "enum Level { Low(Vec<(u8, u8)>), … }"
+        );
+        // Functions are never cut
+        let sig = "pub fn new(name: &str, size: usize) -> Self";
+        assert_eq!(
+            condensed_signature(sig, 10),
+            "fn new(name: &str, size: usize) -> Self"
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use super::index::{CrateIndex, IndexedItem, ItemLookup, MethodInfo};
+
+/// Crate names that refer to the standard library or the current crate, which
+/// snippet resolution skips.
+const SKIPPED_ROOTS: &[&str] = &[
+    "std",
+    "core",
+    "alloc",
+    "proc_macro",
+    "test",
+    "crate",
+    "self",
+    "super",
+    "Self",
+];
+
+/// A path to an item of another crate referenced by a code snippet.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SnippetPath {
+    /// The crate segment as written (e.g. "serde_json").
+    pub crate_name: String,
+    /// The full path, with imported names expanded (e.g. "tokio::sync::Mutex::new").
+    pub path: String,
+}
+
+/// What a referenced path resolved to.
+pub enum Reference<'a> {
+    Item(&'a IndexedItem),
+    Method {
+        owner: &'a IndexedItem,
+        method: &'a MethodInfo,
+    },
+    Ambiguous(Vec<&'a IndexedItem>),
+    NotFound,
+}
+
+impl CrateIndex {
+    /// Resolve a path from a snippet to an item, or to a method of a type or trait.
+    pub fn resolve_reference(&self, path: &str) -> Reference<'_> {
+        match self.resolve_item(path) {
+            ItemLookup::Found(item) => return Reference::Item(item),
+            ItemLookup::Ambiguous(candidates) => return Reference::Ambiguous(candidates),
+            ItemLookup::NotFound => {}
+        }
+        let Some((owner_path, method_name)) = path.rsplit_once("::") else {
+            return Reference::NotFound;
+        };
+        let ItemLookup::Found(owner) = self.resolve_item(owner_path) else {
+            return Reference::NotFound;
+        };
+        let mut blocks: Vec<_> = self
+            .impl_blocks
+            .get(&owner.path)
+            .into_iter()
+            .flatten()
+            .collect();
+        // Inherent methods take precedence over trait methods, as in method resolution
+        blocks.sort_by_key(|block| block.trait_name.is_some());
+        blocks
+            .into_iter()
+            .flat_map(|block| &block.methods)
+            .chain(&owner.detail.methods)
+            .find(|m| m.name == method_name)
+            .map_or(Reference::NotFound, |method| Reference::Method {
+                owner,
+                method,
+            })
+    }
+}
+
+/// Extract the paths into other crates referenced by a snippet of Rust code:
+/// `use` declarations (with groups and renames expanded), and `a::b::c` paths
+/// in the code, where a leading imported name is replaced by its full path.
+///
+/// Paths rooted in the standard library or the current crate are skipped.
+/// The result is deduplicated, in order of first appearance.
+pub fn extract_paths(code: &str) -> Vec<SnippetPath> {
+    let code = strip_comments_and_strings(code);
+    let mut imports: Vec<(String, String)> = Vec::new();
+    let mut rest = String::new();
+
+    // Split `use` declarations from the remaining code
+    let mut remaining = code.as_str();
+    while let Some(start) = find_keyword(remaining, "use") {
+        rest.push_str(&remaining[..start]);
+        let after = &remaining[start + 3..];
+        let end = after.find(';').unwrap_or(after.len());
+        let tree = after[..end]
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        expand_use_tree("", &tree, &mut imports);
+        remaining = after.get(end + 1..).unwrap_or("");
+    }
+    rest.push_str(remaining);
+
+    let mut paths: Vec<String> = imports.iter().map(|(_, path)| path.clone()).collect();
+    for path in path_expressions(&rest) {
+        let (first, tail) = path.split_once("::").unwrap_or((&path, ""));
+        let expanded = match imports.iter().find(|(alias, _)| alias == first) {
+            Some((_, full)) if tail.is_empty() => full.clone(),
+            Some((_, full)) => format!("{full}::{tail}"),
+            None => path.clone(),
+        };
+        paths.push(expanded);
+    }
+
+    let mut result: Vec<SnippetPath> = Vec::new();
+    for path in paths {
+        let path = path.trim_start_matches("::").to_string();
+        let crate_name = path.split("::").next().unwrap_or("").to_string();
+        let is_crate_like = crate_name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase());
+        if !path.contains("::")
+            || !is_crate_like
+            || SKIPPED_ROOTS.contains(&crate_name.as_str())
+            || result.iter().any(|p| p.path == path)
+        {
+            continue;
+        }
+        result.push(SnippetPath { crate_name, path });
+    }
+    result
+}
+
+/// Expand a `use` tree (e.g. `tokio::{sync::Mutex, spawn as go}`)
+/// into (name in scope, full path) pairs. Glob imports are skipped.
+fn expand_use_tree(prefix: &str, tree: &str, out: &mut Vec<(String, String)>) {
+    let tree = tree.trim();
+    let join = |path: &str| -> String {
+        if prefix.is_empty() {
+            path.to_string()
+        } else if path.is_empty() {
+            prefix.to_string()
+        } else {
+            format!("{prefix}::{path}")
+        }
+    };
+
+    if let Some(brace) = tree.find('{') {
+        let base = join(tree[..brace].trim().trim_end_matches("::"));
+        let inner = tree[brace + 1..]
+            .strip_suffix('}')
+            .unwrap_or(&tree[brace + 1..]);
+        for subtree in split_top_level(inner) {
+            expand_use_tree(&base, subtree, out);
+        }
+        return;
+    }
+    if tree.is_empty() || tree.ends_with('*') {
+        return;
+    }
+
+    let (path, alias) = match tree.split_once(" as ") {
+        Some((path, alias)) => (path.trim(), Some(alias.trim())),
+        None => (tree, None),
+    };
+    let full = if path == "self" {
+        prefix.to_string()
+    } else {
+        join(path)
+    };
+    let name = alias
+        .map(str::to_string)
+        .unwrap_or_else(|| full.rsplit("::").next().unwrap_or(&full).to_string());
+    if name != "_" {
+        out.push((name, full));
+    }
+}
+
+/// Split on commas that are not nested inside braces.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+/// Byte offset of the next occurrence of `keyword` as a whole word.
+fn find_keyword(code: &str, keyword: &str) -> Option<usize> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    code.match_indices(keyword).map(|(i, _)| i).find(|&i| {
+        let before = code[..i].chars().next_back();
+        let after = code[i + keyword.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
+
+/// Paths of two or more segments (`a::b`, `a::b::<T>::c` → `a::b::c`) in code.
+fn path_expressions(code: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut segments: Vec<String> = Vec::new();
+    let mut ident = String::new();
+    let mut chars = code.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            ident.push(c);
+            continue;
+        }
+        if !ident.is_empty() {
+            segments.push(std::mem::take(&mut ident));
+        }
+        if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            // Skip turbofish generics: `::<...>`
+            if chars.peek() == Some(&'<') {
+                let mut depth = 0usize;
+                for c in chars.by_ref() {
+                    match c {
+                        '<' => depth += 1,
+                        '>' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            continue;
+        }
+        if segments.len() >= 2 {
+            paths.push(segments.join("::"));
+        }
+        segments.clear();
+    }
+    if !ident.is_empty() {
+        segments.push(ident);
+    }
+    if segments.len() >= 2 {
+        paths.push(segments.join("::"));
+    }
+    paths
+}
+
+/// Blank out comments and string/char literals so they aren't mistaken for paths.
+fn strip_comments_and_strings(code: &str) -> String {
+    let mut out = String::with_capacity(code.len());
+    let mut chars = code.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+                out.push(' ');
+            }
+            '"' => {
+                let mut escaped = false;
+                for c in chars.by_ref() {
+                    match c {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => break,
+                        _ => escaped = false,
+                    }
+                }
+                out.push_str("\"\"");
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(code: &str) -> Vec<String> {
+        extract_paths(code).into_iter().map(|p| p.path).collect()
+    }
+
+    // ========== extract_paths tests ==========
+
+    #[test]
+    fn extract_paths_expands_use_groups_and_renames() {
+        let code = "use tokio::{sync::{Mutex, RwLock as Lock}, spawn};\nuse serde::Serialize;";
+        assert_eq!(
+            paths(code),
+            [
+                "tokio::sync::Mutex",
+                "tokio::sync::RwLock",
+                "tokio::spawn",
+                "serde::Serialize"
+            ]
+        );
+    }
+
+    #[test]
+    fn extract_paths_expands_imported_names_in_code() {
+        let code = "use tokio::sync::Mutex;\nlet m = Mutex::new(0);\nlet s = serde_json::to_string::<u8>(&1);";
+        assert_eq!(
+            paths(code),
+            [
+                "tokio::sync::Mutex",
+                "tokio::sync::Mutex::new",
+                "serde_json::to_string"
+            ]
+        );
+    }
+
+    #[test]
+    fn extract_paths_skips_std_locals_comments_and_strings() {
+        let code = "use std::io::Read;\nuse crate::util;\n// tokio::spawn\nlet s = \"reqwest::get\";\nlet v = Vec::new();\nlet x = self::f();";
+        assert!(paths(code).is_empty());
+    }
+
+    #[test]
+    fn expand_use_tree_handles_self_and_globs() {
+        let mut out = Vec::new();
+        expand_use_tree("", "futures::{self, prelude::*, StreamExt as _}", &mut out);
+        assert_eq!(out, [("futures".to_string(), "futures".to_string())]);
+    }
+}
@@ -0,0 +1,117 @@
+use std::io::Read;
+
+use super::index::SourceSpan;
+use crate::error::Error;
+
+/// The lines of `source` a span covers, with their common indentation removed.
+/// `None` if the span lies outside the file.
+pub fn span_source(source: &str, span: &SourceSpan) -> Option<String> {
+    let lines: Vec<&str> = source
+        .lines()
+        .skip(span.line.checked_sub(1)?)
+        .take(span.end_line.checked_sub(span.line)? + 1)
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let dedented: Vec<&str> = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_else(|| line.trim_start()))
+        .collect();
+    Some(dedented.join("\n"))
+}
+
+/// A file from a `.crate` archive, by its path relative to the package root (as
+/// rustdoc spans of docs.rs builds record it). `None` if the archive lacks it.
+///
+/// Archive entries are prefixed with `{name}-{version}/`, which is stripped.
+pub fn file_from_archive(archive: &[u8], relative: &str) -> Result<Option<String>, Error> {
+    let read_error = |e: std::io::Error| Error::Other(format!("Failed to read crate archive: {e}"));
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    for entry in tar.entries().map_err(read_error)? {
+        let mut entry = entry.map_err(read_error)?;
+        let path = entry
+            .path()
+            .map_err(read_error)?
+            .to_string_lossy()
+            .into_owned();
+        if path
+            .split_once('/')
+            .is_some_and(|(_, rest)| rest == relative)
+        {
+            let mut content = String::new();
+            entry.read_to_string(&mut content).map_err(read_error)?;
+            return Ok(Some(content));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(line: usize, end_line: usize) -> SourceSpan {
+        SourceSpan {
+            file: "src/lib.rs".to_string(),
+            line,
+            end_line,
+        }
+    }
+
+    // ========== span_source tests ==========
+
+    #[test]
+    fn span_source_dedents_the_covered_lines() {
+        let source = "pub trait Greet {\n    fn name(&self) -> String;\n\n    fn greet(&self) -> String {\n        format!(\"Hello, {}\", self.name())\n    }\n}\n";
+        assert_eq!(
+            span_source(source, &span(4, 6)).unwrap(),
+            "fn greet(&self) -> String {\n    format!(\"Hello, {}\", self.name())\n}"
+        );
+        assert_eq!(
+            span_source(source, &span(2, 2)).unwrap(),
+            "fn name(&self) -> String;"
+        );
+    }
+
+    #[test]
+    fn span_source_rejects_spans_outside_the_file() {
+        assert_eq!(span_source("fn a() {}\n", &span(5, 6)), None);
+        assert_eq!(span_source("fn a() {}\n", &span(0, 1)), None);
+        assert_eq!(span_source("fn a() {}\n", &span(2, 1)), None);
+    }
+
+    // ========== file_from_archive tests ==========
+
+    #[test]
+    fn file_from_archive_strips_the_package_directory() {
+        let mut tar = tar::Builder::new(Vec::new());
+        for (path, body) in [
+            ("greet-1.0.0/Cargo.toml", "[package]"),
+            ("greet-1.0.0/src/lib.rs", "pub trait Greet {}"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(body.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, path, body.as_bytes()).unwrap();
+        }
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        std::io::Write::write_all(&mut gz, &tar.into_inner().unwrap()).unwrap();
+        let archive = gz.finish().unwrap();
+
+        assert_eq!(
+            file_from_archive(&archive, "src/lib.rs")
+                .unwrap()
+                .as_deref(),
+            Some("pub trait Greet {}")
+        );
+        assert_eq!(file_from_archive(&archive, "src/main.rs").unwrap(), None);
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+
+use super::index::{CrateIndex, IndexedItem, ItemKind, Visibility};
+
+/// Size and documentation coverage of a crate index (for `crate_stats`).
+pub struct CrateStats<'a> {
+    /// Number of items per kind, most common first.
+    pub kinds: Vec<(ItemKind, usize)>,
+    /// Modules (including the crate root) with the most direct children, largest first.
+    pub largest_modules: Vec<(&'a str, usize)>,
+    /// Public items other than modules, and how many of them have docs.
+    pub public_items: usize,
+    pub documented: usize,
+    /// Impl blocks, and how many of them implement a trait.
+    pub impl_blocks: usize,
+    pub trait_impls: usize,
+    /// Methods across all impl blocks.
+    pub methods: usize,
+}
+
+impl CrateStats<'_> {
+    /// Share of public items with docs, in percent.
+    pub fn coverage(&self) -> f64 {
+        if self.public_items == 0 {
+            return 100.0;
+        }
+        self.documented as f64 * 100.0 / self.public_items as f64
+    }
+}
+
+/// Size and shape of a crate's public API (for `api_surface`).
+pub struct ApiSurface {
+    /// Public, non-hidden items per kind (modules excluded), most common first.
+    pub kinds: Vec<(ItemKind, usize)>,
+    /// Public items taking type or const parameters (or `impl Trait` arguments).
+    pub generic_items: usize,
+    /// Public inherent methods of public types.
+    pub methods: usize,
+    /// Unsafe functions, traits, and inherent methods.
+    pub unsafe_items: usize,
+    /// Public items re-exported under a path other than where they're defined.
+    pub reexports: usize,
+    pub deprecated: usize,
+    /// Types marked `#[non_exhaustive]`.
+    pub non_exhaustive: usize,
+    /// `#[doc(hidden)]` items, public but not part of the documented API.
+    pub hidden: usize,
+}
+
+impl ApiSurface {
+    /// Public items and methods together.
+    pub fn total(&self) -> usize {
+        self.kinds.iter().map(|(_, count)| count).sum::<usize>() + self.methods
+    }
+}
+
+/// Whether an item's signature declares type or const parameters, or takes
+/// `impl Trait` arguments; lifetimes alone don't count.
+fn is_generic(item: &IndexedItem) -> bool {
+    let signature = item.signature.lines().next().unwrap_or("");
+    if item.kind == ItemKind::Function
+        && signature
+            .split_once('(')
+            .is_some_and(|(_, params)| params.contains("impl "))
+    {
+        return true;
+    }
+    let Some((_, rest)) = signature.split_once(&format!("{}<", item.name)) else {
+        return false;
+    };
+    let mut depth = 0;
+    let mut params = vec![String::new()];
+    for c in rest.chars() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' if depth == 0 => break,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                params.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(param) = params.last_mut() {
+            param.push(c);
+        }
+    }
+    params
+        .iter()
+        .any(|param| !param.trim().is_empty() && !param.trim().starts_with('\''))
+}
+
+impl CrateIndex {
+    /// Measure the public API: items per kind, generic items, inherent methods,
+    /// unsafe items, re-exports, deprecated, `#[non_exhaustive]`, and hidden items.
+    pub fn surface_report(&self) -> ApiSurface {
+        let mut kinds: HashMap<ItemKind, usize> = HashMap::new();
+        let mut surface = ApiSurface {
+            kinds: Vec::new(),
+            generic_items: 0,
+            methods: 0,
+            unsafe_items: 0,
+            reexports: 0,
+            deprecated: 0,
+            non_exhaustive: 0,
+            hidden: 0,
+        };
+        for item in self.items.values() {
+            if item.visibility != Visibility::Public || item.kind == ItemKind::Module {
+                continue;
+            }
+            if item.is_hidden {
+                surface.hidden += 1;
+                continue;
+            }
+            *kinds.entry(item.kind.clone()).or_default() += 1;
+            surface.generic_items += usize::from(is_generic(item));
+            surface.reexports += usize::from(!item.aliases.is_empty());
+            surface.deprecated += usize::from(item.deprecation.is_some());
+            surface.non_exhaustive += usize::from(
+                item.attrs
+                    .iter()
+                    .any(|attr| attr.starts_with("#[non_exhaustive")),
+            );
+            surface.methods += self
+                .impl_blocks
+                .get(&item.path)
+                .into_iter()
+                .flatten()
+                .filter(|block| block.trait_name.is_none())
+                .flat_map(|block| &block.methods)
+                .filter(|m| !m.is_hidden)
+                .count();
+        }
+        let report = self.unsafe_report();
+        surface.unsafe_items = report.functions.len() + report.traits.len() + report.methods.len();
+
+        surface.kinds = kinds.into_iter().collect();
+        surface.kinds.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| a.0.to_string().cmp(&b.0.to_string()))
+        });
+        surface
+    }
+
+    /// Count items, impls, and documented items, listing up to `max_modules` of the
+    /// largest modules.
+    pub fn stats(&self, max_modules: usize) -> CrateStats<'_> {
+        let mut kinds: HashMap<ItemKind, usize> = HashMap::new();
+        let mut public_items = 0;
+        let mut documented = 0;
+        for item in self.items.values() {
+            *kinds.entry(item.kind.clone()).or_default() += 1;
+            if item.visibility == Visibility::Public && item.kind != ItemKind::Module {
+                public_items += 1;
+                if !item.doc.trim().is_empty() {
+                    documented += 1;
+                }
+            }
+        }
+        let mut kinds: Vec<(ItemKind, usize)> = kinds.into_iter().collect();
+        kinds.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| a.0.to_string().cmp(&b.0.to_string()))
+        });
+
+        let mut largest_modules: Vec<(&str, usize)> = self
+            .modules
+            .iter()
+            .map(|(path, children)| (path.as_str(), children.len()))
+            .filter(|(path, _)| *path != self.crate_name)
+            .chain(std::iter::once((
+                self.crate_name.as_str(),
+                self.root_items.len(),
+            )))
+            .collect();
+        largest_modules.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        largest_modules.truncate(max_modules);
+
+        let blocks = self.impl_blocks.values().flatten();
+        CrateStats {
+            kinds,
+            largest_modules,
+            public_items,
+            documented,
+            impl_blocks: blocks.clone().count(),
+            trait_impls: blocks.clone().filter(|b| b.trait_name.is_some()).count(),
+            methods: blocks.map(|b| b.methods.len()).sum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::docs::index::{ImplBlock, IndexedItem, ItemDetail};
+    use crate::docs::suggest::NameIndex;
+
+    fn item(path: &str, kind: ItemKind, doc: &str) -> (String, IndexedItem) {
+        let (parent, name) = path.rsplit_once("::").unwrap();
+        let item = IndexedItem {
+            path: path.to_string(),
+            name: name.to_string(),
+            kind,
+            signature: String::new(),
+            attrs: Vec::new(),
+            visibility: Visibility::Public,
+            aliases: Vec::new(),
+            reexports: Vec::new(),
+            short_doc: String::new(),
+            doc: doc.to_string(),
+            deprecation: None,
+            stability: None,
+            cfg: None,
+            is_unsafe: false,
+            async_kind: None,
+            is_hidden: false,
+            span: None,
+            headings: Vec::new(),
+            external_refs: Vec::new(),
+            detail: ItemDetail::default(),
+            parent_module: parent.to_string(),
+        };
+        (path.to_string(), item)
+    }
+
+    fn impl_block(trait_name: Option<&str>) -> ImplBlock {
+        ImplBlock {
+            header: String::new(),
+            trait_name: trait_name.map(str::to_string),
+            trait_path: None,
+            provided_methods: Vec::new(),
+            methods: Vec::new(),
+        }
+    }
+
+    // ========== stats tests ==========
+
+    #[test]
+    fn stats_counts_kinds_coverage_and_impls() {
+        let index = CrateIndex {
+            crate_name: "my_crate".to_string(),
+            package_name: "my_crate".to_string(),
+            version: "1.0.0".to_string(),
+            items: HashMap::from([
+                item("my_crate::net", ItemKind::Module, ""),
+                item("my_crate::net::Socket", ItemKind::Struct, "A socket."),
+                item("my_crate::net::Addr", ItemKind::Struct, ""),
+                item("my_crate::connect", ItemKind::Function, "Connect."),
+            ]),
+            modules: HashMap::from([
+                (
+                    "my_crate::net".to_string(),
+                    vec!["Socket".to_string(), "Addr".to_string()],
+                ),
+                ("my_crate".to_string(), Vec::new()),
+            ]),
+            impl_blocks: HashMap::from([(
+                "my_crate::net::Socket".to_string(),
+                vec![impl_block(None), impl_block(Some("Clone"))],
+            )]),
+            auto_traits: HashMap::new(),
+            root_items: vec!["my_crate::net".to_string(), "my_crate::connect".to_string()],
+            aliases: HashMap::new(),
+            foreign_reexports: HashMap::new(),
+            name_index: NameIndex::default(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            format_version: 56,
+            parse_time: Duration::ZERO,
+            partial: false,
+            stale: None,
+            fetched_at: None,
+        };
+
+        let stats = index.stats(1);
+        assert_eq!(stats.kinds[0], (ItemKind::Struct, 2));
+        assert_eq!(stats.largest_modules, [("my_crate", 2)]);
+        assert_eq!((stats.public_items, stats.documented), (3, 2));
+        assert!((stats.coverage() - 66.7).abs() < 0.1);
+        assert_eq!((stats.impl_blocks, stats.trait_impls), (2, 1));
+    }
+
+    // ========== api_surface tests ==========
+
+    #[test]
+    fn is_generic_ignores_lifetime_only_generics() {
+        let (_, mut parser) = item("my_crate::Parser", ItemKind::Struct, "");
+        parser.signature = "pub struct Parser<'a> { /* private fields */ }".to_string();
+        assert!(!is_generic(&parser));
+        parser.signature =
+            "pub struct Parser<'a, T: Read<'a>> { /* private fields */ }".to_string();
+        assert!(is_generic(&parser));
+
+        let (_, mut func) = item("my_crate::open", ItemKind::Function, "");
+        func.signature = "pub fn open(path: impl AsRef<Path>) -> File".to_string();
+        assert!(is_generic(&func));
+        func.signature = "pub fn open(path: &Path) -> Vec<File>".to_string();
+        assert!(!is_generic(&func));
+    }
+}
@@ -0,0 +1,658 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::index::CrateIndex;
+use crate::error::Error;
+use crate::http::HttpClient;
+
+/// Crates documented by the Rust toolchain's `rust-docs-json` component rather than
+/// docs.rs, which doesn't host them.
+pub const STD_DOC_CRATES: [&str; 5] = ["std", "core", "alloc", "proc_macro", "test"];
+
+/// Target whose `rust-docs-json` artifacts are downloaded. The standard library's public
+/// API only differs between targets in platform modules (`std::os`).
+const DOCS_TARGET: &str = "x86_64-unknown-linux-gnu";
+
+/// Library crates whose `src/keyword_docs.rs` holds the keyword docs: core since they
+/// moved there, std before.
+const KEYWORD_SOURCE_CRATES: [&str; 2] = ["core", "std"];
+
+/// Day (since 1970-01-01) of 2015-05-14, the eve of Rust 1.0: Rust 1.N ships `42 * N`
+/// days after it.
+const RUST_1_0_EPOCH_DAY: i64 = 16_569;
+
+/// Days between a stable release and the last nightly before its beta was branched.
+const NIGHTLY_LEAD_DAYS: i64 = 49;
+
+/// Keywords documented by the standard library (`#[doc(keyword = "...")]`). Their docs
+/// are source-only, missing from rustdoc JSON, so they're read from `rust-src`.
+pub const KEYWORDS: [&str; 40] = [
+    "as", "async", "await", "become", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+    "type", "union", "unsafe", "use", "where", "while",
+];
+
+pub fn is_std_crate(crate_name: &str) -> bool {
+    STD_DOC_CRATES.contains(&crate_name)
+}
+
+/// The keyword an item path asks about, and whether it asked explicitly: "keyword::match"
+/// or rustdoc's "keyword.match" do, a bare "match" doesn't and yields to any item of
+/// that name. A leading "std::" or "core::" is ignored.
+pub fn keyword_query(item_path: &str) -> Option<(&'static str, bool)> {
+    let path = ["std::", "core::"]
+        .iter()
+        .find_map(|prefix| item_path.strip_prefix(prefix))
+        .unwrap_or(item_path);
+    let (name, explicit) = match path
+        .strip_prefix("keyword::")
+        .or_else(|| path.strip_prefix("keyword."))
+    {
+        Some(name) => (name, true),
+        None => (path, false),
+    };
+    KEYWORDS
+        .iter()
+        .find(|keyword| **keyword == name)
+        .map(|keyword| (*keyword, explicit))
+}
+
+/// The keyword `lookup_item` answers an item path in std's or core's docs with, if
+/// any: a bare keyword only when no item has that path (`fn` is also a primitive).
+pub fn keyword_lookup(index: &CrateIndex, item_path: &str) -> Option<&'static str> {
+    if !matches!(index.crate_name.as_str(), "std" | "core") {
+        return None;
+    }
+    let (keyword, explicit) = keyword_query(item_path)?;
+    (explicit || index.get_item_by_path(item_path).is_none()).then_some(keyword)
+}
+
+/// The Rust toolchain a project builds with, as reported by `rustc -vV` in its
+/// directory (rustup applies `rust-toolchain.toml` and directory overrides).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustToolchain {
+    /// e.g. "1.82.0", "1.84.0-beta.3", or "1.85.0-nightly".
+    pub release: String,
+    /// Date of the compiler's last commit, e.g. "2024-11-29".
+    pub commit_date: Option<String>,
+    /// The toolchain's sysroot, where the `rust-docs-json` component is installed.
+    pub sysroot: Option<PathBuf>,
+}
+
+impl RustToolchain {
+    /// Run `rustc -vV` and `rustc --print sysroot` in `dir`. `None` if rustc isn't
+    /// installed or its output isn't understood.
+    pub fn detect(dir: &Path) -> Option<Self> {
+        let rustc = |args: &[&str]| {
+            let output = Command::new("rustc")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .inspect_err(|e| tracing::debug!("Failed to run rustc: {e}"))
+                .ok()?;
+            if !output.status.success() {
+                tracing::debug!(
+                    "rustc {} failed: {}",
+                    args.join(" "),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                return None;
+            }
+            Some(String::from_utf8_lossy(&output.stdout).into_owned())
+        };
+        let mut toolchain = Self::parse(&rustc(&["-vV"])?)?;
+        toolchain.sysroot = rustc(&["--print", "sysroot"]).map(|out| PathBuf::from(out.trim()));
+        Some(toolchain)
+    }
+
+    /// Read the `release:` and `commit-date:` lines of `rustc -vV` output.
+    fn parse(verbose_version: &str) -> Option<Self> {
+        let field = |name: &str| {
+            verbose_version.lines().find_map(|line| {
+                let value = line.strip_prefix(name)?.strip_prefix(':')?.trim();
+                (!value.is_empty() && value != "unknown").then(|| value.to_string())
+            })
+        };
+        Some(Self {
+            release: field("release")?,
+            commit_date: field("commit-date"),
+            sysroot: None,
+        })
+    }
+
+    /// The std docs version matching this toolchain: "1.82.0" for a stable or beta
+    /// release, and "nightly-YYYY-MM-DD" for a nightly, dated the day its docs were
+    /// published (the day after its last commit).
+    pub fn std_version(&self) -> String {
+        let base = self.release.split('-').next().unwrap_or(&self.release);
+        if self.release.ends_with("-nightly")
+            && let Some(date) = self.commit_date.as_deref().and_then(parse_date)
+        {
+            return format!("nightly-{}", format_date(date + 1));
+        }
+        base.to_string()
+    }
+
+    /// The toolchain's own rustdoc JSON for a std crate, if the `rust-docs-json`
+    /// component is installed.
+    pub fn installed_json(&self, crate_name: &str) -> Option<PathBuf> {
+        let path = self
+            .sysroot
+            .as_ref()?
+            .join("share/doc/rust/json")
+            .join(format!("{crate_name}.json"));
+        path.is_file().then_some(path)
+    }
+
+    /// A std source file named by a span of the toolchain's docs
+    /// (`/rustc/{commit}/library/...`), if the `rust-src` component is installed.
+    pub fn installed_source(&self, file: &str) -> Option<PathBuf> {
+        let (_, relative) = file.strip_prefix("/rustc/")?.split_once('/')?;
+        let path = self
+            .sysroot
+            .as_ref()?
+            .join("lib/rustlib/src/rust")
+            .join(relative);
+        path.is_file().then_some(path)
+    }
+
+    /// The toolchain's `keyword_docs.rs`, if the `rust-src` component is installed.
+    pub fn installed_keyword_source(&self) -> Option<PathBuf> {
+        let library = self.sysroot.as_ref()?.join("lib/rustlib/src/rust/library");
+        KEYWORD_SOURCE_CRATES
+            .iter()
+            .map(|krate| library.join(krate).join("src/keyword_docs.rs"))
+            .find(|path| path.is_file())
+    }
+}
+
+/// Normalize a requested std version to the key its docs are loaded and cached under:
+/// "latest" and "stable" become the newest stable release, "beta" the release after it,
+/// and "1.82" becomes "1.82.0". Nightly dates ("nightly-2024-11-30") are kept as they
+/// are; the "nightly" channel needs the network, see `latest_nightly`.
+pub fn std_version_key(version: &str) -> Result<String, Error> {
+    match version {
+        "latest" | "stable" => Ok(format!("1.{}.0", latest_stable_minor(today()))),
+        "beta" => Ok(format!("1.{}.0", latest_stable_minor(today()) + 1)),
+        _ if version.starts_with("nightly-") => {
+            docs_date(version)?;
+            Ok(version.to_string())
+        }
+        _ => {
+            let minor = stable_minor(version)?;
+            Ok(format!("1.{minor}.0"))
+        }
+    }
+}
+
+/// The version key of the newest published nightly ("nightly-2024-11-30"), from the date
+/// at the top of the nightly channel manifest. Only the start of the manifest is read.
+pub async fn latest_nightly(client: &HttpClient) -> Result<String, Error> {
+    let url = "https://static.rust-lang.org/dist/channel-rust-nightly.toml";
+    tracing::info!("Fetching the current nightly from {url}");
+
+    let _slot = client.slot(url).await?;
+    let mut response = client.get(url).send().await?.error_for_status()?;
+    let mut head = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        head.extend_from_slice(&chunk);
+        if let Some(date) = manifest_date(&String::from_utf8_lossy(&head)) {
+            return Ok(format!("nightly-{date}"));
+        }
+        if head.len() > 4096 {
+            break;
+        }
+    }
+    Err(Error::Other(format!("No date found in {url}")))
+}
+
+/// The `date = "YYYY-MM-DD"` line of a channel manifest.
+fn manifest_date(manifest: &str) -> Option<&str> {
+    manifest.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        let date = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+        (key.trim() == "date" && parse_date(date).is_some()).then_some(date)
+    })
+}
+
+/// The date ("YYYY-MM-DD") of the nightly whose `rust-docs-json` documents a std version
+/// key: the nightly's own date, or for a stable release the last nightly before that
+/// release's beta was branched, which has the same API barring backports.
+pub fn docs_date(version: &str) -> Result<String, Error> {
+    let day = match version.strip_prefix("nightly-") {
+        Some(date) => parse_date(date).ok_or_else(|| {
+            Error::Other(format!(
+                "Invalid nightly version {version:?}, expected nightly-YYYY-MM-DD"
+            ))
+        })?,
+        None => release_day(stable_minor(version)?) - NIGHTLY_LEAD_DAYS,
+    };
+    if day > today() {
+        return Err(Error::Other(format!(
+            "Rust {version} has not been released yet"
+        )));
+    }
+    Ok(format_date(day))
+}
+
+/// The minor version of a "1.N" or "1.N.P" std version.
+fn stable_minor(version: &str) -> Result<i64, Error> {
+    let mut parts = version.split('.');
+    match (parts.next(), parts.next().map(str::parse::<i64>)) {
+        (Some("1"), Some(Ok(minor))) if minor > 0 => Ok(minor),
+        _ => Err(Error::Other(format!(
+            "Invalid standard library version {version:?}, expected a Rust release like \
+             \"1.82.0\", \"latest\", or \"nightly-YYYY-MM-DD\""
+        ))),
+    }
+}
+
+/// Day (since 1970-01-01) Rust 1.N is released on, following the six-week train.
+fn release_day(minor: i64) -> i64 {
+    RUST_1_0_EPOCH_DAY + 42 * minor
+}
+
+/// The newest Rust 1.N released on or before `day`.
+fn latest_stable_minor(day: i64) -> i64 {
+    (day - RUST_1_0_EPOCH_DAY).div_euclid(42)
+}
+
+fn today() -> i64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    (secs / 86_400) as i64
+}
+
+/// Days since 1970-01-01 of a "YYYY-MM-DD" date (Howard Hinnant's `days_from_civil`).
+fn parse_date(date: &str) -> Option<i64> {
+    let mut parts = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+    );
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+/// "YYYY-MM-DD" of a day since 1970-01-01 (Howard Hinnant's `civil_from_days`).
+fn format_date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Download the `rust-docs-json` component of the nightly dated `date` from
+/// static.rust-lang.org and extract the rustdoc JSON of every std crate in it, as
+/// zstd-compressed bytes by crate name.
+#[tracing::instrument(name = "fetch_std", skip(client))]
+pub async fn fetch_std_docs(
+    client: &HttpClient,
+    version: &str,
+    date: &str,
+) -> Result<Vec<(String, Vec<u8>)>, Error> {
+    let url = format!(
+        "https://static.rust-lang.org/dist/{date}/rust-docs-json-nightly-{DOCS_TARGET}.tar.gz"
+    );
+    tracing::info!("Fetching standard library docs from {url}");
+
+    let _slot = client.slot(&url).await?;
+    let response = client.get(&url).send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(Error::Other(format!(
+            "Standard library docs for Rust {version} are not available: no rust-docs-json \
+             was published for nightly-{date}"
+        )));
+    }
+    let archive = response.error_for_status()?.bytes().await?;
+    tokio::task::spawn_blocking(move || extract_std_docs(&archive))
+        .await
+        .map_err(|e| Error::Other(format!("Extracting standard library docs failed: {e}")))?
+}
+
+/// The `share/doc/rust/json/{crate}.json` files of a `rust-docs-json` tarball,
+/// zstd-compressed.
+fn extract_std_docs(archive: &[u8]) -> Result<Vec<(String, Vec<u8>)>, Error> {
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    let mut docs = Vec::new();
+    for entry in tar.entries().map_err(Error::Gzip)? {
+        let mut entry = entry.map_err(Error::Gzip)?;
+        let path = entry.path().map_err(Error::Gzip)?.into_owned();
+        let Some(crate_name) = path
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_suffix(".json"))
+            .filter(|name| is_std_crate(name))
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        if !path
+            .parent()
+            .is_some_and(|dir| dir.ends_with("doc/rust/json"))
+        {
+            continue;
+        }
+        let mut json = Vec::new();
+        entry.read_to_end(&mut json).map_err(Error::Gzip)?;
+        docs.push((crate_name, zstd::stream::encode_all(json.as_slice(), 3)?));
+    }
+    if docs.is_empty() {
+        return Err(Error::Other(
+            "The rust-docs-json archive contains no standard library docs".to_string(),
+        ));
+    }
+    Ok(docs)
+}
+
+/// The docs of one keyword, as written in the standard library's `keyword_docs.rs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeywordDoc {
+    pub keyword: String,
+    /// Markdown, as in the source's `///` comments.
+    pub doc: String,
+}
+
+/// Download the `rust-src` component of the nightly dated `date` and extract its
+/// `keyword_docs.rs`.
+#[tracing::instrument(name = "fetch_keywords", skip(client))]
+pub async fn fetch_keyword_source(
+    client: &HttpClient,
+    version: &str,
+    date: &str,
+) -> Result<Vec<u8>, Error> {
+    let url = format!("https://static.rust-lang.org/dist/{date}/rust-src-nightly.tar.gz");
+    tracing::info!("Fetching keyword docs from {url}");
+
+    let _slot = client.slot(&url).await?;
+    let response = client.get(&url).send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(Error::Other(format!(
+            "Keyword docs for Rust {version} are not available: no rust-src was published \
+             for nightly-{date}"
+        )));
+    }
+    let archive = response.error_for_status()?.bytes().await?;
+    tokio::task::spawn_blocking(move || extract_keyword_source(&archive))
+        .await
+        .map_err(|e| Error::Other(format!("Extracting keyword docs failed: {e}")))?
+}
+
+/// The `library/{core,std}/src/keyword_docs.rs` file of a `rust-src` tarball.
+fn extract_keyword_source(archive: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    for entry in tar.entries().map_err(Error::Gzip)? {
+        let mut entry = entry.map_err(Error::Gzip)?;
+        let path = entry.path().map_err(Error::Gzip)?.into_owned();
+        let is_keyword_source = KEYWORD_SOURCE_CRATES.iter().any(|krate| {
+            path.ends_with(Path::new("library").join(krate).join("src/keyword_docs.rs"))
+        });
+        if is_keyword_source {
+            let mut source = Vec::new();
+            entry.read_to_end(&mut source).map_err(Error::Gzip)?;
+            return Ok(source);
+        }
+    }
+    Err(Error::Other(
+        "The rust-src archive contains no keyword docs".to_string(),
+    ))
+}
+
+/// Read the keyword docs out of `keyword_docs.rs`: each `#[doc(keyword = "...")]`
+/// attribute, followed by `///` comments, on a placeholder item (`mod as_keyword {}`
+/// or `const _: () = ();`).
+pub fn parse_keyword_docs(source: &str) -> Vec<KeywordDoc> {
+    let mut keywords = Vec::new();
+    let mut current: Option<KeywordDoc> = None;
+    for line in source.lines() {
+        let line = line.trim_start();
+        if let Some(keyword) = line
+            .strip_prefix("#[doc(keyword = \"")
+            .and_then(|rest| rest.strip_suffix("\")]"))
+        {
+            // rustdoc can't have both `keyword.self.html` and `keyword.Self.html` on
+            // case-insensitive file systems, so `Self` is documented as "SelfTy"
+            let keyword = if keyword == "SelfTy" { "Self" } else { keyword };
+            current = Some(KeywordDoc {
+                keyword: keyword.to_string(),
+                doc: String::new(),
+            });
+            continue;
+        }
+        let Some(keyword) = current.as_mut() else {
+            continue;
+        };
+        let text = match line.strip_prefix("///") {
+            Some(text) => Some(text.strip_prefix(' ').unwrap_or(text)),
+            // Target-specific doc lines: keep the non-wasi variant
+            None if line.starts_with("#[cfg_attr(not(") => line
+                .split_once("doc = \"")
+                .and_then(|(_, rest)| rest.strip_suffix("\")]")),
+            None => None,
+        };
+        if let Some(text) = text {
+            keyword.doc.push_str(text);
+            keyword.doc.push('\n');
+        } else if !line.is_empty() && !line.starts_with("//") && !line.starts_with("#[") {
+            // The item the docs are attached to
+            keyword.doc.truncate(keyword.doc.trim_end().len());
+            keywords.extend(current.take());
+        }
+    }
+    keywords
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== date tests ==========
+
+    #[test]
+    fn dates_round_trip() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(
+            parse_date("2024-02-29").map(format_date).as_deref(),
+            Some("2024-02-29")
+        );
+        assert_eq!(
+            format_date(parse_date("2000-03-01").unwrap() - 1),
+            "2000-02-29"
+        );
+        assert_eq!(parse_date("2024-13-01"), None);
+        assert_eq!(parse_date("nightly"), None);
+    }
+
+    #[test]
+    fn release_days_follow_the_six_week_train() {
+        assert_eq!(format_date(release_day(1)), "2015-06-25");
+        assert_eq!(format_date(release_day(75)), "2023-12-28");
+        assert_eq!(format_date(release_day(82)), "2024-10-17");
+        assert_eq!(latest_stable_minor(parse_date("2024-10-17").unwrap()), 82);
+        assert_eq!(latest_stable_minor(parse_date("2024-10-16").unwrap()), 81);
+    }
+
+    // ========== version tests ==========
+
+    #[test]
+    fn std_version_key_normalizes_versions() {
+        assert_eq!(std_version_key("1.82").unwrap(), "1.82.0");
+        let stable = std_version_key("stable").unwrap();
+        let beta = std_version_key("beta").unwrap();
+        assert_eq!(
+            stable_minor(&beta).unwrap(),
+            stable_minor(&stable).unwrap() + 1
+        );
+        assert_eq!(std_version_key("1.82.1").unwrap(), "1.82.0");
+        assert_eq!(
+            std_version_key("nightly-2024-11-30").unwrap(),
+            "nightly-2024-11-30"
+        );
+        assert!(std_version_key("latest").unwrap().starts_with("1."));
+        assert!(std_version_key("2.0.0").is_err());
+        assert!(std_version_key("nightly-soon").is_err());
+    }
+
+    #[test]
+    fn docs_date_uses_the_nightly_a_release_branched_from() {
+        assert_eq!(docs_date("1.82.0").unwrap(), "2024-08-29");
+        assert_eq!(docs_date("nightly-2024-11-30").unwrap(), "2024-11-30");
+        assert!(docs_date("1.9999.0").is_err());
+        // The current beta branched from a nightly before the last stable release
+        assert!(docs_date(&std_version_key("beta").unwrap()).is_ok());
+    }
+
+    #[test]
+    fn manifest_date_reads_the_date_line() {
+        let manifest = "manifest-version = \"2\"\ndate = \"2024-11-30\"\n[pkg.cargo]\n";
+        assert_eq!(manifest_date(manifest), Some("2024-11-30"));
+        // Cut off mid-line while streaming
+        assert_eq!(
+            manifest_date("manifest-version = \"2\"\ndate = \"2024-11"),
+            None
+        );
+    }
+
+    // ========== RustToolchain tests ==========
+
+    #[test]
+    fn parses_stable_and_nightly_rustc_output() {
+        let stable = RustToolchain::parse(
+            "rustc 1.82.0 (f6e511eec 2024-10-15)\nbinary: rustc\n\
+             commit-hash: f6e511eec7342f59a25f7c0534f1dbea00d01b14\n\
+             commit-date: 2024-10-15\nhost: x86_64-unknown-linux-gnu\nrelease: 1.82.0\n",
+        )
+        .unwrap();
+        assert_eq!(stable.release, "1.82.0");
+        assert_eq!(stable.std_version(), "1.82.0");
+
+        let beta = RustToolchain::parse("release: 1.84.0-beta.3\ncommit-date: 2024-11-20\n");
+        assert_eq!(beta.unwrap().std_version(), "1.84.0");
+
+        let nightly =
+            RustToolchain::parse("release: 1.85.0-nightly\ncommit-date: 2024-11-29\n").unwrap();
+        assert_eq!(nightly.std_version(), "nightly-2024-11-30");
+
+        let unknown_date =
+            RustToolchain::parse("release: 1.85.0-nightly\ncommit-date: unknown\n").unwrap();
+        assert_eq!(unknown_date.std_version(), "1.85.0");
+
+        assert_eq!(RustToolchain::parse("rustc 1.82.0"), None);
+    }
+
+    /// A `.tar.gz` of `(path, contents)` files.
+    fn tarball(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut tar = tar::Builder::new(Vec::new());
+        for (path, body) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(body.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, path, body.as_bytes()).unwrap();
+        }
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        std::io::Write::write_all(&mut gz, &tar.into_inner().unwrap()).unwrap();
+        gz.finish().unwrap()
+    }
+
+    #[test]
+    fn extracts_std_json_from_archive() {
+        let archive = tarball(&[
+            ("rust-docs-json/share/doc/rust/json/std.json", "{}"),
+            ("rust-docs-json/share/doc/rust/json/core.json", "{}"),
+            ("rust-docs-json/share/doc/rust/json/other.json", "{}"),
+            (
+                "rust-docs-json/manifest.in",
+                "file:share/doc/rust/json/std.json",
+            ),
+        ]);
+
+        let docs = extract_std_docs(&archive).unwrap();
+        let names: Vec<&str> = docs.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["std", "core"]);
+        assert_eq!(
+            zstd::stream::decode_all(docs[0].1.as_slice()).unwrap(),
+            b"{}"
+        );
+    }
+
+    // ========== keyword tests ==========
+
+    #[test]
+    fn keyword_query_accepts_bare_and_prefixed_keywords() {
+        assert_eq!(keyword_query("match"), Some(("match", false)));
+        assert_eq!(keyword_query("std::dyn"), Some(("dyn", false)));
+        assert_eq!(keyword_query("keyword::fn"), Some(("fn", true)));
+        assert_eq!(keyword_query("core::keyword.Self"), Some(("Self", true)));
+        assert_eq!(keyword_query("Match"), None);
+        assert_eq!(keyword_query("keyword::vec"), None);
+    }
+
+    #[test]
+    fn parses_keyword_docs_in_both_layouts() {
+        let source = r#"#[doc(keyword = "as")]
+//
+/// Cast between types.
+///
+/// ```rust
+/// let x = 1.0 as u8;
+/// ```
+const _: () = ();
+
+// FIXME: a comment mentioning `#[doc(keyword = "Self")]`
+#[doc(alias = "Self")]
+#[doc(keyword = "SelfTy")]
+//
+/// The implementing type.
+#[cfg_attr(target_os = "wasi", doc = "```rust,ignore")]
+#[cfg_attr(not(target_os = "wasi"), doc = "```rust")]
+/// struct S;
+/// ```
+mod self_upper_keyword {}
+"#;
+        let docs = parse_keyword_docs(source);
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[0].keyword, "as");
+        assert_eq!(
+            docs[0].doc,
+            "Cast between types.\n\n```rust\nlet x = 1.0 as u8;\n```"
+        );
+        assert_eq!(docs[1].keyword, "Self");
+        assert_eq!(
+            docs[1].doc,
+            "The implementing type.\n```rust\nstruct S;\n```"
+        );
+    }
+
+    #[test]
+    fn extracts_keyword_source_from_archive() {
+        let archive = tarball(&[
+            ("rust-src-nightly/README.md", "readme"),
+            (
+                "rust-src-nightly/rust-src/lib/rustlib/src/rust/library/core/src/keyword_docs.rs",
+                "// keywords",
+            ),
+        ]);
+        assert_eq!(extract_keyword_source(&archive).unwrap(), b"// keywords");
+        assert!(extract_keyword_source(&tarball(&[("README.md", "readme")])).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use super::index::IndexedItem;
+
+/// How many names (by shared trigram count) are scored with Levenshtein per query.
+const MAX_CANDIDATES: usize = 64;
+
+/// Trigram index over item names, built at parse time so "did you mean"
+/// suggestions don't compute edit distances against every item on a miss.
+#[derive(Default)]
+pub struct NameIndex {
+    /// Distinct lowercased item names.
+    names: Vec<String>,
+    /// For each entry in `names`, the paths of the items with that name.
+    paths: Vec<Vec<String>>,
+    /// Trigram → indices into `names` containing it.
+    trigrams: HashMap<[char; 3], Vec<usize>>,
+}
+
+impl NameIndex {
+    pub fn build<'a>(items: impl IntoIterator<Item = &'a IndexedItem>) -> Self {
+        let mut index = NameIndex::default();
+        let mut ids: HashMap<String, usize> = HashMap::new();
+
+        for item in items {
+            let name = item.name.to_lowercase();
+            let id = *ids.entry(name.clone()).or_insert_with(|| {
+                let id = index.names.len();
+                for trigram in trigrams(&name) {
+                    let postings = index.trigrams.entry(trigram).or_default();
+                    if postings.last() != Some(&id) {
+                        postings.push(id);
+                    }
+                }
+                index.names.push(name);
+                index.paths.push(Vec::new());
+                id
+            });
+            index.paths[id].push(item.path.clone());
+        }
+        index
+    }
+
+    /// Item paths whose name shares the most trigrams with `segment`, paired with
+    /// the Levenshtein distance between `segment` and the name.
+    pub fn candidates(&self, segment: &str) -> Vec<(&str, usize)> {
+        let segment = segment.to_lowercase();
+        let mut shared: HashMap<usize, usize> = HashMap::new();
+        for trigram in trigrams(&segment) {
+            for &id in self.trigrams.get(&trigram).into_iter().flatten() {
+                *shared.entry(id).or_default() += 1;
+            }
+        }
+
+        let mut ranked: Vec<(usize, usize)> = shared.into_iter().collect();
+        // Ids follow the order items were indexed in, so ties go by name instead
+        ranked.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| self.names[a.0].cmp(&self.names[b.0]))
+        });
+        ranked.truncate(MAX_CANDIDATES);
+
+        ranked
+            .into_iter()
+            .flat_map(|(id, _)| {
+                let distance = levenshtein(&segment, &self.names[id]);
+                self.paths[id].iter().map(move |p| (p.as_str(), distance))
+            })
+            .collect()
+    }
+}
+
+/// Trigrams of a name padded with `^` and `$`, so short names still produce some.
+fn trigrams(name: &str) -> Vec<[char; 3]> {
+    let chars: Vec<char> = std::iter::once('^')
+        .chain(name.chars())
+        .chain(std::iter::once('$'))
+        .collect();
+    chars.windows(3).map(|w| [w[0], w[1], w[2]]).collect()
+}
+
+/// Edit distance between two paths compared segment by segment, aligned from the end.
+///
+/// `name_distance` is the (already computed) distance between the last segments;
+/// each earlier query segment is compared with the path segment at the same depth,
+/// and counts in full when the path is too short to have one.
+pub fn path_distance(query_segments: &[&str], path: &str, name_distance: usize) -> usize {
+    let mut path_segments = path.rsplit("::").skip(1);
+    let parents = query_segments.iter().rev().skip(1);
+    name_distance
+        + parents
+            .map(|q| match path_segments.next() {
+                Some(p) => levenshtein(&q.to_lowercase(), &p.to_lowercase()),
+                None => q.len(),
+            })
+            .sum::<usize>()
+}
+
+/// Simple Levenshtein distance implementation.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a_len = a.len();
+    let b_len = b.len();
+
+    if a_len == 0 {
+        return b_len;
+    }
+    if b_len == 0 {
+        return a_len;
+    }
+
+    let mut prev: Vec<usize> = (0..=b_len).collect();
+    let mut curr = vec![0; b_len + 1];
+
+    for (i, a_ch) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, b_ch) in b.chars().enumerate() {
+            let cost = if a_ch == b_ch { 0 } else { 1 };
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b_len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== NameIndex tests ==========
+
+    #[test]
+    fn trigrams_pad_short_names() {
+        assert_eq!(trigrams("a"), vec![['^', 'a', '$']]);
+        assert_eq!(trigrams("ab"), vec![['^', 'a', 'b'], ['a', 'b', '$']]);
+    }
+
+    #[test]
+    fn path_distance_compares_aligned_segments() {
+        assert_eq!(path_distance(&["sync", "Mutx"], "tokio::sync::Mutex", 1), 1);
+        assert_eq!(path_distance(&["snc", "Mutx"], "tokio::sync::Mutex", 1), 2);
+        assert_eq!(path_distance(&["a", "b", "Mutx"], "Mutex", 1), 3);
+    }
+
+    // ========== levenshtein tests ==========
+
+    #[test]
+    fn levenshtein_counts_edits() {
+        assert_eq!(levenshtein("mutex", "mutex"), 0);
+        assert_eq!(levenshtein("mutx", "mutex"), 1);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+}
@@ -0,0 +1,471 @@
+use std::path::PathBuf;
+
+use minijinja::Environment;
+use serde::Serialize;
+
+use super::index::{CrateIndex, ImplBlock, IndexedItem, ItemKind, MethodInfo, SearchResult};
+use super::render::{RenderOptions, doc_body};
+use crate::error::Error;
+
+/// An output that a template file can replace (`--template NAME=PATH`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateKind {
+    /// An item page (`lookup_item`).
+    Item,
+    /// Search results (`search_crate`).
+    Search,
+    /// An impl listing (`lookup_impl_block`).
+    Impls,
+}
+
+impl TemplateKind {
+    pub const ALL: [TemplateKind; 3] = [
+        TemplateKind::Item,
+        TemplateKind::Search,
+        TemplateKind::Impls,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TemplateKind::Item => "item",
+            TemplateKind::Search => "search",
+            TemplateKind::Impls => "impls",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// User-supplied minijinja templates replacing some of the markdown outputs. Each
+/// template gets the output's data plus the built-in rendering as `default`, so it can
+/// restructure the page or just wrap it. Outputs without a template, and templates that
+/// fail to render, use the built-in rendering.
+#[derive(Default)]
+pub struct Templates {
+    env: Environment<'static>,
+}
+
+impl Templates {
+    /// Read and compile the template files. Fails on the first unreadable or
+    /// malformed one, naming it.
+    pub fn load(files: &[(TemplateKind, PathBuf)]) -> Result<Self, Error> {
+        let mut env = Environment::new();
+        env.set_keep_trailing_newline(true);
+        for (kind, path) in files {
+            let invalid = |message: String| Error::Template {
+                path: path.display().to_string(),
+                message,
+            };
+            let source = std::fs::read_to_string(path).map_err(|e| invalid(e.to_string()))?;
+            env.add_template_owned(kind.name(), source)
+                .map_err(|e| invalid(e.to_string()))?;
+        }
+        Ok(Self { env })
+    }
+
+    /// Render `item`'s page with the `item` template, if there is one.
+    pub fn render_item(
+        &self,
+        index: &CrateIndex,
+        item: &IndexedItem,
+        options: &RenderOptions,
+        default: String,
+    ) -> String {
+        let Some(template) = self.template(TemplateKind::Item) else {
+            return default;
+        };
+        let rendered = template.render(ItemContext {
+            crate_name: &index.crate_name,
+            version: &index.version,
+            item: ItemData::new(index, item, options),
+            default: &default,
+        });
+        or_default(TemplateKind::Item, rendered, default)
+    }
+
+    /// Render search results with the `search` template, if there is one.
+    pub fn render_search(
+        &self,
+        index: &CrateIndex,
+        query: &str,
+        results: &[SearchResult],
+        default: String,
+    ) -> String {
+        let Some(template) = self.template(TemplateKind::Search) else {
+            return default;
+        };
+        let rendered = template.render(SearchContext {
+            crate_name: &index.crate_name,
+            version: &index.version,
+            query,
+            results: results.iter().map(SearchResultData::new).collect(),
+            default: &default,
+        });
+        or_default(TemplateKind::Search, rendered, default)
+    }
+
+    /// Render the impl listing of `item_path` with the `impls` template, if there is one.
+    pub fn render_impls(
+        &self,
+        index: &CrateIndex,
+        item_path: &str,
+        impls: &[&ImplBlock],
+        options: &RenderOptions,
+        default: String,
+    ) -> String {
+        let Some(template) = self.template(TemplateKind::Impls) else {
+            return default;
+        };
+        let (inherent, trait_impls): (Vec<&ImplBlock>, Vec<&ImplBlock>) =
+            impls.iter().partition(|block| block.trait_name.is_none());
+        let rendered = template.render(ImplsContext {
+            crate_name: &index.crate_name,
+            version: &index.version,
+            item_path,
+            inherent_methods: inherent
+                .iter()
+                .flat_map(|block| &block.methods)
+                .map(|m| MethodData::new(m, options))
+                .collect(),
+            trait_impls: trait_impls
+                .iter()
+                .map(|block| TraitImplData::new(block, options))
+                .collect(),
+            default: &default,
+        });
+        or_default(TemplateKind::Impls, rendered, default)
+    }
+
+    fn template(&self, kind: TemplateKind) -> Option<minijinja::Template<'_, '_>> {
+        self.env.get_template(kind.name()).ok()
+    }
+}
+
+/// The template's output, or `default` (with a warning) if it failed to render.
+fn or_default(
+    kind: TemplateKind,
+    rendered: Result<String, minijinja::Error>,
+    default: String,
+) -> String {
+    rendered.unwrap_or_else(|e| {
+        tracing::warn!(
+            "The {} template failed, using the built-in output: {e:#}",
+            kind.name()
+        );
+        default
+    })
+}
+
+// ========== Template contexts ==========
+
+#[derive(Serialize)]
+struct ItemContext<'a> {
+    crate_name: &'a str,
+    version: &'a str,
+    item: ItemData<'a>,
+    default: &'a str,
+}
+
+#[derive(Serialize)]
+struct ItemData<'a> {
+    path: &'a str,
+    name: &'a str,
+    kind: String,
+    /// Wrapped per the render options, without attributes.
+    signature: String,
+    attrs: &'a [String],
+    summary: &'a str,
+    /// The whole doc comment as markdown, headings starting at level 3.
+    docs: String,
+    deprecated: bool,
+    deprecation_note: Option<&'a str>,
+    /// `#[doc(cfg(...))]` predicate, e.g. `feature = "full"`.
+    cfg: Option<&'a str>,
+    fields: Vec<FieldData<'a>>,
+    variants: Vec<VariantData<'a>>,
+    /// Trait methods, required ones first.
+    methods: Vec<MethodData<'a>>,
+}
+
+impl<'a> ItemData<'a> {
+    fn new(index: &CrateIndex, item: &'a IndexedItem, options: &RenderOptions) -> Self {
+        let mut methods: Vec<&MethodInfo> = item.detail.methods.iter().collect();
+        methods.sort_by_key(|m| !m.is_required);
+        Self {
+            path: &item.path,
+            name: &item.name,
+            kind: item.kind.to_string(),
+            signature: options.signature(&item.signature),
+            attrs: &item.attrs,
+            summary: &item.short_doc,
+            docs: doc_body(index, item, 3),
+            deprecated: item.deprecation.is_some(),
+            deprecation_note: item.deprecation.as_ref().and_then(|d| d.note.as_deref()),
+            cfg: item.cfg.as_deref(),
+            fields: item
+                .detail
+                .fields
+                .iter()
+                .map(|f| FieldData {
+                    name: &f.name,
+                    type_: &f.type_str,
+                    doc: &f.doc,
+                })
+                .collect(),
+            variants: item
+                .detail
+                .variants
+                .iter()
+                .map(|v| VariantData {
+                    name: &v.name,
+                    signature: &v.signature,
+                    doc: &v.doc,
+                })
+                .collect(),
+            methods: match item.kind {
+                ItemKind::Trait => methods
+                    .into_iter()
+                    .map(|m| MethodData::new(m, options))
+                    .collect(),
+                _ => Vec::new(),
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct FieldData<'a> {
+    name: &'a str,
+    #[serde(rename = "type")]
+    type_: &'a str,
+    doc: &'a str,
+}
+
+#[derive(Serialize)]
+struct VariantData<'a> {
+    name: &'a str,
+    signature: &'a str,
+    doc: &'a str,
+}
+
+#[derive(Serialize)]
+struct MethodData<'a> {
+    name: &'a str,
+    signature: String,
+    doc: &'a str,
+    required: bool,
+}
+
+impl<'a> MethodData<'a> {
+    fn new(method: &'a MethodInfo, options: &RenderOptions) -> Self {
+        Self {
+            name: &method.name,
+            signature: options.signature(&method.signature),
+            doc: &method.doc,
+            required: method.is_required,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SearchContext<'a> {
+    crate_name: &'a str,
+    version: &'a str,
+    query: &'a str,
+    results: Vec<SearchResultData<'a>>,
+    default: &'a str,
+}
+
+#[derive(Serialize)]
+struct SearchResultData<'a> {
+    path: &'a str,
+    /// The item kind, or "method" for methods.
+    kind: String,
+    summary: &'a str,
+    /// For methods: the type whose impl block defines it.
+    owner: Option<&'a str>,
+    /// The doc heading the query matched, if that's how the item matched.
+    heading: Option<&'a str>,
+}
+
+impl<'a> SearchResultData<'a> {
+    fn new(result: &'a SearchResult) -> Self {
+        Self {
+            path: &result.item.path,
+            kind: match result.owner {
+                Some(_) => "method".to_string(),
+                None => result.item.kind.to_string(),
+            },
+            summary: &result.item.short_doc,
+            owner: result.owner.as_deref(),
+            heading: result.heading.as_deref(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ImplsContext<'a> {
+    crate_name: &'a str,
+    version: &'a str,
+    item_path: &'a str,
+    inherent_methods: Vec<MethodData<'a>>,
+    trait_impls: Vec<TraitImplData<'a>>,
+    default: &'a str,
+}
+
+#[derive(Serialize)]
+struct TraitImplData<'a> {
+    trait_name: &'a str,
+    /// e.g. "impl Serialize for MyStruct"
+    header: &'a str,
+    methods: Vec<MethodData<'a>>,
+    /// Names of the trait's provided methods the impl doesn't override.
+    provided_methods: &'a [String],
+}
+
+impl<'a> TraitImplData<'a> {
+    fn new(block: &'a ImplBlock, options: &RenderOptions) -> Self {
+        Self {
+            trait_name: block.trait_name.as_deref().unwrap_or_default(),
+            header: &block.header,
+            methods: block
+                .methods
+                .iter()
+                .map(|m| MethodData::new(m, options))
+                .collect(),
+            provided_methods: &block.provided_methods,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Write;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::docs::index::{FieldInfo, ItemDetail, Visibility};
+    use crate::docs::suggest::NameIndex;
+
+    fn templates(kind: TemplateKind, source: &str) -> Templates {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(source.as_bytes()).unwrap();
+        Templates::load(&[(kind, file.path().to_path_buf())]).unwrap()
+    }
+
+    fn index() -> CrateIndex {
+        CrateIndex {
+            crate_name: "my_crate".to_string(),
+            package_name: "my_crate".to_string(),
+            version: "1.0.0".to_string(),
+            items: HashMap::new(),
+            modules: HashMap::new(),
+            impl_blocks: HashMap::new(),
+            auto_traits: HashMap::new(),
+            root_items: Vec::new(),
+            aliases: HashMap::new(),
+            foreign_reexports: HashMap::new(),
+            name_index: NameIndex::default(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            format_version: 56,
+            parse_time: Duration::ZERO,
+            partial: false,
+            stale: None,
+            fetched_at: None,
+        }
+    }
+
+    fn item(path: &str, kind: ItemKind, short_doc: &str) -> IndexedItem {
+        let (parent, name) = path.rsplit_once("::").unwrap();
+        IndexedItem {
+            path: path.to_string(),
+            name: name.to_string(),
+            kind,
+            signature: format!("pub struct {name}"),
+            attrs: Vec::new(),
+            visibility: Visibility::Public,
+            aliases: Vec::new(),
+            reexports: Vec::new(),
+            short_doc: short_doc.to_string(),
+            doc: short_doc.to_string(),
+            deprecation: None,
+            stability: None,
+            cfg: None,
+            is_unsafe: false,
+            async_kind: None,
+            is_hidden: false,
+            span: None,
+            headings: Vec::new(),
+            external_refs: Vec::new(),
+            detail: ItemDetail::default(),
+            parent_module: parent.to_string(),
+        }
+    }
+
+    // ========== Templates tests ==========
+
+    #[test]
+    fn render_item_exposes_the_item_data() {
+        let templates = templates(
+            TemplateKind::Item,
+            "# {{ item.name }} ({{ item.kind }}, {{ crate_name }} {{ version }})\n\
+             {% for f in item.fields %}- {{ f.name }}: {{ f.type }}\n{% endfor %}",
+        );
+        let mut config = item("my_crate::Config", ItemKind::Struct, "Settings.");
+        config.detail.fields = vec![FieldInfo {
+            name: "port".to_string(),
+            type_str: "u16".to_string(),
+            doc: String::new(),
+        }];
+        let text =
+            templates.render_item(&index(), &config, &RenderOptions::default(), String::new());
+        assert_eq!(text, "# Config (struct, my_crate 1.0.0)\n- port: u16\n");
+    }
+
+    #[test]
+    fn render_search_can_wrap_the_default_output() {
+        let templates = templates(
+            TemplateKind::Search,
+            "{{ results | length }} hits for {{ query }}\n{{ default }}",
+        );
+        let results = vec![SearchResult {
+            item: item("my_crate::Config", ItemKind::Struct, "Settings."),
+            owner: None,
+            heading: None,
+            rank: 0,
+        }];
+        let text = templates.render_search(&index(), "config", &results, "built-in".to_string());
+        assert_eq!(text, "1 hits for config\nbuilt-in");
+    }
+
+    #[test]
+    fn outputs_without_a_template_or_with_a_failing_one_use_the_default() {
+        let templates = templates(TemplateKind::Search, "{{ no_such_function() }}");
+        let text = templates.render_search(&index(), "config", &[], "built-in".to_string());
+        assert_eq!(text, "built-in");
+
+        let text = templates.render_impls(
+            &index(),
+            "my_crate::Config",
+            &[],
+            &RenderOptions::default(),
+            "built-in".to_string(),
+        );
+        assert_eq!(text, "built-in");
+    }
+
+    #[test]
+    fn load_names_the_malformed_template() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"{% if %}").unwrap();
+        let Err(Error::Template { path, .. }) =
+            Templates::load(&[(TemplateKind::Item, file.path().to_path_buf())])
+        else {
+            panic!("expected a template error");
+        };
+        assert_eq!(path, file.path().display().to_string());
+    }
+}
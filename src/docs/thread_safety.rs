@@ -0,0 +1,192 @@
+use super::index::{AutoTraitImpl, CrateIndex};
+
+/// What a type's impls say about one auto trait.
+#[derive(Debug, Clone, Copy)]
+pub enum AutoTraitStatus<'a> {
+    /// Implemented for every instantiation of the type.
+    Yes(&'a AutoTraitImpl),
+    /// Implemented when the impl's bounds hold (e.g. `T: Send`).
+    Conditional(&'a AutoTraitImpl),
+    /// Negative impl (`impl !Send for ...`).
+    No(&'a AutoTraitImpl),
+    /// No impl recorded, e.g. for type aliases or docs built without auto-trait impls.
+    Unknown,
+}
+
+impl<'a> AutoTraitStatus<'a> {
+    /// The impl the status is based on, if any.
+    pub fn cited(&self) -> Option<&'a AutoTraitImpl> {
+        match self {
+            AutoTraitStatus::Yes(i) | AutoTraitStatus::Conditional(i) | AutoTraitStatus::No(i) => {
+                Some(i)
+            }
+            AutoTraitStatus::Unknown => None,
+        }
+    }
+}
+
+/// `Send`, `Sync`, and `Unpin` status of a type (for `is_thread_safe`).
+pub struct ThreadSafety<'a> {
+    pub send: AutoTraitStatus<'a>,
+    pub sync: AutoTraitStatus<'a>,
+    pub unpin: AutoTraitStatus<'a>,
+}
+
+/// Whether a value can be moved to, or shared with, other threads.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Yes,
+    /// Yes, if these bounds hold.
+    If(Vec<String>),
+    No,
+    Unknown,
+}
+
+impl ThreadSafety<'_> {
+    /// Can a value be moved to another thread (`thread::spawn`, channels)? Needs `Send`.
+    pub fn can_move(&self) -> Verdict {
+        combine(&[self.send])
+    }
+
+    /// Can a value be shared across threads behind an `Arc`? `Arc<T>` is only `Send`
+    /// when `T: Send + Sync`.
+    pub fn can_share(&self) -> Verdict {
+        combine(&[self.send, self.sync])
+    }
+}
+
+/// All of `statuses` must hold: any `No` wins, then any `Unknown`, and the bounds
+/// of conditional impls are collected.
+fn combine(statuses: &[AutoTraitStatus<'_>]) -> Verdict {
+    if statuses.iter().any(|s| matches!(s, AutoTraitStatus::No(_))) {
+        return Verdict::No;
+    }
+    if statuses
+        .iter()
+        .any(|s| matches!(s, AutoTraitStatus::Unknown))
+    {
+        return Verdict::Unknown;
+    }
+    let mut bounds: Vec<String> = statuses
+        .iter()
+        .filter_map(|s| match s {
+            AutoTraitStatus::Conditional(i) => Some(i.bounds.iter().cloned()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    bounds.sort();
+    bounds.dedup();
+    if bounds.is_empty() {
+        Verdict::Yes
+    } else {
+        Verdict::If(bounds)
+    }
+}
+
+impl CrateIndex {
+    /// The `Send`/`Sync`/`Unpin` status of the type at `type_path` (a resolved path).
+    pub fn thread_safety(&self, type_path: &str) -> ThreadSafety<'_> {
+        let impls = self
+            .auto_traits
+            .get(type_path)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let status = |trait_name: &str| {
+            // An explicit impl overrides the one rustdoc derives
+            let mut matching: Vec<&AutoTraitImpl> = impls
+                .iter()
+                .filter(|i| i.trait_name == trait_name)
+                .collect();
+            matching.sort_by_key(|i| i.is_synthetic);
+            match matching.first() {
+                Some(i) if i.is_negative => AutoTraitStatus::No(i),
+                Some(i) if i.bounds.is_empty() => AutoTraitStatus::Yes(i),
+                Some(i) => AutoTraitStatus::Conditional(i),
+                None => AutoTraitStatus::Unknown,
+            }
+        };
+        ThreadSafety {
+            send: status("Send"),
+            sync: status("Sync"),
+            unpin: status("Unpin"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::docs::suggest::NameIndex;
+
+    fn auto_impl(trait_name: &str, is_negative: bool, bounds: &[&str]) -> AutoTraitImpl {
+        AutoTraitImpl {
+            trait_name: trait_name.to_string(),
+            is_negative,
+            bounds: bounds.iter().map(|b| b.to_string()).collect(),
+            header: String::new(),
+            is_synthetic: true,
+        }
+    }
+
+    fn index_with(type_path: &str, impls: Vec<AutoTraitImpl>) -> CrateIndex {
+        CrateIndex {
+            crate_name: "my_crate".to_string(),
+            package_name: "my_crate".to_string(),
+            version: "1.0.0".to_string(),
+            items: HashMap::new(),
+            modules: HashMap::new(),
+            impl_blocks: HashMap::new(),
+            auto_traits: HashMap::from([(type_path.to_string(), impls)]),
+            root_items: Vec::new(),
+            aliases: HashMap::new(),
+            foreign_reexports: HashMap::new(),
+            name_index: NameIndex::default(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            format_version: 56,
+            parse_time: Duration::ZERO,
+            partial: false,
+            stale: None,
+            fetched_at: None,
+        }
+    }
+
+    // ========== thread_safety tests ==========
+
+    #[test]
+    fn thread_safety_combines_send_and_sync() {
+        let index = index_with(
+            "my_crate::Shared",
+            vec![
+                auto_impl("Send", false, &["T: Send"]),
+                auto_impl("Sync", false, &["T: Sync"]),
+                auto_impl("Unpin", false, &[]),
+            ],
+        );
+        let safety = index.thread_safety("my_crate::Shared");
+        assert_eq!(safety.can_move(), Verdict::If(vec!["T: Send".to_string()]));
+        assert_eq!(
+            safety.can_share(),
+            Verdict::If(vec!["T: Send".to_string(), "T: Sync".to_string()])
+        );
+        assert!(matches!(safety.unpin, AutoTraitStatus::Yes(_)));
+    }
+
+    #[test]
+    fn thread_safety_negative_impl_wins_and_missing_is_unknown() {
+        let index = index_with(
+            "my_crate::Local",
+            vec![auto_impl("Send", true, &[]), auto_impl("Sync", false, &[])],
+        );
+        let safety = index.thread_safety("my_crate::Local");
+        assert_eq!(safety.can_move(), Verdict::No);
+        assert_eq!(safety.can_share(), Verdict::No);
+        assert!(matches!(safety.unpin, AutoTraitStatus::Unknown));
+
+        let missing = index.thread_safety("my_crate::Other");
+        assert_eq!(missing.can_share(), Verdict::Unknown);
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use super::index::{CrateIndex, ItemKind};
+
+/// A module and its submodules, for `crate_tree`.
+pub struct ModuleNode<'a> {
+    pub path: &'a str,
+    pub name: &'a str,
+    /// Direct children other than modules, per kind, most common first.
+    pub item_counts: Vec<(ItemKind, usize)>,
+    /// Submodules, by name.
+    pub children: Vec<ModuleNode<'a>>,
+}
+
+impl ModuleNode<'_> {
+    /// This module and all its descendants.
+    pub fn module_count(&self) -> usize {
+        1 + self
+            .children
+            .iter()
+            .map(ModuleNode::module_count)
+            .sum::<usize>()
+    }
+}
+
+impl CrateIndex {
+    /// The module hierarchy under `module_path` (the crate root if `None`), or `None`
+    /// if no such module exists. `#[doc(hidden)]` modules and items are left out
+    /// unless `include_hidden` is set.
+    pub fn module_tree(
+        &self,
+        module_path: Option<&str>,
+        include_hidden: bool,
+    ) -> Option<ModuleNode<'_>> {
+        let root = match module_path {
+            None => self.crate_name.as_str(),
+            Some(path) => {
+                let item = self.get_item_by_path(path)?;
+                if item.kind != ItemKind::Module {
+                    return None;
+                }
+                item.path.as_str()
+            }
+        };
+        Some(self.module_node(root, include_hidden, &mut Vec::new()))
+    }
+
+    /// `ancestors` guards against a module listed inside itself.
+    fn module_node<'a>(
+        &'a self,
+        path: &'a str,
+        include_hidden: bool,
+        ancestors: &mut Vec<&'a str>,
+    ) -> ModuleNode<'a> {
+        let children = if path == self.crate_name {
+            self.root_items.as_slice()
+        } else {
+            self.modules.get(path).map_or(&[][..], Vec::as_slice)
+        };
+        let mut counts: HashMap<ItemKind, usize> = HashMap::new();
+        let mut submodules = Vec::new();
+        ancestors.push(path);
+        for child in children
+            .iter()
+            .filter_map(|child| self.items.get(child))
+            .filter(|child| include_hidden || !child.is_hidden)
+        {
+            if child.kind != ItemKind::Module {
+                *counts.entry(child.kind.clone()).or_default() += 1;
+            } else if !ancestors.contains(&child.path.as_str()) {
+                submodules.push(self.module_node(&child.path, include_hidden, ancestors));
+            }
+        }
+        ancestors.pop();
+
+        let mut item_counts: Vec<(ItemKind, usize)> = counts.into_iter().collect();
+        item_counts.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| a.0.to_string().cmp(&b.0.to_string()))
+        });
+        submodules.sort_by(|a, b| a.name.cmp(b.name));
+        ModuleNode {
+            path,
+            name: path.rsplit("::").next().unwrap_or(path),
+            item_counts,
+            children: submodules,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::docs::index::{IndexedItem, ItemDetail, Visibility};
+    use crate::docs::suggest::NameIndex;
+
+    fn item(path: &str, kind: ItemKind) -> (String, IndexedItem) {
+        let (parent, name) = path.rsplit_once("::").unwrap();
+        let item = IndexedItem {
+            path: path.to_string(),
+            name: name.to_string(),
+            kind,
+            signature: String::new(),
+            attrs: Vec::new(),
+            visibility: Visibility::Public,
+            aliases: Vec::new(),
+            reexports: Vec::new(),
+            short_doc: String::new(),
+            doc: String::new(),
+            deprecation: None,
+            stability: None,
+            cfg: None,
+            is_unsafe: false,
+            async_kind: None,
+            is_hidden: false,
+            span: None,
+            headings: Vec::new(),
+            external_refs: Vec::new(),
+            detail: ItemDetail::default(),
+            parent_module: parent.to_string(),
+        };
+        (path.to_string(), item)
+    }
+
+    fn test_index() -> CrateIndex {
+        let mut items = HashMap::from([
+            item("my_crate::net", ItemKind::Module),
+            item("my_crate::net::tcp", ItemKind::Module),
+            item("my_crate::net::tcp::Stream", ItemKind::Struct),
+            item("my_crate::net::Addr", ItemKind::Struct),
+            item("my_crate::net::Socket", ItemKind::Struct),
+            item("my_crate::net::connect", ItemKind::Function),
+            item("my_crate::fs", ItemKind::Module),
+            item("my_crate::internal", ItemKind::Module),
+            item("my_crate::run", ItemKind::Function),
+        ]);
+        items.get_mut("my_crate::internal").unwrap().is_hidden = true;
+        let paths = |paths: &[&str]| paths.iter().map(|p| p.to_string()).collect();
+        CrateIndex {
+            crate_name: "my_crate".to_string(),
+            package_name: "my_crate".to_string(),
+            version: "1.0.0".to_string(),
+            items,
+            modules: HashMap::from([
+                (
+                    "my_crate::net".to_string(),
+                    paths(&[
+                        "my_crate::net::tcp",
+                        "my_crate::net::Addr",
+                        "my_crate::net::Socket",
+                        "my_crate::net::connect",
+                    ]),
+                ),
+                (
+                    "my_crate::net::tcp".to_string(),
+                    paths(&["my_crate::net::tcp::Stream", "my_crate::net"]),
+                ),
+                ("my_crate::fs".to_string(), Vec::new()),
+                ("my_crate::internal".to_string(), Vec::new()),
+            ]),
+            impl_blocks: HashMap::new(),
+            auto_traits: HashMap::new(),
+            root_items: paths(&[
+                "my_crate::net",
+                "my_crate::fs",
+                "my_crate::internal",
+                "my_crate::run",
+            ]),
+            aliases: HashMap::new(),
+            foreign_reexports: HashMap::new(),
+            name_index: NameIndex::default(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            format_version: 56,
+            parse_time: Duration::ZERO,
+            partial: false,
+            stale: None,
+            fetched_at: None,
+        }
+    }
+
+    // ========== module_tree tests ==========
+
+    #[test]
+    fn module_tree_nests_modules_with_item_counts() {
+        let index = test_index();
+        let tree = index.module_tree(None, false).unwrap();
+        assert_eq!(tree.name, "my_crate");
+        assert_eq!(tree.item_counts, [(ItemKind::Function, 1)]);
+        let names: Vec<&str> = tree.children.iter().map(|c| c.name).collect();
+        assert_eq!(names, ["fs", "net"]);
+
+        let net = &tree.children[1];
+        assert_eq!(
+            net.item_counts,
+            [(ItemKind::Struct, 2), (ItemKind::Function, 1)]
+        );
+        // A module listed inside its own submodule isn't followed again
+        assert_eq!(net.children[0].path, "my_crate::net::tcp");
+        assert!(net.children[0].children.is_empty());
+        assert_eq!(tree.module_count(), 4);
+
+        assert_eq!(index.module_tree(None, true).unwrap().module_count(), 5);
+    }
+
+    #[test]
+    fn module_tree_starts_at_a_module_path() {
+        let index = test_index();
+        assert_eq!(
+            index
+                .module_tree(Some("net"), false)
+                .unwrap()
+                .module_count(),
+            2
+        );
+        assert!(index.module_tree(Some("run"), false).is_none());
+        assert!(index.module_tree(Some("missing"), false).is_none());
+    }
+}
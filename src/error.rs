@@ -12,6 +12,12 @@ pub enum Error {
     #[error("Zstd decompression failed: {0}")]
     Zstd(#[from] std::io::Error),
 
+    #[error("Gzip decompression failed: {0}")]
+    Gzip(std::io::Error),
+
+    #[error("Unrecognized rustdoc JSON response from {url}: {description}")]
+    UnknownEncoding { url: String, description: String },
+
     #[error("Cargo.lock parsing failed: {0}")]
     CargoLock(#[from] cargo_lock::Error),
 
@@ -20,6 +26,18 @@ pub enum Error {
     )]
     JsonNotAvailable { crate_name: String, version: String },
 
+    #[error(
+        "{crate_name} v{version} is a binary-only crate (no library target), so it has no API documentation. Binaries: {binaries}. See its README: https://crates.io/crates/{crate_name}/{version}"
+    )]
+    BinaryOnly {
+        crate_name: String,
+        version: String,
+        binaries: String,
+    },
+
+    #[error("Local documentation build failed for {crate_name}: {message}")]
+    LocalBuild { crate_name: String, message: String },
+
     #[error("Crate not found: {0}")]
     CrateNotFound(String),
 
@@ -29,6 +47,20 @@ pub enum Error {
         item_path: String,
     },
 
+    #[error("Invalid template {path}: {message}")]
+    Template { path: String, message: String },
+
+    #[error("Disabled by policy (--sandbox): {0}")]
+    DisabledByPolicy(&'static str),
+
     #[error("{0}")]
     Other(String),
 }
+
+impl Error {
+    /// Whether docs.rs couldn't be reached or answered with an error, as opposed to
+    /// the docs not existing.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Error::Http(_) | Error::UnknownEncoding { .. })
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::error::Error;
+
+/// Requests allowed in flight to one host when `--max-connections-per-host` isn't given.
+pub const DEFAULT_MAX_PER_HOST: usize = 6;
+
+/// Connection settings for the shared HTTP client, from the `--no-http2`,
+/// `--max-idle-connections`, `--tcp-keepalive-secs`, and `--max-connections-per-host` flags.
+#[derive(Debug, Clone)]
+pub struct HttpOptions {
+    /// Negotiate HTTP/2 with servers that offer it; HTTP/1.1 only when off.
+    pub http2: bool,
+    /// Idle connections kept open per host. Unlimited if `None`.
+    pub max_idle_per_host: Option<usize>,
+    /// Interval of TCP keepalive probes. reqwest's default if `None`.
+    pub tcp_keepalive: Option<Duration>,
+    /// Requests allowed in flight to one host; more wait for a slot.
+    pub max_per_host: usize,
+    /// Refuse every request (`--sandbox`).
+    pub offline: bool,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            http2: true,
+            max_idle_per_host: None,
+            tcp_keepalive: None,
+            max_per_host: DEFAULT_MAX_PER_HOST,
+            offline: false,
+        }
+    }
+}
+
+/// The server's HTTP client: a `reqwest::Client` (reachable through `Deref`) plus a cap on
+/// requests in flight per host, so prefetching many crates doesn't open a connection
+/// to docs.rs for each.
+#[derive(Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    max_per_host: usize,
+    offline: bool,
+    hosts: Arc<Mutex<HashMap<String, Arc<Semaphore>>>>,
+}
+
+impl HttpClient {
+    pub fn new(options: &HttpOptions) -> Self {
+        let mut builder = reqwest::Client::builder().user_agent("docsrs-mcp/0.1.0");
+        if !options.http2 {
+            builder = builder.http1_only();
+        }
+        if let Some(max_idle) = options.max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+        if let Some(interval) = options.tcp_keepalive {
+            builder = builder.tcp_keepalive(interval);
+        }
+        Self {
+            client: builder.build().expect("failed to build HTTP client"),
+            max_per_host: options.max_per_host.max(1),
+            offline: options.offline,
+            hosts: Arc::default(),
+        }
+    }
+
+    /// Wait for a free request slot for `url`'s host. Hold the permit until the
+    /// response body has been read. Fails if the network is off (`--sandbox`).
+    pub async fn slot(&self, url: &str) -> Result<OwnedSemaphorePermit, Error> {
+        if self.offline {
+            return Err(Error::DisabledByPolicy("network access"));
+        }
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let semaphore = {
+            let mut hosts = self.hosts.lock().unwrap_or_else(|e| e.into_inner());
+            let semaphore = hosts
+                .entry(host)
+                .or_insert_with(|| Arc::new(Semaphore::new(self.max_per_host)));
+            Arc::clone(semaphore)
+        };
+        Ok(semaphore
+            .acquire_owned()
+            .await
+            .expect("host semaphores are never closed"))
+    }
+}
+
+impl Deref for HttpClient {
+    type Target = reqwest::Client;
+
+    fn deref(&self) -> &reqwest::Client {
+        &self.client
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== slot tests ==========
+
+    fn client(max_per_host: usize) -> HttpClient {
+        HttpClient::new(&HttpOptions {
+            max_per_host,
+            ..HttpOptions::default()
+        })
+    }
+
+    async fn slot_is_free(client: &HttpClient, url: &str) -> bool {
+        tokio::time::timeout(Duration::from_millis(20), client.slot(url))
+            .await
+            .is_ok()
+    }
+
+    #[tokio::test]
+    async fn slot_waits_once_a_host_is_at_its_limit() {
+        let client = client(2);
+        let first = client.slot("https://docs.rs/crate/serde/latest/json").await;
+        let _second = client.slot("https://docs.rs/crate/tokio/1.0.0/json").await;
+        assert!(!slot_is_free(&client, "https://docs.rs/crate/rand/latest/json").await);
+
+        drop(first);
+        assert!(slot_is_free(&client, "https://docs.rs/crate/rand/latest/json").await);
+    }
+
+    #[tokio::test]
+    async fn slot_refuses_requests_when_offline() {
+        let client = HttpClient::new(&HttpOptions {
+            offline: true,
+            ..HttpOptions::default()
+        });
+        let err = client
+            .slot("https://docs.rs/crate/serde/latest/json")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::DisabledByPolicy(_)));
+    }
+
+    #[tokio::test]
+    async fn slot_limits_each_host_separately() {
+        let client = client(1);
+        let _docs_rs = client.slot("https://docs.rs/crate/serde/latest/json").await;
+        assert!(slot_is_free(&client, "https://crates.io/api/v1/crates/serde").await);
+        assert!(!slot_is_free(&client, "https://docs.rs/crate/tokio/latest/json").await);
+    }
+}
@@ -1,45 +1,240 @@
+mod aliases;
+mod audit;
 mod cargo_lock;
 mod docs;
 mod error;
+mod http;
+mod pager;
+mod prefetch;
 mod server;
+#[cfg(feature = "otel")]
+mod telemetry;
+mod workspace;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use rmcp::ServiceExt;
 use rmcp::transport::stdio;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 
-use crate::cargo_lock::CargoLockIndex;
+use crate::aliases::CrateAliases;
 use crate::docs::cache::DiskCache;
-use crate::server::RustDocsServer;
+use crate::docs::corpus::Corpus;
+use crate::docs::local::FeatureSet;
+use crate::docs::render::RenderOptions;
+use crate::docs::templates::{TemplateKind, Templates};
+use crate::http::{DEFAULT_MAX_PER_HOST, HttpClient, HttpOptions};
+use crate::server::{OutputFormat, RustDocsServer, ServerOptions};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing to stderr (stdout is used for MCP stdio transport)
-    tracing_subscriber::fmt()
-        .with_env_filter(
+    // Initialize tracing to stderr (stdout is used for MCP stdio transport), exporting
+    // spans over OTLP too if built with `otel` and the OTEL_* env vars ask for it
+    #[cfg(feature = "otel")]
+    let (otel_layer, _telemetry) = telemetry::otlp_layer().unzip();
+    #[cfg(not(feature = "otel"))]
+    let otel_layer = None::<tracing_subscriber::layer::Identity>;
+    tracing_subscriber::registry()
+        .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
         )
-        .with_writer(std::io::stderr)
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(otel_layer)
         .init();
 
     // Parse CLI flags
     let args: Vec<String> = std::env::args().collect();
+
+    // `docsrs-mcp cache compact`: recompress the disk cache, report, and exit
+    if args.get(1..3) == Some(&["cache".to_string(), "compact".to_string()]) {
+        return compact_cache().await;
+    }
     let no_cache = args.iter().any(|a| a == "--no-cache");
     let clear_cache = args.iter().any(|a| a == "--clear-cache");
+    let sandbox = args.iter().any(|a| a == "--sandbox");
+    let document_private = args.iter().any(|a| a == "--document-private-items");
+    let mut render_options = RenderOptions {
+        wrap_signatures: !args.iter().any(|a| a == "--no-wrap-signatures"),
+        inline_docs: args.iter().any(|a| a == "--inline-docs"),
+        full_doc: args.iter().any(|a| a == "--full-docs"),
+        ..RenderOptions::default()
+    };
+    if let Some(width) = args
+        .iter()
+        .position(|a| a == "--width")
+        .map(|i| args.get(i + 1))
+    {
+        match width.and_then(|w| w.parse().ok()) {
+            Some(width) => render_options.width = width,
+            None => tracing::warn!("--width expects a number of columns, using the default"),
+        }
+    }
 
-    if clear_cache {
-        DiskCache::clear().await;
+    // `--format markdown|plain|html`: output format of tools called without `format`
+    let format = match args
+        .iter()
+        .position(|a| a == "--format")
+        .map(|i| args.get(i + 1).and_then(|name| OutputFormat::parse(name)))
+    {
+        Some(Some(format)) => format,
+        Some(None) => {
+            tracing::warn!("--format expects markdown, plain, or html, using markdown");
+            OutputFormat::Markdown
+        }
+        None => OutputFormat::Markdown,
+    };
+
+    // `--parse-budget-ms N`: index the rest of larger docs without doc bodies after N ms
+    let parse_budget = match args
+        .iter()
+        .position(|a| a == "--parse-budget-ms")
+        .map(|i| args.get(i + 1).and_then(|ms| ms.parse().ok()))
+    {
+        Some(Some(ms)) => Some(Duration::from_millis(ms)),
+        Some(None) => {
+            tracing::warn!("--parse-budget-ms expects a number of milliseconds, ignoring it");
+            None
+        }
+        None => None,
+    };
+
+    // HTTP client tuning: `--no-http2`, `--max-idle-connections N`,
+    // `--tcp-keepalive-secs N`, and `--max-connections-per-host N`
+    let number_flag = |flag: &str| {
+        let i = args.iter().position(|a| a == flag)?;
+        let value = args.get(i + 1).and_then(|n| n.parse::<u64>().ok());
+        if value.is_none() {
+            tracing::warn!("{flag} expects a number, ignoring it");
+        }
+        value
+    };
+    let http_options = HttpOptions {
+        http2: !args.iter().any(|a| a == "--no-http2"),
+        max_idle_per_host: number_flag("--max-idle-connections").map(|n| n as usize),
+        tcp_keepalive: number_flag("--tcp-keepalive-secs").map(Duration::from_secs),
+        max_per_host: number_flag("--max-connections-per-host")
+            .map_or(DEFAULT_MAX_PER_HOST, |n| n as usize),
+        offline: false,
+    };
+
+    // `docsrs-mcp prefetch --from-file FILE`: fill the disk cache from crate lists and exit
+    if args.get(1).is_some_and(|a| a == "prefetch") {
+        return prefetch_from_files(&args, &http_options).await;
     }
 
-    // Find and parse Cargo.lock from CWD
-    let cwd = std::env::current_dir()?;
-    let cargo_lock = CargoLockIndex::find_and_parse(&cwd);
-    if cargo_lock.is_some() {
-        tracing::info!("Cargo.lock loaded, will auto-resolve crate versions");
-    } else {
-        tracing::info!("No Cargo.lock found, will use explicit versions or 'latest'");
+    // `--max-concurrent-loads N`: crates fetched (or built) and parsed at the same time
+    let max_concurrent_loads = number_flag("--max-concurrent-loads")
+        .map_or(ServerOptions::default().max_concurrent_loads, |n| {
+            n as usize
+        });
+
+    // `--audit-log PATH`: append a JSONL entry per tool call
+    let audit_log = args
+        .iter()
+        .position(|a| a == "--audit-log")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+
+    // `--features CRATE=F1,F2` and `--no-default-features CRATE`, each repeatable
+    let mut features: HashMap<String, FeatureSet> = HashMap::new();
+    for (flag, value) in args.iter().zip(args.iter().skip(1)) {
+        match flag.as_str() {
+            "--features" => match value.split_once('=') {
+                Some((crate_name, list)) => {
+                    let entry = features.entry(crate_name.to_string()).or_default();
+                    *entry = FeatureSet::parse(
+                        &[entry.features.join(","), list.to_string()].join(","),
+                        entry.no_default_features,
+                    );
+                }
+                None => tracing::warn!("--features expects CRATE=FEATURES, ignoring {value}"),
+            },
+            "--no-default-features" => {
+                features
+                    .entry(value.clone())
+                    .or_default()
+                    .no_default_features = true;
+            }
+            _ => {}
+        }
+    }
+
+    // `--alias NAME=CRATE`, repeatable: other names crates can be asked for by
+    let mut aliases = CrateAliases::default();
+    for (flag, value) in args.iter().zip(args.iter().skip(1)) {
+        if flag != "--alias" {
+            continue;
+        }
+        match CrateAliases::parse(value) {
+            Some((alias, crate_name)) => aliases.insert(&alias, &crate_name),
+            None => tracing::warn!("--alias expects NAME=CRATE, ignoring {value}"),
+        }
+    }
+
+    // `--template NAME=PATH`, repeatable: replace the item, search, or impls output
+    let mut template_files = Vec::new();
+    for (flag, value) in args.iter().zip(args.iter().skip(1)) {
+        if flag != "--template" {
+            continue;
+        }
+        match value
+            .split_once('=')
+            .and_then(|(name, path)| Some((TemplateKind::parse(name)?, PathBuf::from(path))))
+        {
+            Some(file) => template_files.push(file),
+            None => tracing::warn!(
+                "--template expects item=PATH, search=PATH, or impls=PATH, ignoring {value}"
+            ),
+        }
     }
+    let templates = Templates::load(&template_files).map_err(|e| e.to_string())?;
 
-    let server = RustDocsServer::new(cargo_lock, !no_cache);
+    // `--corpus-dir DIR`: rustdoc JSON files (`name-version.json[.zst]`) to load first
+    let corpus = match args
+        .iter()
+        .position(|a| a == "--corpus-dir")
+        .map(|i| args.get(i + 1))
+    {
+        Some(Some(dir)) => {
+            Some(Corpus::open(dir.as_ref()).map_err(|e| format!("--corpus-dir {dir}: {e}"))?)
+        }
+        Some(None) => {
+            tracing::warn!("--corpus-dir expects a directory, ignoring it");
+            None
+        }
+        None => None,
+    };
+
+    if clear_cache && sandbox {
+        tracing::warn!("--clear-cache is ignored with --sandbox, whose disk cache is read-only");
+    } else if clear_cache {
+        DiskCache::clear().await;
+    }
+
+    // Cargo.lock and the workspace are discovered from CWD
+    let cwd = std::env::current_dir()?;
+    let server = RustDocsServer::new(
+        cwd,
+        ServerOptions {
+            use_disk_cache: !no_cache,
+            document_private,
+            render_options,
+            templates,
+            format,
+            features,
+            aliases,
+            corpus,
+            parse_budget,
+            http: http_options,
+            max_concurrent_loads,
+            audit_log,
+            sandbox,
+        },
+    );
 
     let service = server.serve(stdio()).await.inspect_err(|e| {
         tracing::error!("Failed to start MCP server: {e}");
@@ -49,3 +244,45 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Recompress the on-disk cache and print how much space it saved.
+async fn compact_cache() -> Result<(), Box<dyn std::error::Error>> {
+    let Some(cache) = DiskCache::new() else {
+        return Err("could not determine the cache directory".into());
+    };
+    let report = tokio::task::spawn_blocking(move || cache.compact()).await?;
+    println!("{report}");
+    Ok(())
+}
+
+/// Download the docs of the crates listed in each `--from-file` into the disk cache,
+/// printing one line per crate. Fails if any crate couldn't be fetched.
+async fn prefetch_from_files(
+    args: &[String],
+    http_options: &HttpOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let files: Vec<&String> = args
+        .iter()
+        .zip(args.iter().skip(1))
+        .filter(|(flag, _)| *flag == "--from-file")
+        .map(|(_, file)| file)
+        .collect();
+    if files.is_empty() {
+        return Err("usage: docsrs-mcp prefetch --from-file <FILE> (name@version per line)".into());
+    }
+    let mut specs = Vec::new();
+    for file in files {
+        let text = std::fs::read_to_string(file).map_err(|e| format!("{file}: {e}"))?;
+        specs.extend(prefetch::parse_crate_list(&text).map_err(|e| format!("{file}: {e}"))?);
+    }
+    let Some(cache) = DiskCache::new() else {
+        return Err("could not determine the cache directory".into());
+    };
+
+    let report = prefetch::prefetch(cache, HttpClient::new(http_options), specs).await;
+    println!("{report}");
+    if report.has_failures() {
+        return Err("some crates could not be prefetched".into());
+    }
+    Ok(())
+}
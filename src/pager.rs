@@ -0,0 +1,117 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Tool responses longer than this (in characters) are split into chunks.
+pub const MAX_RESPONSE_CHARS: usize = 50_000;
+
+/// Continuations kept for `read_more` before the oldest are dropped.
+const MAX_PENDING: usize = 64;
+
+/// Output that didn't fit in a tool response, kept until a `read_more` call asks for it
+/// by the opaque token the truncated response ended with.
+#[derive(Default)]
+pub struct Pager {
+    pending: Mutex<VecDeque<(String, String)>>,
+    next_id: AtomicU64,
+}
+
+impl Pager {
+    /// Keep `rest` for a follow-up `read_more` call and return its token.
+    pub fn stash(&self, rest: String) -> String {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let token = format!("more-{id:x}-{:x}", rest.len());
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        if pending.len() >= MAX_PENDING {
+            pending.pop_front();
+        }
+        pending.push_back((token.clone(), rest));
+        token
+    }
+
+    /// The output stored under `token`, itself paginated if it is still too long.
+    /// `None` if the token is unknown or was already used.
+    pub fn take(&self, token: &str) -> Option<String> {
+        let rest = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            let position = pending.iter().position(|(t, _)| t == token)?;
+            pending.remove(position)?.1
+        };
+        Some(self.paginate(rest, MAX_RESPONSE_CHARS))
+    }
+
+    /// `text` cut to about `max_chars` at a line boundary, ending with the token for
+    /// the rest; unchanged if it fits.
+    pub fn paginate(&self, text: String, max_chars: usize) -> String {
+        let Some((head, rest)) = split_at_line(&text, max_chars) else {
+            return text;
+        };
+        let remaining = rest.chars().count();
+        let token = self.stash(rest);
+        format!(
+            "{head}\n\n_Output truncated: call `read_more` with `token: \"{token}\"` for the next part ({remaining} more chars)._"
+        )
+    }
+}
+
+/// Split `text` at the last line break within `max_chars`, or `None` if it fits.
+///
+/// A code fence open at the split is closed in the first part and reopened in the
+/// second, so both render as code.
+fn split_at_line(text: &str, max_chars: usize) -> Option<(String, String)> {
+    let limit = text.char_indices().nth(max_chars)?.0;
+    let cut = match text[..limit].rfind('\n') {
+        Some(cut) if cut > 0 => cut,
+        _ => limit,
+    };
+    let (head, rest) = (&text[..cut], text[cut..].trim_start_matches('\n'));
+
+    let fences: Vec<&str> = head
+        .lines()
+        .filter(|line| line.trim_start().starts_with("```"))
+        .collect();
+    match fences.last() {
+        Some(fence) if fences.len() % 2 == 1 => Some((
+            format!("{head}\n```"),
+            format!("{}\n{rest}", fence.trim_start()),
+        )),
+        _ => Some((head.to_string(), rest.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== split_at_line tests ==========
+
+    #[test]
+    fn split_at_line_cuts_at_last_line_break() {
+        assert_eq!(split_at_line("short", 10), None);
+        let (head, rest) = split_at_line("one\ntwo\nthree", 9).unwrap();
+        assert_eq!(head, "one\ntwo");
+        assert_eq!(rest, "three");
+    }
+
+    #[test]
+    fn split_at_line_closes_and_reopens_code_fences() {
+        let text = "Intro\n```rust\nlet a = 1;\nlet b = 2;\n```";
+        let (head, rest) = split_at_line(text, 25).unwrap();
+        assert_eq!(head, "Intro\n```rust\nlet a = 1;\n```");
+        assert_eq!(rest, "```rust\nlet b = 2;\n```");
+    }
+
+    // ========== Pager tests ==========
+
+    #[test]
+    fn pager_returns_the_rest_once_per_token() {
+        let pager = Pager::default();
+        let text = "line one\nline two\nline three".to_string();
+        let first = pager.paginate(text, 12);
+        assert!(first.starts_with("line one\n\n_Output truncated"));
+
+        let token = first.split('"').nth(1).unwrap();
+        assert_eq!(pager.take(token).unwrap(), "line two\nline three");
+        assert_eq!(pager.take(token), None);
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt;
+use std::sync::Arc;
+
+use tokio::task::JoinSet;
+
+use crate::docs::cache::DiskCache;
+use crate::docs::crates_io::{crate_versions, find_version};
+use crate::docs::fetcher::fetch_raw_bytes;
+use crate::docs::render::format_size;
+use crate::error::Error;
+use crate::http::HttpClient;
+
+/// One line of a prefetch list: a crate and the version to fetch, or `None` for the
+/// newest release.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CrateSpec {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+/// Parse a prefetch list: `name@version` or just `name` per line. Blank lines and
+/// `#` comments are skipped. Fails on the first malformed line, naming it.
+pub fn parse_crate_list(text: &str) -> Result<Vec<CrateSpec>, String> {
+    let mut specs = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (name, version) = match line.split_once('@') {
+            Some((name, version)) => (name.trim(), Some(version.trim())),
+            None => (line, None),
+        };
+        let valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_name || version.is_some_and(|v| v.is_empty() || v.contains(char::is_whitespace)) {
+            return Err(format!(
+                "line {}: expected `name@version` or `name`, got {line:?}",
+                number + 1
+            ));
+        }
+        specs.push(CrateSpec {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+        });
+    }
+    Ok(specs)
+}
+
+/// What happened to one crate of a prefetch list.
+enum Outcome {
+    Fetched { version: String, size: u64 },
+    AlreadyCached { version: String },
+    Failed(Error),
+}
+
+/// Per-crate results of `prefetch`, in list order.
+pub struct PrefetchReport {
+    results: Vec<(String, Outcome)>,
+}
+
+impl fmt::Display for PrefetchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (mut fetched, mut cached, mut failed, mut bytes) = (0, 0, 0, 0);
+        for (name, outcome) in &self.results {
+            match outcome {
+                Outcome::Fetched { version, size } => {
+                    fetched += 1;
+                    bytes += size;
+                    writeln!(f, "fetched {name} {version} ({})", format_size(*size))?;
+                }
+                Outcome::AlreadyCached { version } => {
+                    cached += 1;
+                    writeln!(f, "cached  {name} {version}")?;
+                }
+                Outcome::Failed(e) => {
+                    failed += 1;
+                    writeln!(f, "failed  {name}: {e}")?;
+                }
+            }
+        }
+        write!(
+            f,
+            "Fetched {fetched} crates ({}), {cached} already cached, {failed} failed",
+            format_size(bytes)
+        )
+    }
+}
+
+impl PrefetchReport {
+    pub fn has_failures(&self) -> bool {
+        self.results
+            .iter()
+            .any(|(_, outcome)| matches!(outcome, Outcome::Failed(_)))
+    }
+}
+
+/// Download the docs of every crate in `specs` into the disk cache, skipping versions
+/// already there. Crates without a version get their newest release from crates.io,
+/// so they're cached under a fixed version. Fetches run concurrently, limited by the
+/// client's per-host slots.
+pub async fn prefetch(
+    cache: DiskCache,
+    client: HttpClient,
+    specs: Vec<CrateSpec>,
+) -> PrefetchReport {
+    let cache = Arc::new(cache);
+    let mut tasks = JoinSet::new();
+    for (position, spec) in specs.into_iter().enumerate() {
+        let (cache, client) = (Arc::clone(&cache), client.clone());
+        tasks.spawn(async move {
+            let outcome = prefetch_one(&cache, &client, &spec).await;
+            (position, spec.name, outcome)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(result) => results.push(result),
+            Err(e) => tracing::warn!("Prefetch task failed: {e}"),
+        }
+    }
+    results.sort_by_key(|(position, _, _)| *position);
+    PrefetchReport {
+        results: results
+            .into_iter()
+            .map(|(_, name, outcome)| (name, outcome))
+            .collect(),
+    }
+}
+
+async fn prefetch_one(cache: &DiskCache, client: &HttpClient, spec: &CrateSpec) -> Outcome {
+    let version = match &spec.version {
+        Some(version) => version.clone(),
+        None => match crate_versions(client, &spec.name).await {
+            Ok(versions) => match find_version(&versions, "latest") {
+                Some(latest) => latest.num.clone(),
+                None => {
+                    return Outcome::Failed(Error::Other(
+                        "no stable, non-yanked release on crates.io".to_string(),
+                    ));
+                }
+            },
+            Err(e) => return Outcome::Failed(e),
+        },
+    };
+    if cache.modified(&spec.name, &version).await.is_some() {
+        return Outcome::AlreadyCached { version };
+    }
+    match fetch_raw_bytes(client, &spec.name, &version).await {
+        Ok(bytes) => {
+            cache.write(&spec.name, &version, &bytes).await;
+            Outcome::Fetched {
+                version,
+                size: bytes.len() as u64,
+            }
+        }
+        Err(e) => Outcome::Failed(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ========== parse_crate_list tests ==========
+
+    fn spec(name: &str, version: Option<&str>) -> CrateSpec {
+        CrateSpec {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parse_crate_list_reads_names_versions_and_skips_comments() {
+        let text =
+            "# docs for the CI image\nserde@1.0.210\n\n  tokio @ 1.40.0  \nanyhow # newest\n";
+        assert_eq!(
+            parse_crate_list(text).unwrap(),
+            vec![
+                spec("serde", Some("1.0.210")),
+                spec("tokio", Some("1.40.0")),
+                spec("anyhow", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_crate_list_names_the_malformed_line() {
+        let err = parse_crate_list("serde@1.0.0\nnot a crate\n").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{err}");
+        assert!(parse_crate_list("serde@\n").is_err());
+        assert!(parse_crate_list("../etc@1.0.0\n").is_err());
+    }
+}
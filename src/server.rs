@@ -1,31 +1,193 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
-use rmcp::handler::server::tool::ToolRouter;
+use cargo_lock::Version;
+use rmcp::handler::server::common::schema_for_type;
+use rmcp::handler::server::router::prompt::PromptRouter;
+use rmcp::handler::server::tool::{ToolCallContext, ToolRouter};
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::*;
 use rmcp::schemars;
 use rmcp::schemars::JsonSchema;
-use rmcp::{ServerHandler, tool, tool_handler, tool_router};
+use rmcp::service::RequestContext;
+use rmcp::{
+    Peer, RoleServer, ServerHandler, prompt, prompt_handler, prompt_router, tool, tool_router,
+};
 use serde::Deserialize;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
+use tracing::Instrument;
 
-use crate::cargo_lock::CargoLockIndex;
+use crate::aliases::{CrateAliases, replace_path_root};
+use crate::audit::{AuditEntry, AuditLog, CacheOutcome, collect_loads, note_load};
+use crate::cargo_lock::{CargoLockIndex, FROM_LOCK_PREFIX};
+use crate::docs::bound_error::{matching_features, parse_bound_error};
 use crate::docs::cache::DiskCache;
-use crate::docs::fetcher::{decode_raw_bytes, fetch_raw_bytes};
-use crate::docs::index::CrateIndex;
+use crate::docs::corpus::Corpus;
+use crate::docs::crates_io;
+use crate::docs::diff::ApiDiff;
+use crate::docs::doctests::DoctestMode;
+use crate::docs::examples::{
+    ExampleFile, examples_from_archive, examples_from_dir, fetch_crate_archive,
+};
+use crate::docs::fetcher::{decode_json_bytes, decode_raw_bytes, fetch_builds, fetch_raw_bytes};
+use crate::docs::format::{markdown_to_html, markdown_to_plain};
+use crate::docs::index::{
+    CrateIndex, ForeignReexport, ImplBlock, IndexedItem, ItemKind, ItemLookup, ItemOrder,
+    MethodInfo, StaleDocs, Visibility,
+};
+use crate::docs::local::{
+    FeatureSet, build_key_suffix, build_rustdoc_json, build_with_features, prebuilt_rustdoc_json,
+    source_hash,
+};
 use crate::docs::parser::parse_crate;
-use crate::docs::render;
+use crate::docs::render::{self, MemberDocs, RenderOptions};
+use crate::docs::snippet::{Reference, extract_paths};
+use crate::docs::source;
+use crate::docs::std_docs::{self, KeywordDoc, RustToolchain, is_std_crate};
+use crate::docs::templates::Templates;
+use crate::http::{HttpClient, HttpOptions};
+use crate::pager::{MAX_RESPONSE_CHARS, Pager};
+use crate::workspace::{LocalPackage, PackageSource, WorkspaceIndex, file_uri_path};
 
 type CrateCache = Arc<RwLock<HashMap<(String, String), Arc<CrateIndex>>>>;
 
+/// Version marker for crates documented from local sources instead of docs.rs.
+const LOCAL_VERSION: &str = "local";
+
+/// Whether a crate version names one release, so its docs never change ("1.0.3", not
+/// "latest" or a requirement like "0.8" that docs.rs resolves to the newest match).
+fn is_exact_version(version: &str) -> bool {
+    cargo_lock::Version::parse(version).is_ok()
+}
+
+/// Whether docs cached under version key `cached` are those of `requested` (every
+/// version if `None`). A version also matches its feature builds ("1.0.3 (features:
+/// rt)"), and "local" matches the disk keys of local builds (`local-*`, `git-*`).
+fn cached_version_matches(requested: Option<&str>, cached: &str) -> bool {
+    let matches = |version: &str| {
+        cached == version
+            || cached
+                .strip_prefix(version)
+                .is_some_and(|rest| rest.starts_with(" ("))
+    };
+    match requested {
+        None => true,
+        Some(LOCAL_VERSION) => {
+            matches(LOCAL_VERSION) || cached.starts_with("local-") || cached.starts_with("git-")
+        }
+        Some(version) => matches(version),
+    }
+}
+
+/// Maximum number of paths from one snippet that `resolve_snippet` looks up.
+const MAX_SNIPPET_PATHS: usize = 30;
+
+/// Maximum number of not-yet-loaded crates one `which_crate_defines` call loads.
+const MAX_DEFINES_LOADS: usize = 10;
+
+/// Number of largest modules listed by `crate_stats`.
+const MAX_STATS_MODULES: usize = 10;
+
+/// Maximum cached crates listed in the server instructions.
+const MAX_INSTRUCTION_CRATES: usize = 15;
+
+/// Crates loaded (fetched or built, then parsed) at once unless `--max-concurrent-loads`
+/// says otherwise.
+const DEFAULT_CONCURRENT_LOADS: usize = 3;
+
+/// How long `document_workspace` waits for the client to list its roots.
+const ROOTS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The project the server was started in: its Cargo.lock and locally documented
+/// packages. Replaced as a whole by `reload_project`.
+#[derive(Default)]
+struct Project {
+    cargo_lock: Option<Arc<CargoLockIndex>>,
+    workspace: Option<Arc<WorkspaceIndex>>,
+    /// The Rust toolchain the project builds with, which picks the std docs version.
+    toolchain: Option<Arc<RustToolchain>>,
+}
+
+impl Project {
+    /// Find and parse Cargo.lock and discover the Cargo workspace from `dir`.
+    /// Without `run_cargo` (`--sandbox`), neither `cargo metadata` nor `rustc` is run, so
+    /// no local packages are found and std docs default to the latest stable release.
+    fn discover(dir: &Path, run_cargo: bool) -> Self {
+        let cargo_lock = CargoLockIndex::find_and_parse(dir);
+        if cargo_lock.is_some() {
+            tracing::info!("Cargo.lock loaded, will auto-resolve crate versions");
+        } else {
+            tracing::info!("No Cargo.lock found, will use explicit versions or 'latest'");
+        }
+
+        // Workspace members and path dependencies are documented locally
+        let workspace = run_cargo.then(|| WorkspaceIndex::discover(dir)).flatten();
+        if let Some(ws) = &workspace {
+            tracing::info!(
+                "Cargo workspace found with {} local package(s), will build their docs locally",
+                ws.packages().len()
+            );
+        }
+
+        let toolchain = run_cargo.then(|| RustToolchain::detect(dir)).flatten();
+        if let Some(toolchain) = &toolchain {
+            tracing::info!(
+                "Rust toolchain {} found, will use std docs for {}",
+                toolchain.release,
+                toolchain.std_version()
+            );
+        }
+
+        Self {
+            cargo_lock: cargo_lock.map(Arc::new),
+            workspace: workspace.map(Arc::new),
+            toolchain: toolchain.map(Arc::new),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct RustDocsServer {
-    cargo_lock: Option<Arc<CargoLockIndex>>,
-    http_client: reqwest::Client,
+    /// Directory the server was started in, where the project is discovered.
+    project_dir: PathBuf,
+    project: Arc<std::sync::RwLock<Project>>,
+    /// Versions set with `set_default_version` for this session, by crate name
+    /// (hyphens normalized to underscores).
+    version_overrides: Arc<std::sync::RwLock<HashMap<String, String>>>,
+    /// Feature sets to build crates' docs with locally instead of using docs.rs,
+    /// keyed by crate name with underscores.
+    feature_overrides: Arc<std::sync::RwLock<HashMap<String, FeatureSet>>>,
+    /// Other names for crates that `crate_name` arguments may use (`--alias`).
+    aliases: Arc<CrateAliases>,
+    /// Output held back from truncated responses, for `read_more`.
+    pager: Arc<Pager>,
+    /// Pass `--document-private-items` to local rustdoc builds.
+    document_private: bool,
+    /// Default layout of rendered signatures, overridable per tool call.
+    render_options: RenderOptions,
+    /// User templates replacing the item, search, and impl outputs (`--template`).
+    templates: Arc<Templates>,
+    /// Format of tool output unless a call asks for another (`--format`).
+    format: OutputFormat,
+    /// Time to parse docs in before skipping doc bodies and finishing the full index
+    /// in the background (`--parse-budget-ms`).
+    parse_budget: Option<Duration>,
+    /// Permits for fetching or building and then parsing a crate's docs, so a burst of
+    /// calls for uncached crates doesn't load them all at once (`--max-concurrent-loads`).
+    load_permits: Arc<Semaphore>,
+    /// No network, read-only disk cache, no local builds (`--sandbox`).
+    sandbox: bool,
+    /// JSONL log of every tool call (`--audit-log`).
+    audit_log: Option<Arc<AuditLog>>,
+    http_client: HttpClient,
     cache: CrateCache,
     disk_cache: Option<Arc<DiskCache>>,
+    /// Pre-generated rustdoc JSON read before docs.rs (`--corpus-dir`).
+    corpus: Option<Arc<Corpus>>,
     tool_router: ToolRouter<Self>,
+    prompt_router: PromptRouter<Self>,
 }
 
 // ========== Tool parameter structs ==========
@@ -34,23 +196,212 @@ pub struct RustDocsServer {
 struct LookupCrateItemsParams {
     /// The crate name (e.g. "serde", "tokio")
     crate_name: String,
-    /// Specific version. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
     #[serde(default)]
     version: Option<String>,
     /// Module path to list items from (e.g. "tokio::sync"). Lists root items if omitted.
+    /// Accepts `*` wildcards within a segment (e.g. "sync::*", "net::Tcp*") to list all matching items.
+    #[serde(default)]
+    module_path: Option<String>,
+    /// Only list items with this visibility. Non-public items exist only in locally built docs.
+    #[serde(default)]
+    visibility: Option<VisibilityFilter>,
+    /// Include `#[doc(hidden)]` items, which are left out by default (default: false)
+    #[serde(default)]
+    include_hidden: Option<bool>,
+    /// Show each item's signature condensed to one line (a function's full signature, a type's fields or variants) instead of just its name (default: false)
+    #[serde(default)]
+    include_signatures: Option<bool>,
+    /// Leave out items without a doc comment, common in generated bindings crates (default: false)
+    #[serde(default)]
+    documented_only: Option<bool>,
+    /// Order of the listing (default: "kind", modules first and grouped by kind)
+    #[serde(default)]
+    sort: Option<SortOrder>,
+}
+
+impl LookupCrateItemsParams {
+    /// Whether a module listing shows `item` under the hidden, documentation and
+    /// visibility filters.
+    fn lists(&self, item: &IndexedItem) -> bool {
+        (self.include_hidden.unwrap_or(false) || !item.is_hidden)
+            && (!self.documented_only.unwrap_or(false) || item.is_documented())
+            && self
+                .visibility
+                .is_none_or(|filter| filter.matches(&item.visibility))
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct CrateTreeParams {
+    /// The crate name (e.g. "serde", "tokio")
+    crate_name: String,
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    #[serde(default)]
+    version: Option<String>,
+    /// Module to start the tree at (e.g. "tokio::sync"). The crate root if omitted.
     #[serde(default)]
     module_path: Option<String>,
+    /// Show how many items of each kind every module holds directly (default: false)
+    #[serde(default)]
+    item_counts: Option<bool>,
+    /// Include `#[doc(hidden)]` modules and items, which are left out by default (default: false)
+    #[serde(default)]
+    include_hidden: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum VisibilityFilter {
+    /// `pub` items
+    Public,
+    /// `pub(crate)` items
+    Crate,
+    /// `pub(in path)` items
+    Restricted,
+    /// Items without a visibility modifier
+    Private,
+}
+
+impl VisibilityFilter {
+    fn matches(self, vis: &Visibility) -> bool {
+        matches!(
+            (self, vis),
+            (Self::Public, Visibility::Public)
+                | (Self::Crate, Visibility::Crate)
+                | (Self::Restricted, Visibility::Restricted(_))
+                | (Self::Private, Visibility::Private)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum SortOrder {
+    /// Alphabetically by name
+    Alpha,
+    /// Modules first, then grouped by kind
+    Kind,
+    /// Longest doc comment first
+    DocLength,
+}
+
+impl SortOrder {
+    fn order(self) -> ItemOrder {
+        match self {
+            Self::Alpha => ItemOrder::Alpha,
+            Self::Kind => ItemOrder::Kind,
+            Self::DocLength => ItemOrder::DocLength,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 struct LookupItemParams {
     /// The crate name (e.g. "serde", "tokio")
     crate_name: String,
-    /// Fully qualified path to the item (e.g. "Serialize", "sync::Mutex")
+    /// Fully qualified path to the item (e.g. "Serialize", "sync::Mutex").
+    /// Accepts `*` wildcards within a segment (e.g. "sync::*") to list all matching items.
     item_path: String,
-    /// Specific version. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
     #[serde(default)]
     version: Option<String>,
+    /// For traits: also show the default implementation of each provided method, read from the crate's source (default: false)
+    #[serde(default)]
+    show_default_body: Option<bool>,
+    #[serde(flatten)]
+    render: RenderParams,
+}
+
+/// How tool output is formatted (`--format`, or a call's `format` argument).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Markdown with headings and fenced code
+    #[default]
+    Markdown,
+    /// Plain text without markdown syntax, for narrow terminals
+    Plain,
+    /// An HTML fragment, for clients embedding output in web pages
+    Html,
+}
+
+impl OutputFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::deserialize(serde_json::Value::from(name)).ok()
+    }
+
+    /// The text content of `result`, rendered as markdown, converted to this format.
+    fn apply(self, mut result: CallToolResult) -> CallToolResult {
+        let convert = match self {
+            OutputFormat::Markdown => return result,
+            OutputFormat::Plain => markdown_to_plain,
+            OutputFormat::Html => markdown_to_html,
+        };
+        for content in &mut result.content {
+            if let RawContent::Text(text) = &mut content.raw {
+                text.text = convert(&text.text);
+            }
+        }
+        result
+    }
+}
+
+// The `format` argument of every tool, added to each tool's input schema by
+// `list_tools()` and applied to the output in `call_tool()`. A plain comment, as a doc
+// comment would become the description of the schema.
+#[derive(Debug, Deserialize, JsonSchema)]
+struct FormatParams {
+    /// Output format: "markdown", "plain" text without markdown syntax, or an "html" fragment (default: markdown, or the server's `--format`). Pass it to `read_more` too.
+    #[serde(default)]
+    format: Option<OutputFormat>,
+}
+
+/// `schema` with the properties and definitions of `extra` added.
+fn merge_schema(schema: &JsonObject, extra: &JsonObject) -> JsonObject {
+    let mut merged = schema.clone();
+    for key in ["properties", "$defs"] {
+        let Some(serde_json::Value::Object(entries)) = extra.get(key) else {
+            continue;
+        };
+        if let serde_json::Value::Object(target) = merged
+            .entry(key)
+            .or_insert_with(|| serde_json::Value::Object(JsonObject::new()))
+        {
+            target.extend(entries.clone());
+        }
+    }
+    merged
+}
+
+// Per-call overrides of the server's rendering options. A plain comment, as a doc
+// comment would become the description of every flattening params schema.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+struct RenderParams {
+    /// Column width long signatures are wrapped to (default: 80, or the server's `--width`)
+    #[serde(default)]
+    width: Option<usize>,
+    /// Wrap long function signatures to one parameter per line (default: true)
+    #[serde(default)]
+    wrap_signatures: Option<bool>,
+    /// Show method summaries on the same line as their signatures (default: false)
+    #[serde(default)]
+    inline_docs: Option<bool>,
+    /// Show the whole doc comment instead of the leading paragraphs of a long one (default: false)
+    #[serde(default)]
+    full_doc: Option<bool>,
+}
+
+impl RenderParams {
+    /// `defaults` with the options given in this call applied.
+    fn apply(&self, defaults: RenderOptions) -> RenderOptions {
+        RenderOptions {
+            width: self.width.unwrap_or(defaults.width),
+            wrap_signatures: self.wrap_signatures.unwrap_or(defaults.wrap_signatures),
+            inline_docs: self.inline_docs.unwrap_or(defaults.inline_docs),
+            full_doc: self.full_doc.unwrap_or(defaults.full_doc),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -59,12 +410,258 @@ struct SearchCrateParams {
     crate_name: String,
     /// Search query (matches against item names and doc text)
     query: String,
-    /// Specific version. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
     #[serde(default)]
     version: Option<String>,
     /// Maximum number of results (default: 20)
     #[serde(default)]
     limit: Option<usize>,
+    /// Match case exactly, e.g. to tell `Read` from `read` (default: false)
+    #[serde(default)]
+    case_sensitive: Option<bool>,
+    /// Include `#[doc(hidden)]` items, which are left out by default (default: false)
+    #[serde(default)]
+    include_hidden: Option<bool>,
+    /// Leave out items without a doc comment, common in generated bindings crates (default: false)
+    #[serde(default)]
+    documented_only: Option<bool>,
+    /// Reorder the best matches (by default they're ordered by relevance)
+    #[serde(default)]
+    sort: Option<SortOrder>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ListItemsByKindParams {
+    /// The crate name (e.g. "serde", "tokio")
+    crate_name: String,
+    /// The kind of item to list
+    kind: ItemKindFilter,
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    #[serde(default)]
+    version: Option<String>,
+    /// Maximum number of items to list (default: 200)
+    #[serde(default)]
+    limit: Option<usize>,
+    /// Include `#[doc(hidden)]` items, which are left out by default (default: false)
+    #[serde(default)]
+    include_hidden: Option<bool>,
+    /// Leave out items without a doc comment, common in generated bindings crates (default: false)
+    #[serde(default)]
+    documented_only: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum ItemKindFilter {
+    Module,
+    Struct,
+    Enum,
+    Trait,
+    Function,
+    TypeAlias,
+    Constant,
+    Static,
+    /// Declarative (`macro_rules!`) and function-like procedural macros
+    Macro,
+    AttributeMacro,
+    DeriveMacro,
+    Union,
+    /// Primitive types documented by std and core (`str`, `u64`, `slice`, ...)
+    Primitive,
+}
+
+impl ItemKindFilter {
+    fn kind(self) -> ItemKind {
+        match self {
+            Self::Module => ItemKind::Module,
+            Self::Struct => ItemKind::Struct,
+            Self::Enum => ItemKind::Enum,
+            Self::Trait => ItemKind::Trait,
+            Self::Function => ItemKind::Function,
+            Self::TypeAlias => ItemKind::TypeAlias,
+            Self::Constant => ItemKind::Constant,
+            Self::Static => ItemKind::Static,
+            Self::Macro => ItemKind::Macro,
+            Self::AttributeMacro => ItemKind::AttributeMacro,
+            Self::DeriveMacro => ItemKind::DeriveMacro,
+            Self::Union => ItemKind::Union,
+            Self::Primitive => ItemKind::Primitive,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct CrateParams {
+    /// The crate name (e.g. "serde", "tokio")
+    crate_name: String,
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    #[serde(default)]
+    version: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct TypeParams {
+    /// The crate name (e.g. "reqwest", "tokio")
+    crate_name: String,
+    /// Path to the type (e.g. "Client", "runtime::Runtime")
+    item_path: String,
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    #[serde(default)]
+    version: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct FindMethodOriginParams {
+    /// The crate name (e.g. "futures", "tokio")
+    crate_name: String,
+    /// Method name (e.g. "poll_next"); a "Type::method" path is also accepted
+    method_name: String,
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    #[serde(default)]
+    version: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct FindCratesParams {
+    /// What the crate should do (e.g. "http client", "argument parsing")
+    query: String,
+    /// Maximum number of crates (default: 10)
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ReverseDependenciesParams {
+    /// The crate name (e.g. "serde", "tokio")
+    crate_name: String,
+    /// Maximum number of dependents (default: 20)
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct GetCrateExamplesParams {
+    /// The crate name (e.g. "axum", "clap")
+    crate_name: String,
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    #[serde(default)]
+    version: Option<String>,
+    /// Example to return the source of (e.g. "hello_world"). Lists all examples if omitted.
+    #[serde(default)]
+    example: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ListDoctestsParams {
+    /// The crate name (e.g. "serde", "tokio")
+    crate_name: String,
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    #[serde(default)]
+    version: Option<String>,
+    /// Only doctests of items under this path (e.g. "sync", "sync::Mutex")
+    #[serde(default)]
+    item_path: Option<String>,
+    /// Only doctests of this kind
+    #[serde(default)]
+    mode: Option<DoctestModeFilter>,
+    /// Maximum number of items to show (default: 20)
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum DoctestModeFilter {
+    /// Doctests that are compiled and run
+    Run,
+    /// `no_run` doctests (compiled only)
+    NoRun,
+    /// `ignore` doctests
+    Ignore,
+    /// `should_panic` doctests
+    ShouldPanic,
+    /// `compile_fail` doctests
+    CompileFail,
+}
+
+impl DoctestModeFilter {
+    fn mode(self) -> DoctestMode {
+        match self {
+            Self::Run => DoctestMode::Run,
+            Self::NoRun => DoctestMode::NoRun,
+            Self::Ignore => DoctestMode::Ignore,
+            Self::ShouldPanic => DoctestMode::ShouldPanic,
+            Self::CompileFail => DoctestMode::CompileFail,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ResolveSnippetParams {
+    /// Rust code: `use` declarations, type annotations, calls like `Mutex::new(..)`
+    code: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct WhichCrateDefinesParams {
+    /// A type, trait, function, or macro name (e.g. "DateTime", "Deserializer"), optionally
+    /// with leading path segments (e.g. "de::Deserializer")
+    name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct DocumentWorkspaceParams {
+    /// Workspace members to document (e.g. ["my-crate"]). Documents every member if omitted.
+    #[serde(default)]
+    packages: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct EvictCrateParams {
+    /// The crate name (e.g. "serde", "tokio")
+    crate_name: String,
+    /// Version to evict. Evicts every cached version if omitted.
+    #[serde(default)]
+    version: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SetDefaultVersionParams {
+    /// The crate name (e.g. "tokio")
+    crate_name: String,
+    /// Version to use when a call omits `version` (e.g. "0.2.25"). Clears the default if omitted.
+    #[serde(default)]
+    version: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct SetCrateFeaturesParams {
+    /// The crate name (e.g. "tokio")
+    crate_name: String,
+    /// Comma-separated features to enable (e.g. "rt,macros"). Omit both this and `no_default_features` to go back to the docs.rs build.
+    #[serde(default)]
+    features: Option<String>,
+    /// Disable the crate's default features (default: false)
+    #[serde(default)]
+    no_default_features: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ReadMoreParams {
+    /// The token from the end of a truncated response
+    token: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ExplainBoundErrorParams {
+    /// The rustc error text, e.g. "the trait bound `Config: Serialize` is not satisfied"
+    error: String,
+    /// The crate defining the trait. Inferred from the trait path in the error if omitted.
+    #[serde(default)]
+    crate_name: Option<String>,
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    #[serde(default)]
+    version: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -73,18 +670,117 @@ struct LookupImplBlockParams {
     crate_name: String,
     /// Path to the type or trait (e.g. "HashMap", "sync::Mutex")
     item_path: String,
-    /// Specific version. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(flatten)]
+    render: RenderParams,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct ExplainApiPromptParams {
+    /// The crate name (e.g. "serde", "tokio")
+    crate_name: String,
+    /// Path to the item to explain (e.g. "sync::Mutex")
+    item_path: String,
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
+    #[serde(default)]
+    version: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct CompareTypesPromptParams {
+    /// The crate name (e.g. "std", "tokio")
+    crate_name: String,
+    /// Path to the first type (e.g. "sync::Mutex")
+    type_a: String,
+    /// Path to the second type (e.g. "sync::RwLock")
+    type_b: String,
+    /// Specific version, or "from-lock:0.8" for the locked version starting with 0.8. Auto-detected from Cargo.lock if omitted, falls back to "latest".
     #[serde(default)]
     version: Option<String>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+struct MigrationGuidePromptParams {
+    /// The crate name (e.g. "axum")
+    crate_name: String,
+    /// The version migrating from. Defaults to the version pinned in Cargo.lock.
+    #[serde(default)]
+    from_version: Option<String>,
+    /// The version migrating to (e.g. "0.8.0")
+    to_version: String,
+}
+
 // ========== Server implementation ==========
 
+/// Settings the server is started with, from the command-line flags.
+pub struct ServerOptions {
+    /// Read and write the on-disk cache (off with `--no-cache`).
+    pub use_disk_cache: bool,
+    pub document_private: bool,
+    pub render_options: RenderOptions,
+    pub templates: Templates,
+    pub format: OutputFormat,
+    /// Feature sets to build crates' docs with (`--features`, `--no-default-features`).
+    pub features: HashMap<String, FeatureSet>,
+    /// Other names for crates (`--alias NAME=CRATE`).
+    pub aliases: CrateAliases,
+    /// Directory of rustdoc JSON files to load crates from before docs.rs.
+    pub corpus: Option<Corpus>,
+    pub parse_budget: Option<Duration>,
+    pub http: HttpOptions,
+    pub max_concurrent_loads: usize,
+    /// File to append a JSONL entry to for every tool call.
+    pub audit_log: Option<PathBuf>,
+    /// Serve only what is already cached: no network, no cache writes, and no
+    /// commands run (so no local doc builds).
+    pub sandbox: bool,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            use_disk_cache: true,
+            document_private: false,
+            render_options: RenderOptions::default(),
+            templates: Templates::default(),
+            format: OutputFormat::default(),
+            features: HashMap::new(),
+            aliases: CrateAliases::default(),
+            corpus: None,
+            parse_budget: None,
+            http: HttpOptions::default(),
+            max_concurrent_loads: DEFAULT_CONCURRENT_LOADS,
+            audit_log: None,
+            sandbox: false,
+        }
+    }
+}
+
 #[tool_router]
 impl RustDocsServer {
-    pub fn new(cargo_lock: Option<CargoLockIndex>, use_disk_cache: bool) -> Self {
+    pub fn new(project_dir: PathBuf, options: ServerOptions) -> Self {
+        let ServerOptions {
+            use_disk_cache,
+            document_private,
+            render_options,
+            templates,
+            format,
+            features,
+            aliases,
+            corpus,
+            parse_budget,
+            mut http,
+            max_concurrent_loads,
+            audit_log,
+            sandbox,
+        } = options;
+        http.offline |= sandbox;
         let disk_cache = if use_disk_cache {
-            DiskCache::new().map(Arc::new)
+            DiskCache::new()
+                .map(|cache| if sandbox { cache.read_only() } else { cache })
+                .map(Arc::new)
         } else {
             None
         };
@@ -96,16 +792,60 @@ impl RustDocsServer {
             }
             None => tracing::info!("Disk cache disabled"),
         }
+        if let Some(corpus) = &corpus {
+            tracing::info!(
+                "Loading docs from {} first ({} crate versions)",
+                corpus.dir().display(),
+                corpus.entries().len()
+            );
+        }
+        if sandbox {
+            tracing::info!("Sandbox mode: no network, read-only disk cache, no local builds");
+        }
+
+        let audit_log = audit_log.and_then(|path| match AuditLog::open(&path) {
+            Ok(log) => {
+                tracing::info!("Logging tool calls to {}", path.display());
+                Some(Arc::new(log))
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to open audit log {}, not logging: {e}",
+                    path.display()
+                );
+                None
+            }
+        });
 
         Self {
-            cargo_lock: cargo_lock.map(Arc::new),
-            http_client: reqwest::Client::builder()
-                .user_agent("docsrs-mcp/0.1.0")
-                .build()
-                .expect("failed to build HTTP client"),
+            project: Arc::new(std::sync::RwLock::new(Project::discover(
+                &project_dir,
+                !sandbox,
+            ))),
+            project_dir,
+            version_overrides: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            feature_overrides: Arc::new(std::sync::RwLock::new(
+                features
+                    .into_iter()
+                    .map(|(name, features)| (name.replace('-', "_"), features))
+                    .collect(),
+            )),
+            aliases: Arc::new(aliases),
+            pager: Arc::new(Pager::default()),
+            document_private,
+            render_options,
+            templates: Arc::new(templates),
+            format,
+            parse_budget,
+            load_permits: Arc::new(Semaphore::new(max_concurrent_loads.max(1))),
+            sandbox,
+            audit_log,
+            http_client: HttpClient::new(&http),
             cache: Arc::new(RwLock::new(HashMap::new())),
             disk_cache,
+            corpus: corpus.map(Arc::new),
             tool_router: Self::tool_router(),
+            prompt_router: Self::prompt_router(),
         }
     }
 
@@ -120,15 +860,34 @@ impl RustDocsServer {
         let version = self.resolve_version(&params.crate_name, params.version.as_deref());
         match self.get_or_load_index(&params.crate_name, &version).await {
             Ok(index) => {
+                let include_signatures = params.include_signatures.unwrap_or(false);
+                if let Some(pattern) = params.module_path.as_deref().filter(|p| p.contains('*')) {
+                    let mut items = index.glob_items(pattern);
+                    items.retain(|item| params.lists(item));
+                    if let Some(sort) = params.sort {
+                        items.sort_by(|a, b| sort.order().compare(a, b));
+                    }
+                    let text =
+                        render::render_glob_matches(&index, pattern, &items, include_signatures);
+                    return Ok(self.index_result(&index, text));
+                }
                 let module = params.module_path.as_deref().map(|p| {
-                    if p.contains("::") {
+                    if let Some(rest) = index.strip_package_prefix(p) {
+                        format!("{}::{rest}", index.crate_name)
+                    } else if p.contains("::") {
                         p.to_string()
                     } else {
                         format!("{}::{p}", index.crate_name)
                     }
                 });
-                let text = render::render_crate_items(&index, module.as_deref());
-                Ok(CallToolResult::success(vec![Content::text(text)]))
+                let text = render::render_crate_items(
+                    &index,
+                    module.as_deref(),
+                    include_signatures,
+                    params.sort.map_or(ItemOrder::Kind, SortOrder::order),
+                    |item| params.lists(item),
+                );
+                Ok(self.index_result(&index, text))
             }
             Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
         }
@@ -145,12 +904,54 @@ impl RustDocsServer {
         let version = self.resolve_version(&params.crate_name, params.version.as_deref());
         match self.get_or_load_index(&params.crate_name, &version).await {
             Ok(index) => {
-                let text = if let Some(item) = index.get_item(&params.item_path) {
-                    render::render_item(item)
+                let text = if params.item_path.contains('*') {
+                    let mut items = index.glob_items(&params.item_path);
+                    items.retain(|item| !item.is_hidden);
+                    render::render_glob_matches(&index, &params.item_path, &items, false)
+                } else if let Some(keyword) = std_docs::keyword_lookup(&index, &params.item_path) {
+                    match self.load_keyword_doc(keyword, &index.version).await {
+                        Ok(doc) => render::render_keyword(
+                            &index,
+                            &doc,
+                            &params.render.apply(self.render_options),
+                        ),
+                        Err(e) => {
+                            return Ok(CallToolResult::error(vec![Content::text(e.to_string())]));
+                        }
+                    }
                 } else {
-                    render::render_not_found(&index, &params.item_path)
+                    match index.resolve_item(&params.item_path) {
+                        ItemLookup::Found(item) => {
+                            let options = params.render.apply(self.render_options);
+                            let text = render::render_item(&index, item, &options);
+                            let text = self.templates.render_item(&index, item, &options, text);
+                            if params.show_default_body.unwrap_or(false) {
+                                let bodies = self.default_method_bodies(&index, item).await;
+                                format!(
+                                    "{}\n\n{}",
+                                    text.trim_end(),
+                                    render::render_default_bodies(item, &bodies)
+                                )
+                            } else {
+                                text
+                            }
+                        }
+                        ItemLookup::Ambiguous(candidates) => {
+                            render::render_ambiguous(&index, &params.item_path, &candidates)
+                        }
+                        ItemLookup::NotFound => match index.foreign_reexport(&params.item_path) {
+                            Some(reexport) => {
+                                self.follow_reexport(
+                                    reexport,
+                                    &params.render.apply(self.render_options),
+                                )
+                                .await
+                            }
+                            None => render::render_not_found(&index, &params.item_path),
+                        },
+                    }
                 };
-                Ok(CallToolResult::success(vec![Content::text(text)]))
+                Ok(self.index_result(&index, text))
             }
             Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
         }
@@ -158,7 +959,7 @@ impl RustDocsServer {
 
     #[tool(
         name = "search_crate",
-        description = "Search within a Rust crate for items matching a query. Searches item names and documentation text. Returns ranked results."
+        description = "Search within a Rust crate for items and methods (inherent, trait impl, and trait definition) matching a query. Searches names, documentation text and headings, and method signatures. Returns ranked results."
     )]
     async fn search_crate(
         &self,
@@ -168,93 +969,2012 @@ impl RustDocsServer {
         let limit = params.limit.unwrap_or(20).min(50);
         match self.get_or_load_index(&params.crate_name, &version).await {
             Ok(index) => {
-                let results = index.search(&params.query, limit);
+                let case_sensitive = params.case_sensitive.unwrap_or(false);
+                let include_hidden = params.include_hidden.unwrap_or(false);
+                let documented_only = params.documented_only.unwrap_or(false);
+                let mut results = index.search(
+                    &params.query,
+                    limit,
+                    case_sensitive,
+                    include_hidden,
+                    documented_only,
+                );
+                if let Some(sort) = params.sort {
+                    results.sort_by(|a, b| sort.order().compare(&a.item, &b.item));
+                }
                 let text = render::render_search_results(&index, &params.query, &results);
-                Ok(CallToolResult::success(vec![Content::text(text)]))
+                let text = self
+                    .templates
+                    .render_search(&index, &params.query, &results, text);
+                Ok(self.index_result(&index, text))
             }
             Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
         }
     }
 
     #[tool(
-        name = "lookup_impl_block",
-        description = "Look up trait implementations for a type, or implementors of a trait. Shows method signatures and documentation."
+        name = "list_items_by_kind",
+        description = "List every item of one kind (e.g. all traits, all macros) across a whole Rust crate, with counts and one-line docs."
     )]
-    async fn lookup_impl_block(
+    async fn list_items_by_kind(
         &self,
-        Parameters(params): Parameters<LookupImplBlockParams>,
+        Parameters(params): Parameters<ListItemsByKindParams>,
     ) -> Result<CallToolResult, rmcp::ErrorData> {
         let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        let limit = params.limit.unwrap_or(200).min(1000);
         match self.get_or_load_index(&params.crate_name, &version).await {
             Ok(index) => {
-                let impls = index.get_impl_blocks(&params.item_path);
-                let text = render::render_impls(&params.item_path, &impls);
-                Ok(CallToolResult::success(vec![Content::text(text)]))
+                let kind = params.kind.kind();
+                let mut items = index.items_of_kind(&kind);
+                if !params.include_hidden.unwrap_or(false) {
+                    items.retain(|item| !item.is_hidden);
+                }
+                if params.documented_only.unwrap_or(false) {
+                    items.retain(|item| item.is_documented());
+                }
+                let more_token = (items.len() > limit).then(|| {
+                    self.pager
+                        .stash(render::render_item_entries(&items[limit..]))
+                });
+                let text = render::render_items_by_kind(
+                    &index,
+                    &kind,
+                    &items,
+                    limit,
+                    more_token.as_deref(),
+                );
+                Ok(self.index_result(&index, text))
             }
             Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
         }
     }
-}
 
-#[tool_handler]
+    #[tool(
+        name = "list_deprecated_items",
+        description = "List every deprecated public item and method in a Rust crate, with the version it was deprecated in and the migration note. Useful when upgrading dependencies."
+    )]
+    async fn list_deprecated_items(
+        &self,
+        Parameters(params): Parameters<CrateParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let entries = index.deprecated_items();
+                let text = render::render_deprecated(&index, &entries);
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "list_unsafe_items",
+        description = "List the unsafe API of a Rust crate: unsafe functions, unsafe traits and traits with unsafe methods, and unsafe inherent methods. Useful for safety reviews of dependencies."
+    )]
+    async fn list_unsafe_items(
+        &self,
+        Parameters(params): Parameters<CrateParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let report = index.unsafe_report();
+                let text = render::render_unsafe_report(&index, &report);
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "list_async_items",
+        description = "List the async API of a Rust crate: `async fn`s and functions and methods returning futures (`impl Future`, `BoxFuture`, types implementing `Future`) or streams. Useful for finding what can be awaited."
+    )]
+    async fn list_async_items(
+        &self,
+        Parameters(params): Parameters<CrateParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let report = index.async_report();
+                let text = render::render_async_report(&index, &report);
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "feature_map",
+        description = "Show a Rust crate's module tree annotated with the features (or other cfgs) each module and item requires, from `#[doc(cfg)]`, with a summary of what each feature gates. Useful to see what `full` or other features unlock."
+    )]
+    async fn feature_map(
+        &self,
+        Parameters(params): Parameters<CrateParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let text = render::render_feature_map(&index, &index.feature_map());
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "list_error_types",
+        description = "Find the error types of a Rust crate (types implementing Error, named *Error, or returned in Results) and summarize their variants. Use this before writing error-handling code."
+    )]
+    async fn list_error_types(
+        &self,
+        Parameters(params): Parameters<CrateParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let errors = index.error_types();
+                let text = render::render_error_types(&index, &errors);
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "find_builder",
+        description = "Find the builder for a Rust type: *Builder types that produce it, builder() constructors, and chainable setters, rendered as a step-by-step construction guide."
+    )]
+    async fn find_builder(
+        &self,
+        Parameters(params): Parameters<TypeParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let text = match resolve_or_explain(&index, &params.item_path) {
+                    Ok(target) => render::render_builders(target, &index.find_builders(target)),
+                    Err(text) => text,
+                };
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "how_to_construct",
+        description = "Show every way to create a value of a Rust type: Default, constructors, From/TryFrom/FromStr conversions, builders, struct literals, and other functions returning it, easiest first."
+    )]
+    async fn how_to_construct(
+        &self,
+        Parameters(params): Parameters<TypeParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let text = match resolve_or_explain(&index, &params.item_path) {
+                    Ok(target) => {
+                        let guide = index.construction_guide(target);
+                        render::render_construction_guide(target, &guide)
+                    }
+                    Err(text) => text,
+                };
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "is_thread_safe",
+        description = "Check whether a Rust type is Send, Sync, and Unpin, citing the impl behind each: can it be moved to another thread, or shared across threads behind an Arc?"
+    )]
+    async fn is_thread_safe(
+        &self,
+        Parameters(params): Parameters<TypeParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let text = match resolve_or_explain(&index, &params.item_path) {
+                    Ok(target) => {
+                        render::render_thread_safety(target, &index.thread_safety(&target.path))
+                    }
+                    Err(text) => text,
+                };
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "find_method_origin",
+        description = "Find which trait defines a method (e.g. where `poll_next` comes from): traits declaring it, trait impls providing it, and inherent methods with that name."
+    )]
+    async fn find_method_origin(
+        &self,
+        Parameters(params): Parameters<FindMethodOriginParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let origins = index.method_origins(&params.method_name);
+                let text = render::render_method_origins(&index, &params.method_name, &origins);
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "find_crates",
+        description = "Search crates.io for crates matching a description (e.g. \"http client\", \"argument parsing\"). Returns candidates with descriptions, download counts, and last update date, to help pick a dependency before reading its docs."
+    )]
+    async fn find_crates(
+        &self,
+        Parameters(params): Parameters<FindCratesParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let limit = params.limit.unwrap_or(10).clamp(1, 50);
+        match crates_io::search_crates(&self.http_client, &params.query, limit).await {
+            Ok((crates, total)) => {
+                let text = render::render_crate_search(&params.query, &crates, total);
+                Ok(CallToolResult::success(vec![Content::text(text)]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "reverse_dependencies",
+        description = "List the most downloaded crates on crates.io that depend on a crate, with the version requirement each declares. Useful for judging how widely a crate is trusted and finding its integration crates."
+    )]
+    async fn reverse_dependencies(
+        &self,
+        Parameters(params): Parameters<ReverseDependenciesParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let limit = params.limit.unwrap_or(20).clamp(1, 100);
+        match crates_io::reverse_dependencies(&self.http_client, &params.crate_name, limit).await {
+            Ok((dependents, total)) => {
+                let text =
+                    render::render_reverse_dependencies(&params.crate_name, &dependents, total);
+                Ok(CallToolResult::success(vec![Content::text(text)]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "upgrade_report",
+        description = "Show what changed since the crate version pinned in Cargo.lock (or the given version): newer releases, yanked status, and the public API added, removed, or changed in the newest release."
+    )]
+    async fn upgrade_report(
+        &self,
+        Parameters(params): Parameters<CrateParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let pinned = self.resolve_version(&params.crate_name, params.version.as_deref());
+        let Ok(pinned_semver) = Version::parse(&pinned) else {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "No pinned version of {} to compare: pass `version`, or run the server \
+                 from a project whose Cargo.lock includes the crate.",
+                params.crate_name
+            ))]));
+        };
+        let versions = match crates_io::crate_versions(&self.http_client, &params.crate_name).await
+        {
+            Ok(versions) => versions,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        };
+
+        let pinned_info = versions.iter().find(|v| v.num == pinned);
+        let newer = crates_io::newer_versions(&versions, &pinned_semver);
+        let target = crates_io::upgrade_target(&newer, &pinned_semver);
+        let diff = match target {
+            Some(target) => Some(
+                self.api_diff(&params.crate_name, &pinned, &target.num)
+                    .await,
+            ),
+            None => None,
+        };
+        let text = render::render_upgrade_report(
+            &params.crate_name,
+            &pinned,
+            pinned_info,
+            &newer,
+            target,
+            diff,
+            self.workspace().as_ref().and_then(|ws| ws.toolchain()),
+        );
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        name = "docs_build_info",
+        description = "Show how docs.rs built a crate version's documentation: build status, rustc and docs.rs versions, target triple, rustdoc JSON format, and default vs. other features. Use this to understand why an item is missing from the docs."
+    )]
+    async fn docs_build_info(
+        &self,
+        Parameters(params): Parameters<CrateParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        if version == LOCAL_VERSION {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "{} is a local package; its docs are built locally, not on docs.rs.",
+                params.crate_name
+            ))]));
+        }
+
+        let (builds, index, versions) = tokio::join!(
+            fetch_builds(&self.http_client, &params.crate_name, &version),
+            self.get_or_load_index(&params.crate_name, &version),
+            crates_io::crate_versions(&self.http_client, &params.crate_name),
+        );
+        let versions = versions.unwrap_or_default();
+        let text = render::render_build_info(
+            &params.crate_name,
+            &version,
+            builds.map_err(|e| e.to_string()),
+            index.as_deref().map_err(|e| e.to_string()),
+            crates_io::find_version(&versions, &version),
+        );
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        name = "list_doctests",
+        description = "Collect the code examples (doctests) from a crate's documentation, grouped by item, optionally under a module or type path and filtered by kind (run, no_run, ignore, ...). Use this to find working code that exercises an API."
+    )]
+    async fn list_doctests(
+        &self,
+        Parameters(params): Parameters<ListDoctestsParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        let limit = params.limit.unwrap_or(20).min(200);
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let groups = index.doctests(
+                    params.item_path.as_deref(),
+                    params.mode.map(DoctestModeFilter::mode),
+                );
+                let more_token = (groups.len() > limit).then(|| {
+                    self.pager
+                        .stash(render::render_doctest_groups(&groups[limit..]))
+                });
+                let text = render::render_doctests(&index, &groups, limit, more_token.as_deref());
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "get_crate_examples",
+        description = "List the programs in a crate's examples/ directory, or return the source of one. Examples are end-to-end usage references that rustdoc JSON does not include."
+    )]
+    async fn get_crate_examples(
+        &self,
+        Parameters(params): Parameters<GetCrateExamplesParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.load_examples(&params.crate_name, &version).await {
+            Ok((version, files)) => {
+                let text = render::render_examples(
+                    &params.crate_name,
+                    &version,
+                    &files,
+                    params.example.as_deref(),
+                );
+                Ok(CallToolResult::success(vec![Content::text(
+                    self.pager.paginate(text, MAX_RESPONSE_CHARS),
+                )]))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "resolve_snippet",
+        description = "Resolve every dependency path referenced by a block of Rust code (use declarations, type paths, calls like `Mutex::new`) and return the signature and summary of each, in one call instead of many lookups."
+    )]
+    async fn resolve_snippet(
+        &self,
+        Parameters(params): Parameters<ResolveSnippetParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let paths = extract_paths(&params.code);
+        if paths.is_empty() {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No paths into dependency crates found in the snippet.".to_string(),
+            )]));
+        }
+
+        let mut crates: Vec<(&str, Vec<&str>)> = Vec::new();
+        for path in paths.iter().take(MAX_SNIPPET_PATHS) {
+            match crates.iter_mut().find(|(name, _)| *name == path.crate_name) {
+                Some((_, paths)) => paths.push(&path.path),
+                None => crates.push((&path.crate_name, vec![&path.path])),
+            }
+        }
+
+        let mut parts = vec![format!(
+            "## Snippet references ({} paths)\n",
+            paths.len().min(MAX_SNIPPET_PATHS)
+        )];
+        for (segment, crate_paths) in crates {
+            let Some(crate_name) = self.dependency_name(segment) else {
+                parts.push(format!(
+                    "### {segment}\n\nNot a dependency in Cargo.lock: {}\n",
+                    crate_paths.join(", ")
+                ));
+                continue;
+            };
+            let version = self.resolve_version(&crate_name, None);
+            match self.get_or_load_index(&crate_name, &version).await {
+                Ok(index) => {
+                    // Paths rooted at an alias are looked up under the crate's own name
+                    let lookup_paths: Vec<String> = crate_paths
+                        .iter()
+                        .map(|path| {
+                            replace_path_root(path, segment, &crate_name)
+                                .unwrap_or_else(|| path.to_string())
+                        })
+                        .collect();
+                    let references: Vec<(&str, Reference<'_>)> = crate_paths
+                        .iter()
+                        .zip(&lookup_paths)
+                        .map(|(path, lookup)| (*path, index.resolve_reference(lookup)))
+                        .collect();
+                    parts.push(render::render_snippet_references(&index, &references));
+                }
+                Err(e) => parts.push(format!("### {crate_name}\n\n{e}\n")),
+            }
+        }
+        Ok(CallToolResult::success(vec![Content::text(
+            parts.join("\n"),
+        )]))
+    }
+
+    #[tool(
+        name = "which_crate_defines",
+        description = "Find which dependency defines a type, trait, function, or macro from its bare name (e.g. `DateTime`, `Deserializer`), with its path. Searches loaded crates first, then loads Cargo.lock crates (direct dependencies first) a few at a time; call again to search more."
+    )]
+    async fn which_crate_defines(
+        &self,
+        Parameters(params): Parameters<WhichCrateDefinesParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let mut indexes: Vec<Arc<CrateIndex>> = self.cache.read().await.values().cloned().collect();
+        indexes.sort_by(|a, b| (&a.crate_name, &a.version).cmp(&(&b.crate_name, &b.version)));
+        let mut found = indexes
+            .iter()
+            .any(|index| !index.find_definitions(&params.name).is_empty());
+
+        // Lockfile crates not loaded yet, direct dependencies first
+        let mut unloaded: Vec<String> = Vec::new();
+        if let Some(lock) = self.cargo_lock() {
+            let mut rest: Vec<&str> = lock.crate_names().collect();
+            rest.sort_unstable();
+            let candidates = lock
+                .direct_dependencies()
+                .iter()
+                .map(String::as_str)
+                .chain(rest);
+            for name in candidates {
+                let normalized = name.replace('-', "_");
+                let loaded = indexes
+                    .iter()
+                    .any(|index| index.crate_name == normalized || index.package_name == name);
+                if !loaded && !unloaded.iter().any(|n| n == name) {
+                    unloaded.push(name.to_string());
+                }
+            }
+        }
+
+        let mut failed = Vec::new();
+        let mut loads = 0;
+        while !found && loads < MAX_DEFINES_LOADS && !unloaded.is_empty() {
+            let crate_name = unloaded.remove(0);
+            loads += 1;
+            let version = self.resolve_version(&crate_name, None);
+            match self.get_or_load_index(&crate_name, &version).await {
+                Ok(index) => {
+                    found = !index.find_definitions(&params.name).is_empty();
+                    indexes.push(index);
+                }
+                Err(e) => {
+                    tracing::debug!("which_crate_defines could not load {crate_name}: {e}");
+                    failed.push(crate_name);
+                }
+            }
+        }
+
+        let definitions: Vec<(&CrateIndex, Vec<&IndexedItem>)> = indexes
+            .iter()
+            .map(|index| (index.as_ref(), index.find_definitions(&params.name)))
+            .filter(|(_, items)| !items.is_empty())
+            .collect();
+        let text = render::render_definitions(
+            &params.name,
+            &definitions,
+            indexes.len(),
+            &failed,
+            unloaded.len(),
+        );
+        Ok(CallToolResult::success(vec![Content::text(
+            self.pager.paginate(text, MAX_RESPONSE_CHARS),
+        )]))
+    }
+
+    #[tool(
+        name = "explain_bound_error",
+        description = "Explain a rustc trait-bound error (E0277, e.g. \"the trait bound `T: Serialize` is not satisfied\"): returns the trait's docs and required methods, its implementors for the types in the error, and feature flags that may provide the missing impl."
+    )]
+    async fn explain_bound_error(
+        &self,
+        Parameters(params): Parameters<ExplainBoundErrorParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let Some(error) = parse_bound_error(&params.error) else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "No trait-bound error (E0277) found in the message.".to_string(),
+            )]));
+        };
+
+        let trait_crate = match params.crate_name.clone() {
+            Some(name) => Some(name),
+            None => error.trait_crate().and_then(|c| self.dependency_name(c)),
+        };
+        let index = match &trait_crate {
+            Some(name) => {
+                let version = self.resolve_version(name, params.version.as_deref());
+                Some(
+                    self.get_or_load_index(name, &version)
+                        .await
+                        .map_err(|e| e.to_string()),
+                )
+            }
+            None => None,
+        };
+        let trait_docs = match &index {
+            Some(Ok(index)) => resolve_or_explain(index, error.trait_item_path())
+                .map(|item| (index.as_ref(), item)),
+            Some(Err(e)) => Err(e.clone()),
+            None => Err(format!(
+                "`{}` is not qualified with a dependency crate; pass `crate_name` if it is defined in one.",
+                error.trait_path
+            )),
+        };
+
+        // The type's crate may gate its impl of the trait behind a feature named
+        // after the trait's crate (e.g. chrono's `serde` feature)
+        let type_crate = error.type_crate().and_then(|c| self.dependency_name(c));
+        let versions = match (&type_crate, &trait_crate) {
+            (Some(type_crate), Some(trait_crate)) if type_crate != trait_crate => {
+                crates_io::crate_versions(&self.http_client, type_crate)
+                    .await
+                    .unwrap_or_default()
+            }
+            _ => Vec::new(),
+        };
+        let mut features: Vec<(String, Vec<&str>)> = Vec::new();
+        if let (Some(type_crate), Some(trait_crate)) = (type_crate, &trait_crate) {
+            let version = self.resolve_version(&type_crate, None);
+            if let Some(info) = crates_io::find_version(&versions, &version) {
+                let matching = matching_features(&info.features, trait_crate);
+                if !matching.is_empty() {
+                    features.push((type_crate, matching));
+                }
+            }
+        }
+
+        let text = render::render_bound_error(&error, trait_docs, &features, &self.render_options);
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        name = "list_cached_crates",
+        description = "List the crate versions whose docs are already loaded in memory or cached on disk, with size and age. Lookups in these crates need no download."
+    )]
+    async fn list_cached_crates(&self) -> Result<CallToolResult, rmcp::ErrorData> {
+        let mut memory: Vec<(String, String, usize)> = self
+            .cache
+            .read()
+            .await
+            .iter()
+            .map(|((name, version), index)| (name.clone(), version.clone(), index.items.len()))
+            .collect();
+        memory.sort();
+        let disk = match &self.disk_cache {
+            Some(disk) => {
+                let disk = Arc::clone(disk);
+                Some(
+                    tokio::task::spawn_blocking(move || disk.entries())
+                        .await
+                        .unwrap_or_default(),
+                )
+            }
+            None => None,
+        };
+        let text = render::render_cached_crates(&memory, disk.as_deref(), SystemTime::now());
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        name = "evict_crate",
+        description = "Drop a crate's cached docs from memory and disk: one version, or every version if `version` is omitted. The next lookup downloads them again."
+    )]
+    async fn evict_crate(
+        &self,
+        Parameters(params): Parameters<EvictCrateParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let (memory, disk) = self
+            .evict(&params.crate_name, params.version.as_deref())
+            .await;
+        let target = match &params.version {
+            Some(version) => format!("{} {version}", params.crate_name),
+            None => params.crate_name.clone(),
+        };
+        let text = if memory + disk == 0 {
+            format!("Nothing cached for {target}.")
+        } else {
+            format!("Evicted {target}: {memory} in-memory and {disk} on-disk entries removed.")
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        name = "refresh_crate",
+        description = "Drop a crate version's cached docs and load them again, e.g. after docs.rs rebuilt the crate or a local package changed. The version is resolved as in the other tools."
+    )]
+    async fn refresh_crate(
+        &self,
+        Parameters(params): Parameters<CrateParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        self.evict(&params.crate_name, Some(&version)).await;
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => Ok(CallToolResult::success(vec![Content::text(format!(
+                "Reloaded {} v{}: {} items.",
+                index.crate_name,
+                index.version,
+                index.items.len()
+            ))])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "reload_project",
+        description = "Re-read the project's Cargo.lock and Cargo.toml files (e.g. after `cargo update` or adding a dependency) and report which pinned versions and local packages changed."
+    )]
+    async fn reload_project(&self) -> Result<CallToolResult, rmcp::ErrorData> {
+        let (dir, run_cargo) = (self.project_dir.clone(), !self.sandbox);
+        let project =
+            match tokio::task::spawn_blocking(move || Project::discover(&dir, run_cargo)).await {
+                Ok(project) => project,
+                Err(e) => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Project discovery failed: {e}"
+                    ))]));
+                }
+            };
+
+        let old_lock = self.cargo_lock().unwrap_or_default();
+        let new_lock = project.cargo_lock.clone().unwrap_or_default();
+        let lock_diff = old_lock.diff(&new_lock);
+        let package_names = |ws: Option<Arc<WorkspaceIndex>>| -> Vec<String> {
+            ws.map(|ws| ws.packages().iter().map(|p| p.name.clone()).collect())
+                .unwrap_or_default()
+        };
+        let old_packages = package_names(self.workspace());
+        let new_packages = package_names(project.workspace.clone());
+        let added: Vec<&str> = new_packages
+            .iter()
+            .filter(|p| !old_packages.contains(p))
+            .map(String::as_str)
+            .collect();
+        let removed: Vec<&str> = old_packages
+            .iter()
+            .filter(|p| !new_packages.contains(p))
+            .map(String::as_str)
+            .collect();
+
+        let has_lock = project.cargo_lock.is_some();
+        *self.project.write().unwrap_or_else(|e| e.into_inner()) = project;
+        // Manifests may have changed, so local packages are rebuilt on next use
+        self.cache
+            .write()
+            .await
+            .retain(|(_, version), _| !version.starts_with(LOCAL_VERSION));
+
+        let text = render::render_project_reload(has_lock, &lock_diff, &added, &removed);
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        name = "document_workspace",
+        description = "Build rustdoc JSON for the current workspace's members (within the client's roots) from their sources as they are now, index it, and report which crates became queryable. Use this after editing local code to look up its current API."
+    )]
+    async fn document_workspace(
+        &self,
+        Parameters(params): Parameters<DocumentWorkspaceParams>,
+        peer: Peer<RoleServer>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        if self.sandbox {
+            return Ok(CallToolResult::error(vec![Content::text(
+                crate::error::Error::DisabledByPolicy("local documentation builds").to_string(),
+            )]));
+        }
+        let Some(ws) = self.workspace() else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "The server was not started in a Cargo workspace, so there are no members to document."
+                    .to_string(),
+            )]));
+        };
+        let roots = client_roots(&peer).await;
+        let normalize = |name: &str| name.replace('-', "_");
+
+        let mut members = Vec::new();
+        for package in ws.packages() {
+            if package.source != PackageSource::Member
+                || params.packages.as_ref().is_some_and(|wanted| {
+                    !wanted
+                        .iter()
+                        .any(|w| normalize(w) == normalize(&package.name))
+                })
+            {
+                continue;
+            }
+            let dir = package.manifest_path.parent().unwrap_or(ws.root());
+            let docs = if package.lib_name.is_none() {
+                MemberDocs::Skipped("no library target".to_string())
+            } else if let Some(roots) = &roots
+                && !roots.iter().any(|root| dir.starts_with(root))
+            {
+                MemberDocs::Skipped("outside the client's roots".to_string())
+            } else {
+                // Drop the indexed copy so edits made since are documented
+                self.cache.write().await.retain(|(name, version), _| {
+                    !(normalize(name) == normalize(&package.name)
+                        && version.starts_with(LOCAL_VERSION))
+                });
+                match self.get_or_load_index(&package.name, LOCAL_VERSION).await {
+                    Ok(index) => MemberDocs::Indexed(index),
+                    Err(e) => MemberDocs::Failed(e.to_string()),
+                }
+            };
+            members.push((package.name.clone(), docs));
+        }
+
+        if members.is_empty() {
+            let text = match &params.packages {
+                Some(wanted) => format!("No workspace member named {}.", wanted.join(", ")),
+                None => "The workspace has no members.".to_string(),
+            };
+            return Ok(CallToolResult::error(vec![Content::text(text)]));
+        }
+        Ok(CallToolResult::success(vec![Content::text(
+            render::render_workspace_docs(&members),
+        )]))
+    }
+
+    #[tool(
+        name = "set_default_version",
+        description = "Set the version of a crate to use for the rest of this session when a call omits `version`, overriding Cargo.lock (e.g. to study tokio 0.2). Omit `version` to go back to automatic resolution."
+    )]
+    async fn set_default_version(
+        &self,
+        Parameters(params): Parameters<SetDefaultVersionParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let key = params.crate_name.replace('-', "_");
+        let mut overrides = self
+            .version_overrides
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        let text = match params.version {
+            Some(version) => {
+                let text = format!(
+                    "{} now defaults to v{version} for this session.",
+                    params.crate_name
+                );
+                overrides.insert(key, version);
+                text
+            }
+            None => match overrides.remove(&key) {
+                Some(previous) => format!(
+                    "Cleared the session default v{previous} of {}; versions resolve automatically again.",
+                    params.crate_name
+                ),
+                None => format!("{} has no session default version.", params.crate_name),
+            },
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        name = "set_crate_features",
+        description = "Build a crate's docs locally with a chosen feature set (like `--features rt,macros --no-default-features`) for the rest of this session, so feature-gated items docs.rs didn't build become visible. Requires a nightly toolchain. Omit `features` and `no_default_features` to go back to the docs.rs build."
+    )]
+    async fn set_crate_features(
+        &self,
+        Parameters(params): Parameters<SetCrateFeaturesParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        if self.sandbox {
+            let e = crate::error::Error::DisabledByPolicy("local documentation builds");
+            return Ok(CallToolResult::error(vec![Content::text(e.to_string())]));
+        }
+        let key = params.crate_name.replace('-', "_");
+        let mut overrides = self
+            .feature_overrides
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        let no_default_features = params.no_default_features.unwrap_or(false);
+        let text = if params.features.is_none() && !no_default_features {
+            match overrides.remove(&key) {
+                Some(previous) => format!(
+                    "Cleared {} ({}); its docs come from docs.rs again.",
+                    params.crate_name,
+                    previous.describe()
+                ),
+                None => format!("{} has no custom feature set.", params.crate_name),
+            }
+        } else {
+            let features = FeatureSet::parse(
+                params.features.as_deref().unwrap_or(""),
+                no_default_features,
+            );
+            let text = format!(
+                "{} will be documented locally with {} for this session.",
+                params.crate_name,
+                features.describe()
+            );
+            overrides.insert(key, features);
+            text
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        name = "read_more",
+        description = "Get the next part of a truncated tool response. Pass the `token` the truncated response ended with; each token can be used once."
+    )]
+    async fn read_more(
+        &self,
+        Parameters(params): Parameters<ReadMoreParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        match self.pager.take(&params.token) {
+            Some(text) => Ok(CallToolResult::success(vec![Content::text(text)])),
+            None => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unknown or already used token `{}`. Repeat the original call to get a new one.",
+                params.token
+            ))])),
+        }
+    }
+
+    #[tool(
+        name = "api_surface",
+        description = "Summarize the size and shape of a Rust crate's public API: items per kind, generic items, inherent methods, unsafe surface, re-exports, deprecated and #[non_exhaustive] items. Useful for dependency review and judging how careful an upgrade needs to be."
+    )]
+    async fn api_surface(
+        &self,
+        Parameters(params): Parameters<CrateParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let text = render::render_api_surface(&index, &index.surface_report());
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "crate_stats",
+        description = "Show statistics for a crate's docs: item counts per kind, the largest modules, documentation coverage, impl blocks, and index parse time."
+    )]
+    async fn crate_stats(
+        &self,
+        Parameters(params): Parameters<CrateParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let stats = index.stats(MAX_STATS_MODULES);
+                let text = render::render_crate_stats(&index, &stats);
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "crate_tree",
+        description = "Show a crate's whole module hierarchy as an indented tree, optionally with each module's item counts per kind. A one-call overview of how a crate is organized."
+    )]
+    async fn crate_tree(
+        &self,
+        Parameters(params): Parameters<CrateTreeParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let include_hidden = params.include_hidden.unwrap_or(false);
+                let text = match index.module_tree(params.module_path.as_deref(), include_hidden) {
+                    Some(tree) => render::render_module_tree(
+                        &index,
+                        &tree,
+                        params.item_counts.unwrap_or(false),
+                    ),
+                    None => {
+                        let path = params.module_path.as_deref().unwrap_or_default();
+                        match index.get_item_by_path(path) {
+                            Some(item) => render::render_not_a_module(item),
+                            None => render::render_not_found(&index, path),
+                        }
+                    }
+                };
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        name = "lookup_impl_block",
+        description = "Look up trait implementations for a type, or implementors of a trait. Shows method signatures and documentation."
+    )]
+    async fn lookup_impl_block(
+        &self,
+        Parameters(params): Parameters<LookupImplBlockParams>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let version = self.resolve_version(&params.crate_name, params.version.as_deref());
+        match self.get_or_load_index(&params.crate_name, &version).await {
+            Ok(index) => {
+                let mut impls = index.get_impl_blocks(&params.item_path);
+                let sources = self
+                    .std_primitive_sources(&index, &params.item_path, &impls)
+                    .await;
+                for (name, source) in &sources {
+                    let inherent = source.get_impl_blocks(name);
+                    impls.extend(inherent.into_iter().filter(|b| b.trait_name.is_none()));
+                }
+                let text = match index.resolve_item(&params.item_path) {
+                    ItemLookup::Ambiguous(candidates) if impls.is_empty() => {
+                        render::render_ambiguous(&index, &params.item_path, &candidates)
+                    }
+                    _ => {
+                        let options = params.render.apply(self.render_options);
+                        let text = render::render_impls(
+                            &index,
+                            &params.item_path,
+                            &impls,
+                            &self.provided_trait_methods(&index, &impls).await,
+                            &options,
+                        );
+                        self.templates.render_impls(
+                            &index,
+                            &params.item_path,
+                            &impls,
+                            &options,
+                            text,
+                        )
+                    }
+                };
+                Ok(self.index_result(&index, text))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+}
+
+// ========== Prompts ==========
+
+/// Prompts gather documentation with the same lookups as the tools and hand it
+/// to the model along with the task, so a workflow takes one request.
+#[prompt_router]
+impl RustDocsServer {
+    #[prompt(
+        name = "explain_api",
+        description = "Explain how to use an item of a crate, based on its docs and implementations."
+    )]
+    async fn explain_api_prompt(
+        &self,
+        Parameters(params): Parameters<ExplainApiPromptParams>,
+    ) -> Result<GetPromptResult, rmcp::ErrorData> {
+        let docs = self
+            .item_context(
+                &params.crate_name,
+                &params.item_path,
+                params.version.as_deref(),
+            )
+            .await?;
+        let task = format!(
+            "Explain how to use `{}` from the `{}` crate: what it is for, how to create and \
+             use it, and any pitfalls its documentation mentions. Include a short example.",
+            params.item_path, params.crate_name
+        );
+        Ok(prompt_result(
+            format!("Explain {}::{}", params.crate_name, params.item_path),
+            task,
+            docs,
+        ))
+    }
+
+    #[prompt(
+        name = "compare_types",
+        description = "Compare two types of a crate and when to use each, based on their docs and implementations."
+    )]
+    async fn compare_types_prompt(
+        &self,
+        Parameters(params): Parameters<CompareTypesPromptParams>,
+    ) -> Result<GetPromptResult, rmcp::ErrorData> {
+        let version = params.version.as_deref();
+        let docs_a = self
+            .item_context(&params.crate_name, &params.type_a, version)
+            .await?;
+        let docs_b = self
+            .item_context(&params.crate_name, &params.type_b, version)
+            .await?;
+        let task = format!(
+            "Compare `{}` and `{}` from the `{}` crate: how they differ in purpose, API, \
+             trait implementations, and performance or thread-safety characteristics, and \
+             when to choose each.",
+            params.type_a, params.type_b, params.crate_name
+        );
+        Ok(prompt_result(
+            format!("Compare {} and {}", params.type_a, params.type_b),
+            task,
+            format!("{docs_a}\n\n{docs_b}"),
+        ))
+    }
+
+    #[prompt(
+        name = "migration_guide",
+        description = "Suggest how to migrate code from one version of a crate to another, based on the public API changes between them."
+    )]
+    async fn migration_guide_prompt(
+        &self,
+        Parameters(params): Parameters<MigrationGuidePromptParams>,
+    ) -> Result<GetPromptResult, rmcp::ErrorData> {
+        let from = self.resolve_version(&params.crate_name, params.from_version.as_deref());
+        if Version::parse(&from).is_err() {
+            return Err(rmcp::ErrorData::invalid_params(
+                format!(
+                    "No pinned version of {} to migrate from: pass `from_version`.",
+                    params.crate_name
+                ),
+                None,
+            ));
+        }
+        let diff = self
+            .api_diff(&params.crate_name, &from, &params.to_version)
+            .await
+            .map_err(|e| rmcp::ErrorData::invalid_params(e, None))?;
+        let changes = render::render_api_diff(&params.crate_name, &from, &params.to_version, &diff);
+        let task = format!(
+            "Suggest how to migrate code using `{}` from v{from} to v{}. For each removed or \
+             changed item, explain what replaces it and show the code change. Call out \
+             breaking changes first.",
+            params.crate_name, params.to_version
+        );
+        Ok(prompt_result(
+            format!(
+                "Migrate {} v{from} → v{}",
+                params.crate_name, params.to_version
+            ),
+            task,
+            changes,
+        ))
+    }
+}
+
+/// Local directories the client exposes as MCP roots. `None` if it doesn't support
+/// roots, lists none, or doesn't answer within `ROOTS_TIMEOUT`.
+async fn client_roots(peer: &Peer<RoleServer>) -> Option<Vec<PathBuf>> {
+    peer.peer_info()?.capabilities.roots.as_ref()?;
+    let roots = match tokio::time::timeout(ROOTS_TIMEOUT, peer.list_roots()).await {
+        Ok(Ok(result)) => result.roots,
+        Ok(Err(e)) => {
+            tracing::debug!("Listing the client's roots failed: {e}");
+            return None;
+        }
+        Err(_) => {
+            tracing::debug!("The client didn't list its roots in time");
+            return None;
+        }
+    };
+    let paths: Vec<PathBuf> = roots
+        .iter()
+        .filter_map(|root| file_uri_path(&root.uri))
+        .collect();
+    (!paths.is_empty()).then_some(paths)
+}
+
+/// A single user message stating `task`, followed by the gathered `context`.
+fn prompt_result(description: String, task: String, context: String) -> GetPromptResult {
+    GetPromptResult {
+        description: Some(description),
+        messages: vec![PromptMessage::new_text(
+            PromptMessageRole::User,
+            format!("{task}\n\n---\n\n{context}"),
+        )],
+    }
+}
+
+/// Resolve `item_path` to a single item, or render the ambiguity or not-found
+/// response to return instead.
+fn resolve_or_explain<'a>(
+    index: &'a CrateIndex,
+    item_path: &str,
+) -> Result<&'a IndexedItem, String> {
+    match index.resolve_item(item_path) {
+        ItemLookup::Found(item) => Ok(item),
+        ItemLookup::Ambiguous(candidates) => {
+            Err(render::render_ambiguous(index, item_path, &candidates))
+        }
+        ItemLookup::NotFound => Err(render::render_not_found(index, item_path)),
+    }
+}
+
+#[prompt_handler]
 impl ServerHandler for RustDocsServer {
+    /// Run a tool from the router inside a `tool` span, which the fetch, parse, search,
+    /// and render spans of the call nest under.
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        let mut request = request;
+        if let Some(arguments) = request.arguments.as_mut() {
+            self.resolve_alias_arguments(arguments);
+        }
+        let crate_name = request
+            .arguments
+            .as_ref()
+            .and_then(|arguments| arguments.get("crate_name"))
+            .and_then(|name| name.as_str())
+            .unwrap_or_default();
+        let format = match request
+            .arguments
+            .as_ref()
+            .and_then(|arguments| arguments.get("format"))
+        {
+            Some(format) => FormatParams::deserialize(serde_json::json!({ "format": format }))
+                .map_err(|e| rmcp::ErrorData::invalid_params(format!("format: {e}"), None))?
+                .format
+                .unwrap_or(self.format),
+            None => self.format,
+        };
+        let span = tracing::info_span!("tool", name = %request.name, crate_name);
+        let Some(audit_log) = &self.audit_log else {
+            let call = ToolCallContext::new(self, request, context);
+            let result = self.tool_router.call(call).instrument(span).await;
+            return result.map(|result| format.apply(result));
+        };
+
+        let started = Instant::now();
+        let (tool, arguments) = (request.name.clone(), request.arguments.clone());
+        let call = ToolCallContext::new(self, request, context);
+        let (result, loads) = collect_loads(self.tool_router.call(call).instrument(span)).await;
+        let is_error = result
+            .as_ref()
+            .map_or(true, |result| result.is_error == Some(true));
+        audit_log.record(&AuditEntry::new(
+            &tool,
+            arguments.as_ref(),
+            started.elapsed(),
+            is_error,
+            loads,
+        ));
+        result.map(|result| format.apply(result))
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, rmcp::ErrorData> {
+        let format_param = schema_for_type::<FormatParams>();
+        let tools = self
+            .tool_router
+            .list_all()
+            .into_iter()
+            .map(|mut tool| {
+                tool.input_schema = Arc::new(merge_schema(&tool.input_schema, &format_param));
+                tool
+            })
+            .collect();
+        Ok(ListToolsResult {
+            tools,
+            meta: None,
+            next_cursor: None,
+        })
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
-            instructions: Some(
-                "Rust documentation server. Fetches and serves crate documentation from docs.rs. \
-                 Use lookup_crate_items to explore crate structure, lookup_item for detailed docs, \
-                 search_crate to find items, and lookup_impl_block for implementations."
-                    .into(),
+            instructions: Some(self.instructions()),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_prompts()
+                .enable_completions()
+                .build(),
+            ..Default::default()
+        }
+    }
+
+    /// Complete `crate_name` from Cargo.lock, local packages, and loaded crates, and
+    /// `item_path`/`module_path`/`type_a`/`type_b` from the index of the crate named
+    /// by the `crate_name` argument.
+    async fn complete(
+        &self,
+        request: CompleteRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CompleteResult, rmcp::ErrorData> {
+        let arguments = request
+            .context
+            .and_then(|c| c.arguments)
+            .unwrap_or_default();
+        let value = &request.argument.value;
+        let mut values = match request.argument.name.as_str() {
+            "crate_name" => self.complete_crate_names(value).await,
+            name @ ("item_path" | "module_path" | "type_a" | "type_b") => {
+                match arguments.get("crate_name") {
+                    Some(crate_name) => {
+                        self.complete_item_paths(
+                            crate_name,
+                            arguments.get("version").map(String::as_str),
+                            value,
+                            name == "module_path",
+                        )
+                        .await
+                    }
+                    None => Vec::new(),
+                }
+            }
+            _ => Vec::new(),
+        };
+        let total = values.len();
+        values.truncate(CompletionInfo::MAX_VALUES);
+        let completion = CompletionInfo::with_pagination(
+            values,
+            Some(total as u32),
+            total > CompletionInfo::MAX_VALUES,
+        )
+        .map_err(|e| rmcp::ErrorData::internal_error(e, None))?;
+        Ok(CompleteResult { completion })
+    }
+}
+
+impl RustDocsServer {
+    /// The current project's Cargo.lock, if one was found.
+    fn cargo_lock(&self) -> Option<Arc<CargoLockIndex>> {
+        self.project
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .cargo_lock
+            .clone()
+    }
+
+    /// The current project's locally documented packages, if in a Cargo workspace.
+    fn workspace(&self) -> Option<Arc<WorkspaceIndex>> {
+        self.project
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .workspace
+            .clone()
+    }
+
+    /// The current project's Rust toolchain, if `rustc` could be run.
+    fn toolchain(&self) -> Option<Arc<RustToolchain>> {
+        self.project
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .toolchain
+            .clone()
+    }
+
+    /// Look up a re-exported item in the crate defining it, at the version Cargo.lock
+    /// pins (or the latest), falling back to a pointer if that crate can't be loaded.
+    async fn follow_reexport(&self, reexport: &ForeignReexport, options: &RenderOptions) -> String {
+        let crate_name = self
+            .cargo_lock()
+            .and_then(|lock| lock.package_name(&reexport.crate_name).map(str::to_string))
+            .unwrap_or_else(|| reexport.crate_name.clone());
+        let version = self.resolve_version(&crate_name, None);
+        match self.get_or_load_index(&crate_name, &version).await {
+            Ok(target) => match target.resolve_item(&reexport.target_path) {
+                ItemLookup::Found(item) => format!(
+                    "{}\n{}",
+                    render::render_reexport_note(reexport, &target.version),
+                    render::render_item(&target, item, options)
+                ),
+                _ => render::render_foreign_reexport(reexport, None),
+            },
+            Err(e) => render::render_foreign_reexport(reexport, Some(&e.to_string())),
+        }
+    }
+
+    /// Resolve the version to use: explicit > session default (`set_default_version`) >
+    /// local package ("local") > Cargo.lock > "latest"
+    ///
+    /// Standard library crates default to the docs of the project's toolchain instead.
+    ///
+    /// Local packages (workspace members, path and git dependencies) take precedence over
+    /// Cargo.lock so git forks aren't served from the wrong crates.io release.
+    fn resolve_version(&self, crate_name: &str, explicit: Option<&str>) -> String {
+        if let Some(v) = explicit {
+            return self.resolve_from_lock(crate_name, v);
+        }
+        if let Some(v) = self
+            .version_overrides
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&crate_name.replace('-', "_"))
+        {
+            tracing::debug!("Resolved {crate_name} from the session default: {v}");
+            return self.resolve_from_lock(crate_name, v);
+        }
+        if is_std_crate(crate_name)
+            && let Some(toolchain) = self.toolchain()
+        {
+            let version = toolchain.std_version();
+            tracing::debug!("Resolved {crate_name} from the Rust toolchain: {version}");
+            return version;
+        }
+        if let Some(ref ws) = self.workspace()
+            && ws.package(crate_name).is_some()
+        {
+            tracing::debug!("Resolved {crate_name} as a local package");
+            return LOCAL_VERSION.to_string();
+        }
+        if let Some(ref lock) = self.cargo_lock()
+            && let Some(v) = lock.get_version(crate_name)
+        {
+            tracing::debug!("Resolved {crate_name} version from Cargo.lock: {v}");
+            return v.to_string();
+        }
+        "latest".to_string()
+    }
+
+    /// Resolve a "from-lock:0.8" version to the matching Cargo.lock version. Without a
+    /// match the prefix itself is used, which docs.rs treats as a semver requirement
+    /// (and which, like "latest", isn't read back from the disk cache).
+    fn resolve_from_lock(&self, crate_name: &str, version: &str) -> String {
+        let Some(prefix) = version.strip_prefix(FROM_LOCK_PREFIX) else {
+            return version.to_string();
+        };
+        match self
+            .cargo_lock()
+            .as_ref()
+            .and_then(|lock| lock.select(crate_name, prefix).map(str::to_string))
+        {
+            Some(locked) => {
+                tracing::debug!("Resolved {crate_name} {version} from Cargo.lock: {locked}");
+                locked
+            }
+            None => {
+                tracing::debug!("No locked {crate_name} version matches {prefix}");
+                prefix.to_string()
+            }
+        }
+    }
+
+    /// Server instructions: how to use the tools, followed by what the server knows
+    /// about the project it was started in, so the model can skip probing.
+    fn instructions(&self) -> String {
+        let mut text = String::from(
+            "Rust documentation server. Fetches and serves crate documentation from docs.rs. \
+             Use lookup_crate_items to explore crate structure, lookup_item for detailed docs, \
+             search_crate to find items, list_items_by_kind to list e.g. every trait, \
+             lookup_impl_block for implementations, and find_crates to discover crates \
+             on crates.io.",
+        );
+
+        if let Some(ws) = &self.workspace() {
+            let members: Vec<&str> = ws
+                .packages()
+                .iter()
+                .filter(|p| p.source == PackageSource::Member)
+                .map(|p| p.name.as_str())
+                .collect();
+            if !members.is_empty() {
+                text.push_str(&format!(
+                    "\n\nProject: {} (documented from local sources).",
+                    members.join(", ")
+                ));
+            }
+        }
+        if let Some(lock) = &self.cargo_lock() {
+            let dependencies = lock
+                .crate_names()
+                .filter(|name| {
+                    self.workspace()
+                        .as_ref()
+                        .is_none_or(|ws| ws.package(name).is_none())
+                })
+                .count();
+            if dependencies > 0 {
+                let noun = if dependencies == 1 {
+                    "dependency"
+                } else {
+                    "dependencies"
+                };
+                text.push_str(&format!(
+                    "\n\nCargo.lock pins {dependencies} {noun}; their versions are used \
+                     automatically, so `version` can usually be omitted."
+                ));
+            }
+            let duplicates: Vec<String> = lock
+                .duplicates()
+                .into_iter()
+                .map(|(name, versions)| format!("{name} ({})", versions.join(", ")))
+                .collect();
+            if !duplicates.is_empty() {
+                text.push_str(&format!(
+                    " Several versions are locked for: {}. The newest is used by default; \
+                     pass e.g. `version: \"from-lock:0.8\"` to read another.",
+                    duplicates.join(", ")
+                ));
+            }
+        }
+
+        // Cached docs of the versions the tools would resolve to load without a fetch
+        let cached: Vec<String> = self
+            .disk_cache
+            .as_ref()
+            .map(|disk| disk.entries())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|entry| match &self.cargo_lock() {
+                Some(lock) => lock
+                    .all_versions(&entry.crate_name)
+                    .contains(&entry.version),
+                None => Version::parse(&entry.version).is_ok(),
+            })
+            .map(|entry| format!("{} {}", entry.crate_name, entry.version))
+            .collect();
+        if !cached.is_empty() {
+            text.push_str(&format!(
+                "\n\nAlready cached (instant): {}",
+                cached[..cached.len().min(MAX_INSTRUCTION_CRATES)].join(", ")
+            ));
+            if cached.len() > MAX_INSTRUCTION_CRATES {
+                text.push_str(&format!(
+                    ", and {} more",
+                    cached.len() - MAX_INSTRUCTION_CRATES
+                ));
+            }
+            text.push('.');
+        }
+        text
+    }
+
+    /// Crate names starting with `prefix` (treating `-` and `_` alike): Cargo.lock
+    /// entries, local packages, and crates already loaded into the cache.
+    async fn complete_crate_names(&self, prefix: &str) -> Vec<String> {
+        let normalize = |name: &str| name.to_lowercase().replace('-', "_");
+        let prefix = normalize(prefix);
+
+        let mut names: Vec<String> = Vec::new();
+        if let Some(lock) = &self.cargo_lock() {
+            names.extend(lock.crate_names().map(str::to_string));
+        }
+        if let Some(ws) = &self.workspace() {
+            names.extend(ws.packages().iter().map(|p| p.name.clone()));
+        }
+        names.extend(self.cache.read().await.keys().map(|(name, _)| name.clone()));
+        names.retain(|name| normalize(name).starts_with(&prefix));
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Paths in an already loaded crate starting with `prefix`. Completion runs on
+    /// every keystroke, so a crate that isn't loaded yet is neither fetched nor built.
+    async fn complete_item_paths(
+        &self,
+        crate_name: &str,
+        version: Option<&str>,
+        prefix: &str,
+        modules_only: bool,
+    ) -> Vec<String> {
+        let version = self.resolve_version(crate_name, version);
+        let key = self.cache_key(crate_name, &version);
+        match self.cache.read().await.get(&key) {
+            Some(index) => index.complete_paths(prefix, modules_only),
+            None => Vec::new(),
+        }
+    }
+
+    /// An item's rendered docs followed by its impl blocks, for use as prompt context.
+    async fn item_context(
+        &self,
+        crate_name: &str,
+        item_path: &str,
+        version: Option<&str>,
+    ) -> Result<String, rmcp::ErrorData> {
+        let version = self.resolve_version(crate_name, version);
+        let index = self
+            .get_or_load_index(crate_name, &version)
+            .await
+            .map_err(|e| rmcp::ErrorData::invalid_params(e.to_string(), None))?;
+        let item = resolve_or_explain(&index, item_path)
+            .map_err(|text| rmcp::ErrorData::invalid_params(text, None))?;
+        let mut context = render::render_item(&index, item, &self.render_options);
+        let impls = index.get_impl_blocks(&item.path);
+        if !impls.is_empty() {
+            context.push_str("\n\n");
+            context.push_str(&render::render_impls(
+                &index,
+                &item.path,
+                &impls,
+                &self.provided_trait_methods(&index, &impls).await,
+                &self.render_options,
+            ));
+        }
+        Ok(context)
+    }
+
+    /// Diff the public API of two published versions of a crate.
+    async fn api_diff(
+        &self,
+        crate_name: &str,
+        old_version: &str,
+        new_version: &str,
+    ) -> Result<ApiDiff, String> {
+        let old = self
+            .get_or_load_index(crate_name, old_version)
+            .await
+            .map_err(|e| e.to_string())?;
+        let new = self
+            .get_or_load_index(crate_name, new_version)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(old.diff(&new))
+    }
+
+    /// The crate `name` stands for: its `--alias` target, else the package a project
+    /// member imports under that name with `package = "..."`. `None` if it's no alias.
+    fn crate_alias(&self, name: &str) -> Option<String> {
+        if let Some(crate_name) = self.aliases.resolve(name) {
+            return Some(crate_name.to_string());
+        }
+        self.workspace()?
+            .renamed_dependency(name)
+            .map(str::to_string)
+    }
+
+    /// Replace a `crate_name` argument that is an alias with the crate it stands for,
+    /// along with item and module paths rooted at the alias (e.g. "k8s::Api").
+    fn resolve_alias_arguments(&self, arguments: &mut JsonObject) {
+        let Some(alias) = arguments
+            .get("crate_name")
+            .and_then(|name| name.as_str())
+            .map(str::to_string)
+        else {
+            return;
+        };
+        let Some(crate_name) = self.crate_alias(&alias) else {
+            return;
+        };
+        tracing::debug!("Resolved crate alias {alias} to {crate_name}");
+        for key in ["item_path", "module_path", "type_a", "type_b"] {
+            if let Some(path) = arguments.get(key).and_then(|path| path.as_str())
+                && let Some(path) = replace_path_root(path, &alias, &crate_name)
+            {
+                arguments.insert(key.to_string(), path.into());
+            }
+        }
+        arguments.insert("crate_name".to_string(), crate_name.into());
+    }
+
+    /// The package name for a crate name as written in code (e.g. `serde_json`), if
+    /// it is a dependency, following aliases and renamed dependencies. Without a
+    /// Cargo.lock, every crate name is accepted.
+    fn dependency_name(&self, segment: &str) -> Option<String> {
+        if let Some(crate_name) = self.crate_alias(segment) {
+            return Some(crate_name);
+        }
+        if let Some(package) = self.workspace().as_ref().and_then(|ws| ws.package(segment)) {
+            return Some(package.name.clone());
+        }
+        let Some(lock) = &self.cargo_lock() else {
+            return Some(segment.to_string());
+        };
+        [segment.to_string(), segment.replace('_', "-")]
+            .into_iter()
+            .find(|name| lock.get_version(name).is_some())
+    }
+
+    /// Read a crate's example sources, with the concrete version they came from.
+    ///
+    /// Local packages are read from disk. Published crates come from their `.crate`
+    /// archive on crates.io, disk-cached under a `crate-{version}` key.
+    async fn load_examples(
+        &self,
+        crate_name: &str,
+        version: &str,
+    ) -> Result<(String, Vec<ExampleFile>), crate::error::Error> {
+        if version == LOCAL_VERSION {
+            let package = self
+                .workspace()
+                .and_then(|ws| ws.package(crate_name).cloned())
+                .ok_or_else(|| crate::error::Error::CrateNotFound(crate_name.to_string()))?;
+            let dir = package
+                .manifest_path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default();
+            let files = tokio::task::spawn_blocking(move || examples_from_dir(&dir))
+                .await
+                .map_err(|e| crate::error::Error::Other(format!("Reading examples failed: {e}")))?;
+            return Ok((package.version.clone(), files));
+        }
+
+        let version = if version == "latest" {
+            let versions = crates_io::crate_versions(&self.http_client, crate_name).await?;
+            crates_io::find_version(&versions, version)
+                .map(|v| v.num.clone())
+                .ok_or_else(|| crate::error::Error::CrateNotFound(crate_name.to_string()))?
+        } else {
+            version.to_string()
+        };
+
+        let archive = self.crate_archive(crate_name, &version).await?;
+        Ok((version, examples_from_archive(&archive)?))
+    }
+
+    /// A published crate version's `.crate` archive from crates.io, disk-cached under a
+    /// `crate-{version}` key.
+    async fn crate_archive(
+        &self,
+        crate_name: &str,
+        version: &str,
+    ) -> Result<Vec<u8>, crate::error::Error> {
+        let cache_key = format!("crate-{version}");
+        if let Some(disk) = &self.disk_cache
+            && let Some(archive) = disk.read(crate_name, &cache_key).await
+        {
+            return Ok(archive);
+        }
+        let archive = fetch_crate_archive(&self.http_client, crate_name, version).await?;
+        if let Some(disk) = &self.disk_cache {
+            disk.write(crate_name, &cache_key, &archive).await;
+        }
+        Ok(archive)
+    }
+
+    /// The default bodies of a trait's provided methods, by method name, cut from the
+    /// source files their spans point to. Methods whose source can't be read are left
+    /// out; empty for anything but a trait.
+    async fn default_method_bodies(
+        &self,
+        index: &CrateIndex,
+        item: &IndexedItem,
+    ) -> Vec<(String, String)> {
+        let mut files: HashMap<String, Option<String>> = HashMap::new();
+        let mut archive = None;
+        let mut bodies = Vec::new();
+        let provided = item
+            .detail
+            .methods
+            .iter()
+            .filter(|m| item.kind == ItemKind::Trait && !m.is_required);
+        for method in provided {
+            let Some(span) = &method.span else {
+                continue;
+            };
+            if !files.contains_key(&span.file) {
+                let source = self.read_span_file(index, &span.file, &mut archive).await;
+                files.insert(span.file.clone(), source);
+            }
+            if let Some(body) = files[&span.file]
+                .as_deref()
+                .and_then(|source| source::span_source(source, span))
+            {
+                bodies.push((method.name.clone(), body));
+            }
+        }
+        bodies
+    }
+
+    /// Read a source file named by a rustdoc span: from disk for local builds (relative
+    /// to the workspace root) and the toolchain's std, or from the `.crate` archive of a
+    /// published crate, downloaded at most once per call through `archive`.
+    async fn read_span_file(
+        &self,
+        index: &CrateIndex,
+        file: &str,
+        archive: &mut Option<Option<Vec<u8>>>,
+    ) -> Option<String> {
+        let path = Path::new(file);
+        let std = is_std_crate(&index.crate_name);
+        let published = !std && index.version.parse::<cargo_lock::Version>().is_ok();
+        let on_disk = if std {
+            self.toolchain()
+                .filter(|toolchain| toolchain.std_version() == index.version)
+                .and_then(|toolchain| toolchain.installed_source(file))
+        } else if path.is_absolute() {
+            Some(path.to_path_buf())
+        } else if !published {
+            self.workspace().map(|ws| ws.root().join(path))
+        } else {
+            None
+        };
+        if let Some(path) = on_disk {
+            return tokio::fs::read_to_string(&path)
+                .await
+                .inspect_err(|e| tracing::debug!("Failed to read {}: {e}", path.display()))
+                .ok();
+        }
+        if !published {
+            return None;
+        }
+        let archive = match archive {
+            Some(archive) => archive,
+            None => archive.insert(
+                self.crate_archive(&index.package_name, &index.version)
+                    .await
+                    .inspect_err(|e| tracing::warn!("No source for {}: {e}", index.crate_name))
+                    .ok(),
             ),
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
-            ..Default::default()
+        };
+        source::file_from_archive(archive.as_deref()?, file)
+            .inspect_err(|e| tracing::warn!("No source for {}: {e}", index.crate_name))
+            .ok()
+            .flatten()
+    }
+
+    /// Remove a crate's docs from the in-memory and disk caches, returning how many
+    /// entries were removed from each. Versions match as in `cached_version_matches()`.
+    async fn evict(&self, crate_name: &str, version: Option<&str>) -> (usize, usize) {
+        let normalize = |name: &str| name.replace('-', "_");
+        let wanted = normalize(crate_name);
+        let matches_version = |v: &str| cached_version_matches(version, v);
+
+        let memory = {
+            let mut cache = self.cache.write().await;
+            let before = cache.len();
+            cache.retain(|(name, v), _| !(normalize(name) == wanted && matches_version(v)));
+            before - cache.len()
+        };
+
+        let mut disk_removed = 0;
+        if let Some(disk) = &self.disk_cache {
+            let entries = {
+                let disk = Arc::clone(disk);
+                tokio::task::spawn_blocking(move || disk.entries())
+                    .await
+                    .unwrap_or_default()
+            };
+            for entry in entries {
+                if normalize(&entry.crate_name) == wanted
+                    && matches_version(&entry.version)
+                    && disk.remove(&entry.crate_name, &entry.version).await
+                {
+                    disk_removed += 1;
+                }
+            }
         }
+        tracing::info!("Evicted {crate_name}: {memory} in memory, {disk_removed} on disk");
+        (memory, disk_removed)
     }
-}
 
-impl RustDocsServer {
-    /// Resolve the version to use: explicit > Cargo.lock > "latest"
-    fn resolve_version(&self, crate_name: &str, explicit: Option<&str>) -> String {
-        if let Some(v) = explicit {
-            return v.to_string();
+    /// A tool response built from a crate's docs, prefixed with notes about the docs
+    /// themselves: other versions locked in Cargo.lock, or an unknown rustdoc JSON format.
+    /// Output over `MAX_RESPONSE_CHARS` is cut, with a `read_more` token for the rest.
+    fn index_result(&self, index: &CrateIndex, text: String) -> CallToolResult {
+        let notes: Vec<String> = [
+            render::render_stale_note(index),
+            render::render_fetched_note(index),
+            self.locked_versions_note(index),
+            render::render_format_note(index),
+            render::render_partial_note(index),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let text = if notes.is_empty() {
+            text
+        } else {
+            format!("{}\n\n{text}", notes.join("\n"))
+        };
+        CallToolResult::success(vec![Content::text(
+            self.pager.paginate(text, MAX_RESPONSE_CHARS),
+        )])
+    }
+
+    /// A note listing the other Cargo.lock versions of the crate and how to select them.
+    fn locked_versions_note(&self, index: &CrateIndex) -> Option<String> {
+        let lock = self.cargo_lock()?;
+        let others: Vec<(&str, String)> = lock
+            .all_versions(&index.package_name)
+            .iter()
+            .filter(|version| index.version.split(' ').next() != Some(version.as_str()))
+            .map(|version| {
+                (
+                    version.as_str(),
+                    lock.selector(&index.package_name, version),
+                )
+            })
+            .collect();
+        if others.is_empty() || lock.all_versions(&index.package_name).len() < 2 {
+            return None;
         }
-        if let Some(ref lock) = self.cargo_lock
-            && let Some(v) = lock.get_version(crate_name)
-        {
-            tracing::debug!("Resolved {crate_name} version from Cargo.lock: {v}");
-            return v.to_string();
+        Some(render::render_locked_versions_note(index, &others))
+    }
+
+    /// The methods of each trait whose provided methods `impls` inherit, by trait path,
+    /// for `render_impls`. Traits of other crates are looked up in that crate's docs;
+    /// ones that fail to load are left out and `render_impls` lists their methods by name.
+    async fn provided_trait_methods(
+        &self,
+        index: &CrateIndex,
+        impls: &[&ImplBlock],
+    ) -> HashMap<String, Vec<MethodInfo>> {
+        let mut trait_methods = HashMap::new();
+        let mut foreign: HashMap<String, Option<Arc<CrateIndex>>> = HashMap::new();
+        for block in impls {
+            let Some(trait_path) = &block.trait_path else {
+                continue;
+            };
+            if block.provided_methods.is_empty() || trait_methods.contains_key(trait_path) {
+                continue;
+            }
+            let crate_name = trait_path.split("::").next().unwrap_or(trait_path);
+            let methods = if crate_name == index.crate_name {
+                index.trait_methods(trait_path)
+            } else {
+                if !foreign.contains_key(crate_name) {
+                    let version = self.resolve_version(crate_name, None);
+                    let loaded = match self.get_or_load_index(crate_name, &version).await {
+                        Ok(trait_index) => Some(trait_index),
+                        Err(e) => {
+                            tracing::debug!("No docs for provided methods of {trait_path}: {e}");
+                            None
+                        }
+                    };
+                    foreign.insert(crate_name.to_string(), loaded);
+                }
+                foreign[crate_name]
+                    .as_ref()
+                    .and_then(|trait_index| trait_index.trait_methods(trait_path))
+            };
+            if let Some(methods) = methods {
+                trait_methods.insert(trait_path.clone(), methods.to_vec());
+            }
+        }
+        trait_methods
+    }
+
+    /// The session's custom feature set for a crate, if any (`set_crate_features`).
+    fn crate_features(&self, crate_name: &str) -> Option<FeatureSet> {
+        self.feature_overrides
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&crate_name.replace('-', "_"))
+            .cloned()
+    }
+
+    /// The in-memory cache key of a crate version: the version, followed by the feature
+    /// description when the session builds the crate with custom features.
+    fn cache_key(&self, crate_name: &str, version: &str) -> (String, String) {
+        match self.crate_features(crate_name) {
+            Some(features) => (
+                crate_name.to_string(),
+                format!("{version} ({})", features.describe()),
+            ),
+            None => (crate_name.to_string(), version.to_string()),
         }
-        "latest".to_string()
     }
 
     /// Get a cached CrateIndex or fetch/parse/cache a new one.
     ///
     /// Cache layers (checked in order):
     /// 1. In-memory `CrateCache` (fast path)
-    /// 2. On-disk cache of raw zstd bytes (skipped for "latest")
-    /// 3. HTTP fetch from docs.rs (writes to disk cache for pinned versions)
+    /// 2. On-disk cache of raw zstd bytes (skipped for "latest" and "local")
+    /// 3. HTTP fetch from docs.rs (writes to disk cache for pinned versions),
+    ///    or a local `cargo rustdoc` build for workspace members and path dependencies
     async fn get_or_load_index(
         &self,
         crate_name: &str,
         version: &str,
     ) -> Result<Arc<CrateIndex>, crate::error::Error> {
-        let key = (crate_name.to_string(), version.to_string());
+        let features = self.crate_features(crate_name);
+        let key = self.cache_key(crate_name, version);
 
         // Fast path: in-memory cache read lock
         {
             let cache = self.cache.read().await;
             if let Some(index) = cache.get(&key) {
+                note_load(&key.0, &index.version, CacheOutcome::Memory);
                 return Ok(Arc::clone(index));
             }
         }
 
-        // Disk cache is only used for pinned (non-"latest") versions
-        let disk = self.disk_cache.as_ref().filter(|_| version != "latest");
-        let krate = self.fetch_crate(disk, crate_name, version).await?;
+        let _permit = self
+            .load_permits
+            .acquire()
+            .await
+            .expect("load semaphore is never closed");
+        // Another call may have loaded the crate while this one waited
+        if let Some(index) = self.cache.read().await.get(&key) {
+            note_load(&key.0, &index.version, CacheOutcome::Memory);
+            return Ok(Arc::clone(index));
+        }
+
+        if self.sandbox && (version == LOCAL_VERSION || features.is_some()) {
+            return Err(crate::error::Error::DisabledByPolicy(
+                "local documentation builds",
+            ));
+        }
 
-        // Normalize crate name (hyphens -> underscores in rustdoc)
-        let normalized_name = crate_name.replace('-', "_");
-        let index = Arc::new(parse_crate(&krate, &normalized_name, version));
+        let mut stale = None;
+        // Pinned versions never change, so only "latest" and local builds get a timestamp
+        let mut fetched_at = None;
+        let (krate, package_name, display_version) = if version == LOCAL_VERSION {
+            let (krate, package_name, display_version, built_at) = self
+                .build_local_crate(crate_name, features.as_ref())
+                .await?;
+            fetched_at = built_at;
+            (krate, package_name, display_version)
+        } else if let Some(features) = &features {
+            let json = build_with_features(
+                crate_name,
+                version,
+                features,
+                &std::env::temp_dir().join("docsrs-mcp-features"),
+            )
+            .await?;
+            let krate = decode_json_bytes(&json, crate_name, version)?;
+            let display_version = format!("{version} ({})", features.describe());
+            note_load(crate_name, &display_version, CacheOutcome::Built);
+            fetched_at = Some(SystemTime::now());
+            (krate, crate_name.to_string(), display_version)
+        } else if let Some((krate, package_name, corpus_version)) =
+            self.load_from_corpus(crate_name, version).await
+        {
+            (krate, package_name, corpus_version)
+        } else if is_std_crate(crate_name) {
+            let (krate, std_version) = self.load_std_crate(crate_name, version).await?;
+            (krate, crate_name.to_string(), std_version)
+        } else {
+            // Disk cache is only read for exact versions: "latest" and semver
+            // requirements like "0.8" move on as crates are published
+            let disk = self
+                .disk_cache
+                .as_ref()
+                .filter(|_| is_exact_version(version));
+            match self.fetch_crate_by_name(disk, crate_name, version).await {
+                Ok((krate, package_name)) => {
+                    if !is_exact_version(version) {
+                        fetched_at = Some(SystemTime::now());
+                    }
+                    (krate, package_name, version.to_string())
+                }
+                // In the sandbox, cached copies are all there is
+                Err(e) if e.is_transient() || self.sandbox => {
+                    let Some((krate, package_name, cached_version, docs)) =
+                        self.load_stale(crate_name, version, &e).await
+                    else {
+                        return Err(e);
+                    };
+                    stale = Some(docs);
+                    (krate, package_name, cached_version)
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        let mut index = parse_crate(&krate, &package_name, &display_version, self.parse_budget);
+        index.stale = stale;
+        index.fetched_at = fetched_at;
+        let index = Arc::new(index);
+        if index.partial {
+            self.finish_index_in_background(key.clone(), krate, &index);
+        }
 
         // Double-check locking: someone else may have populated while we fetched
         let mut cache = self.cache.write().await;
@@ -263,6 +2983,40 @@ impl RustDocsServer {
         Ok(index)
     }
 
+    /// Parse docs whose index ran over the parse budget again without one, replacing
+    /// the partial index in the in-memory cache when done.
+    fn finish_index_in_background(
+        &self,
+        key: (String, String),
+        krate: rustdoc_types::Crate,
+        partial: &CrateIndex,
+    ) {
+        let (package_name, version) = (partial.package_name.clone(), partial.version.clone());
+        let (stale, fetched_at) = (partial.stale.clone(), partial.fetched_at);
+        tracing::info!(
+            "Parse budget exceeded for {package_name} v{version}, finishing in the background"
+        );
+        let cache = Arc::clone(&self.cache);
+        let permits = Arc::clone(&self.load_permits);
+        tokio::spawn(async move {
+            let Ok(_permit) = permits.acquire_owned().await else {
+                return;
+            };
+            let parsed = tokio::task::spawn_blocking(move || {
+                parse_crate(&krate, &package_name, &version, None)
+            })
+            .await;
+            match parsed {
+                Ok(mut index) => {
+                    index.stale = stale;
+                    index.fetched_at = fetched_at;
+                    cache.write().await.insert(key, Arc::new(index));
+                }
+                Err(e) => tracing::warn!("Finishing a partial index failed: {e}"),
+            }
+        });
+    }
+
     /// Fetch and decode rustdoc JSON, using the disk cache when available.
     ///
     /// On disk cache hit, decodes directly. On miss or corruption, fetches from
@@ -277,7 +3031,10 @@ impl RustDocsServer {
             && let Some(bytes) = disk.read(crate_name, version).await
         {
             match decode_raw_bytes(&bytes, crate_name, version) {
-                Ok(krate) => return Ok(krate),
+                Ok(krate) => {
+                    note_load(crate_name, version, CacheOutcome::Disk);
+                    return Ok(krate);
+                }
                 Err(e) => {
                     tracing::warn!(
                         "Corrupted cache entry for {crate_name} v{version}, \
@@ -290,11 +3047,1070 @@ impl RustDocsServer {
 
         tracing::info!("Loading {crate_name} v{version} from docs.rs...");
         let bytes = fetch_raw_bytes(&self.http_client, crate_name, version).await?;
+        note_load(crate_name, version, CacheOutcome::Fetched);
 
         if let Some(disk) = disk {
             disk.write(crate_name, version, &bytes).await;
+        } else if !is_exact_version(version)
+            && let Some(disk) = &self.disk_cache
+        {
+            // Never read back while docs.rs works, since "latest" moves on
+            disk.write(crate_name, version, &bytes).await;
         }
 
         decode_raw_bytes(&bytes, crate_name, version)
     }
+
+    /// Decode a crate's docs from the `--corpus-dir` file matching `version`, with the
+    /// crate name and version from the file name. `None` without a corpus, a matching
+    /// file, or if the file can't be decoded, so docs.rs is tried next.
+    async fn load_from_corpus(
+        &self,
+        crate_name: &str,
+        version: &str,
+    ) -> Option<(rustdoc_types::Crate, String, String)> {
+        let entry = self.corpus.as_ref()?.find(crate_name, version)?.clone();
+        tracing::info!(
+            "Loading {crate_name} v{} from {}",
+            entry.version,
+            entry.path.display()
+        );
+        let (name, corpus_version) = (entry.crate_name.clone(), entry.version.clone());
+        match tokio::task::spawn_blocking(move || entry.load()).await {
+            Ok(Ok(krate)) => {
+                note_load(&name, &corpus_version, CacheOutcome::Corpus);
+                Some((krate, name, corpus_version))
+            }
+            Ok(Err(e)) => {
+                tracing::warn!("Ignoring corpus file for {name} v{corpus_version}: {e}");
+                None
+            }
+            Err(e) => {
+                tracing::warn!("Corpus loading task for {name} failed: {e}");
+                None
+            }
+        }
+    }
+
+    /// The core and alloc docs (with the primitive's name) to take a std primitive's
+    /// inherent impls from when std's docs lack them: rustdoc doesn't inline them for
+    /// slices, arrays, and pointers. Empty for anything else.
+    async fn std_primitive_sources(
+        &self,
+        index: &CrateIndex,
+        item_path: &str,
+        impls: &[&ImplBlock],
+    ) -> Vec<(String, Arc<CrateIndex>)> {
+        let Some(item) = index
+            .get_item(item_path)
+            .filter(|item| index.crate_name == "std" && item.kind == ItemKind::Primitive)
+        else {
+            return Vec::new();
+        };
+        if impls.iter().any(|block| block.trait_name.is_none()) {
+            return Vec::new();
+        }
+        let mut sources = Vec::new();
+        for crate_name in ["core", "alloc"] {
+            match self.get_or_load_index(crate_name, &index.version).await {
+                Ok(source) => sources.push((item.name.clone(), source)),
+                Err(e) => tracing::debug!("No {crate_name} impls for {}: {e}", item.name),
+            }
+        }
+        sources
+    }
+
+    /// Load a standard library crate's docs for a Rust release, nightly, or channel, with the
+    /// version key they're cached under: from the toolchain's installed `rust-docs-json`
+    /// component when it matches, the disk cache, or the matching nightly's component on
+    /// static.rust-lang.org, which documents all std crates at once.
+    async fn load_std_crate(
+        &self,
+        crate_name: &str,
+        version: &str,
+    ) -> Result<(rustdoc_types::Crate, String), crate::error::Error> {
+        let version = self.std_version_key(version).await?;
+        if let Some(toolchain) = self.toolchain()
+            && toolchain.std_version() == version
+            && let Some(path) = toolchain.installed_json(crate_name)
+        {
+            match tokio::fs::read(&path).await {
+                Ok(json) => {
+                    tracing::info!("Loading {crate_name} v{version} from {}", path.display());
+                    let krate = decode_json_bytes(&json, crate_name, &version)?;
+                    note_load(crate_name, &version, CacheOutcome::Prebuilt);
+                    return Ok((krate, version));
+                }
+                Err(e) => tracing::warn!("Failed to read {}: {e}", path.display()),
+            }
+        }
+
+        if let Some(disk) = &self.disk_cache
+            && let Some(bytes) = disk.read(crate_name, &version).await
+        {
+            match decode_raw_bytes(&bytes, crate_name, &version) {
+                Ok(krate) => {
+                    note_load(crate_name, &version, CacheOutcome::Disk);
+                    return Ok((krate, version));
+                }
+                Err(e) => {
+                    tracing::warn!("Corrupted cache entry for {crate_name} v{version}: {e}");
+                    disk.remove(crate_name, &version).await;
+                }
+            }
+        }
+
+        let date = std_docs::docs_date(&version)?;
+        tracing::info!("Loading {crate_name} v{version} from the nightly-{date} rust-docs-json...");
+        let docs = std_docs::fetch_std_docs(&self.http_client, &version, &date).await?;
+        note_load(crate_name, &version, CacheOutcome::Fetched);
+        if let Some(disk) = &self.disk_cache {
+            for (name, bytes) in &docs {
+                disk.write(name, &version, bytes).await;
+            }
+        }
+        let (_, bytes) = docs
+            .iter()
+            .find(|(name, _)| name == crate_name)
+            .ok_or_else(|| crate::error::Error::JsonNotAvailable {
+                crate_name: crate_name.to_string(),
+                version: version.clone(),
+            })?;
+        let krate = decode_raw_bytes(bytes, crate_name, &version)?;
+        Ok((krate, version))
+    }
+
+    /// The key a std version's docs are loaded and cached under; the "nightly" channel
+    /// is looked up on static.rust-lang.org.
+    async fn std_version_key(&self, version: &str) -> Result<String, crate::error::Error> {
+        match version {
+            "nightly" => std_docs::latest_nightly(&self.http_client).await,
+            _ => std_docs::std_version_key(version),
+        }
+    }
+
+    /// The standard library's docs for a keyword, from the `keyword_docs.rs` source of
+    /// the same release: the toolchain's `rust-src` component when it matches, the disk
+    /// cache (under a `keywords-{version}` key), or the matching nightly's `rust-src`.
+    async fn load_keyword_doc(
+        &self,
+        keyword: &str,
+        version: &str,
+    ) -> Result<KeywordDoc, crate::error::Error> {
+        let version = self.std_version_key(version).await?;
+        let cache_key = format!("keywords-{version}");
+        let installed = self
+            .toolchain()
+            .filter(|toolchain| toolchain.std_version() == version)
+            .and_then(|toolchain| toolchain.installed_keyword_source());
+        let cached = match (&installed, &self.disk_cache) {
+            (Some(path), _) => tokio::fs::read(path)
+                .await
+                .inspect_err(|e| tracing::warn!("Failed to read {}: {e}", path.display()))
+                .ok(),
+            (None, Some(disk)) => disk.read("std", &cache_key).await,
+            (None, None) => None,
+        };
+        let source = match cached {
+            Some(source) => source,
+            None => {
+                let date = std_docs::docs_date(&version)?;
+                let source =
+                    std_docs::fetch_keyword_source(&self.http_client, &version, &date).await?;
+                if let Some(disk) = &self.disk_cache {
+                    disk.write("std", &cache_key, &source).await;
+                }
+                source
+            }
+        };
+        std_docs::parse_keyword_docs(&String::from_utf8_lossy(&source))
+            .into_iter()
+            .find(|doc| doc.keyword == keyword)
+            .ok_or_else(|| {
+                crate::error::Error::Other(format!(
+                    "The standard library's docs for Rust {version} don't cover the `{keyword}` keyword"
+                ))
+            })
+    }
+
+    /// Decode the rustdoc JSON an earlier doc build left in the target dir for a local
+    /// package, with when it was written, if the package hasn't changed since. Skipped
+    /// when it was built with private items and `--document-private-items` isn't set, or
+    /// the other way around.
+    async fn load_prebuilt(
+        &self,
+        package: &LocalPackage,
+        target_dir: &Path,
+    ) -> Option<(rustdoc_types::Crate, SystemTime)> {
+        let (package_clone, target_dir) = (package.clone(), target_dir.to_path_buf());
+        let (path, built_at) =
+            tokio::task::spawn_blocking(move || prebuilt_rustdoc_json(&package_clone, &target_dir))
+                .await
+                .ok()??;
+        let json = tokio::fs::read(&path)
+            .await
+            .inspect_err(|e| tracing::warn!("Failed to read {}: {e}", path.display()))
+            .ok()?;
+        let krate = decode_json_bytes(&json, &package.name, &package.version)
+            .inspect_err(|e| tracing::warn!("Ignoring {}: {e}", path.display()))
+            .ok()?;
+        if krate.includes_private != self.document_private {
+            tracing::debug!("{} differs in private items, rebuilding", path.display());
+            return None;
+        }
+        tracing::info!("Using rustdoc JSON from {}", path.display());
+        Some((krate, built_at))
+    }
+
+    /// The best cached copy of a crate's docs after fetching them failed with `error`:
+    /// decoded, with the package name and version key it was cached under.
+    async fn load_stale(
+        &self,
+        crate_name: &str,
+        version: &str,
+        error: &crate::error::Error,
+    ) -> Option<(rustdoc_types::Crate, String, String, StaleDocs)> {
+        let disk = Arc::clone(self.disk_cache.as_ref()?);
+        let (name, wanted) = (crate_name.to_string(), version.to_string());
+        let entry = tokio::task::spawn_blocking(move || disk.stale_entry(&name, &wanted))
+            .await
+            .ok()??;
+        let bytes = self
+            .disk_cache
+            .as_ref()?
+            .read(&entry.crate_name, &entry.version)
+            .await?;
+        let krate = decode_raw_bytes(&bytes, &entry.crate_name, &entry.version).ok()?;
+        note_load(&entry.crate_name, &entry.version, CacheOutcome::Stale);
+        tracing::warn!(
+            "Fetching {crate_name} v{version} failed ({error}), serving cached v{}",
+            entry.version
+        );
+        let docs = StaleDocs {
+            requested: version.to_string(),
+            cached_at: entry.modified,
+            error: error.to_string(),
+        };
+        Some((krate, entry.crate_name, entry.version, docs))
+    }
+
+    /// Fetch a crate's rustdoc JSON, retrying with hyphens and underscores swapped when
+    /// docs.rs has nothing under the given name. Returns the name that worked.
+    async fn fetch_crate_by_name(
+        &self,
+        disk: Option<&Arc<DiskCache>>,
+        crate_name: &str,
+        version: &str,
+    ) -> Result<(rustdoc_types::Crate, String), crate::error::Error> {
+        let error = match self.fetch_crate(disk, crate_name, version).await {
+            Ok(krate) => return Ok((krate, crate_name.to_string())),
+            Err(e @ crate::error::Error::JsonNotAvailable { .. }) => e,
+            Err(e) => return Err(e),
+        };
+        if let Some(swapped) = crates_io::swap_separators(crate_name)
+            && let Ok(krate) = self.fetch_crate(disk, &swapped, version).await
+        {
+            tracing::info!("Loaded {crate_name} as {swapped}");
+            return Ok((krate, swapped));
+        }
+        Err(self.explain_missing_json(crate_name, version, error).await)
+    }
+
+    /// Explain a missing-JSON error: `BinaryOnly` when crates.io says the version has no
+    /// library target, and `CrateNotFound` with the closest crates.io name when the crate
+    /// doesn't exist. Otherwise (or if crates.io can't be reached) keep the original error.
+    async fn explain_missing_json(
+        &self,
+        crate_name: &str,
+        version: &str,
+        error: crate::error::Error,
+    ) -> crate::error::Error {
+        let versions = match crates_io::crate_versions(&self.http_client, crate_name).await {
+            Ok(versions) => versions,
+            Err(crate::error::Error::CrateNotFound(_)) => {
+                let crates = crates_io::search_crates(&self.http_client, crate_name, 10)
+                    .await
+                    .map(|(crates, _)| crates)
+                    .unwrap_or_default();
+                return crate::error::Error::CrateNotFound(
+                    match crates_io::closest_crate_name(&crates, crate_name) {
+                        Some(name) => format!("{crate_name} (did you mean `{name}`?)"),
+                        None => crate_name.to_string(),
+                    },
+                );
+            }
+            Err(e) => {
+                tracing::debug!("Could not check crates.io targets of {crate_name}: {e}");
+                return error;
+            }
+        };
+        match crates_io::find_version(&versions, version) {
+            Some(info) if info.is_binary_only() => crate::error::Error::BinaryOnly {
+                crate_name: crate_name.to_string(),
+                version: info.num.clone(),
+                binaries: if info.bin_names.is_empty() {
+                    "unknown".to_string()
+                } else {
+                    info.bin_names.join(", ")
+                },
+            },
+            _ => error,
+        }
+    }
+
+    /// Build and decode rustdoc JSON for a local package (workspace member, path or git
+    /// dependency), with its package name, the version to display, and when it was built.
+    ///
+    /// Path and git dependencies change rarely, so their builds are cached on disk (as zstd,
+    /// like docs.rs downloads) under a version key derived from a hash of their sources
+    /// or from the pinned git revision, plus the build options (`build_key_suffix()`).
+    async fn build_local_crate(
+        &self,
+        crate_name: &str,
+        features: Option<&FeatureSet>,
+    ) -> Result<(rustdoc_types::Crate, String, String, Option<SystemTime>), crate::error::Error>
+    {
+        let Some(ws) = self.workspace() else {
+            return Err(crate::error::Error::CrateNotFound(format!(
+                "{crate_name} is not a local package of the current workspace"
+            )));
+        };
+        let Some(package) = ws.package(crate_name) else {
+            return Err(crate::error::Error::CrateNotFound(format!(
+                "{crate_name} is not a local package of the current workspace"
+            )));
+        };
+
+        let display_version = match &package.source {
+            PackageSource::Git { rev, .. } => {
+                format!("{} (git {})", package.version, &rev[..rev.len().min(8)])
+            }
+            _ => format!("{} (local)", package.version),
+        };
+        let display_version = match features {
+            Some(features) => format!("{display_version} ({})", features.describe()),
+            None => display_version,
+        };
+
+        // JSON left in the target dir by an earlier doc build beats building again
+        let prebuilt = match (&package.source, features) {
+            (PackageSource::Member | PackageSource::Path, None) => {
+                self.load_prebuilt(package, ws.target_dir()).await
+            }
+            _ => None,
+        };
+
+        let key_suffix = build_key_suffix(self.document_private, features);
+        let cache_key = match (&package.source, &self.disk_cache) {
+            _ if prebuilt.is_some() => None,
+            (PackageSource::Git { rev, .. }, Some(disk)) => {
+                Some((disk, format!("git-{rev}{key_suffix}")))
+            }
+            (PackageSource::Path, Some(disk)) => {
+                let dir = package
+                    .manifest_path
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default();
+                match tokio::task::spawn_blocking(move || source_hash(&dir)).await {
+                    Ok(Ok(hash)) => Some((disk, format!("local-{hash:016x}{key_suffix}"))),
+                    Ok(Err(e)) => {
+                        tracing::warn!("Failed to hash sources of {crate_name}: {e}");
+                        None
+                    }
+                    Err(e) => {
+                        tracing::warn!("Source hashing task for {crate_name} failed: {e}");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        let cached = match &cache_key {
+            Some((disk, key)) => disk.read(&package.name, key).await.and_then(|bytes| {
+                decode_raw_bytes(&bytes, crate_name, &package.version)
+                    .inspect_err(|e| tracing::warn!("Ignoring corrupted local build cache: {e}"))
+                    .ok()
+            }),
+            None => None,
+        };
+        let cached_at = match (&cached, &cache_key) {
+            (Some(_), Some((disk, key))) => disk.modified(&package.name, key).await,
+            _ => None,
+        };
+
+        let krate = match (prebuilt, cached) {
+            (Some((krate, built_at)), _) => {
+                note_load(&package.name, &display_version, CacheOutcome::Prebuilt);
+                return Ok((krate, package.name.clone(), display_version, Some(built_at)));
+            }
+            (None, Some(krate)) => krate,
+            (None, None) => {
+                let json = build_rustdoc_json(
+                    package,
+                    ws.root(),
+                    ws.target_dir(),
+                    self.document_private,
+                    features,
+                )
+                .await?;
+                if let Some((disk, key)) = &cache_key {
+                    match zstd::stream::encode_all(json.as_slice(), 3) {
+                        Ok(bytes) => disk.write(&package.name, key, &bytes).await,
+                        Err(e) => tracing::warn!("Failed to compress local build: {e}"),
+                    }
+                }
+                decode_json_bytes(&json, crate_name, &package.version)?
+            }
+        };
+
+        let outcome = match cached_at {
+            Some(_) => CacheOutcome::Disk,
+            None => CacheOutcome::Built,
+        };
+        note_load(&package.name, &display_version, outcome);
+        let built_at = cached_at.unwrap_or_else(SystemTime::now);
+        Ok((krate, package.name.clone(), display_version, Some(built_at)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::docs::index::{ItemDetail, Visibility};
+
+    /// A public unit struct `my_crate::{name}` with two doc sentences, with `fields`
+    /// (e.g. `visibility`, `attrs`, `docs`) overridden.
+    fn struct_item(id: u32, name: &str, fields: serde_json::Value) -> serde_json::Value {
+        let mut item = json!({
+            "id": id, "crate_id": 0, "name": name, "span": null, "visibility": "public",
+            "docs": format!("The {name} item. More about it."), "links": {}, "attrs": [],
+            "deprecation": null,
+            "inner": { "struct": {
+                "kind": "unit",
+                "generics": { "params": [], "where_predicates": [] },
+                "impls": [],
+            } },
+        });
+        for (key, value) in fields.as_object().unwrap() {
+            item[key] = value.clone();
+        }
+        item
+    }
+
+    /// rustdoc JSON for `my_crate` v1.0.0 with `items` in its root module.
+    fn test_crate(items: Vec<serde_json::Value>) -> serde_json::Value {
+        let mut index = serde_json::Map::new();
+        let mut paths = serde_json::Map::new();
+        let ids: Vec<u64> = items
+            .iter()
+            .map(|item| item["id"].as_u64().unwrap())
+            .collect();
+        index.insert(
+            "0".to_string(),
+            json!({
+                "id": 0, "crate_id": 0, "name": "my_crate", "span": null,
+                "visibility": "public", "docs": null, "links": {}, "attrs": [],
+                "deprecation": null,
+                "inner": { "module": { "is_crate": true, "items": ids, "is_stripped": false } },
+            }),
+        );
+        paths.insert(
+            "0".to_string(),
+            json!({ "crate_id": 0, "path": ["my_crate"], "kind": "module" }),
+        );
+        for item in items {
+            let id = item["id"].to_string();
+            let name = item["name"].as_str().unwrap().to_string();
+            paths.insert(
+                id.clone(),
+                json!({ "crate_id": 0, "path": ["my_crate", name], "kind": "struct" }),
+            );
+            index.insert(id, item);
+        }
+        json!({
+            "root": 0, "crate_version": "1.0.0", "includes_private": false,
+            "index": index, "paths": paths, "external_crates": {},
+            "target": { "triple": "x86_64-unknown-linux-gnu", "target_features": [] },
+            "format_version": 56,
+        })
+    }
+
+    /// A sandboxed server without disk cache that loads `krate` as `my_crate` v1.0.0
+    /// from a corpus in `dir`.
+    fn corpus_server(
+        dir: &Path,
+        krate: &serde_json::Value,
+        options: ServerOptions,
+    ) -> RustDocsServer {
+        std::fs::write(dir.join("my_crate-1.0.0.json"), krate.to_string()).unwrap();
+        RustDocsServer::new(
+            dir.to_path_buf(),
+            ServerOptions {
+                use_disk_cache: false,
+                corpus: Some(Corpus::open(dir).unwrap()),
+                sandbox: true,
+                ..options
+            },
+        )
+    }
+
+    /// An index of `my_crate` without items, to fill the in-memory cache with.
+    fn empty_index() -> Arc<CrateIndex> {
+        let krate = serde_json::from_value(test_crate(Vec::new())).unwrap();
+        Arc::new(parse_crate(&krate, "my_crate", "1.0.0", None))
+    }
+
+    /// The text of a tool result.
+    fn result_text(result: CallToolResult) -> String {
+        result
+            .content
+            .iter()
+            .filter_map(|content| content.as_text())
+            .map(|text| text.text.as_str())
+            .collect()
+    }
+
+    // ========== parse budget tests ==========
+
+    #[tokio::test]
+    async fn partial_index_is_replaced_once_finished_in_the_background() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = corpus_server(
+            dir.path(),
+            &test_crate(vec![struct_item(1, "Widget", json!({}))]),
+            ServerOptions {
+                parse_budget: Some(Duration::ZERO),
+                ..Default::default()
+            },
+        );
+
+        let partial = server.get_or_load_index("my_crate", "1.0.0").await.unwrap();
+        assert!(partial.partial);
+        assert_eq!(partial.items["my_crate::Widget"].doc, "The Widget item.");
+        assert!(render::render_partial_note(&partial).is_some());
+
+        let mut full = partial;
+        for _ in 0..100 {
+            if !full.partial {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            full = server.get_or_load_index("my_crate", "1.0.0").await.unwrap();
+        }
+        assert!(
+            !full.partial,
+            "the full index never replaced the partial one"
+        );
+        assert_eq!(
+            full.items["my_crate::Widget"].doc,
+            "The Widget item. More about it."
+        );
+        assert!(render::render_partial_note(&full).is_none());
+    }
+
+    // ========== resolve_version tests ==========
+
+    /// A server for a project locking serde 1.0.210 and 0.9.15 and tokio-util 0.7.11,
+    /// with the local package `my-lib` and a 1.82.0 toolchain.
+    fn project_server(dir: &Path) -> RustDocsServer {
+        let registry = r#"source = "registry+https://github.com/rust-lang/crates.io-index""#;
+        let lock = format!(
+            "version = 3\n\n\
+             [[package]]\nname = \"my-lib\"\nversion = \"0.2.0\"\n\n\
+             [[package]]\nname = \"serde\"\nversion = \"0.9.15\"\n{registry}\n\n\
+             [[package]]\nname = \"serde\"\nversion = \"1.0.210\"\n{registry}\n\n\
+             [[package]]\nname = \"tokio-util\"\nversion = \"0.7.11\"\n{registry}\n"
+        );
+        std::fs::write(dir.join("Cargo.lock"), lock).unwrap();
+        let server = corpus_server(dir, &test_crate(Vec::new()), ServerOptions::default());
+        let metadata = json!({
+            "packages": [{
+                "id": "my-lib 0.2.0 (path+file:///ws/lib)",
+                "name": "my-lib",
+                "version": "0.2.0",
+                "source": null,
+                "manifest_path": "/ws/lib/Cargo.toml",
+                "targets": [{ "name": "my-lib", "kind": ["lib"] }],
+            }],
+            "workspace_members": ["my-lib 0.2.0 (path+file:///ws/lib)"],
+            "workspace_root": "/ws",
+            "target_directory": "/ws/target",
+        });
+        {
+            let mut project = server.project.write().unwrap();
+            assert!(project.cargo_lock.is_some());
+            project.workspace = Some(Arc::new(
+                WorkspaceIndex::from_metadata(metadata.to_string().as_bytes()).unwrap(),
+            ));
+            project.toolchain = Some(Arc::new(RustToolchain {
+                release: "1.82.0".to_string(),
+                commit_date: None,
+                sysroot: None,
+            }));
+        }
+        server
+    }
+
+    #[test]
+    fn resolve_version_prefers_explicit_then_local_then_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = project_server(dir.path());
+
+        assert_eq!(server.resolve_version("serde", Some("0.9.15")), "0.9.15");
+        assert_eq!(
+            server.resolve_version("serde", Some("from-lock:0.9")),
+            "0.9.15"
+        );
+        assert_eq!(server.resolve_version("serde", Some("from-lock:2")), "2");
+        assert_eq!(server.resolve_version("serde", None), "1.0.210");
+        assert_eq!(server.resolve_version("tokio-util", None), "0.7.11");
+        assert_eq!(server.resolve_version("my-lib", None), "local");
+        assert_eq!(server.resolve_version("std", None), "1.82.0");
+        assert_eq!(server.resolve_version("anyhow", None), "latest");
+    }
+
+    #[tokio::test]
+    async fn session_default_versions_come_after_explicit_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = project_server(dir.path());
+        let set_default = |crate_name: &str, version: Option<&str>| {
+            let server = server.clone();
+            let params = json!({ "crate_name": crate_name, "version": version });
+            async move {
+                let params = serde_json::from_value(params).unwrap();
+                result_text(
+                    server
+                        .set_default_version(Parameters(params))
+                        .await
+                        .unwrap(),
+                )
+            }
+        };
+
+        // Hyphens and underscores name the same crate
+        set_default("tokio_util", Some("0.7.0")).await;
+        set_default("my-lib", Some("0.1.0")).await;
+        set_default("std", Some("1.80.0")).await;
+        set_default("serde", Some("from-lock:0.9")).await;
+        assert_eq!(server.resolve_version("tokio-util", None), "0.7.0");
+        assert_eq!(server.resolve_version("tokio_util", None), "0.7.0");
+        assert_eq!(server.resolve_version("my_lib", None), "0.1.0");
+        assert_eq!(server.resolve_version("std", None), "1.80.0");
+        assert_eq!(server.resolve_version("serde", None), "0.9.15");
+        assert_eq!(
+            server.resolve_version("tokio-util", Some("0.6.10")),
+            "0.6.10"
+        );
+
+        assert_eq!(
+            set_default("tokio-util", None).await,
+            "Cleared the session default v0.7.0 of tokio-util; versions resolve automatically again."
+        );
+        assert_eq!(server.resolve_version("tokio-util", None), "0.7.11");
+        assert_eq!(
+            set_default("tokio-util", None).await,
+            "tokio-util has no session default version."
+        );
+    }
+
+    // ========== load permit tests ==========
+
+    #[tokio::test]
+    async fn zero_concurrent_loads_still_allows_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = corpus_server(
+            dir.path(),
+            &test_crate(Vec::new()),
+            ServerOptions {
+                max_concurrent_loads: 0,
+                ..Default::default()
+            },
+        );
+        assert_eq!(server.load_permits.available_permits(), 1);
+        let load = server.get_or_load_index("my_crate", "1.0.0");
+        tokio::time::timeout(Duration::from_secs(5), load)
+            .await
+            .expect("loading waited for a permit that never comes")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn load_waiting_for_a_permit_reuses_the_index_loaded_meanwhile() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = corpus_server(
+            dir.path(),
+            &test_crate(Vec::new()),
+            ServerOptions {
+                max_concurrent_loads: 1,
+                ..Default::default()
+            },
+        );
+        let permit = Arc::clone(&server.load_permits)
+            .acquire_owned()
+            .await
+            .unwrap();
+        let waiting = {
+            let server = server.clone();
+            tokio::spawn(async move { server.get_or_load_index("my_crate", "1.0.0").await })
+        };
+        // Let the call miss the cache and queue for the permit
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        let loaded = empty_index();
+        let key = ("my_crate".to_string(), "1.0.0".to_string());
+        server.cache.write().await.insert(key, Arc::clone(&loaded));
+        drop(permit);
+
+        let index = waiting.await.unwrap().unwrap();
+        assert!(Arc::ptr_eq(&index, &loaded));
+    }
+
+    // ========== evict tests ==========
+
+    #[test]
+    fn cached_version_matches_feature_builds_and_local_keys() {
+        let cases = [
+            (Some("1.38.0"), "1.38.0", true),
+            (Some("1.38.0"), "1.38.0 (features: rt; no default)", true),
+            (Some("1.38.0"), "1.38.01", false),
+            (Some("1.38"), "1.38.0", false),
+            (Some("local"), "local", true),
+            (Some("local"), "local (features: rt)", true),
+            (Some("local"), "local-00ff00ff00ff00ff-private", true),
+            (Some("local"), "git-abc123", true),
+            (Some("local"), "localhost", false),
+            (Some("latest"), "latest (features: macros)", true),
+            (None, "0.1.0 (features: none)", true),
+        ];
+        for (requested, cached, expected) in cases {
+            assert_eq!(
+                cached_version_matches(requested, cached),
+                expected,
+                "{requested:?} {cached}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn evict_removes_feature_builds_of_the_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = corpus_server(
+            dir.path(),
+            &test_crate(vec![struct_item(1, "Widget", json!({}))]),
+            ServerOptions::default(),
+        );
+        let key = ("my_crate".to_string(), "1.0.0 (features: rt)".to_string());
+        server
+            .cache
+            .write()
+            .await
+            .insert(key.clone(), empty_index());
+
+        assert_eq!(server.evict("my_crate", Some("1.0.0")).await, (1, 0));
+        assert!(!server.cache.read().await.contains_key(&key));
+    }
+
+    #[tokio::test]
+    async fn evict_removes_matching_entries_from_memory_and_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut server = corpus_server(
+            dir.path(),
+            &test_crate(Vec::new()),
+            ServerOptions::default(),
+        );
+        let disk = DiskCache::with_base_dir(dir.path().join("cache"));
+        for (crate_name, version) in [
+            ("my-crate", "1.0.0"),
+            ("my-crate", "2.0.0"),
+            ("my_crate", "local-00ff00ff00ff00ff"),
+            ("my_crate", "git-abc123"),
+            ("other", "1.0.0"),
+        ] {
+            disk.write(crate_name, version, b"docs").await;
+        }
+        server.disk_cache = Some(Arc::new(disk));
+        for (crate_name, version) in [
+            ("my_crate", "1.0.0"),
+            ("my_crate", "2.0.0"),
+            ("my_crate", "local"),
+            ("other", "1.0.0"),
+        ] {
+            let key = (crate_name.to_string(), version.to_string());
+            server.cache.write().await.insert(key, empty_index());
+        }
+        let cached = |server: &RustDocsServer| {
+            let server = server.clone();
+            async move {
+                let mut memory: Vec<String> = server
+                    .cache
+                    .read()
+                    .await
+                    .keys()
+                    .map(|(name, version)| format!("{name} {version}"))
+                    .collect();
+                memory.sort();
+                let disk: Vec<String> = server
+                    .disk_cache
+                    .as_ref()
+                    .unwrap()
+                    .entries()
+                    .iter()
+                    .map(|entry| format!("{} {}", entry.crate_name, entry.version))
+                    .collect();
+                (memory, disk)
+            }
+        };
+
+        // Hyphens and underscores name the same crate
+        assert_eq!(server.evict("my_crate", Some("1.0.0")).await, (1, 1));
+        assert_eq!(server.evict("my-crate", Some("local")).await, (1, 2));
+        assert_eq!(server.evict("my_crate", Some("3.0.0")).await, (0, 0));
+        assert_eq!(
+            cached(&server).await,
+            (
+                vec!["my_crate 2.0.0".to_string(), "other 1.0.0".to_string()],
+                vec!["my-crate 2.0.0".to_string(), "other 1.0.0".to_string()],
+            )
+        );
+
+        // Without a version, every version goes
+        assert_eq!(server.evict("my_crate", None).await, (1, 1));
+        assert_eq!(
+            cached(&server).await,
+            (
+                vec!["other 1.0.0".to_string()],
+                vec!["other 1.0.0".to_string()]
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn evict_crate_counts_no_removals_from_a_read_only_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut server = corpus_server(
+            dir.path(),
+            &test_crate(Vec::new()),
+            ServerOptions::default(),
+        );
+        let disk = DiskCache::with_base_dir(dir.path().join("cache"));
+        disk.write("my_crate", "1.0.0", b"docs").await;
+        server.disk_cache = Some(Arc::new(disk.read_only()));
+
+        let params = serde_json::from_value(json!({ "crate_name": "my_crate" })).unwrap();
+        let text = result_text(server.evict_crate(Parameters(params)).await.unwrap());
+        assert_eq!(text, "Nothing cached for my_crate.");
+        let disk = server.disk_cache.as_ref().unwrap();
+        assert!(disk.read("my_crate", "1.0.0").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn reload_project_drops_local_feature_builds() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = corpus_server(
+            dir.path(),
+            &test_crate(Vec::new()),
+            ServerOptions::default(),
+        );
+        let index = server.get_or_load_index("my_crate", "1.0.0").await.unwrap();
+        for version in ["local", "local (features: rt)"] {
+            let key = ("my_crate".to_string(), version.to_string());
+            server.cache.write().await.insert(key, Arc::clone(&index));
+        }
+
+        server.reload_project().await.unwrap();
+        let versions: Vec<String> = server
+            .cache
+            .read()
+            .await
+            .keys()
+            .map(|(_, v)| v.clone())
+            .collect();
+        assert_eq!(versions, ["1.0.0"]);
+    }
+
+    // ========== completion tests ==========
+
+    #[tokio::test]
+    async fn completion_only_reads_loaded_crates() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = corpus_server(
+            dir.path(),
+            &test_crate(vec![struct_item(1, "Widget", json!({}))]),
+            ServerOptions::default(),
+        );
+
+        assert!(
+            server
+                .complete_item_paths("my_crate", Some("1.0.0"), "Wid", false)
+                .await
+                .is_empty()
+        );
+        assert!(server.cache.read().await.is_empty());
+
+        server.get_or_load_index("my_crate", "1.0.0").await.unwrap();
+        assert_eq!(
+            server
+                .complete_item_paths("my_crate", Some("1.0.0"), "Wid", false)
+                .await,
+            ["Widget"]
+        );
+    }
+
+    // ========== lookup_crate_items filter tests ==========
+
+    fn item_with(name: &str, visibility: Visibility) -> IndexedItem {
+        IndexedItem {
+            path: format!("my_crate::{name}"),
+            name: name.to_string(),
+            kind: ItemKind::Struct,
+            signature: format!("{}struct {name};", visibility.prefix()),
+            attrs: Vec::new(),
+            visibility,
+            aliases: Vec::new(),
+            reexports: Vec::new(),
+            short_doc: "Docs.".to_string(),
+            doc: "Docs.".to_string(),
+            deprecation: None,
+            stability: None,
+            cfg: None,
+            is_unsafe: false,
+            async_kind: None,
+            is_hidden: false,
+            span: None,
+            headings: Vec::new(),
+            external_refs: Vec::new(),
+            detail: ItemDetail::default(),
+            parent_module: "my_crate".to_string(),
+        }
+    }
+
+    #[test]
+    fn lookup_crate_items_filters_by_visibility() {
+        let items = [
+            item_with("Open", Visibility::Public),
+            item_with("Shared", Visibility::Crate),
+            item_with("Scoped", Visibility::Restricted("crate::inner".to_string())),
+            item_with("Secret", Visibility::Private),
+        ];
+        let listed = |params: serde_json::Value| -> Vec<String> {
+            let params: LookupCrateItemsParams = serde_json::from_value(params).unwrap();
+            items
+                .iter()
+                .filter(|item| params.lists(item))
+                .map(|item| item.name.clone())
+                .collect()
+        };
+
+        assert_eq!(
+            listed(json!({ "crate_name": "my_crate" })),
+            ["Open", "Shared", "Scoped", "Secret"]
+        );
+        for (visibility, name) in [
+            ("public", "Open"),
+            ("crate", "Shared"),
+            ("restricted", "Scoped"),
+            ("private", "Secret"),
+        ] {
+            assert_eq!(
+                listed(json!({ "crate_name": "my_crate", "visibility": visibility })),
+                [name]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn lookup_crate_items_glob_applies_the_listing_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = corpus_server(
+            dir.path(),
+            &test_crate(vec![
+                struct_item(1, "WidgetOpen", json!({})),
+                struct_item(2, "WidgetShared", json!({ "visibility": "crate" })),
+                struct_item(3, "WidgetBare", json!({ "docs": null })),
+            ]),
+            ServerOptions::default(),
+        );
+        let listing = |params: serde_json::Value| {
+            let server = server.clone();
+            async move {
+                let params = serde_json::from_value(params).unwrap();
+                result_text(server.lookup_crate_items(Parameters(params)).await.unwrap())
+            }
+        };
+
+        let all = listing(json!({ "crate_name": "my_crate", "module_path": "Widget*" })).await;
+        for name in ["WidgetOpen", "WidgetShared", "WidgetBare"] {
+            assert!(all.contains(name), "{all}");
+        }
+
+        let public = listing(json!({
+            "crate_name": "my_crate", "module_path": "Widget*", "visibility": "public",
+        }))
+        .await;
+        assert!(public.contains("WidgetOpen"), "{public}");
+        assert!(!public.contains("WidgetShared"), "{public}");
+
+        let documented = listing(json!({
+            "crate_name": "my_crate", "module_path": "Widget*", "documented_only": true,
+        }))
+        .await;
+        assert!(documented.contains("WidgetShared"), "{documented}");
+        assert!(!documented.contains("WidgetBare"), "{documented}");
+    }
+
+    #[tokio::test]
+    async fn globs_leave_out_hidden_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = corpus_server(
+            dir.path(),
+            &test_crate(vec![
+                struct_item(1, "TcpStream", json!({})),
+                struct_item(
+                    2,
+                    "TcpInternal",
+                    json!({ "attrs": [{ "other": "#[doc(hidden)]" }] }),
+                ),
+            ]),
+            ServerOptions::default(),
+        );
+
+        let params =
+            serde_json::from_value(json!({ "crate_name": "my_crate", "module_path": "Tcp*" }));
+        let listing = result_text(
+            server
+                .lookup_crate_items(Parameters(params.unwrap()))
+                .await
+                .unwrap(),
+        );
+        assert!(listing.contains("TcpStream"), "{listing}");
+        assert!(!listing.contains("TcpInternal"), "{listing}");
+
+        let params = serde_json::from_value(json!({
+            "crate_name": "my_crate", "module_path": "Tcp*", "include_hidden": true,
+        }));
+        let listing = result_text(
+            server
+                .lookup_crate_items(Parameters(params.unwrap()))
+                .await
+                .unwrap(),
+        );
+        assert!(listing.contains("TcpInternal"), "{listing}");
+
+        let params = serde_json::from_value(
+            json!({ "crate_name": "my_crate", "item_path": "my_crate::Tcp*" }),
+        );
+        let lookup = result_text(
+            server
+                .lookup_item(Parameters(params.unwrap()))
+                .await
+                .unwrap(),
+        );
+        assert!(lookup.contains("TcpStream"), "{lookup}");
+        assert!(!lookup.contains("TcpInternal"), "{lookup}");
+    }
 }
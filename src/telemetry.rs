@@ -0,0 +1,58 @@
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use tracing_opentelemetry::OpenTelemetryLayer;
+
+/// Environment variables that turn on span export; the exporter reads the rest of the
+/// standard `OTEL_*` settings (headers, timeout, resource attributes) itself.
+const ENDPOINT_VARS: [&str; 2] = [
+    "OTEL_EXPORTER_OTLP_ENDPOINT",
+    "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+];
+
+/// Flushes pending spans and stops the exporter when dropped at the end of `main`.
+pub struct TelemetryGuard(SdkTracerProvider);
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.0.shutdown() {
+            eprintln!("Failed to flush OpenTelemetry spans: {e}");
+        }
+    }
+}
+
+/// A `tracing` layer exporting spans over OTLP/HTTP, if an OTLP endpoint is configured
+/// in the environment. Runs before logging is set up, so failures go to stderr directly.
+pub fn otlp_layer<S>() -> Option<(OpenTelemetryLayer<S, SdkTracer>, TelemetryGuard)>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    if !ENDPOINT_VARS
+        .iter()
+        .any(|var| std::env::var_os(var).is_some())
+    {
+        return None;
+    }
+    let exporter = match SpanExporter::builder().with_http().build() {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            eprintln!("Failed to set up OpenTelemetry export, continuing without it: {e}");
+            return None;
+        }
+    };
+
+    let mut resource = Resource::builder();
+    if std::env::var_os("OTEL_SERVICE_NAME").is_none() {
+        resource = resource.with_service_name(env!("CARGO_PKG_NAME"));
+    }
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource.build())
+        .build();
+    let tracer = provider.tracer(env!("CARGO_PKG_NAME"));
+    Some((
+        tracing_opentelemetry::layer().with_tracer(tracer),
+        TelemetryGuard(provider),
+    ))
+}
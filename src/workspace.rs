@@ -0,0 +1,558 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Packages of the Cargo workspace the server was started in that must be
+/// documented from local sources, from `cargo metadata`.
+pub struct WorkspaceIndex {
+    packages: Vec<LocalPackage>,
+    /// Directory containing the workspace's root Cargo.toml.
+    root: PathBuf,
+    /// Cargo's target directory, where `cargo rustdoc` writes its JSON output.
+    target_dir: PathBuf,
+    /// Toolchain channel pinned by `rust-toolchain.toml` at the root (e.g. "1.75.0").
+    toolchain: Option<String>,
+    /// Dependencies members rename in their Cargo.toml (`k8s = { package = "kube" }`),
+    /// from the name used in code (hyphens as underscores) to the package name.
+    renames: HashMap<String, String>,
+}
+
+/// A package whose docs are built locally rather than fetched from docs.rs.
+#[derive(Debug, Clone)]
+pub struct LocalPackage {
+    /// Package name (e.g. "my-crate").
+    pub name: String,
+    /// Package version from its Cargo.toml.
+    pub version: String,
+    /// Path to the package's Cargo.toml.
+    pub manifest_path: PathBuf,
+    /// Library target name as used in rustdoc paths (e.g. "my_crate"), if the package has one.
+    pub lib_name: Option<String>,
+    /// Where the package's sources come from.
+    pub source: PackageSource,
+}
+
+/// Where a local package's sources come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageSource {
+    /// A member of the current workspace.
+    Member,
+    /// A dependency declared with `path = "..."`.
+    Path,
+    /// A `git` dependency pinned to a revision in Cargo.lock.
+    Git { url: String, rev: String },
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<MetadataPackage>,
+    workspace_members: Vec<String>,
+    workspace_root: PathBuf,
+    target_directory: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct MetadataPackage {
+    id: String,
+    name: String,
+    version: String,
+    /// `None` for workspace members and path dependencies.
+    source: Option<String>,
+    manifest_path: PathBuf,
+    targets: Vec<MetadataTarget>,
+    #[serde(default)]
+    dependencies: Vec<MetadataDependency>,
+}
+
+impl MetadataPackage {
+    /// Name of the library target as used in rustdoc paths, if the package has one.
+    fn lib_name(&self) -> Option<String> {
+        self.targets
+            .iter()
+            .find(|t| {
+                t.kind
+                    .iter()
+                    .any(|k| matches!(k.as_str(), "lib" | "rlib" | "proc-macro"))
+            })
+            .map(|t| t.name.replace('-', "_"))
+    }
+}
+
+#[derive(Deserialize)]
+struct MetadataDependency {
+    name: String,
+    /// The name the dependency is imported as, if renamed with `package = "..."`.
+    rename: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct MetadataTarget {
+    name: String,
+    kind: Vec<String>,
+}
+
+impl WorkspaceIndex {
+    /// Run `cargo metadata` in `start_dir` and collect the locally documented packages.
+    /// Returns `None` if `start_dir` is not inside a Cargo workspace.
+    ///
+    /// The full dependency graph is resolved offline so path and git dependencies are
+    /// found. If that fails (e.g. dependencies not yet downloaded), only members are
+    /// listed, plus the git dependencies pinned in Cargo.lock; `cargo rustdoc` clones
+    /// those on demand.
+    pub fn discover(start_dir: &Path) -> Option<Self> {
+        let mut index = if let Some(json) = run_cargo_metadata(start_dir, &["--offline"]) {
+            Self::from_metadata(&json)
+                .inspect_err(|e| tracing::warn!("Failed to parse cargo metadata: {e}"))
+                .ok()?
+        } else {
+            let json = run_cargo_metadata(start_dir, &["--no-deps"])?;
+            let mut index = Self::from_metadata(&json)
+                .inspect_err(|e| tracing::warn!("Failed to parse cargo metadata: {e}"))
+                .ok()?;
+            index
+                .packages
+                .extend(git_packages_from_lockfile(&index.root.join("Cargo.lock")));
+            index
+        };
+        index.toolchain = read_toolchain_channel(&index.root);
+        Some(index)
+    }
+
+    /// Parse the JSON output of `cargo metadata --format-version 1`.
+    pub(crate) fn from_metadata(json: &[u8]) -> Result<Self, crate::error::Error> {
+        let metadata: Metadata = serde_json::from_slice(json)?;
+
+        let mut renames = HashMap::new();
+        for p in &metadata.packages {
+            if !metadata.workspace_members.contains(&p.id) {
+                continue;
+            }
+            for dep in &p.dependencies {
+                if let Some(rename) = &dep.rename {
+                    renames
+                        .entry(rename.replace('-', "_"))
+                        .or_insert_with(|| dep.name.clone());
+                }
+            }
+        }
+
+        let packages = metadata
+            .packages
+            .into_iter()
+            .filter_map(|p| {
+                let source = if metadata.workspace_members.contains(&p.id) {
+                    PackageSource::Member
+                } else {
+                    match p.source.as_deref() {
+                        None => PackageSource::Path,
+                        Some(src) => {
+                            let (url, rev) = parse_git_source(src)?;
+                            PackageSource::Git { url, rev }
+                        }
+                    }
+                };
+                let lib_name = p.lib_name();
+                Some(LocalPackage {
+                    name: p.name,
+                    version: p.version,
+                    manifest_path: p.manifest_path,
+                    lib_name,
+                    source,
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            packages,
+            root: metadata.workspace_root,
+            target_dir: metadata.target_directory,
+            toolchain: None,
+            renames,
+        })
+    }
+
+    /// Look up a local package by name (hyphens and underscores are interchangeable).
+    pub fn package(&self, crate_name: &str) -> Option<&LocalPackage> {
+        let wanted = crate_name.replace('-', "_");
+        self.packages
+            .iter()
+            .find(|p| p.name.replace('-', "_") == wanted)
+    }
+
+    /// The package a member imports under `name` (e.g. "kube" for `k8s`), if it renames
+    /// that dependency. Hyphens and underscores are interchangeable.
+    pub fn renamed_dependency(&self, name: &str) -> Option<&str> {
+        self.renames
+            .get(&name.replace('-', "_"))
+            .map(String::as_str)
+    }
+
+    /// All locally documented packages.
+    pub fn packages(&self) -> &[LocalPackage] {
+        &self.packages
+    }
+
+    /// Directory containing the workspace's root Cargo.toml.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Cargo's target directory for this workspace.
+    pub fn target_dir(&self) -> &Path {
+        &self.target_dir
+    }
+
+    /// Toolchain channel pinned by the workspace's `rust-toolchain.toml`, if any.
+    pub fn toolchain(&self) -> Option<&str> {
+        self.toolchain.as_deref()
+    }
+}
+
+/// Read the toolchain channel from `rust-toolchain.toml` (or the legacy
+/// `rust-toolchain` file, which may also hold a bare channel name) in `root`.
+fn read_toolchain_channel(root: &Path) -> Option<String> {
+    ["rust-toolchain.toml", "rust-toolchain"]
+        .iter()
+        .find_map(|name| std::fs::read_to_string(root.join(name)).ok())
+        .and_then(|content| parse_toolchain_channel(&content))
+}
+
+/// Extract `channel = "..."` from a toolchain file, or the whole content if it is
+/// a bare channel name.
+fn parse_toolchain_channel(content: &str) -> Option<String> {
+    let channel = content.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "channel").then(|| value.trim().trim_matches(['"', '\''].as_ref()))
+    });
+    let channel = match channel {
+        Some(channel) => channel,
+        None if !content.contains('[') => content.trim(),
+        None => return None,
+    };
+    (!channel.is_empty()).then(|| channel.to_string())
+}
+
+/// Whether a `rust-version` requirement (e.g. "1.80") is newer than a toolchain
+/// channel (e.g. "1.75.0"). Named channels like "stable" never compare as older.
+pub fn rust_version_exceeds(required: &str, toolchain: &str) -> bool {
+    fn parse(version: &str) -> Option<(u64, u64, u64)> {
+        let mut parts = version.split('.').map(|p| p.parse::<u64>().ok());
+        let major = parts.next()??;
+        let minor = parts.next().flatten().unwrap_or(0);
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some((major, minor, patch))
+    }
+    match (parse(required), parse(toolchain)) {
+        (Some(required), Some(toolchain)) => required > toolchain,
+        _ => false,
+    }
+}
+
+/// Split a cargo git source (`git+https://host/repo?branch=main#rev`) into URL and revision.
+/// Returns `None` for non-git sources.
+fn parse_git_source(source: &str) -> Option<(String, String)> {
+    let rest = source.strip_prefix("git+")?;
+    let (url, rev) = rest.split_once('#')?;
+    let url = url.split_once('?').map_or(url, |(base, _)| base);
+    Some((url.to_string(), rev.to_string()))
+}
+
+/// Collect the git dependencies pinned in a Cargo.lock.
+///
+/// Used when `cargo metadata` can't resolve offline, so the checkout path and
+/// library target name are unknown; the library name falls back to the package name.
+fn git_packages_from_lockfile(lock_path: &Path) -> Vec<LocalPackage> {
+    let Ok(lockfile) = cargo_lock::Lockfile::load(lock_path) else {
+        return Vec::new();
+    };
+    lockfile
+        .packages
+        .iter()
+        .filter_map(|p| {
+            let source = p.source.as_ref().filter(|s| s.is_git())?;
+            Some(LocalPackage {
+                name: p.name.as_str().to_string(),
+                version: p.version.to_string(),
+                manifest_path: PathBuf::new(),
+                lib_name: Some(p.name.as_str().replace('-', "_")),
+                source: PackageSource::Git {
+                    url: source.url().to_string(),
+                    rev: source.precise()?.to_string(),
+                },
+            })
+        })
+        .collect()
+}
+
+/// The local path of a `file://` URI, as MCP clients list their roots. Percent-escapes
+/// are decoded; other schemes give `None`.
+pub fn file_uri_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    // Skip an authority (`file://localhost/...`)
+    let path = &rest[rest.find('/')?..];
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| path.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    Some(PathBuf::from(
+        String::from_utf8_lossy(&decoded).into_owned(),
+    ))
+}
+
+/// The library target name of package `name` (at `version`, if given) in the
+/// dependency graph of the project at `start_dir`, from `cargo metadata --offline`.
+/// For crates whose `[lib] name` differs from the package name. Blocking.
+pub fn dependency_lib_name(start_dir: &Path, name: &str, version: Option<&str>) -> Option<String> {
+    let json = run_cargo_metadata(start_dir, &["--offline"])?;
+    lib_name_in_metadata(&json, name, version)
+}
+
+fn lib_name_in_metadata(json: &[u8], name: &str, version: Option<&str>) -> Option<String> {
+    let metadata: Metadata = serde_json::from_slice(json)
+        .inspect_err(|e| tracing::warn!("Failed to parse cargo metadata: {e}"))
+        .ok()?;
+    metadata
+        .packages
+        .iter()
+        .find(|p| p.name == name && version.is_none_or(|v| p.version == v))?
+        .lib_name()
+}
+
+/// Run `cargo metadata` with extra flags, returning stdout on success.
+fn run_cargo_metadata(start_dir: &Path, extra_args: &[&str]) -> Option<Vec<u8>> {
+    let output = std::process::Command::new("cargo")
+        .args(["metadata", "--format-version", "1"])
+        .args(extra_args)
+        .current_dir(start_dir)
+        .output()
+        .inspect_err(|e| tracing::debug!("Failed to run cargo metadata: {e}"))
+        .ok()?;
+
+    if !output.status.success() {
+        tracing::debug!(
+            "cargo metadata {} failed: {}",
+            extra_args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+    Some(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_metadata() -> serde_json::Value {
+        serde_json::json!({
+            "packages": [
+                {
+                    "id": "my-app 0.1.0 (path+file:///ws/app)",
+                    "name": "my-app",
+                    "version": "0.1.0",
+                    "source": null,
+                    "manifest_path": "/ws/app/Cargo.toml",
+                    "targets": [{ "name": "my-app", "kind": ["bin"] }]
+                },
+                {
+                    "id": "my-lib 0.2.0 (path+file:///ws/lib)",
+                    "name": "my-lib",
+                    "version": "0.2.0",
+                    "source": null,
+                    "manifest_path": "/ws/lib/Cargo.toml",
+                    "targets": [
+                        { "name": "my-lib", "kind": ["lib"] },
+                        { "name": "bench", "kind": ["bench"] }
+                    ]
+                },
+                {
+                    "id": "vendored 1.0.0 (path+file:///vendor/vendored)",
+                    "name": "vendored",
+                    "version": "1.0.0",
+                    "source": null,
+                    "manifest_path": "/vendor/vendored/Cargo.toml",
+                    "targets": [{ "name": "vendored", "kind": ["lib"] }]
+                },
+                {
+                    "id": "registry+https://github.com/rust-lang/crates.io-index#serde@1.0.0",
+                    "name": "serde",
+                    "version": "1.0.0",
+                    "source": "registry+https://github.com/rust-lang/crates.io-index",
+                    "manifest_path": "/registry/serde-1.0.0/Cargo.toml",
+                    "targets": [{ "name": "serde", "kind": ["lib"] }]
+                }
+            ],
+            "workspace_members": [
+                "my-app 0.1.0 (path+file:///ws/app)",
+                "my-lib 0.2.0 (path+file:///ws/lib)"
+            ],
+            "workspace_root": "/ws",
+            "target_directory": "/ws/target"
+        })
+    }
+
+    #[test]
+    fn from_metadata_collects_members_and_lib_names() {
+        let json = serde_json::to_vec(&sample_metadata()).unwrap();
+        let ws = WorkspaceIndex::from_metadata(&json).unwrap();
+
+        assert_eq!(ws.root(), Path::new("/ws"));
+        assert_eq!(ws.target_dir(), Path::new("/ws/target"));
+
+        let lib = ws.package("my-lib").unwrap();
+        assert_eq!(lib.version, "0.2.0");
+        assert_eq!(lib.lib_name.as_deref(), Some("my_lib"));
+        assert_eq!(lib.source, PackageSource::Member);
+
+        let app = ws.package("my-app").unwrap();
+        assert!(app.lib_name.is_none());
+    }
+
+    #[test]
+    fn from_metadata_collects_renamed_dependencies_of_members() {
+        let mut metadata = sample_metadata();
+        metadata["packages"][0]["dependencies"] = serde_json::json!([
+            { "name": "serde", "rename": null },
+            { "name": "kube", "rename": "k8s" },
+            { "name": "tokio-fork", "rename": "tokio-compat" }
+        ]);
+        // Renames inside dependencies don't apply to the project's code
+        metadata["packages"][2]["dependencies"] =
+            serde_json::json!([{ "name": "other", "rename": "serde" }]);
+        let json = serde_json::to_vec(&metadata).unwrap();
+        let ws = WorkspaceIndex::from_metadata(&json).unwrap();
+
+        assert_eq!(ws.renamed_dependency("k8s"), Some("kube"));
+        assert_eq!(ws.renamed_dependency("tokio_compat"), Some("tokio-fork"));
+        assert_eq!(ws.renamed_dependency("serde"), None);
+        assert_eq!(ws.renamed_dependency("kube"), None);
+    }
+
+    #[test]
+    fn from_metadata_detects_path_dependencies_and_skips_registry_crates() {
+        let json = serde_json::to_vec(&sample_metadata()).unwrap();
+        let ws = WorkspaceIndex::from_metadata(&json).unwrap();
+
+        assert_eq!(ws.packages().len(), 3);
+        assert_eq!(ws.package("vendored").unwrap().source, PackageSource::Path);
+        assert!(ws.package("serde").is_none());
+    }
+
+    #[test]
+    fn lib_name_in_metadata_reads_renamed_lib_targets() {
+        let mut metadata = sample_metadata();
+        metadata["packages"][3]["targets"][0]["name"] = "serde_core".into();
+        let json = serde_json::to_vec(&metadata).unwrap();
+
+        assert_eq!(
+            lib_name_in_metadata(&json, "serde", None).as_deref(),
+            Some("serde_core")
+        );
+        assert_eq!(
+            lib_name_in_metadata(&json, "serde", Some("1.0.0")).as_deref(),
+            Some("serde_core")
+        );
+        assert_eq!(lib_name_in_metadata(&json, "serde", Some("2.0.0")), None);
+        assert_eq!(lib_name_in_metadata(&json, "my-app", None), None);
+    }
+
+    #[test]
+    fn from_metadata_detects_git_dependencies() {
+        let mut metadata = sample_metadata();
+        metadata["packages"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!({
+                "id": "git+https://github.com/org/forked?branch=main#0123abcd",
+                "name": "forked",
+                "version": "0.5.0",
+                "source": "git+https://github.com/org/forked?branch=main#0123abcd",
+                "manifest_path": "/git/checkouts/forked-1/0123abc/Cargo.toml",
+                "targets": [{ "name": "forked", "kind": ["lib"] }]
+            }));
+        let json = serde_json::to_vec(&metadata).unwrap();
+        let ws = WorkspaceIndex::from_metadata(&json).unwrap();
+
+        assert_eq!(
+            ws.package("forked").unwrap().source,
+            PackageSource::Git {
+                url: "https://github.com/org/forked".to_string(),
+                rev: "0123abcd".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_git_source_handles_plain_and_registry_sources() {
+        assert_eq!(
+            parse_git_source("git+https://github.com/org/repo#deadbeef"),
+            Some((
+                "https://github.com/org/repo".to_string(),
+                "deadbeef".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_git_source("registry+https://github.com/rust-lang/crates.io-index"),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_toolchain_channel_reads_toml_and_legacy_files() {
+        let toml = "[toolchain]\nchannel = \"1.75.0\"\ncomponents = [\"clippy\"]\n";
+        assert_eq!(parse_toolchain_channel(toml).as_deref(), Some("1.75.0"));
+        assert_eq!(
+            parse_toolchain_channel("nightly-2024-05-01\n").as_deref(),
+            Some("nightly-2024-05-01")
+        );
+        assert_eq!(
+            parse_toolchain_channel("[toolchain]\nprofile = \"minimal\""),
+            None
+        );
+    }
+
+    #[test]
+    fn rust_version_exceeds_compares_numeric_channels() {
+        assert!(rust_version_exceeds("1.80", "1.75.0"));
+        assert!(!rust_version_exceeds("1.75", "1.75.0"));
+        assert!(!rust_version_exceeds("1.70.1", "1.75"));
+        assert!(!rust_version_exceeds("1.80", "stable"));
+    }
+
+    #[test]
+    fn file_uri_path_decodes_local_paths() {
+        assert_eq!(
+            file_uri_path("file:///home/me/my%20project"),
+            Some(PathBuf::from("/home/me/my project"))
+        );
+        assert_eq!(
+            file_uri_path("file://localhost/ws"),
+            Some(PathBuf::from("/ws"))
+        );
+        assert_eq!(file_uri_path("https://example.com/ws"), None);
+    }
+
+    #[test]
+    fn package_lookup_ignores_hyphen_underscore_difference() {
+        let json = serde_json::to_vec(&sample_metadata()).unwrap();
+        let ws = WorkspaceIndex::from_metadata(&json).unwrap();
+
+        assert!(ws.package("my_lib").is_some());
+        assert!(ws.package("other").is_none());
+    }
+}